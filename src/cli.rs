@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 /// rzen - A TUI-based CLI tool for building, deploying, and monitoring Rust projects
@@ -20,9 +20,35 @@ pub struct Cli {
     pub log_level: u8,
 
     /// Dry run mode - simulate operations without making changes
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub dry_run: bool,
 
+    /// Plain-text markers instead of emoji (automatic on the Linux console and non-UTF-8 locales)
+    #[arg(long)]
+    pub ascii: bool,
+
+    /// Apply the config's [env.<NAME>] overrides (default: $RZEN_ENV)
+    #[arg(long, value_name = "NAME")]
+    pub env: Option<String>,
+
+    /// Use the config's [[projects]] entry named NAME
+    #[arg(long, value_name = "NAME")]
+    pub project: Option<String>,
+
+    /// Never prompt: questions get their default answer, and what can't be answered fails
+    /// (automatic when $CI is set)
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+
+    /// Never prompt, answering yes to confirmations (host keys are never trusted this way)
+    #[arg(short = 'y', long, global = true)]
+    pub yes: bool,
+
+    /// Output format; json prints the result as JSON on stdout (status, check-rebuild, validate,
+    /// deploy, monitor and service)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -51,17 +77,77 @@ pub enum Commands {
         /// Force redeployment even if already deployed
         #[arg(long)]
         force: bool,
+
+        /// Deploy a prebuilt binary from a URL or local path instead of building
+        #[arg(
+            long,
+            value_name = "URL|PATH",
+            group = "prebuilt",
+            conflicts_with_all = ["skip_build", "github_release"]
+        )]
+        artifact: Option<String>,
+
+        /// Deploy the binary asset of a GitHub Release (default: latest)
+        #[arg(
+            long,
+            value_name = "TAG",
+            num_args = 0..=1,
+            default_missing_value = "latest",
+            group = "prebuilt",
+            conflicts_with = "skip_build"
+        )]
+        github_release: Option<String>,
+
+        /// Expected SHA-256 checksum of the prebuilt binary
+        #[arg(long, value_name = "HEX", requires = "prebuilt")]
+        sha256: Option<String>,
+
+        /// Deploy an --artifact that has no checksum to verify it against
+        #[arg(long, requires = "artifact", conflicts_with = "sha256")]
+        skip_checksum: bool,
+
+        /// Build and deploy a git tag, branch, or commit from a clean worktree
+        #[arg(
+            long = "ref",
+            value_name = "REF",
+            conflicts_with_all = ["skip_build", "artifact", "github_release"]
+        )]
+        git_ref: Option<String>,
     },
 
     /// Monitor the deployed application
     Monitor {
-        /// Continuous monitoring mode
+        /// Continuous monitoring mode (runs until Ctrl-C)
         #[arg(long)]
         continuous: bool,
 
+        /// Stop continuous monitoring after this long (e.g. 30m, 2h)
+        #[arg(
+            long,
+            requires = "continuous",
+            value_parser = crate::utils::timing::parse_duration
+        )]
+        duration: Option<std::time::Duration>,
+
+        /// Stop continuous monitoring after this many cycles
+        #[arg(long, requires = "continuous")]
+        cycles: Option<u64>,
+
         /// Number of log lines to show initially
         #[arg(long, default_value = "50")]
         lines: usize,
+
+        /// Write a report from the stored history instead of checking (.html or .json)
+        #[arg(long, value_name = "FILE", conflicts_with = "continuous")]
+        report: Option<std::path::PathBuf>,
+
+        /// Period covered by the report (e.g. 24h, 7d)
+        #[arg(
+            long,
+            default_value = "7d",
+            value_parser = crate::utils::timing::parse_duration
+        )]
+        period: std::time::Duration,
     },
 
     /// Initialize a new rzen configuration file
@@ -84,6 +170,11 @@ pub enum Commands {
         /// Path to configuration file to validate
         #[arg(default_value = "rzen.toml")]
         path: PathBuf,
+
+        /// Also connect to each host and check sudo, systemctl, the deploy path, free disk
+        /// space and the health endpoint
+        #[arg(long)]
+        remote: bool,
     },
 
     /// Clean build artifacts
@@ -96,6 +187,12 @@ pub enum Commands {
     /// Rollback deployment to previous version
     Rollback,
 
+    /// Manage the binary backups kept on the server for rollback
+    Releases {
+        #[command(subcommand)]
+        action: ReleasesAction,
+    },
+
     /// Stream logs in real-time
     Logs {
         /// Number of initial log lines to show
@@ -105,13 +202,271 @@ pub enum Commands {
         /// Follow logs in real-time
         #[arg(short, long)]
         follow: bool,
+
+        /// Only show lines matching this regex (e.g. "ERROR|WARN")
+        #[arg(long, value_name = "REGEX")]
+        grep: Option<String>,
+
+        /// Hide lines matching this regex (e.g. "GET /health")
+        #[arg(long, value_name = "REGEX")]
+        exclude: Option<String>,
+
+        /// Only show entries at or above this level: error, warn, info, debug or trace
+        #[arg(long, value_name = "LEVEL")]
+        level: Option<crate::commands::monitor::structured::Severity>,
+
+        /// Read the journal of this systemd unit instead of the configured log (e.g. nginx)
+        #[arg(long, value_name = "UNIT")]
+        service: Option<String>,
+
+        /// Only show entries at or after this time (e.g. 2h, "2024-06-01 12:00")
+        #[arg(long, value_name = "TIME")]
+        since: Option<String>,
+
+        /// Only show entries at or before this time (e.g. 30m, "2024-06-01 13:00")
+        #[arg(long, value_name = "TIME", conflicts_with = "follow")]
+        until: Option<String>,
+
+        /// Columns shown for structured (JSON or logfmt) lines
+        #[arg(
+            long,
+            value_name = "FIELDS",
+            value_delimiter = ',',
+            default_value = "timestamp,level,target,message"
+        )]
+        fields: Vec<String>,
+
+        /// Print structured lines as-is instead of as a table
+        #[arg(long, conflicts_with = "fields")]
+        raw: bool,
+
+        /// Save the whole log to FILE instead of printing it; a journal is saved as text
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["follow", "grep", "exclude", "level", "since", "until"]
+        )]
+        download: Option<PathBuf>,
+    },
+
+    /// Download the config files on the server: rendered templates, the systemd unit and the
+    /// deploy manifest
+    Backup {
+        /// Directory to save them in, one subdirectory per host (default: rzen-backup-<time>)
+        #[arg(short = 'o', long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+
+    /// Download the newest core dump of the deployed binary (needs systemd-coredump)
+    Coredump {
+        /// File to save it as (default: <binary>.core)
+        #[arg(short = 'o', long, value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+
+    /// Start, stop or restart the systemd service, or show its state, on every host
+    Service {
+        /// What to do with the service
+        #[arg(value_enum)]
+        action: ServiceAction,
+
+        /// Unit to manage instead of the configured service
+        #[arg(long, value_name = "NAME")]
+        service: Option<String>,
+    },
+
+    /// Run a shell command on every host, streaming its output and exiting with its exit code
+    Exec {
+        /// Command to run, after `--`; several words are joined with spaces, as ssh does
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+
+    /// Copy a local file or directory to every host
+    Cp {
+        /// Local file or directory
+        local: PathBuf,
+
+        /// Path on the server; a directory there receives the copy under its own name
+        remote: String,
+
+        /// Copy directories and everything in them
+        #[arg(short, long)]
+        recursive: bool,
+    },
+
+    /// Copy a file or directory from every host to this machine
+    Fetch {
+        /// Path on the server
+        remote: String,
+
+        /// Where to save it; a directory receives the copy under its own name, and with
+        /// several hosts each one gets a subdirectory
+        #[arg(default_value = ".")]
+        local: PathBuf,
+
+        /// Copy directories and everything in them
+        #[arg(short, long)]
+        recursive: bool,
     },
 
     /// Check deployment status
     Status,
 
+    /// List downtime incidents recorded by continuous monitoring
+    Incidents {
+        /// Maximum number of incidents to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
     /// Check if project needs rebuilding
     CheckRebuild,
+
+    /// Config helpers: reading and changing settings, encrypted values, the JSON Schema and layout upgrades
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Manage the SSH password kept in the OS keyring (`password_source = "keyring"`)
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+
+    /// Open the interactive TUI, optionally on a specific tab
+    Tui {
+        /// Tab to open on
+        #[arg(value_enum)]
+        tab: Option<TuiTab>,
+
+        /// Tab to open on (same as the positional argument)
+        #[arg(long = "tab", value_enum, value_name = "TAB", conflicts_with = "tab")]
+        tab_option: Option<TuiTab>,
+    },
+}
+
+/// `rzen config` subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// Print a value as `enc:...` for rzen.toml (prompts for it when not given)
+    Encrypt {
+        /// Value to encrypt; prefer the prompt to keep it out of the shell history
+        value: Option<String>,
+
+        /// age public key to encrypt for (default: the key of the identity file)
+        #[arg(long, value_name = "AGE1...")]
+        recipient: Option<String>,
+    },
+
+    /// Print the plaintext of an `enc:...` value
+    Decrypt {
+        /// Encrypted value, with or without the `enc:` prefix
+        value: String,
+    },
+
+    /// Print the JSON Schema of rzen.toml for editor completion and validation
+    Schema,
+
+    /// Print the value of a setting, e.g. `deploy.ssh_port` (defaults and --env applied)
+    Get {
+        /// Setting as `<section>.<key>`
+        key: String,
+    },
+
+    /// Change a setting in the config file, keeping its comments and layout
+    ///
+    /// String settings take the value as is; others read it as TOML (`2222`, `true`,
+    /// `'["a.example.com", "b.example.com"]'`). With --env, the setting goes into
+    /// `[env.<name>]`. The file is only written when the result is valid.
+    Set {
+        /// Setting as `<section>.<key>`
+        key: String,
+
+        /// New value
+        value: String,
+    },
+
+    /// Upgrade the config file to the current layout version (printed unless --write is given)
+    Migrate {
+        /// Rewrite the file in place, keeping its comments
+        #[arg(long)]
+        write: bool,
+    },
+}
+
+/// `rzen releases` subcommands
+#[derive(Subcommand, Debug, Clone, Copy)]
+pub enum ReleasesAction {
+    /// Remove all but the newest backups on every host to reclaim disk space
+    Prune {
+        /// Backups to keep on each host (default: deploy.keep_backups)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        keep: Option<u64>,
+    },
+}
+
+/// `rzen auth` subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum AuthAction {
+    /// Store the SSH password or key passphrase for the configured user and host
+    Set {
+        /// Host from `deploy.hosts` to store it for (default: `vps_host`)
+        #[arg(long)]
+        host: Option<String>,
+    },
+    /// Remove the stored password
+    Delete {
+        /// Host from `deploy.hosts` to remove it for (default: `vps_host`)
+        #[arg(long)]
+        host: Option<String>,
+    },
+}
+
+/// TUI tab selectable from the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuiTab {
+    Build,
+    Deploy,
+    /// Starts monitoring right away
+    Monitor,
+    /// Starts monitoring right away
+    Dashboard,
+    Console,
+    Config,
+}
+
+/// `rzen service` actions
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+    /// Only show the state
+    Status,
+}
+
+impl ServiceAction {
+    /// systemctl command that applies the action, if it changes anything
+    pub fn verb(self) -> Option<&'static str> {
+        match self {
+            ServiceAction::Start => Some("start"),
+            ServiceAction::Stop => Some("stop"),
+            ServiceAction::Restart => Some("restart"),
+            ServiceAction::Status => None,
+        }
+    }
+}
+
+/// How commands print their results
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable output
+    #[default]
+    Text,
+    /// Machine-readable JSON on stdout
+    Json,
 }
 
 impl Cli {
@@ -126,16 +481,43 @@ impl Cli {
         crate::logging::LogLevel::from_number(self.log_level)
     }
 
+    /// Environment to resolve the config for: `--env`, else `RZEN_ENV` when set
+    pub fn environment(&self) -> Option<String> {
+        self.env.clone().or_else(|| {
+            std::env::var(crate::config::ENV_VAR)
+                .ok()
+                .filter(|name| !name.is_empty())
+        })
+    }
+
     /// Check if we should run in TUI mode (no subcommand specified)
     pub fn should_run_tui(&self) -> bool {
         self.command.is_none()
     }
 
-    /// Validate log level
+    /// Validate log level, and that the command can print JSON when asked to
     pub fn validate(&self) -> Result<(), String> {
         if self.log_level > 5 {
             return Err("Log level must be between 0 and 5".to_string());
         }
+        if self.output == OutputFormat::Json
+            && !matches!(
+                self.command,
+                Some(
+                    Commands::Status
+                        | Commands::CheckRebuild
+                        | Commands::Validate { .. }
+                        | Commands::Deploy { .. }
+                        | Commands::Monitor { .. }
+                        | Commands::Service { .. }
+                )
+            )
+        {
+            return Err(
+                "--output json works with status, check-rebuild, validate, deploy, monitor and service"
+                    .to_string(),
+            );
+        }
         Ok(())
     }
 }
@@ -150,6 +532,12 @@ mod tests {
             config: None,
             log_level: 0,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: OutputFormat::Text,
             command: None,
         };
         assert_eq!(cli.log_filter(), "off");
@@ -158,6 +546,12 @@ mod tests {
             config: None,
             log_level: 3,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: OutputFormat::Text,
             command: None,
         };
         assert_eq!(cli.log_filter(), "info");
@@ -169,6 +563,12 @@ mod tests {
             config: None,
             log_level: 3,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: OutputFormat::Text,
             command: None,
         };
         assert!(cli.should_run_tui());
@@ -177,6 +577,12 @@ mod tests {
             config: None,
             log_level: 3,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: OutputFormat::Text,
             command: Some(Commands::Build {
                 mode: None,
                 cargo_args: vec![],
@@ -191,6 +597,12 @@ mod tests {
             config: None,
             log_level: 3,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: OutputFormat::Text,
             command: None,
         };
         assert!(cli.validate().is_ok());
@@ -199,8 +611,101 @@ mod tests {
             config: None,
             log_level: 10,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: OutputFormat::Text,
             command: None,
         };
         assert!(cli.validate().is_err());
     }
+
+    #[test]
+    fn test_output_argument() {
+        let cli = Cli::try_parse_from(["rzen", "status", "--output", "json"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(cli.validate().is_ok());
+        let cli = Cli::try_parse_from(["rzen", "--output", "json", "check-rebuild"]).unwrap();
+        assert!(cli.validate().is_ok());
+
+        let cli = Cli::try_parse_from(["rzen", "build", "--output", "json"]).unwrap();
+        assert!(cli.validate().is_err());
+        let cli = Cli::try_parse_from(["rzen", "backup", "-o", "backups"]).unwrap();
+        assert!(cli.validate().is_ok());
+    }
+
+    #[test]
+    fn test_prompt_flags() {
+        let cli = Cli::try_parse_from(["rzen", "deploy", "--yes"]).unwrap();
+        assert!(cli.yes && !cli.non_interactive);
+        let cli = Cli::try_parse_from(["rzen", "--non-interactive", "status"]).unwrap();
+        assert!(cli.non_interactive);
+        let cli = Cli::try_parse_from(["rzen", "-y", "monitor"]).unwrap();
+        assert!(cli.yes);
+    }
+
+    #[test]
+    fn test_releases_prune_arguments() {
+        let cli = Cli::try_parse_from(["rzen", "releases", "prune", "--keep", "3", "--dry-run"]).unwrap();
+        assert!(cli.dry_run);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Releases { action: ReleasesAction::Prune { keep: Some(3) } })
+        ));
+        assert!(Cli::try_parse_from(["rzen", "releases", "prune", "--keep", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_logs_filter_arguments() {
+        use crate::commands::monitor::structured::Severity;
+
+        let cli = Cli::try_parse_from([
+            "rzen", "logs", "-f", "--level", "warn", "--grep", "timeout", "--since", "1h", "--service", "nginx",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Commands::Logs { follow, level, grep, since, service, .. }) => {
+                assert!(follow);
+                assert_eq!(level, Some(Severity::Warn));
+                assert_eq!(grep.as_deref(), Some("timeout"));
+                assert_eq!(since.as_deref(), Some("1h"));
+                assert_eq!(service.as_deref(), Some("nginx"));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["rzen", "logs", "--level", "loud"]).is_err());
+        assert!(Cli::try_parse_from(["rzen", "logs", "--level", "error", "--download", "app.log"]).is_err());
+    }
+
+    #[test]
+    fn test_copy_arguments() {
+        let cli = Cli::try_parse_from(["rzen", "cp", "-r", "conf", "/etc/app"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Cp { recursive: true, ref remote, .. }) if remote == "/etc/app"
+        ));
+        match Cli::try_parse_from(["rzen", "fetch", "/var/log/app.log"]).unwrap().command {
+            Some(Commands::Fetch { local, recursive, .. }) => {
+                assert_eq!(local, PathBuf::from("."));
+                assert!(!recursive);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["rzen", "cp", "conf"]).is_err());
+    }
+
+    #[test]
+    fn test_tui_tab_argument() {
+        let tab = |args: &[&str]| match Cli::try_parse_from(args).unwrap().command {
+            Some(Commands::Tui { tab, tab_option }) => tab.or(tab_option),
+            other => panic!("unexpected command: {:?}", other),
+        };
+        assert_eq!(tab(&["rzen", "tui"]), None);
+        assert_eq!(tab(&["rzen", "tui", "monitor"]), Some(TuiTab::Monitor));
+        assert_eq!(tab(&["rzen", "tui", "--tab", "dashboard"]), Some(TuiTab::Dashboard));
+        assert!(Cli::try_parse_from(["rzen", "tui", "logs"]).is_err());
+        assert!(Cli::try_parse_from(["rzen", "tui", "build", "--tab", "deploy"]).is_err());
+    }
 }
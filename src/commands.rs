@@ -1,4 +1,8 @@
 // Command modules
 pub mod build;
 pub mod deploy;
+pub mod exec;
+pub mod hooks;
 pub mod monitor;
+pub mod service;
+pub mod transfer;
@@ -1,8 +1,10 @@
 use anyhow::{Context, Result, anyhow};
 use std::path::Path;
 use std::process::Stdio;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command as TokioCommand;
 
+use crate::commands::hooks::{self, Stage};
 use crate::config::Config;
 use crate::logging::log;
 use crate::utils;
@@ -13,7 +15,20 @@ pub async fn build_project(
     build_mode: Option<&str>,
     dry_run: bool,
 ) -> Result<String> {
-    let project_path = config.project_path()?;
+    build_project_with_progress(config, build_mode, dry_run, None).await
+}
+
+/// Build the Rust project, reporting progress and each line of cargo output
+///
+/// The callback receives the share of compilation units built so far (0-100)
+/// and, for output lines, the line itself.
+#[allow(clippy::type_complexity)]
+pub async fn build_project_with_progress(
+    config: &Config,
+    build_mode: Option<&str>,
+    dry_run: bool,
+    progress_callback: Option<&(dyn Fn(f64, Option<&str>) + Send + Sync)>,
+) -> Result<String> {
     let build_mode = build_mode.unwrap_or(&config.project.build_mode);
     let binary_name = config.binary_name();
 
@@ -23,18 +38,46 @@ pub async fn build_project(
     ));
 
     if dry_run {
+        hooks::run(config, Stage::PreBuild, None, true).await?;
         log::dry_run(&format!(
             "cargo build --{} --bin {}",
             build_mode, binary_name
         ));
+        hooks::run(config, Stage::PostBuild, None, true).await?;
         return Ok(format!(
             "Would build {} in {} mode",
             binary_name, build_mode
         ));
     }
 
+    let result = async {
+        hooks::run(config, Stage::PreBuild, None, false).await?;
+        let output = compile(config, build_mode, progress_callback).await?;
+        hooks::run(config, Stage::PostBuild, None, false).await?;
+        Ok(output)
+    }
+    .await;
+    if let Err(e) = &result {
+        hooks::run_on_failure(config, e).await;
+    }
+    result
+}
+
+/// Run cargo unless the binary is newer than the sources
+#[allow(clippy::type_complexity)]
+async fn compile(
+    config: &Config,
+    build_mode: &str,
+    progress_callback: Option<&(dyn Fn(f64, Option<&str>) + Send + Sync)>,
+) -> Result<String> {
+    let project_path = config.project_path()?;
+    let binary_name = config.binary_name();
+
     if !needs_rebuild(config)? {
         log::build_step("Project is up to date, skipping build");
+        if let Some(callback) = progress_callback {
+            callback(100.0, Some("Project is up to date, skipping build"));
+        }
         return Ok(format!("Project '{}' is already built", binary_name));
     }
 
@@ -47,7 +90,7 @@ pub async fn build_project(
     }
 
     let (result, duration) = utils::timing::measure(|| async {
-        execute_cargo_build(&project_path, build_mode, &binary_name).await
+        execute_cargo_build(&project_path, build_mode, &binary_name, progress_callback).await
     })
     .await;
 
@@ -68,10 +111,12 @@ pub async fn build_project(
 }
 
 /// Execute cargo build command
+#[allow(clippy::type_complexity)]
 async fn execute_cargo_build(
     project_path: &Path,
     build_mode: &str,
     binary_name: &str,
+    progress_callback: Option<&(dyn Fn(f64, Option<&str>) + Send + Sync)>,
 ) -> Result<String> {
     let mut args = vec!["build", "--bin", binary_name];
 
@@ -88,26 +133,44 @@ async fn execute_cargo_build(
 
     log::build_step(&format!("Running: cargo {}", args.join(" ")));
 
-    let output = TokioCommand::new("cargo")
+    // Cargo only draws its `Building [==>  ] 12/80` bar for terminals unless asked to
+    let mut child = TokioCommand::new("cargo")
         .args(&args)
         .current_dir(project_path)
+        .env("CARGO_TERM_PROGRESS_WHEN", "always")
+        .env("CARGO_TERM_PROGRESS_WIDTH", "80")
+        .env("CARGO_TERM_COLOR", "never")
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .output()
-        .await
+        .spawn()
         .with_context(|| "Failed to execute cargo build".to_string())?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("Failed to capture cargo output"))?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| anyhow!("Failed to capture cargo output"))?;
+
+    let mut stdout_output = String::new();
+    let (stdout_result, stderr_result) = tokio::join!(
+        stdout.read_to_string(&mut stdout_output),
+        stream_cargo_output(&mut stderr, progress_callback)
+    );
+    stdout_result.context("Failed to read cargo output")?;
+    let stderr_lines = stderr_result?;
+    let status = child.wait().await.context("Failed to wait for cargo")?;
 
-    for line in stdout.lines() {
+    for line in stdout_output.lines() {
         if !line.trim().is_empty() {
             log::build_step(line);
         }
     }
 
-    if !output.status.success() {
-        return Err(anyhow!("Cargo build failed:\n{}", stderr));
+    if !status.success() {
+        return Err(anyhow!("Cargo build failed:\n{}", stderr_lines.join("\n")));
     }
 
     let binary_path = utils::fs::find_binary(project_path, binary_name, build_mode)
@@ -126,6 +189,67 @@ async fn execute_cargo_build(
     ))
 }
 
+/// Read cargo's stderr as it is written, reporting progress and output lines
+///
+/// Progress bar redraws are separated by `\r` rather than newlines, so both end
+/// a segment. Returns the output lines without the progress bar.
+#[allow(clippy::type_complexity)]
+async fn stream_cargo_output(
+    stderr: &mut tokio::process::ChildStderr,
+    progress_callback: Option<&(dyn Fn(f64, Option<&str>) + Send + Sync)>,
+) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+    let mut pending = String::new();
+    let mut progress = 0.0;
+    let mut buf = [0; 4096];
+
+    loop {
+        let n = stderr
+            .read(&mut buf)
+            .await
+            .context("Failed to read cargo output")?;
+        if n == 0 {
+            break;
+        }
+        pending.push_str(&String::from_utf8_lossy(&buf[..n]));
+
+        while let Some(end) = pending.find(['\r', '\n']) {
+            let segment: String = pending.drain(..=end).collect();
+            let segment = segment.trim_end();
+            if segment.trim().is_empty() {
+                continue;
+            }
+
+            if let Some((done, total)) = parse_progress(segment) {
+                progress = done as f64 / total.max(1) as f64 * 100.0;
+                if let Some(callback) = progress_callback {
+                    callback(progress, None);
+                }
+            } else {
+                tracing::debug!("cargo: {}", segment);
+                if let Some(callback) = progress_callback {
+                    callback(progress, Some(segment));
+                }
+                lines.push(segment.to_string());
+            }
+        }
+    }
+
+    if !pending.trim().is_empty() {
+        lines.push(pending.trim_end().to_string());
+    }
+    Ok(lines)
+}
+
+/// Parse cargo's progress bar, e.g. `Building [=====>   ] 45/120: serde, tokio`
+fn parse_progress(line: &str) -> Option<(usize, usize)> {
+    let rest = line.trim_start().strip_prefix("Building [")?;
+    let (_, counts) = rest.split_once("] ")?;
+    let counts = counts.split(':').next()?;
+    let (done, total) = counts.trim().split_once('/')?;
+    Some((done.parse().ok()?, total.parse().ok()?))
+}
+
 /// Check if project needs rebuilding
 pub fn needs_rebuild(config: &Config) -> Result<bool> {
     let project_path = config.project_path()?;
@@ -248,18 +372,7 @@ impl BuildInfo {
     /// Format file size for display
     pub fn format_size(&self) -> String {
         match self.file_size {
-            Some(size) => {
-                const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
-                let mut size = size as f64;
-                let mut unit_index = 0;
-
-                while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-                    size /= 1024.0;
-                    unit_index += 1;
-                }
-
-                format!("{:.1} {}", size, UNITS[unit_index])
-            }
+            Some(size) => utils::fs::format_size(size),
             None => "N/A".to_string(),
         }
     }
@@ -270,10 +383,25 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_parse_progress() {
+        assert_eq!(
+            parse_progress("    Building [=====>                 ] 45/120: serde, tokio"),
+            Some((45, 120))
+        );
+        assert_eq!(
+            parse_progress("    Building [=======================> ] 119/120: app(bin)"),
+            Some((119, 120))
+        );
+        assert_eq!(parse_progress("   Compiling serde v1.0.200"), None);
+        assert_eq!(parse_progress("error[E0425]: cannot find value `x`"), None);
+    }
+
     #[tokio::test]
     async fn test_build_nonexistent_project() {
         let temp_dir = tempdir().unwrap();
         let config = Config {
+            version: crate::config::CONFIG_VERSION,
             project: crate::config::ProjectConfig {
                 path: temp_dir.path().to_string_lossy().to_string(),
                 name: "test".to_string(),
@@ -288,13 +416,22 @@ mod tests {
                 deploy_path: "/tmp".to_string(),
                 service_name: Some("test.service".to_string()),
                 ssh_port: 22,
+                ..Default::default()
             },
             monitor: crate::config::MonitorConfig {
                 health_endpoint: None,
                 log_path: None,
                 interval_secs: 10,
                 health_timeout_secs: 5,
+                ..Default::default()
             },
+            hooks: Default::default(),
+            tui: Default::default(),
+            retry: Default::default(),
+            env: Default::default(),
+            projects: Default::default(),
+            overrides: Default::default(),
+            extends: None,
         };
 
         let result = build_project(&config, None, false).await;
@@ -1,11 +1,23 @@
-use anyhow::{Result, anyhow};
-use ssh2::Session;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 use crate::commands::build;
+use crate::commands::hooks::{self, Stage};
 use crate::config::Config;
 use crate::logging::log;
 use crate::utils;
+use crate::utils::host::Host;
+use crate::utils::ssh::shell_quote;
+
+pub mod artifact;
+pub mod git_ref;
+pub mod preflight;
+pub mod templates;
+
+/// Name of the manifest file written next to the deployed binary
+pub const MANIFEST_FILE: &str = "rzen-manifest.toml";
 
 /// Deploy the project to a remote server
 pub async fn deploy_project(
@@ -18,13 +30,16 @@ pub async fn deploy_project(
 }
 
 /// Deploy the project to a remote server with progress callback
+///
+/// The callback gets the overall percentage, the current step, and a line for
+/// the deploy log when the step starts (upload progress updates have none).
 #[allow(clippy::type_complexity)]
 pub async fn deploy_project_with_progress(
     config: &Config,
     skip_build: bool,
     _force: bool,
     dry_run: bool,
-    progress_callback: Option<&(dyn Fn(f64, &str) + Send + Sync)>,
+    progress_callback: Option<&(dyn Fn(f64, &str, Option<&str>) + Send + Sync)>,
 ) -> Result<String> {
     let binary_name = config.binary_name();
 
@@ -57,8 +72,86 @@ pub async fn deploy_project_with_progress(
         ));
     }
 
+    let origin = DeployOrigin::new("local build");
+    deploy_binary(config, &binary_path, &origin, progress_callback).await
+}
+
+/// Where a deployed binary came from
+#[derive(Debug, Clone, Default)]
+pub struct DeployOrigin {
+    pub source: String,
+    pub git_ref: Option<String>,
+    pub commit: Option<String>,
+}
+
+impl DeployOrigin {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Deploy an already available binary, recording its origin in the remote manifest
+///
+/// With several `deploy.hosts` the binary goes to each of them, `max_parallel_hosts` at a
+/// time, and the deploy fails if it fails on any.
+#[allow(clippy::type_complexity)]
+pub async fn deploy_binary(
+    config: &Config,
+    binary_path: &Path,
+    origin: &DeployOrigin,
+    progress_callback: Option<&(dyn Fn(f64, &str, Option<&str>) + Send + Sync)>,
+) -> Result<String> {
+    let hosts = config.hosts();
+    if hosts.len() == 1 {
+        return deploy_to_host(config, binary_path, origin, progress_callback).await;
+    }
+
+    log::operation_start(&format!("Deploying to {} hosts", hosts.len()));
+    // Hosts deploy on threads of their own, so their progress is passed back to the caller's
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let reporting = progress_callback.is_some();
+    let deploys = hosts.into_iter().map(move |host| {
+        let host_config = config.for_host(&host);
+        let (binary_path, origin) = (binary_path.to_path_buf(), origin.clone());
+        let sender = sender.clone();
+        let deploy = async move {
+            let forward = move |percent: f64, step: &str, line: Option<&str>| {
+                let _ = sender.send((percent, step.to_string(), line.map(str::to_string)));
+            };
+            let callback: Option<&(dyn Fn(f64, &str, Option<&str>) + Send + Sync)> =
+                if reporting { Some(&forward) } else { None };
+            deploy_to_host(&host_config, &binary_path, &origin, callback).await
+        };
+        (host, deploy)
+    });
+    let report = async {
+        while let Some((percent, step, line)) = receiver.recv().await {
+            if let Some(callback) = progress_callback {
+                callback(percent, &step, line.as_deref());
+            }
+        }
+    };
+    let (deployed, ()) = tokio::join!(
+        utils::ssh::on_all(deploys, config.deploy.ssh.max_parallel_hosts),
+        report
+    );
+    let deployed = deployed.into_result()?;
+    Ok(format!("Deployed to {} hosts", deployed.len()))
+}
+
+/// Deploy to `config.deploy.vps_host` alone
+#[allow(clippy::type_complexity)]
+async fn deploy_to_host(
+    config: &Config,
+    binary_path: &Path,
+    origin: &DeployOrigin,
+    progress_callback: Option<&(dyn Fn(f64, &str, Option<&str>) + Send + Sync)>,
+) -> Result<String> {
     let (result, duration) = utils::timing::measure(|| async {
-        execute_deployment(config, &binary_path, progress_callback).await
+        execute_deployment(config, binary_path, origin, progress_callback).await
     })
     .await;
 
@@ -72,6 +165,7 @@ pub async fn deploy_project_with_progress(
         }
         Err(e) => {
             log::operation_failed("Deployment", &e.to_string());
+            hooks::run_on_failure(config, &e).await;
             Err(e)
         }
     }
@@ -82,82 +176,113 @@ pub async fn deploy_project_with_progress(
 async fn execute_deployment(
     config: &Config,
     binary_path: &Path,
-    progress_callback: Option<&(dyn Fn(f64, &str) + Send + Sync)>,
+    origin: &DeployOrigin,
+    progress_callback: Option<&(dyn Fn(f64, &str, Option<&str>) + Send + Sync)>,
 ) -> Result<String> {
+    // Broken templates stop the deploy before the server is touched
+    let templates = templates::render_all(config)?;
     let progress = utils::progress::deploy_progress(6);
 
     let message = "Connecting to server...";
     progress.set_message(message);
     if let Some(callback) = progress_callback {
-        callback(16.67, message);
+        callback(16.67, message, Some(message));
     }
 
-    let ssh_config = utils::ssh::SshConfig {
-        host: config.deploy.vps_host.clone(),
-        port: config.deploy.ssh_port,
-        username: config.deploy.vps_user.clone(),
-        key_path: config.deploy.vps_key_path.clone(),
-        password: config.deploy.vps_password.clone(),
-    };
-
-    let session = utils::ssh::connect_with_retry(&ssh_config, 3).await?;
+    let connection = utils::host::connect(config).await?;
+    let host = connection.as_ref();
+    let binary_size = std::fs::metadata(binary_path)?.len();
+    crate::commands::monitor::disk::ensure_deploy_space(host, config, binary_size)?;
+    hooks::run(config, Stage::PreDeploy, Some(host), false).await?;
     progress.inc(1);
 
     let message = "Creating remote directory...";
     progress.set_message(message);
     if let Some(callback) = progress_callback {
-        callback(33.33, message);
+        callback(33.33, message, Some(message));
     }
-    utils::ssh::create_remote_directory(&session, &config.deploy.deploy_path)?;
+    utils::ssh::create_remote_directory(host, &config.deploy.deploy_path)?;
     progress.inc(1);
 
     let message = "Uploading binary...";
     progress.set_message(message);
     if let Some(callback) = progress_callback {
-        callback(50.0, message);
+        callback(50.0, message, Some(message));
     }
     let remote_binary_path = format!("{}/{}", config.deploy.deploy_path, config.binary_name());
     let backup_binary_path = format!(
-        "{}/{}.backup",
+        "{}/{}.backup.{}",
         config.deploy.deploy_path,
-        config.binary_name()
+        config.binary_name(),
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
     );
 
     // Create backup of existing binary if it exists
-    let binary_exists = utils::ssh::remote_file_exists(&session, &remote_binary_path)?;
+    let binary_exists = utils::ssh::remote_file_exists(host, &remote_binary_path)?;
     if binary_exists {
         log::deploy_step("Creating backup of existing binary");
-        utils::ssh::execute_command(
-            &session,
-            &format!("cp {} {}", remote_binary_path, backup_binary_path),
-        )?;
+        host.execute_command(&format!(
+            "cp {} {}",
+            shell_quote(&remote_binary_path),
+            shell_quote(&backup_binary_path)
+        ))?;
+        if config.deploy.keep_backups > 0 {
+            prune_backups(host, config, config.deploy.keep_backups, false)?;
+        }
     }
 
-    utils::ssh::upload_file(&session, binary_path, &remote_binary_path)?;
+    // Byte-level updates move the bar through the upload stage without adding log lines
+    let on_upload = |transfer: &utils::progress::Transfer| {
+        let summary = format!("Uploading binary: {}", transfer.summary());
+        progress.set_message(summary.clone());
+        if let Some(callback) = progress_callback {
+            callback(50.0 + transfer.fraction() * 16.67, &summary, None);
+        }
+    };
+    let connection = upload_binary(
+        connection,
+        config,
+        binary_path,
+        &remote_binary_path,
+        &on_upload,
+    )
+    .await?;
+    let host = connection.as_ref();
+    let manifest = DeployManifest {
+        binary: config.binary_name(),
+        sha256: utils::fs::sha256_file(binary_path)?,
+        source: origin.source.clone(),
+        git_ref: origin.git_ref.clone(),
+        commit: origin.commit.clone(),
+        deployed_at: chrono::Utc::now(),
+    };
+    write_manifest(host, config, &manifest)?;
+    templates::upload(host, &templates)?;
     progress.inc(1);
 
     let message = "Setting executable permissions...";
     progress.set_message(message);
     if let Some(callback) = progress_callback {
-        callback(66.67, message);
+        callback(66.67, message, Some(message));
     }
-    utils::ssh::execute_command(&session, &format!("chmod +x {}", remote_binary_path))?;
+    host.execute_command(&format!("chmod +x {}", shell_quote(&remote_binary_path)))?;
     progress.inc(1);
 
     let message = "Creating systemd service...";
     progress.set_message(message);
     if let Some(callback) = progress_callback {
-        callback(83.33, message);
+        callback(83.33, message, Some(message));
     }
-    create_systemd_service(&session, config)?;
+    create_systemd_service(host, config)?;
     progress.inc(1);
 
     let message = "Starting service...";
     progress.set_message(message);
     if let Some(callback) = progress_callback {
-        callback(100.0, message);
+        callback(100.0, message, Some(message));
     }
-    start_service(&session, &config.service_name())?;
+    start_service(host, config)?;
+    hooks::run(config, Stage::PostDeploy, Some(host), false).await?;
     progress.inc(1);
 
     progress.finish_with_message("Deployment completed successfully!");
@@ -168,26 +293,72 @@ async fn execute_deployment(
     ))
 }
 
+/// Upload the binary, reconnecting and starting over when a retryable failure stops it
+///
+/// Returns the connection the upload finished on, for the rest of the deploy.
+async fn upload_binary(
+    host: Box<dyn Host>,
+    config: &Config,
+    binary_path: &Path,
+    remote_path: &str,
+    on_progress: &(dyn Fn(&utils::progress::Transfer) + Sync),
+) -> Result<Box<dyn Host>> {
+    let host = std::sync::Mutex::new(host);
+    let policy = config.retry_policy();
+    utils::retry::with_backoff(&policy, "Upload", |attempt| {
+        let host = &host;
+        async move {
+            if attempt > 1 {
+                let connection = utils::host::connect(config).await?;
+                *host.lock().unwrap() = connection;
+            }
+            host.lock()
+                .unwrap()
+                .upload_file(binary_path, remote_path, Some(on_progress))
+        }
+    })
+    .await?;
+    Ok(host.into_inner().unwrap())
+}
+
+/// Record of what was deployed, stored next to the binary on the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployManifest {
+    pub binary: String,
+    pub sha256: String,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub deployed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Write the deploy manifest to the remote deploy path
+fn write_manifest(host: &dyn Host, config: &Config, manifest: &DeployManifest) -> Result<()> {
+    let contents = toml::to_string_pretty(manifest)?;
+    let manifest_path = format!("{}/{}", config.deploy.deploy_path, MANIFEST_FILE);
+    host.write_file(&manifest_path, contents.as_bytes(), 0o644)?;
+
+    log::deploy_step(&format!("Recorded deploy manifest ({})", manifest.source));
+    Ok(())
+}
+
 /// Create systemd service file
-fn create_systemd_service(session: &Session, config: &Config) -> Result<()> {
+fn create_systemd_service(host: &dyn Host, config: &Config) -> Result<()> {
     let service_name = config.service_name();
     let service_content = generate_systemd_service(config);
 
     let temp_service_path = format!("/tmp/{}", service_name);
-    utils::ssh::execute_command(
-        session,
-        &format!(
-            "cat > {} << 'EOF'\n{}\nEOF",
-            temp_service_path, service_content
-        ),
-    )?;
+    host.write_file(&temp_service_path, service_content.as_bytes(), 0o644)?;
 
-    utils::ssh::execute_command(
-        session,
-        &format!("sudo mv {} /etc/systemd/system/", temp_service_path),
-    )?;
+    host.execute_command(&format!(
+        "sudo mv {} {}",
+        shell_quote(&temp_service_path),
+        shell_quote(&format!("/etc/systemd/system/{}", service_name))
+    ))?;
 
-    utils::ssh::execute_command(session, "sudo systemctl daemon-reload")?;
+    host.execute_command("sudo systemctl daemon-reload")?;
 
     log::deploy_step(&format!("Created systemd service: {}", service_name));
     Ok(())
@@ -210,6 +381,8 @@ WorkingDirectory={2}
 ExecStart={3}
 Restart=always
 RestartSec=5
+KillSignal=SIGTERM
+TimeoutStopSec={4}
 StandardOutput=journal
 StandardError=journal
 SyslogIdentifier={0}
@@ -227,20 +400,46 @@ WantedBy=multi-user.target
         config.binary_name(),
         config.deploy.vps_user,
         working_directory,
-        binary_path
+        binary_path,
+        config.deploy.shutdown_grace_secs
     )
 }
 
-/// Start systemd service
-fn start_service(session: &Session, service_name: &str) -> Result<()> {
-    let _ = utils::ssh::execute_command(session, &format!("sudo systemctl stop {}", service_name));
+/// Start or restart the systemd service using the configured restart strategy
+fn start_service(host: &dyn Host, config: &Config) -> Result<()> {
+    let service_name = config.service_name();
+    let strategy = config.deploy.restart_strategy.as_str();
+    let grace = Duration::from_secs(config.deploy.shutdown_grace_secs);
 
-    utils::ssh::execute_command(session, &format!("sudo systemctl enable {}", service_name))?;
-    utils::ssh::execute_command(session, &format!("sudo systemctl start {}", service_name))?;
+    host.execute_command(&format!("sudo systemctl enable {}", shell_quote(&service_name)))?;
+
+    let old_pid = main_pid(host, &service_name);
+    if old_pid.is_none() {
+        host.execute_command(&format!("sudo systemctl start {}", shell_quote(&service_name)))?;
+    } else {
+        log::deploy_step(&format!(
+            "Applying '{}' restart strategy to {}",
+            strategy, service_name
+        ));
+        let (stop, start) = restart_commands(strategy, &service_name);
+        for command in stop {
+            host.execute_command(&command)?;
+        }
 
-    let (output, _) = utils::ssh::execute_command(
-        session,
-        &format!("sudo systemctl is-active {}", service_name),
+        // A reload keeps the same process; every other strategy must replace it, and
+        // stop-start only starts the new one once the old one is gone
+        if strategy != "reload"
+            && let Some(pid) = old_pid
+        {
+            wait_for_exit(host, pid, grace)?;
+        }
+        for command in start {
+            host.execute_command(&command)?;
+        }
+    }
+
+    let (output, _) = host.execute_command(
+        &format!("sudo systemctl is-active {}", shell_quote(&service_name)),
     )?;
     if output.trim() != "active" {
         return Err(anyhow!("Service {} failed to start", service_name));
@@ -250,17 +449,81 @@ fn start_service(session: &Session, service_name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Commands used to apply a restart strategy to a running service: the ones run before the old
+/// process has exited, and the ones run after
+fn restart_commands(strategy: &str, service_name: &str) -> (Vec<String>, Vec<String>) {
+    let service_name = shell_quote(service_name);
+    match strategy {
+        "reload" => (vec![format!("sudo systemctl reload-or-restart {}", service_name)], Vec::new()),
+        "stop-start" => (
+            vec![format!("sudo systemctl stop {}", service_name)],
+            vec![format!("sudo systemctl start {}", service_name)],
+        ),
+        _ => (vec![format!("sudo systemctl restart {}", service_name)], Vec::new()),
+    }
+}
+
+/// Get the main PID of a running service, if any
+fn main_pid(host: &dyn Host, service_name: &str) -> Option<u32> {
+    let (output, _) = host.execute_command(
+        &format!("systemctl show -p MainPID --value {}", shell_quote(service_name)),
+    )
+    .ok()?;
+    output.trim().parse().ok().filter(|pid| *pid != 0)
+}
+
+/// Wait until a process has exited, failing once the grace period (plus slack) has passed
+fn wait_for_exit(host: &dyn Host, pid: u32, grace: Duration) -> Result<()> {
+    let deadline = Instant::now() + grace + Duration::from_secs(5);
+    loop {
+        let (output, _) = host.execute_command(
+            &format!("[ -d /proc/{} ] && echo running || echo exited", pid),
+        )?;
+        if output.trim() == "exited" {
+            log::deploy_step(&format!("Previous process {} exited", pid));
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow!(
+                "Previous process {} still running {}s after shutdown was requested",
+                pid,
+                grace.as_secs() + 5
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
 /// Simulate deployment for dry run
 async fn simulate_deployment(config: &Config) -> Result<String> {
-    log::dry_run("SSH connection to server");
+    if !config.deploy.is_local() {
+        log::dry_run("SSH connection to server");
+    }
+    hooks::run(config, Stage::PreDeploy, None, true).await?;
     log::dry_run(&format!("Create directory: {}", config.deploy.deploy_path));
+    match config.deploy.keep_backups {
+        0 => log::dry_run("Back up the current binary"),
+        keep => log::dry_run(&format!(
+            "Back up the current binary, keeping the {} newest backups",
+            keep
+        )),
+    }
     log::dry_run(&format!("Upload binary: {}", config.binary_name()));
+    for template in templates::render_all(config)? {
+        log::dry_run(&format!(
+            "Upload {} to {} ({} bytes)",
+            template.source,
+            template.destination,
+            template.contents.len()
+        ));
+    }
     log::dry_run("Set executable permissions");
     log::dry_run(&format!(
         "Create systemd service: {}",
         config.service_name()
     ));
     log::dry_run(&format!("Start systemd service: {}", config.service_name()));
+    hooks::run(config, Stage::PostDeploy, None, true).await?;
 
     Ok(format!(
         "DRY RUN: Would deploy {} to {}",
@@ -269,21 +532,26 @@ async fn simulate_deployment(config: &Config) -> Result<String> {
     ))
 }
 
-/// Check deployment status
-pub async fn check_deployment_status(config: &Config) -> Result<DeploymentStatus> {
-    // Create SSH connection
-    let ssh_config = utils::ssh::SshConfig {
-        host: config.deploy.vps_host.clone(),
-        port: config.deploy.ssh_port,
-        username: config.deploy.vps_user.clone(),
-        key_path: config.deploy.vps_key_path.clone(),
-        password: config.deploy.vps_password.clone(),
-    };
+/// Check deployment status on every configured host, `max_parallel_hosts` at a time
+pub async fn check_deployment_status(config: &Config) -> Result<Vec<DeploymentStatus>> {
+    let checks = config.hosts().into_iter().map(|host| {
+        let host_config = config.for_host(&host);
+        (host, async move { check_host_status(&host_config).await })
+    });
+
+    let statuses = utils::ssh::on_all(checks, config.deploy.ssh.max_parallel_hosts)
+        .await
+        .into_result()?;
+    Ok(statuses.into_iter().map(|(_, status)| status).collect())
+}
 
-    let session = match utils::ssh::connect_with_retry(&ssh_config, 3).await {
-        Ok(sess) => sess,
+/// Check deployment status on `config.deploy.vps_host`
+pub async fn check_host_status(config: &Config) -> Result<DeploymentStatus> {
+    let host = match utils::host::connect(config).await {
+        Ok(host) => host,
         Err(_) => {
             return Ok(DeploymentStatus {
+                host: config.deploy.vps_host.clone(),
                 service_active: false,
                 last_deployment: None,
                 version: None,
@@ -294,9 +562,8 @@ pub async fn check_deployment_status(config: &Config) -> Result<DeploymentStatus
     let service_name = config.service_name();
 
     // Check service status
-    let service_active = match utils::ssh::execute_command(
-        &session,
-        &format!("sudo systemctl is-active {}", service_name),
+    let service_active = match host.execute_command(
+        &format!("sudo systemctl is-active {}", shell_quote(&service_name)),
     ) {
         Ok((output, _)) => output.trim() == "active",
         Err(_) => false,
@@ -305,12 +572,12 @@ pub async fn check_deployment_status(config: &Config) -> Result<DeploymentStatus
     // Get service file modification time as last deployment time
     let service_file = format!("/etc/systemd/system/{}", service_name);
     let last_deployment =
-        match utils::ssh::execute_command(&session, &format!("stat -c %Y {}", service_file)) {
+        match host.execute_command(&format!("stat -c %Y {}", shell_quote(&service_file))) {
             Ok((output, _)) => {
                 if let Ok(timestamp) = output.trim().parse::<i64>() {
                     Some(
                         chrono::DateTime::from_timestamp(timestamp, 0)
-                            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                            .map(|dt| config.display_timezone().format(dt))
                             .unwrap_or_else(|| "Unknown".to_string()),
                     )
                 } else {
@@ -325,7 +592,7 @@ pub async fn check_deployment_status(config: &Config) -> Result<DeploymentStatus
     let binary_name = config.binary_name();
     let binary_path = format!("{}/{}", deploy_path, binary_name);
 
-    let version = match utils::ssh::execute_command(&session, &format!("ls -lh {}", binary_path)) {
+    let version = match host.execute_command(&format!("ls -lh {}", shell_quote(&binary_path))) {
         Ok((output, _)) => {
             let parts: Vec<&str> = output.split_whitespace().collect();
             if parts.len() >= 5 {
@@ -338,82 +605,253 @@ pub async fn check_deployment_status(config: &Config) -> Result<DeploymentStatus
     };
 
     Ok(DeploymentStatus {
+        host: config.deploy.vps_host.clone(),
         service_active,
         last_deployment,
         version,
     })
 }
 
+/// Outcome of a successful `rzen deploy`, printed with `--output json`
+#[derive(Debug, Clone, Serialize)]
+pub struct DeploySummary {
+    pub project: String,
+    pub binary: String,
+    pub hosts: Vec<String>,
+    /// Where the binary came from: `local build`, an artifact, or a git ref
+    pub source: String,
+    pub dry_run: bool,
+    pub duration_secs: f64,
+    pub message: String,
+}
+
 /// Deployment status information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DeploymentStatus {
+    pub host: String,
     pub service_active: bool,
     pub last_deployment: Option<String>,
     pub version: Option<String>,
 }
 
-/// Rollback deployment to previous version
-pub async fn rollback_deployment(config: &Config) -> Result<()> {
+/// Previous binary kept on the server for rollback
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub path: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub size: u64,
+}
+
+impl Backup {
+    /// File name without the deploy path
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// One-line summary, e.g. `app.backup.20240601120000 (2024-06-01 12:00 UTC, 4.2 MB)`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} ({}, {})",
+            self.name(),
+            self.created_at.format("%Y-%m-%d %H:%M UTC"),
+            utils::fs::format_size(self.size)
+        )
+    }
+}
+
+/// List the backups on the server, newest first
+pub async fn list_backups(config: &Config) -> Result<Vec<Backup>> {
+    let host = utils::host::connect(config).await?;
+    remote_backups(host.as_ref(), config)
+}
+
+fn remote_backups(host: &dyn Host, config: &Config) -> Result<Vec<Backup>> {
+    // `true` keeps a deploy path without backups from failing the command
+    let (output, _) = host.execute_command(
+        &format!(
+            "stat -c '%Y %s %n' {}* 2>/dev/null; true",
+            shell_quote(&format!("{}/{}.backup", config.deploy.deploy_path, config.binary_name()))
+        ),
+    )?;
+    Ok(parse_backups(&output))
+}
+
+/// Parse `stat -c '%Y %s %n'` lines into backups, newest first
+fn parse_backups(output: &str) -> Vec<Backup> {
+    let mut backups: Vec<Backup> = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim().splitn(3, ' ');
+            let modified = parts.next()?.parse().ok()?;
+            let size = parts.next()?.parse().ok()?;
+            Some(Backup {
+                path: parts.next()?.to_string(),
+                created_at: chrono::DateTime::from_timestamp(modified, 0)?,
+                size,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.path.cmp(&a.path)));
+    backups
+}
+
+/// Download the config files of every host into `dir/<host>/`, at the paths they have there:
+/// the rendered `[[deploy.templates]]`, the systemd unit and the deploy manifest
+///
+/// Files a host doesn't have yet, like templates added since its last deploy, are skipped.
+/// Returns the number of files saved.
+pub async fn backup_config_files(config: &Config, dir: &Path) -> Result<usize> {
+    let dir = dir.to_path_buf();
+    let saved = utils::ssh::run_on_all(config, move |host, host_config| {
+        backup_host_config(host, host_config, &dir.join(&host_config.deploy.vps_host))
+    })
+    .await
+    .into_result()?;
+    Ok(saved.iter().map(|(_, count)| count).sum())
+}
+
+fn backup_host_config(host: &dyn Host, config: &Config, dir: &Path) -> Result<usize> {
+    let mut paths = templates::destinations(config);
+    paths.push(format!("/etc/systemd/system/{}", config.service_name()));
+    paths.push(format!("{}/{}", config.deploy.deploy_path, MANIFEST_FILE));
+
+    let mut saved = 0;
+    for path in paths {
+        if !utils::ssh::remote_file_exists(host, &path)? {
+            log::deploy_step(&format!("Skipping {}, not on {}", path, config.deploy.vps_host));
+            continue;
+        }
+        let local = dir.join(path.trim_start_matches('/'));
+        if let Some(parent) = local.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        host.download_file(&path, &local, None)?;
+        saved += 1;
+    }
+    Ok(saved)
+}
+
+/// Remove all but the newest `keep` backups on every host, `max_parallel_hosts` at a time
+///
+/// Returns the backups each host had removed; in a dry run they are only listed.
+pub async fn prune_releases(
+    config: &Config,
+    keep: usize,
+    dry_run: bool,
+) -> Result<Vec<(String, Vec<Backup>)>> {
+    utils::ssh::run_on_all(config, move |host, host_config| {
+        prune_backups(host, host_config, keep, dry_run)
+    })
+    .await
+    .into_result()
+}
+
+/// Remove all but the newest `keep` backups on `host`, returning the ones removed
+fn prune_backups(
+    host: &dyn Host,
+    config: &Config,
+    keep: usize,
+    dry_run: bool,
+) -> Result<Vec<Backup>> {
+    let old: Vec<Backup> = remote_backups(host, config)?
+        .into_iter()
+        .skip(keep)
+        .collect();
+    for backup in &old {
+        if dry_run {
+            log::dry_run(&format!("Remove old backup {}", backup.summary()));
+        } else {
+            log::deploy_step(&format!("Removing old backup {}", backup.name()));
+            host.execute_command(&format!("rm -f {}", shell_quote(&backup.path)))?;
+        }
+    }
+    Ok(old)
+}
+
+/// Rollback deployment to a backup, by default the most recent one
+pub async fn rollback_deployment(config: &Config, backup: Option<&str>) -> Result<()> {
     let service_name = config.service_name();
 
     log::operation_start("Rolling back deployment");
 
-    // Create SSH connection
-    let ssh_config = utils::ssh::SshConfig {
-        host: config.deploy.vps_host.clone(),
-        port: config.deploy.ssh_port,
-        username: config.deploy.vps_user.clone(),
-        key_path: config.deploy.vps_key_path.clone(),
-        password: config.deploy.vps_password.clone(),
-    };
-
-    let session = utils::ssh::connect_with_retry(&ssh_config, 3).await?;
+    let host = utils::host::connect(config).await?;
+    let host = host.as_ref();
 
     // Stop current service
     log::deploy_step("Stopping current service");
-    let _ = utils::ssh::execute_command(&session, &format!("sudo systemctl stop {}", service_name));
+    let _ = host.execute_command(&format!("sudo systemctl stop {}", shell_quote(&service_name)));
 
     // Check if backup exists
     let deploy_path = &config.deploy.deploy_path;
     let binary_name = config.binary_name();
     let current_binary = format!("{}/{}", deploy_path, binary_name);
-    let backup_binary = format!("{}/{}.backup", deploy_path, binary_name);
 
-    let backup_exists = utils::ssh::remote_file_exists(&session, &backup_binary)?;
-
-    if !backup_exists {
-        return Err(anyhow!(
-            "No backup found for rollback. Backup file: {}",
-            backup_binary
-        ));
-    }
+    let backups = remote_backups(host, config)?;
+    let backup_binary = match backup {
+        Some(name) => backups
+            .iter()
+            .find(|b| b.path == name || b.name() == name)
+            .ok_or_else(|| anyhow!("Backup not found in {}: {}", deploy_path, name))?,
+        None => backups
+            .first()
+            .ok_or_else(|| anyhow!("No backup found for rollback in {}", deploy_path))?,
+    };
+    let backup_binary = &backup_binary.path;
 
     // Restore backup
-    log::deploy_step("Restoring backup");
-    utils::ssh::execute_command(
-        &session,
-        &format!("cp {} {}", backup_binary, current_binary),
-    )?;
-    utils::ssh::execute_command(&session, &format!("chmod +x {}", current_binary))?;
+    log::deploy_step(&format!("Restoring backup {}", backup_binary));
+    host.execute_command(&format!(
+        "cp {} {}",
+        shell_quote(backup_binary),
+        shell_quote(&current_binary)
+    ))?;
+    host.execute_command(&format!("chmod +x {}", shell_quote(&current_binary)))?;
+
+    // Record the restored binary so drift detection does not flag the rollback
+    let (checksum, _) = host.execute_command(&format!("sha256sum {}", shell_quote(&current_binary)))?;
+    if let Some(sha256) = checksum.split_whitespace().next() {
+        let manifest = DeployManifest {
+            binary: binary_name.clone(),
+            sha256: sha256.to_string(),
+            source: "rollback".to_string(),
+            git_ref: None,
+            commit: None,
+            deployed_at: chrono::Utc::now(),
+        };
+        write_manifest(host, config, &manifest)?;
+    }
 
     // Restart service
     log::deploy_step("Restarting service");
-    utils::ssh::execute_command(&session, &format!("sudo systemctl start {}", service_name))?;
+    host.execute_command(&format!("sudo systemctl start {}", shell_quote(&service_name)))?;
 
     // Verify service is running
-    let (output, _) = utils::ssh::execute_command(
-        &session,
-        &format!("sudo systemctl is-active {}", service_name),
+    let (output, _) = host.execute_command(
+        &format!("sudo systemctl is-active {}", shell_quote(&service_name)),
     )?;
 
     if output.trim() != "active" {
         return Err(anyhow!("Service failed to start after rollback"));
     }
+    hooks::run(config, Stage::OnRollback, Some(host), false).await?;
 
     log::operation_success("Rollback completed successfully");
     Ok(())
 }
 
+/// Restart the service on the target host using the configured restart strategy
+pub async fn restart_service(config: &Config) -> Result<()> {
+    log::operation_start("Restarting service");
+
+    let host = utils::host::connect(config).await?;
+
+    start_service(host.as_ref(), config)?;
+
+    log::operation_success("Service restarted");
+    Ok(())
+}
+
 /// Validate deployment prerequisites
 pub fn validate_deployment_prerequisites(config: &Config) -> Result<()> {
     let project_path = config.project_path()?;
@@ -435,7 +873,7 @@ pub fn validate_deployment_prerequisites(config: &Config) -> Result<()> {
         return Err(anyhow!("Binary file is empty: {}", binary_path.display()));
     }
 
-    if config.deploy.vps_key_path.is_none() && config.deploy.vps_password.is_none() {
+    if config.deploy.vps_key_path.is_none() && config.ssh_password().is_none() {
         return Err(anyhow!(
             "SSH authentication not configured. Provide either key_path or password."
         ));
@@ -447,11 +885,180 @@ pub fn validate_deployment_prerequisites(config: &Config) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::host::mock::MockHost;
     use tempfile::tempdir;
 
+    /// Config deploying to the mock registered as `host`
+    fn mock_config(host: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "{}"
+vps_user = "deploy"
+deploy_path = "/opt/app"
+
+[monitor]
+"#,
+            host
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_deploy_to_mock_host() {
+        let host = MockHost::register("deploy.mock.invalid");
+        let config = mock_config("deploy.mock.invalid");
+        host.reply("df -Pk", "deploy_path /dev/sda1 10485760 1048576 9437184 10% /")
+            .reply("[ -f '/opt/app/app' ]", "exists")
+            .reply("systemctl show -p MainPID", "1234")
+            .reply("[ -d /proc/1234 ]", "exited")
+            .reply("systemctl is-active", "active");
+        let dir = tempdir().unwrap();
+        let binary = dir.path().join("app");
+        std::fs::write(&binary, b"new binary").unwrap();
+
+        let origin = DeployOrigin::new("local build");
+        execute_deployment(&config, &binary, &origin, None)
+            .await
+            .unwrap();
+
+        assert_eq!(host.file("/opt/app/app").unwrap().0, b"new binary");
+        let (manifest, _) = host.file("/opt/app/rzen-manifest.toml").unwrap();
+        let manifest: DeployManifest = toml::from_str(std::str::from_utf8(&manifest).unwrap()).unwrap();
+        assert_eq!(manifest.sha256, utils::fs::sha256_file(&binary).unwrap());
+        assert_eq!(manifest.source, "local build");
+        let (unit, mode) = host.file("/tmp/app.service").unwrap();
+        assert!(String::from_utf8(unit).unwrap().contains("ExecStart=/opt/app/app"));
+        assert_eq!(mode, 0o644);
+
+        let commands = host.commands();
+        let position = |pattern: &str| {
+            commands
+                .iter()
+                .position(|command| command.contains(pattern))
+                .unwrap_or_else(|| panic!("not run: {}\n{:#?}", pattern, commands))
+        };
+        // The running binary is backed up before the upload replaces it
+        assert!(position("cp '/opt/app/app' '/opt/app/app.backup.") < position("chmod +x"));
+        assert!(position("sudo mv '/tmp/app.service'") < position("daemon-reload"));
+        // The default stop-start strategy only starts the new process once the old one is gone
+        assert!(position("daemon-reload") < position("sudo systemctl stop 'app.service'"));
+        assert!(position("sudo systemctl stop 'app.service'") < position("[ -d /proc/1234 ]"));
+        assert!(position("[ -d /proc/1234 ]") < position("sudo systemctl start 'app.service'"));
+
+        // A service that doesn't come up fails the deploy
+        host.reply("systemctl is-active", "failed");
+        let error = execute_deployment(&config, &binary, &origin, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Service app.service failed to start");
+    }
+
+    #[tokio::test]
+    async fn test_backup_config_files() {
+        let host = MockHost::register("backup.mock.invalid");
+        let config = mock_config("backup.mock.invalid");
+        host.add_file("/opt/app/rzen-manifest.toml", b"binary = \"app\"\n")
+            .reply("[ -f '/opt/app/rzen-manifest.toml' ]", "exists");
+
+        let dir = tempdir().unwrap();
+        assert_eq!(backup_config_files(&config, dir.path()).await.unwrap(), 1);
+        // The unit isn't on the server, so only the manifest is saved
+        let saved = dir.path().join("backup.mock.invalid/opt/app/rzen-manifest.toml");
+        assert_eq!(std::fs::read(saved).unwrap(), b"binary = \"app\"\n");
+        assert!(!dir.path().join("backup.mock.invalid/etc").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_on_mock_host() {
+        let host = MockHost::register("rollback.mock.invalid");
+        let config = mock_config("rollback.mock.invalid");
+        host.reply(
+            "stat -c '%Y %s %n'",
+            "1717243200 4404019 /opt/app/app.backup.20240601120000\n\
+             1717329600 4410000 /opt/app/app.backup.20240602120000\n",
+        )
+        .reply("sha256sum", "abc123  /opt/app/app\n")
+        .reply("systemctl is-active", "active");
+
+        rollback_deployment(&config, None).await.unwrap();
+        assert!(host.ran("cp '/opt/app/app.backup.20240602120000' '/opt/app/app'"));
+        assert!(host.ran("sudo systemctl start 'app.service'"));
+        let (manifest, _) = host.file("/opt/app/rzen-manifest.toml").unwrap();
+        let manifest: DeployManifest = toml::from_str(std::str::from_utf8(&manifest).unwrap()).unwrap();
+        assert_eq!((manifest.source.as_str(), manifest.sha256.as_str()), ("rollback", "abc123"));
+
+        rollback_deployment(&config, Some("app.backup.20240601120000"))
+            .await
+            .unwrap();
+        assert!(host.ran("cp '/opt/app/app.backup.20240601120000' '/opt/app/app'"));
+
+        let error = rollback_deployment(&config, Some("app.backup.missing"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Backup not found in /opt/app: app.backup.missing"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prune_releases() {
+        let host = MockHost::register("prune.mock.invalid");
+        let config = mock_config("prune.mock.invalid");
+        host.reply(
+            "stat -c '%Y %s %n'",
+            "1717243200 4404019 /opt/app/app.backup.20240601120000\n\
+             1717329600 4410000 /opt/app/app.backup.20240602120000\n\
+             1717416000 4410000 /opt/app/app.backup.20240603120000\n",
+        );
+
+        let pruned = prune_releases(&config, 1, true).await.unwrap();
+        assert_eq!(pruned.len(), 1);
+        let (name, backups) = &pruned[0];
+        assert_eq!(name, "prune.mock.invalid");
+        assert_eq!(
+            backups.iter().map(Backup::name).collect::<Vec<_>>(),
+            ["app.backup.20240602120000", "app.backup.20240601120000"]
+        );
+        assert!(!host.ran("rm -f"));
+
+        prune_releases(&config, 2, false).await.unwrap();
+        assert!(host.ran("rm -f '/opt/app/app.backup.20240601120000'"));
+        assert!(!host.ran("rm -f '/opt/app/app.backup.20240602120000'"));
+    }
+
+    #[tokio::test]
+    async fn test_check_deployment_status_overlaps_hosts() {
+        let latency = Duration::from_millis(50);
+        let hosts = ["status1.mock.invalid", "status2.mock.invalid"];
+        let mocks: Vec<_> = hosts.iter().map(|host| MockHost::register(host)).collect();
+        for mock in &mocks {
+            mock.latency(latency).reply("systemctl is-active", "active");
+        }
+        let mut config = mock_config(hosts[0]);
+        config.deploy.hosts = hosts.iter().map(|host| host.to_string()).collect();
+
+        let start = Instant::now();
+        let statuses = check_deployment_status(&config).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[1].host, "status2.mock.invalid");
+        assert!(statuses.iter().all(|status| status.service_active));
+        // A slow host doesn't hold up the other
+        let commands = mocks.iter().map(|mock| mock.commands().len()).sum::<usize>();
+        let serial = latency * commands as u32;
+        assert!(elapsed < serial * 3 / 4, "took {:?} of {:?}", elapsed, serial);
+    }
+
     #[test]
     fn test_systemd_service_generation() {
         let config = Config {
+            version: crate::config::CONFIG_VERSION,
             project: crate::config::ProjectConfig {
                 path: ".".to_string(),
                 name: "test-app".to_string(),
@@ -466,13 +1073,22 @@ mod tests {
                 deploy_path: "/opt/test-app".to_string(),
                 service_name: Some("test-app.service".to_string()),
                 ssh_port: 22,
+                ..Default::default()
             },
             monitor: crate::config::MonitorConfig {
                 health_endpoint: Some("http://example.com/health".to_string()),
                 log_path: Some("/var/log/test-app.log".to_string()),
                 interval_secs: 10,
                 health_timeout_secs: 5,
+                ..Default::default()
             },
+            hooks: Default::default(),
+            tui: Default::default(),
+            retry: Default::default(),
+            env: Default::default(),
+            projects: Default::default(),
+            overrides: Default::default(),
+            extends: None,
         };
 
         let service_content = generate_systemd_service(&config);
@@ -480,11 +1096,54 @@ mod tests {
         assert!(service_content.contains("User=deploy"));
         assert!(service_content.contains("ExecStart=/opt/test-app/test-app"));
         assert!(service_content.contains("WorkingDirectory=/opt/test-app"));
+        assert!(service_content.contains("TimeoutStopSec=10"));
+    }
+
+    #[test]
+    fn test_restart_commands() {
+        assert_eq!(
+            restart_commands("restart", "app.service"),
+            (vec!["sudo systemctl restart 'app.service'".to_string()], vec![])
+        );
+        assert_eq!(
+            restart_commands("reload", "app.service"),
+            (vec!["sudo systemctl reload-or-restart 'app.service'".to_string()], vec![])
+        );
+        // The start waits for the old process to exit
+        assert_eq!(
+            restart_commands("stop-start", "app.service"),
+            (
+                vec!["sudo systemctl stop 'app.service'".to_string()],
+                vec!["sudo systemctl start 'app.service'".to_string()]
+            )
+        );
+        assert_eq!(
+            restart_commands("restart", "app; reboot").0,
+            vec!["sudo systemctl restart 'app; reboot'"]
+        );
+    }
+
+    #[test]
+    fn test_parse_backups() {
+        let output = "1717243200 4404019 /opt/app/app.backup.20240601120000\n\
+                      1717156800 4398000 /opt/app/app.backup\n\
+                      1717329600 4410000 /opt/app/app.backup.20240602120000\n\
+                      stat: cannot stat '/opt/app/app.backup*'\n";
+        let backups = parse_backups(output);
+        assert_eq!(backups.len(), 3);
+        assert_eq!(backups[0].name(), "app.backup.20240602120000");
+        assert_eq!(backups[2].name(), "app.backup");
+        assert_eq!(
+            backups[1].summary(),
+            "app.backup.20240601120000 (2024-06-01 12:00 UTC, 4.2 MB)"
+        );
+        assert!(parse_backups("").is_empty());
     }
 
     #[test]
     fn test_deployment_status_creation() {
         let status = DeploymentStatus {
+            host: "example.com".to_string(),
             service_active: true,
             last_deployment: Some("2024-01-01".to_string()),
             version: Some("1.0.0".to_string()),
@@ -499,6 +1158,7 @@ mod tests {
     fn test_validate_deployment_prerequisites_no_binary() {
         let temp_dir = tempdir().unwrap();
         let config = Config {
+            version: crate::config::CONFIG_VERSION,
             project: crate::config::ProjectConfig {
                 path: temp_dir.path().to_string_lossy().to_string(),
                 name: "nonexistent".to_string(),
@@ -513,13 +1173,22 @@ mod tests {
                 deploy_path: "/opt/app".to_string(),
                 service_name: Some("app.service".to_string()),
                 ssh_port: 22,
+                ..Default::default()
             },
             monitor: crate::config::MonitorConfig {
                 health_endpoint: None,
                 log_path: None,
                 interval_secs: 10,
                 health_timeout_secs: 5,
+                ..Default::default()
             },
+            hooks: Default::default(),
+            tui: Default::default(),
+            retry: Default::default(),
+            env: Default::default(),
+            projects: Default::default(),
+            overrides: Default::default(),
+            extends: None,
         };
 
         let result = validate_deployment_prerequisites(&config);
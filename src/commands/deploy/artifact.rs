@@ -0,0 +1,637 @@
+use anyhow::{Context, Result, anyhow};
+use reqwest::Client;
+use serde::Deserialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+use crate::config::{Config, GithubReleaseConfig};
+use crate::logging::log;
+use crate::utils;
+
+/// Where a prebuilt binary comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtifactSource {
+    /// A file on the local machine (e.g. downloaded by a CI job)
+    Path(PathBuf),
+    /// An HTTP(S) URL
+    Url(String),
+    /// An asset attached to a GitHub Release ("latest" or a tag)
+    GithubRelease(String),
+}
+
+impl ArtifactSource {
+    /// Interpret a `--artifact` argument as a URL or local path
+    pub fn parse(value: &str) -> Self {
+        if value.starts_with("http://") || value.starts_with("https://") {
+            ArtifactSource::Url(value.to_string())
+        } else {
+            ArtifactSource::Path(PathBuf::from(shellexpand::tilde(value).to_string()))
+        }
+    }
+
+    /// Human-readable description recorded in the deploy manifest
+    pub fn describe(&self) -> String {
+        match self {
+            ArtifactSource::Path(path) => format!("artifact {}", path.display()),
+            ArtifactSource::Url(url) => format!("artifact {}", url),
+            ArtifactSource::GithubRelease(tag) => format!("github release {}", tag),
+        }
+    }
+}
+
+/// A verified binary, ready to deploy
+pub struct Artifact {
+    /// Where it came from, with a "latest" release resolved to its tag
+    pub source: ArtifactSource,
+    pub path: PathBuf,
+    /// A downloaded binary, removed once this is dropped
+    _download: Option<NamedTempFile>,
+}
+
+impl Artifact {
+    fn downloaded(source: ArtifactSource, file: NamedTempFile) -> Self {
+        Self {
+            source,
+            path: file.path().to_path_buf(),
+            _download: Some(file),
+        }
+    }
+}
+
+/// Deploy a prebuilt binary instead of building locally
+///
+/// An artifact without a checksum is refused unless `skip_checksum` is set. Returns where
+/// the binary came from, with a "latest" release resolved to its tag, and the deploy message.
+pub async fn deploy_artifact(
+    config: &Config,
+    source: &ArtifactSource,
+    expected_sha256: Option<&str>,
+    skip_checksum: bool,
+    dry_run: bool,
+) -> Result<(ArtifactSource, String)> {
+    log::operation_start(&format!(
+        "Deploying {} to {}",
+        source.describe(),
+        config.deploy.vps_host
+    ));
+
+    if dry_run {
+        log::dry_run(&format!("Fetch {}", source.describe()));
+        log::dry_run("Verify SHA-256 checksum");
+        let message = super::simulate_deployment(config).await?;
+        return Ok((source.clone(), message));
+    }
+
+    let artifact = resolve_artifact(config, source, expected_sha256, skip_checksum).await?;
+
+    if utils::fs::get_file_size(&artifact.path)? == 0 {
+        return Err(anyhow!("Artifact is empty: {}", artifact.path.display()));
+    }
+
+    let origin = super::DeployOrigin::new(artifact.source.describe());
+    let message = super::deploy_binary(config, &artifact.path, &origin, None).await?;
+    Ok((artifact.source, message))
+}
+
+/// Fetch (if needed) and verify an artifact
+pub async fn resolve_artifact(
+    config: &Config,
+    source: &ArtifactSource,
+    expected_sha256: Option<&str>,
+    skip_checksum: bool,
+) -> Result<Artifact> {
+    match source {
+        ArtifactSource::Path(path) => Ok(Artifact {
+            source: source.clone(),
+            path: resolve_local(path, expected_sha256, skip_checksum)?,
+            _download: None,
+        }),
+        ArtifactSource::Url(url) => {
+            let client = http_client()?;
+            let checksum_url = format!("{}.sha256", url);
+            let expected = match expected_sha256 {
+                Some(sum) => Some(sum.to_string()),
+                // Only a checksum the server doesn't have is left to `skip_checksum`
+                None => match fetch_text_if_found(&client, &checksum_url).await? {
+                    Some(contents) => Some(
+                        parse_checksum(&contents, file_name_from_url(url)).ok_or_else(|| {
+                            anyhow!("Could not find a SHA-256 checksum in {}", checksum_url)
+                        })?,
+                    ),
+                    None => None,
+                },
+            };
+            if expected.is_none() && !skip_checksum {
+                return Err(anyhow!(
+                    "No checksum available at {}. Pass --sha256 to verify the artifact, or --skip-checksum to deploy it unverified",
+                    checksum_url
+                ));
+            }
+
+            let (file, sha256) = download(config, &client, url, None).await?;
+            match expected {
+                Some(expected) => check(file.path(), &sha256, &expected)?,
+                None => unverified(file.path(), &sha256),
+            }
+            Ok(Artifact::downloaded(source.clone(), file))
+        }
+        ArtifactSource::GithubRelease(tag) => {
+            let release = config.deploy.github_release.as_ref().ok_or_else(|| {
+                anyhow!(
+                    "[deploy.github_release] must be configured to deploy from a GitHub Release"
+                )
+            })?;
+            resolve_github_release(config, release, tag, expected_sha256).await
+        }
+    }
+}
+
+/// Verify a local artifact against an explicit checksum or a `<file>.sha256` sidecar
+fn resolve_local(
+    path: &Path,
+    expected_sha256: Option<&str>,
+    skip_checksum: bool,
+) -> Result<PathBuf> {
+    if !path.exists() {
+        return Err(anyhow!("Artifact not found: {}", path.display()));
+    }
+
+    let sidecar = PathBuf::from(format!("{}.sha256", path.display()));
+    let expected = match expected_sha256 {
+        Some(sum) => Some(sum.to_string()),
+        None if sidecar.exists() => {
+            let contents = std::fs::read_to_string(&sidecar)
+                .with_context(|| format!("Failed to read checksum file: {}", sidecar.display()))?;
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            Some(parse_checksum(&contents, file_name).ok_or_else(|| {
+                anyhow!("Could not find a SHA-256 checksum in {}", sidecar.display())
+            })?)
+        }
+        None => None,
+    };
+
+    match expected {
+        Some(expected) => verify(path, &expected),
+        None if skip_checksum => {
+            unverified(path, &utils::fs::sha256_file(path)?);
+            Ok(path.to_path_buf())
+        }
+        None => Err(anyhow!(
+            "No checksum for {}. Pass --sha256 or add {} to verify the artifact, or --skip-checksum to deploy it unverified",
+            path.display(),
+            sidecar.display()
+        )),
+    }
+}
+
+/// Accept an artifact that has no checksum, logging its digest
+fn unverified(path: &Path, sha256: &str) {
+    tracing::warn!(
+        "No checksum provided for {}; deploying unverified artifact (sha256 {})",
+        path.display(),
+        sha256
+    );
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Look up a release on GitHub and download its binary and checksum assets
+async fn resolve_github_release(
+    config: &Config,
+    release_config: &GithubReleaseConfig,
+    tag: &str,
+    expected_sha256: Option<&str>,
+) -> Result<Artifact> {
+    let client = http_client()?;
+    let token = std::env::var(&release_config.token_env).ok();
+
+    let api_url = if tag == "latest" {
+        format!(
+            "https://api.github.com/repos/{}/releases/latest",
+            release_config.repo
+        )
+    } else {
+        format!(
+            "https://api.github.com/repos/{}/releases/tags/{}",
+            release_config.repo, tag
+        )
+    };
+
+    let mut request = client.get(&api_url);
+    if let Some(token) = &token {
+        request = request.bearer_auth(token);
+    }
+    let release: GithubRelease = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to query GitHub release: {}", api_url))?
+        .error_for_status()
+        .with_context(|| format!("GitHub release lookup failed: {}", api_url))?
+        .json()
+        .await
+        .context("Failed to parse GitHub release response")?;
+
+    log::deploy_step(&format!(
+        "Using release {} of {}",
+        release.tag_name, release_config.repo
+    ));
+
+    let find_asset = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| anyhow!("Release {} has no asset named '{}'", release.tag_name, name))
+    };
+
+    let binary_asset = find_asset(&release_config.asset)?;
+    let expected = match expected_sha256 {
+        Some(sum) => sum.to_string(),
+        None => {
+            let checksum_name = release_config
+                .checksum_asset
+                .clone()
+                .unwrap_or_else(|| format!("{}.sha256", release_config.asset));
+            let checksum_asset = find_asset(&checksum_name)?;
+            let contents = fetch_text(
+                &client,
+                &checksum_asset.browser_download_url,
+                token.as_deref(),
+            )
+            .await?;
+            parse_checksum(&contents, &release_config.asset).ok_or_else(|| {
+                anyhow!(
+                    "Could not find a checksum for '{}' in {}",
+                    release_config.asset,
+                    checksum_name
+                )
+            })?
+        }
+    };
+
+    let (file, sha256) = download(
+        config,
+        &client,
+        &binary_asset.browser_download_url,
+        token.as_deref(),
+    )
+    .await?;
+    check(file.path(), &sha256, &expected)?;
+    Ok(Artifact::downloaded(
+        ArtifactSource::GithubRelease(release.tag_name.clone()),
+        file,
+    ))
+}
+
+/// Check a file against the expected SHA-256 digest
+fn verify(path: &Path, expected: &str) -> Result<PathBuf> {
+    check(path, &utils::fs::sha256_file(path)?, expected)?;
+    Ok(path.to_path_buf())
+}
+
+/// Check the digest `actual` of the file at `path` against the expected one
+fn check(path: &Path, actual: &str, expected: &str) -> Result<()> {
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        return Err(anyhow!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            path.display(),
+            expected.trim(),
+            actual
+        ));
+    }
+
+    log::deploy_step(&format!("Verified SHA-256 checksum: {}", actual));
+    Ok(())
+}
+
+/// Extract the digest for `file_name` from `sha256sum`-style output
+///
+/// Accepts a bare digest, `<digest>  <file>` lines, or a multi-file listing.
+pub fn parse_checksum(contents: &str, file_name: &str) -> Option<String> {
+    let is_digest = |s: &str| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit());
+    let lines: Vec<&str> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    for line in &lines {
+        let mut parts = line.split_whitespace();
+        let digest = parts.next().unwrap_or_default();
+        let name = parts.next().map(|n| n.trim_start_matches('*'));
+
+        if is_digest(digest)
+            && name.is_some_and(|n| n == file_name || n.ends_with(&format!("/{}", file_name)))
+        {
+            return Some(digest.to_lowercase());
+        }
+    }
+
+    match lines.as_slice() {
+        [line] => line
+            .split_whitespace()
+            .next()
+            .filter(|digest| is_digest(digest))
+            .map(str::to_lowercase),
+        _ => None,
+    }
+}
+
+fn http_client() -> Result<Client> {
+    Client::builder()
+        .user_agent(concat!("rzen/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+fn file_name_from_url(url: &str) -> &str {
+    url.rsplit('/').next().unwrap_or(url)
+}
+
+async fn fetch_text(client: &Client, url: &str, token: Option<&str>) -> Result<String> {
+    let mut request = client.get(url);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch: {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Request failed: {}", url))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read response body: {}", url))
+}
+
+/// [`fetch_text`] without a token, or `None` when the server has no such file
+async fn fetch_text_if_found(client: &Client, url: &str) -> Result<Option<String>> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch: {}", url))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let text = response
+        .error_for_status()
+        .with_context(|| format!("Request failed: {}", url))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read response body: {}", url))?;
+    Ok(Some(text))
+}
+
+/// Stream an artifact into a new temporary file, hashing it on the way
+///
+/// Returns the file, which is removed when dropped, and its SHA-256 digest.
+async fn download(
+    config: &Config,
+    client: &Client,
+    url: &str,
+    token: Option<&str>,
+) -> Result<(NamedTempFile, String)> {
+    use sha2::{Digest, Sha256};
+
+    log::deploy_step(&format!("Downloading artifact: {}", url));
+
+    let mut request = client.get(url).header("Accept", "application/octet-stream");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let mut response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to download artifact: {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Artifact download failed: {}", url))?;
+
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!("rzen-artifact-{}-", config.binary_name()))
+        .tempfile()
+        .context("Failed to create a file for the artifact")?;
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("Failed to read artifact body: {}", url))?
+    {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .with_context(|| format!("Failed to write artifact to: {}", file.path().display()))?;
+    }
+    file.flush()?;
+
+    crate::logging::log::file_transfer(&file.path().display().to_string(), "downloaded");
+    Ok((file, format!("{:x}", hasher.finalize())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_artifact_source_parse() {
+        assert_eq!(
+            ArtifactSource::parse("https://ci.example.com/app"),
+            ArtifactSource::Url("https://ci.example.com/app".to_string())
+        );
+        assert_eq!(
+            ArtifactSource::parse("dist/app"),
+            ArtifactSource::Path(PathBuf::from("dist/app"))
+        );
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        assert_eq!(
+            parse_checksum(HELLO_SHA256, "app").as_deref(),
+            Some(HELLO_SHA256)
+        );
+        assert_eq!(
+            parse_checksum(&format!("{}  app\n", HELLO_SHA256), "app").as_deref(),
+            Some(HELLO_SHA256)
+        );
+
+        let listing = format!(
+            "{}  other\n{} *dist/app\n",
+            "0".repeat(64),
+            HELLO_SHA256.to_uppercase()
+        );
+        assert_eq!(
+            parse_checksum(&listing, "app").as_deref(),
+            Some(HELLO_SHA256)
+        );
+        assert_eq!(parse_checksum(&listing, "missing"), None);
+        assert_eq!(parse_checksum("not a checksum", "app"), None);
+    }
+
+    #[test]
+    fn test_resolve_local_artifact_checksum() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("app");
+        std::fs::write(&path, b"hello").unwrap();
+
+        assert_eq!(
+            resolve_local(&path, Some(HELLO_SHA256), false).unwrap(),
+            path
+        );
+
+        let result = resolve_local(&path, Some(&"0".repeat(64)), false);
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Checksum mismatch")
+        );
+
+        std::fs::write(
+            temp_dir.path().join("app.sha256"),
+            format!("{}  app\n", "f".repeat(64)),
+        )
+        .unwrap();
+        assert!(resolve_local(&path, None, false).is_err());
+    }
+
+    /// Serve `routes` of (path, status, body) on a local port, 404 for any other path
+    async fn serve(routes: Vec<(&'static str, u16, Vec<u8>)>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0; 4096];
+                let read = stream.read(&mut request).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..read]).into_owned();
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = routes
+                    .iter()
+                    .find(|(route, ..)| *route == path)
+                    .map_or((404, &[][..]), |(_, status, body)| (*status, &body[..]));
+                let head = format!(
+                    "HTTP/1.1 {} Test\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(body).await;
+            }
+        });
+        address
+    }
+
+    fn test_config() -> Config {
+        toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            [deploy]
+            vps_host = "example.com"
+            [monitor]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_resolve_url_artifact() {
+        let config = test_config();
+        let checksum = format!("{}  app\n", HELLO_SHA256);
+        let server = serve(vec![
+            ("/app", 200, b"hello".to_vec()),
+            ("/app.sha256", 200, checksum.into_bytes()),
+            ("/bare", 200, b"hello".to_vec()),
+            ("/broken", 200, b"hello".to_vec()),
+            ("/broken.sha256", 500, Vec::new()),
+        ])
+        .await;
+        let source = |path: &str| ArtifactSource::Url(format!("{}{}", server, path));
+
+        // Streamed to a temporary file of its own and checked against the sidecar
+        let artifact = resolve_artifact(&config, &source("/app"), None, false)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&artifact.path).unwrap(), b"hello");
+        assert!(
+            artifact
+                .path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("rzen-artifact-app-")
+        );
+        let other = resolve_artifact(&config, &source("/app"), None, false)
+            .await
+            .unwrap();
+        assert_ne!(other.path, artifact.path);
+        let path = artifact.path.clone();
+        drop(artifact);
+        assert!(!path.exists());
+
+        let error = resolve_artifact(&config, &source("/app"), Some(&"0".repeat(64)), false)
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("Checksum mismatch"));
+
+        // Without a sidecar, --skip-checksum deploys the artifact unverified
+        let error = resolve_artifact(&config, &source("/bare"), None, false)
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("--skip-checksum"));
+        let artifact = resolve_artifact(&config, &source("/bare"), None, true)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&artifact.path).unwrap(), b"hello");
+
+        // but doesn't hide a server that fails or can't be reached
+        let error = resolve_artifact(&config, &source("/broken"), None, true)
+            .await
+            .err()
+            .unwrap();
+        assert!(format!("{:#}", error).contains("500"), "{:#}", error);
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let unreachable = format!("http://{}/app", closed.local_addr().unwrap());
+        drop(closed);
+        let error = resolve_artifact(&config, &ArtifactSource::Url(unreachable), None, true)
+            .await
+            .err()
+            .unwrap();
+        assert!(
+            error.to_string().starts_with("Failed to fetch"),
+            "{:#}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_resolve_local_artifact_without_checksum() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("app");
+        std::fs::write(&path, b"hello").unwrap();
+
+        let error = resolve_local(&path, None, false).unwrap_err();
+        assert!(error.to_string().contains("--skip-checksum"));
+        assert_eq!(resolve_local(&path, None, true).unwrap(), path);
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+
+use crate::commands::build;
+use crate::config::Config;
+use crate::logging::log;
+use crate::utils;
+
+/// Build and deploy a tag, branch, or commit from a clean temporary worktree
+pub async fn deploy_ref(config: &Config, git_ref: &str, dry_run: bool) -> Result<String> {
+    log::operation_start(&format!(
+        "Deploying git ref '{}' to {}",
+        git_ref, config.deploy.vps_host
+    ));
+
+    let project_path = config.project_path()?;
+    let repo = utils::git::toplevel(&project_path)
+        .with_context(|| format!("{} is not inside a git repository", project_path.display()))?;
+    let commit = utils::git::resolve_commit(&repo, git_ref)?;
+
+    if dry_run {
+        log::dry_run(&format!("git worktree add --detach <tmp> {}", commit));
+        log::dry_run(&format!(
+            "cargo build --{} --bin {}",
+            config.project.build_mode,
+            config.binary_name()
+        ));
+        return super::simulate_deployment(config).await;
+    }
+
+    let worktree = utils::git::TempWorktree::create(&repo, &commit)?;
+    log::deploy_step(&format!(
+        "Checked out {} ({}) into {}",
+        git_ref,
+        &commit[..commit.len().min(12)],
+        worktree.path.display()
+    ));
+
+    let ref_config = worktree_config(config, &project_path, &repo, &worktree.path)?;
+    build::build_project(&ref_config, None, false).await?;
+
+    let binary_path = utils::fs::find_binary(
+        &ref_config.project_path()?,
+        &ref_config.binary_name(),
+        &ref_config.project.build_mode,
+    )?;
+
+    let origin = super::DeployOrigin {
+        source: format!("git ref {}", git_ref),
+        git_ref: Some(git_ref.to_string()),
+        commit: Some(commit),
+    };
+    super::deploy_binary(&ref_config, &binary_path, &origin, None).await
+}
+
+/// Point the project path at the same subdirectory inside the worktree
+fn worktree_config(
+    config: &Config,
+    project_path: &std::path::Path,
+    repo: &std::path::Path,
+    worktree: &std::path::Path,
+) -> Result<Config> {
+    let project_path = project_path
+        .canonicalize()
+        .unwrap_or_else(|_| project_path.to_path_buf());
+    let repo = repo.canonicalize().unwrap_or_else(|_| repo.to_path_buf());
+    let relative = project_path.strip_prefix(&repo).with_context(|| {
+        format!(
+            "Project path {} is outside repository {}",
+            project_path.display(),
+            repo.display()
+        )
+    })?;
+
+    let mut ref_config = config.clone();
+    ref_config.project.path = worktree.join(relative).to_string_lossy().to_string();
+    Ok(ref_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_worktree_config_keeps_subdirectory() {
+        let repo = tempdir().unwrap();
+        let project = repo.path().join("services").join("api");
+        std::fs::create_dir_all(&project).unwrap();
+
+        let config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "api"
+            [deploy]
+            vps_host = "example.com"
+            vps_user = "deploy"
+            vps_key_path = "~/.ssh/id_rsa"
+            [monitor]
+            "#,
+        )
+        .unwrap();
+
+        let ref_config =
+            worktree_config(&config, &project, repo.path(), Path::new("/tmp/wt")).unwrap();
+        assert_eq!(
+            Path::new(&ref_config.project.path),
+            Path::new("/tmp/wt/services/api")
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::{Result, anyhow};
+use reqwest::Client;
+use std::time::{Duration, Instant};
+
+use crate::commands::monitor::{disk, tunnel};
+use crate::config::Config;
+use crate::logging::glyph;
+use crate::utils;
+use crate::utils::host::{Host, Local};
+use crate::utils::timing::format_duration;
+
+/// Outcome of one prerequisite check on a deploy target
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub name: String,
+    pub passed: bool,
+    /// What was found, or why the check failed
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: &str, result: Result<String>) -> Self {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(e) => (false, format!("{:#}", e)),
+        };
+        Self {
+            name: name.to_string(),
+            passed,
+            detail,
+        }
+    }
+
+    /// Checklist line, e.g. `✅ Passwordless sudo`
+    pub fn line(&self) -> String {
+        let mark = glyph(if self.passed { "✅" } else { "❌" });
+        if self.detail.is_empty() {
+            format!("{} {}", mark, self.name)
+        } else {
+            format!("{} {}: {}", mark, self.name, self.detail)
+        }
+    }
+}
+
+/// Check that `config.deploy.vps_host` can be deployed to and serves the health endpoint
+///
+/// Server checks need the SSH connection and are left out when it fails. A local target
+/// is checked without one.
+pub async fn check_host(config: &Config) -> Vec<CheckResult> {
+    let ssh_config = config.ssh_config();
+
+    let mut results = Vec::new();
+    if config.deploy.is_local() {
+        results.extend(check_server(&Local, config));
+    } else {
+        match utils::ssh::connect_with_retry(&ssh_config, 1).await {
+            Ok(session) => {
+                let mut target = format!(
+                    "{}@{}",
+                    ssh_config.username,
+                    utils::net::host_port(&ssh_config.host, ssh_config.port)
+                );
+                let compression = utils::ssh::Compression::negotiated(
+                    ssh_config.options.compression,
+                    utils::ssh::compression(&session),
+                );
+                if let Some(note) = compression.describe() {
+                    target.push_str(&format!(" ({})", note));
+                }
+                results.push(CheckResult::new("SSH connection", Ok(target)));
+                results.extend(check_server(
+                    &utils::ssh::Connection::new(session, &ssh_config),
+                    config,
+                ));
+            }
+            Err(e) => results.push(CheckResult::new("SSH connection", Err(e))),
+        }
+    }
+
+    if let Some(endpoint) = &config.monitor.health_endpoint {
+        results.push(CheckResult::new(
+            "Health endpoint",
+            probe_health(config, endpoint).await,
+        ));
+    }
+    results
+}
+
+/// Checks run on the deploy target: the tools deploys use and the space they write to
+fn check_server(host: &dyn Host, config: &Config) -> Vec<CheckResult> {
+    let run = |command: &str| {
+        host.execute_command(command)
+            .map(|(output, _)| output.trim().to_string())
+    };
+
+    // A local target runs as whoever started rzen
+    let user = if config.deploy.is_local() {
+        std::env::var("USER").unwrap_or_else(|_| "the current user".to_string())
+    } else {
+        config.deploy.vps_user.clone()
+    };
+
+    // Deploys run sudo without a terminal, so it must not ask for a password unless rzen
+    // has one to give it
+    let (sudo_check, sudo) = if config.sudo_password().is_some() && !config.deploy.is_local() {
+        let sudo = run("sudo true")
+            .map(|_| String::new())
+            .map_err(|_| anyhow!("sudo rejected the password of {}", user));
+        ("Sudo password", sudo)
+    } else {
+        let sudo = run("sudo -n true").map(|_| String::new()).map_err(|_| {
+            anyhow!(
+                "sudo asks for a password; allow {} to run it without one, or set sudo_password_source",
+                user
+            )
+        });
+        ("Passwordless sudo", sudo)
+    };
+    let systemctl = run("systemctl --version")
+        .map(|version| version.lines().next().unwrap_or_default().to_string());
+
+    let deploy_path = &config.deploy.deploy_path;
+    let writable = run(&format!(
+        "p={}; while [ ! -e \"$p\" ]; do p=$(dirname \"$p\"); done; \
+         echo \"$p\"; [ -w \"$p\" ] && echo yes || echo no",
+        utils::ssh::shell_quote(deploy_path)
+    ))
+    .and_then(|output| writable_detail(deploy_path, &user, &output));
+
+    let min_free_mb = config.deploy.min_free_disk_mb;
+    let disk_space = disk::check(host, &disk::watched_paths(config)).and_then(|disks| {
+        let summary = disks
+            .iter()
+            .map(disk::DiskSpace::summary)
+            .collect::<Vec<_>>();
+        if disks.iter().any(|disk| disk.is_low(min_free_mb)) {
+            return Err(anyhow!(
+                "{} (min_free_disk_mb = {})",
+                summary.join("; "),
+                min_free_mb
+            ));
+        }
+        Ok(summary.join("; "))
+    });
+
+    vec![
+        CheckResult::new(sudo_check, sudo),
+        CheckResult::new("systemctl", systemctl),
+        CheckResult::new("Deploy path writable", writable),
+        CheckResult::new("Free disk space", disk_space),
+    ]
+}
+
+/// Interpret the `<nearest existing path>\n<yes|no>` output of the writability probe
+fn writable_detail(deploy_path: &str, user: &str, output: &str) -> Result<String> {
+    let mut lines = output.lines();
+    let (Some(existing), Some(answer)) = (lines.next(), lines.next()) else {
+        return Err(anyhow!("Unexpected output: {}", output));
+    };
+    let location = if existing == deploy_path {
+        deploy_path.to_string()
+    } else {
+        format!("{} (to be created in {})", deploy_path, existing)
+    };
+    match answer {
+        "yes" => Ok(location),
+        _ => Err(anyhow!("{} is not writable by {}", location, user)),
+    }
+}
+
+/// GET the health endpoint, through an SSH tunnel with `health_via = "ssh"`; passes on a
+/// success status
+async fn probe_health(config: &Config, endpoint: &str) -> Result<String> {
+    let health_tunnel = if tunnel::is_wanted(config) {
+        Some(tunnel::HealthTunnel::open(config, endpoint).await?)
+    } else {
+        None
+    };
+    let request = match &health_tunnel {
+        Some(health_tunnel) => health_tunnel.client.get(health_tunnel.url.clone()),
+        None => Client::builder()
+            .timeout(Duration::from_secs(config.monitor.health_timeout_secs))
+            .build()
+            .unwrap_or_else(|_| Client::new())
+            .get(endpoint),
+    };
+    let start = Instant::now();
+    // reqwest repeats the cause at every level of its error chain
+    let response = request
+        .send()
+        .await
+        .map_err(|e| anyhow!("{}", anyhow::Error::from(e).root_cause()))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("{} returned {}", endpoint, status));
+    }
+    let via = if health_tunnel.is_some() {
+        " through an SSH tunnel"
+    } else {
+        ""
+    };
+    Ok(format!(
+        "{} returned {} in {}{}",
+        endpoint,
+        status,
+        format_duration(start.elapsed()),
+        via
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writable_detail() {
+        assert_eq!(
+            writable_detail("/opt/app", "deploy", "/opt/app\nyes\n").unwrap(),
+            "/opt/app"
+        );
+        assert_eq!(
+            writable_detail("/opt/app", "deploy", "/opt\nyes\n").unwrap(),
+            "/opt/app (to be created in /opt)"
+        );
+        let error = writable_detail("/opt/app", "deploy", "/opt\nno\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "/opt/app (to be created in /opt) is not writable by deploy"
+        );
+
+        let result = CheckResult::new("Deploy path writable", Err(error));
+        assert!(!result.passed);
+        assert!(result.line().ends_with(
+            "Deploy path writable: /opt/app (to be created in /opt) is not writable by deploy"
+        ));
+    }
+}
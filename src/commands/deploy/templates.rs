@@ -0,0 +1,174 @@
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::logging::log;
+use crate::utils;
+use crate::utils::host::Host;
+
+/// `[[deploy.templates]]` entry rendered for one host, ready to upload
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rendered {
+    /// Template path as configured
+    pub source: String,
+    /// Absolute path on the server
+    pub destination: String,
+    pub mode: i32,
+    pub contents: String,
+}
+
+/// Render every `[[deploy.templates]]` entry with the settings of `config`
+///
+/// Everything is rendered before anything is uploaded, so a broken template stops the deploy
+/// before the server is touched.
+pub fn render_all(config: &Config) -> Result<Vec<Rendered>> {
+    if config.deploy.templates.is_empty() {
+        return Ok(Vec::new());
+    }
+    let settings = toml::Table::try_from(config).context("Failed to serialize config to TOML")?;
+    let project_path = config.project_path()?;
+
+    config
+        .deploy
+        .templates
+        .iter()
+        .map(|template| {
+            let path = project_path.join(&template.source);
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read template: {}", path.display()))?;
+            let contents = render(&text, |name| variable(&settings, name))
+                .map_err(|e| anyhow!("Template {}: {}", template.source, e))?;
+            Ok(Rendered {
+                source: template.source.clone(),
+                destination: remote_path(config, &template.destination),
+                mode: template.mode_bits()?,
+                contents,
+            })
+        })
+        .collect()
+}
+
+/// Upload rendered templates to `host`, creating their directories
+pub fn upload(host: &dyn Host, rendered: &[Rendered]) -> Result<()> {
+    for template in rendered {
+        if let Some(parent) = Path::new(&template.destination).parent() {
+            let parent = utils::ssh::shell_quote(&parent.to_string_lossy());
+            host.execute_command(&format!("mkdir -p {}", parent))?;
+        }
+        host.write_file(
+            &template.destination,
+            template.contents.as_bytes(),
+            template.mode,
+        )?;
+        log::deploy_step(&format!(
+            "Rendered {} to {}",
+            template.source, template.destination
+        ));
+    }
+    Ok(())
+}
+
+/// Server paths of every `[[deploy.templates]]` entry
+pub fn destinations(config: &Config) -> Vec<String> {
+    config
+        .deploy
+        .templates
+        .iter()
+        .map(|template| remote_path(config, &template.destination))
+        .collect()
+}
+
+/// Destination on the server; relative paths are under `deploy_path`
+fn remote_path(config: &Config, destination: &str) -> String {
+    if destination.starts_with('/') {
+        destination.to_string()
+    } else {
+        format!("{}/{}", config.deploy.deploy_path, destination)
+    }
+}
+
+/// Value of `{{ name }}`: a setting like `deploy.vps_host`, or `env.NAME` for an environment variable
+fn variable(settings: &toml::Table, name: &str) -> Result<String> {
+    if let Some(variable) = name.strip_prefix("env.") {
+        return std::env::var(variable)
+            .map_err(|_| anyhow!("Environment variable {} is not set", variable));
+    }
+
+    let mut keys = name.split('.');
+    let first = keys.next().unwrap_or_default();
+    let value = keys.try_fold(settings.get(first), |value, key| {
+        Some(value.and_then(|value| value.get(key)))
+    });
+    match value.flatten() {
+        Some(toml::Value::String(text)) => Ok(text.clone()),
+        Some(toml::Value::Table(_) | toml::Value::Array(_)) => {
+            Err(anyhow!("'{}' is not a single value", name))
+        }
+        Some(value) => Ok(value.to_string()),
+        None => Err(anyhow!("Unknown variable '{}'", name)),
+    }
+}
+
+/// Replace each `{{ name }}` in `template` with its value
+fn render(template: &str, lookup: impl Fn(&str) -> Result<String>) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    for (index, line) in template.split_inclusive('\n').enumerate() {
+        let mut rest = line;
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find("}}")
+                .ok_or_else(|| anyhow!("Unclosed '{{{{' on line {}", index + 1))?;
+            let name = rest[start + 2..start + end].trim();
+            let value = lookup(name).map_err(|e| anyhow!("{} on line {}", e, index + 1))?;
+            output.push_str(&value);
+            rest = &rest[start + end + 2..];
+        }
+        output.push_str(rest);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let settings: toml::Table = toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            [deploy]
+            vps_host = "example.com"
+            ssh_port = 22
+            hosts = ["a", "b"]
+            "#,
+        )
+        .unwrap();
+        let lookup = |name: &str| variable(&settings, name);
+
+        assert_eq!(
+            render(
+                "name={{project.name}}\nurl=http://{{ deploy.vps_host }}:{{deploy.ssh_port}}\n",
+                lookup
+            )
+            .unwrap(),
+            "name=app\nurl=http://example.com:22\n"
+        );
+        assert_eq!(render("no variables", lookup).unwrap(), "no variables");
+
+        let error = render("a\nport = {{ deploy.port }}", lookup).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown variable 'deploy.port' on line 2"
+        );
+        let error = render("{{ deploy.hosts }}", lookup).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'deploy.hosts' is not a single value on line 1"
+        );
+        assert!(render("{{ project.name", lookup).is_err());
+    }
+}
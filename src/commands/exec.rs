@@ -0,0 +1,174 @@
+use anyhow::Result;
+use std::io::Write;
+
+use crate::config::Config;
+use crate::logging::log;
+use crate::utils;
+use crate::utils::host::OutputStream;
+
+/// Run `command` with `sh` on every host, `max_parallel_hosts` at a time, streaming its output
+///
+/// Returns the exit code to leave rzen with: 0 when the command succeeded everywhere, else the
+/// code of the first host in order where it didn't. With several hosts, each line is prefixed
+/// with its host's name. In a dry run the command is only listed.
+pub async fn run_command(config: &Config, command: &str, dry_run: bool) -> Result<i32> {
+    let hosts = config.hosts();
+    if dry_run {
+        for host in &hosts {
+            log::dry_run(&format!("Would run on {}: {}", host, command));
+        }
+        return Ok(0);
+    }
+
+    let prefixed = hosts.len() > 1;
+    let command = command.to_string();
+    let results = utils::ssh::run_on_all(config, move |target, host_config| {
+        let prefix = prefixed.then(|| format!("[{}] ", host_config.deploy.vps_host));
+        let mut printer = Printer::new(prefix, std::io::stdout(), std::io::stderr());
+        let code = target.stream_command(&command, &mut |stream, data| printer.write(stream, data));
+        printer.finish();
+        code
+    })
+    .await
+    .into_result()?;
+    if prefixed {
+        for (host, code) in &results {
+            if *code != 0 {
+                eprintln!("[{}] exited with code {}", host, code);
+            }
+        }
+    }
+    Ok(results
+        .iter()
+        .map(|(_, code)| *code)
+        .find(|code| *code != 0)
+        .unwrap_or(0))
+}
+
+/// Writes a command's output to local stdout and stderr as it arrives, optionally starting
+/// each line with a prefix
+///
+/// Prefixed output is written a line at a time, so lines from hosts running in parallel
+/// don't mix.
+struct Printer<O: Write, E: Write> {
+    prefix: Option<String>,
+    stdout: O,
+    stderr: E,
+    /// Start of a line not ended yet, for stdout and stderr
+    partial: [Vec<u8>; 2],
+}
+
+impl<O: Write, E: Write> Printer<O, E> {
+    fn new(prefix: Option<String>, stdout: O, stderr: E) -> Self {
+        Self {
+            prefix,
+            stdout,
+            stderr,
+            partial: [Vec::new(), Vec::new()],
+        }
+    }
+
+    fn write(&mut self, stream: OutputStream, data: &[u8]) {
+        let index = match stream {
+            OutputStream::Stdout => 0,
+            OutputStream::Stderr => 1,
+        };
+        let Some(prefix) = &self.prefix else {
+            let _ = self.output(index).write_all(data);
+            let _ = self.output(index).flush();
+            return;
+        };
+
+        let mut buffer = std::mem::take(&mut self.partial[index]);
+        buffer.extend_from_slice(data);
+        let prefix = prefix.clone();
+        let mut lines = buffer.split_inclusive(|byte| *byte == b'\n').peekable();
+        while let Some(line) = lines.next() {
+            if lines.peek().is_none() && !line.ends_with(b"\n") {
+                self.partial[index] = line.to_vec();
+                break;
+            }
+            let mut text = prefix.clone().into_bytes();
+            text.extend_from_slice(line);
+            let _ = self.output(index).write_all(&text);
+        }
+        let _ = self.output(index).flush();
+    }
+
+    /// Write out what is left of unfinished lines
+    fn finish(&mut self) {
+        for index in 0..2 {
+            let partial = std::mem::take(&mut self.partial[index]);
+            if !partial.is_empty() {
+                self.write(
+                    [OutputStream::Stdout, OutputStream::Stderr][index],
+                    &[partial.as_slice(), b"\n"].concat(),
+                );
+            }
+        }
+    }
+
+    fn output(&mut self, index: usize) -> &mut dyn Write {
+        if index == 0 {
+            &mut self.stdout
+        } else {
+            &mut self.stderr
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::host::mock::MockHost;
+
+    #[test]
+    fn test_printer() {
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let mut printer = Printer::new(Some("[web1] ".to_string()), &mut stdout, &mut stderr);
+        printer.write(OutputStream::Stdout, b"one\ntw");
+        printer.write(OutputStream::Stderr, b"warning\n");
+        printer.write(OutputStream::Stdout, b"o\nthree");
+        printer.finish();
+        assert_eq!(
+            String::from_utf8_lossy(&stdout),
+            "[web1] one\n[web1] two\n[web1] three\n"
+        );
+        assert_eq!(String::from_utf8_lossy(&stderr), "[web1] warning\n");
+
+        // Without a prefix, output is passed through as it comes
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let mut printer = Printer::new(None, &mut stdout, &mut stderr);
+        printer.write(OutputStream::Stdout, b"progress: 50%\r");
+        printer.finish();
+        assert_eq!(stdout, b"progress: 50%\r");
+    }
+
+    #[tokio::test]
+    async fn test_run_command() {
+        let host = MockHost::register("exec.mock.invalid");
+        let config: Config = toml::from_str(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "exec.mock.invalid"
+vps_user = "deploy"
+
+[monitor]
+"#,
+        )
+        .unwrap();
+        host.reply("df -h", "/dev/sda1  10G  4G  6G  40% /\n")
+            .fail("false", "");
+
+        assert_eq!(run_command(&config, "df -h", false).await.unwrap(), 0);
+        assert_eq!(run_command(&config, "false", false).await.unwrap(), 1);
+        assert_eq!(
+            run_command(&config, "rm -rf /tmp/x", true).await.unwrap(),
+            0
+        );
+        assert_eq!(host.commands(), ["df -h", "false"]);
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::{Context, Result, anyhow};
+use std::process::Stdio;
+use tokio::process::Command as TokioCommand;
+
+use crate::config::{Config, HookCommand};
+use crate::logging::log;
+use crate::utils;
+use crate::utils::host::Host;
+
+/// Point of the build and deploy pipelines where `[hooks]` run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    PreBuild,
+    PostBuild,
+    PreDeploy,
+    PostDeploy,
+    OnRollback,
+    OnFailure,
+}
+
+impl Stage {
+    /// Key of the stage under `[hooks]`, also passed to hooks as `RZEN_HOOK`
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::PreBuild => "pre_build",
+            Stage::PostBuild => "post_build",
+            Stage::PreDeploy => "pre_deploy",
+            Stage::PostDeploy => "post_deploy",
+            Stage::OnRollback => "on_rollback",
+            Stage::OnFailure => "on_failure",
+        }
+    }
+
+    fn commands(self, config: &Config) -> &[HookCommand] {
+        let hooks = &config.hooks;
+        match self {
+            Stage::PreBuild => &hooks.pre_build,
+            Stage::PostBuild => &hooks.post_build,
+            Stage::PreDeploy => &hooks.pre_deploy,
+            Stage::PostDeploy => &hooks.post_deploy,
+            Stage::OnRollback => &hooks.on_rollback,
+            Stage::OnFailure => &hooks.on_failure,
+        }
+    }
+
+    fn log(self, message: &str) {
+        match self {
+            Stage::PreBuild | Stage::PostBuild => log::build_step(message),
+            _ => log::deploy_step(message),
+        }
+    }
+}
+
+/// Run the hooks of `stage` in order, stopping at the first one that fails
+///
+/// Remote hooks run on `host` when it is connected, and on a new connection to the
+/// deploy target otherwise. In a dry run the hooks are only listed.
+pub async fn run(
+    config: &Config,
+    stage: Stage,
+    host: Option<&dyn Host>,
+    dry_run: bool,
+) -> Result<()> {
+    let hooks = stage.commands(config);
+    if dry_run {
+        for hook in hooks {
+            let place = if hook.is_remote() { "remote" } else { "local" };
+            log::dry_run(&format!(
+                "{} hook ({}): {}",
+                stage.name(),
+                place,
+                hook.command()
+            ));
+        }
+        return Ok(());
+    }
+
+    let connected = match host {
+        None if hooks.iter().any(HookCommand::is_remote) => {
+            Some(utils::host::connect(config).await?)
+        }
+        _ => None,
+    };
+    let host = host.or(connected.as_deref());
+
+    for hook in hooks {
+        stage.log(&format!(
+            "Running {} hook: {}",
+            stage.name(),
+            hook.command()
+        ));
+        let output = match host {
+            Some(host) if hook.is_remote() => run_remote(host, config, stage, hook.command()),
+            _ => run_local(config, stage, hook.command()).await,
+        }
+        .with_context(|| format!("{} hook failed: {}", stage.name(), hook.command()))?;
+
+        for line in output.lines().filter(|line| !line.trim().is_empty()) {
+            stage.log(&format!("  {}", line));
+        }
+    }
+    Ok(())
+}
+
+/// Run the `on_failure` hooks for a failed build or deploy; their own failure is only logged
+pub async fn run_on_failure(config: &Config, error: &anyhow::Error) {
+    if config.hooks.on_failure.is_empty() {
+        return;
+    }
+    log::deploy_step(&format!("Running on_failure hooks after: {}", error));
+    if let Err(e) = run(config, Stage::OnFailure, None, false).await {
+        log::operation_failed("on_failure hook", &format!("{:#}", e));
+    }
+}
+
+/// Variables describing the hook, set for local and remote commands alike
+fn hook_env(config: &Config, stage: Stage) -> [(&'static str, String); 2] {
+    [
+        ("RZEN_HOOK", stage.name().to_string()),
+        ("RZEN_HOOK_HOST", config.deploy.vps_host.clone()),
+    ]
+}
+
+/// Run a hook with `sh -c` in the project directory
+async fn run_local(config: &Config, stage: Stage, command: &str) -> Result<String> {
+    let output = TokioCommand::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(config.project_path()?)
+        .envs(hook_env(config, stage))
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .context("Failed to start sh")?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(anyhow!("Exited with {}: {}", output.status, stderr.trim()));
+    }
+    Ok(format!("{}{}", stdout, stderr))
+}
+
+/// Run a hook on the deploy target in the SSH user's login directory
+fn run_remote(host: &dyn Host, config: &Config, stage: Stage, command: &str) -> Result<String> {
+    let env = hook_env(config, stage)
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, utils::ssh::shell_quote(value)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (stdout, stderr) = host.execute_command(&format!(
+        "{} sh -c {}",
+        env,
+        utils::ssh::shell_quote(command)
+    ))?;
+    Ok(format!("{}{}", stdout, stderr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(hooks: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+
+[monitor]
+
+[hooks]
+{}
+"#,
+            hooks
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_local_hooks() {
+        let project = tempfile::tempdir().unwrap();
+        let mut config = config(
+            r#"pre_build = ["echo $RZEN_HOOK > hook.txt", { command = "echo second >> hook.txt" }]
+post_build = ["false", "echo unreachable > after.txt"]"#,
+        );
+        config.project.path = project.path().display().to_string();
+
+        run(&config, Stage::PreBuild, None, false).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(project.path().join("hook.txt")).unwrap(),
+            "pre_build\nsecond\n"
+        );
+
+        let error = run(&config, Stage::PostBuild, None, false)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "post_build hook failed: false");
+        assert!(!project.path().join("after.txt").exists());
+
+        // A dry run only lists them
+        run(&config, Stage::PostBuild, None, true).await.unwrap();
+    }
+}
@@ -1,28 +1,85 @@
 use anyhow::{Context, Result, anyhow};
 use reqwest::Client;
-use ssh2::Session;
+use serde::{Serialize, Serializer};
 use std::io::Read;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 
+use crate::cli::OutputFormat;
 use crate::config::Config;
-use crate::logging::log;
+use crate::config::reload::{ConfigWatcher, Reload};
+use crate::logging::{glyph, log};
 use crate::utils;
+use crate::utils::host::Host;
+use crate::utils::prompt;
+use crate::utils::timing::format_duration;
+
+pub mod access;
+pub mod alerts;
+pub mod assertions;
+pub mod checks;
+pub mod crashloop;
+pub mod disk;
+pub mod drift;
+pub mod fleet;
+pub mod heartbeat;
+pub mod history;
+pub mod incidents;
+pub mod latency;
+pub mod logs;
+pub mod network;
+pub mod ports;
+pub mod process;
+pub mod prometheus;
+pub mod report;
+pub mod structured;
+pub mod system;
+pub mod tunnel;
+pub mod watchdog;
+
+/// Window over which uptime and averages are computed from history
+const UPTIME_WINDOW_HOURS: i64 = 24;
+
+/// Process samples kept for trend display
+const PROCESS_HISTORY_LEN: usize = 60;
+
+/// Optional limits for continuous monitoring; unlimited runs stop on Ctrl-C
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunLimits {
+    pub duration: Option<Duration>,
+    pub cycles: Option<u64>,
+}
 
 /// Monitor the deployed application
+///
+/// Continuous monitoring takes over changes to the file `watcher` watches between cycles.
 pub async fn monitor_application(
     config: &Config,
     continuous: bool,
     lines: usize,
+    limits: RunLimits,
+    output: OutputFormat,
+    watcher: Option<ConfigWatcher>,
 ) -> Result<String> {
     log::operation_start("Starting application monitoring");
 
+    if config.hosts().len() > 1 {
+        let mut fleet = fleet::Fleet::new(config);
+        return if continuous {
+            fleet.run_continuous(limits, output, watcher).await
+        } else {
+            fleet.run_once(output).await
+        };
+    }
+
     let mut monitor = ApplicationMonitor::new(config.clone());
 
     if continuous {
-        monitor.run_continuous().await
+        monitor.run_continuous(limits, output, watcher).await
     } else {
-        monitor.run_once(lines).await
+        monitor.run_once(lines, output).await
     }
 }
 
@@ -30,50 +87,156 @@ pub async fn monitor_application(
 pub struct ApplicationMonitor {
     config: Config,
     http_client: Client,
+    health_assertions: Vec<assertions::JsonAssertion>,
+    alerts: Option<alerts::AlertManager>,
+    watchdog: Option<watchdog::Watchdog>,
+    history: Option<Mutex<history::HistoryStore>>,
+    last_scrape: Mutex<Option<prometheus::ScrapeSnapshot>>,
+    latency: Mutex<latency::LatencyWindow>,
+    process_history: Mutex<process::ProcessHistory>,
+    access_log: Mutex<access::AccessLogTracker>,
+    drift: Mutex<drift::DriftTracker>,
+    /// Opened on the first health check with `health_via = "ssh"`, and again after it drops
+    health_tunnel: tokio::sync::Mutex<Option<tunnel::HealthTunnel>>,
 }
 
 impl ApplicationMonitor {
     /// Create a new monitor instance
     pub fn new(config: Config) -> Self {
-        let http_client = Client::builder()
-            .timeout(Duration::from_secs(config.monitor.health_timeout_secs))
-            .build()
-            .unwrap_or_else(|_| Client::new());
+        let http_client = http_client(&config);
+        let health_assertions = health_assertions(&config);
+        let alerts = alerts::AlertManager::from_config(&config);
+        let watchdog = watchdog::Watchdog::from_config(config.monitor.watchdog.as_ref());
+        let history = history::HistoryStore::open(&config)
+            .map_err(|e| tracing::warn!("Monitoring history disabled: {}", e))
+            .ok()
+            .map(Mutex::new);
+
+        // Seed the latency window from history so percentiles survive restarts
+        let window = config.monitor.latency_window;
+        let recent = history
+            .as_ref()
+            .and_then(|history| history.lock().unwrap().recent_response_times(window).ok())
+            .unwrap_or_default();
+        let latency = Mutex::new(latency::LatencyWindow::new(window, recent));
 
         Self {
             config,
             http_client,
+            health_assertions,
+            alerts,
+            watchdog,
+            history,
+            last_scrape: Mutex::new(None),
+            latency,
+            process_history: Mutex::new(process::ProcessHistory::new(PROCESS_HISTORY_LEN)),
+            access_log: Mutex::new(access::AccessLogTracker::default()),
+            drift: Mutex::new(drift::DriftTracker::default()),
+            health_tunnel: tokio::sync::Mutex::new(None),
         }
     }
 
-    /// Run continuous monitoring
-    pub async fn run_continuous(&mut self) -> Result<String> {
-        log::monitor_event("Starting continuous monitoring");
+    /// Take over a reloaded configuration, keeping history and trends; `changed` lists the
+    /// settings that differ from the current one
+    fn reconfigure(&mut self, config: Config, changed: &[String]) {
+        let changed = |prefix: &str| changed.iter().any(|key| key.starts_with(prefix));
+        if changed("monitor.health_timeout_secs") {
+            self.http_client = http_client(&config);
+        }
+        if changed("monitor.health_assertions") {
+            self.health_assertions = health_assertions(&config);
+        }
+        // The tunnel leads to the old endpoint, through the old connection
+        if changed("monitor.health") || changed("deploy.") {
+            *self.health_tunnel.get_mut() = None;
+        }
+        // Alerts name the project and host, and their state belongs to the old target
+        if changed("monitor.alerts") || changed("project.name") || changed("deploy.vps_host") {
+            self.alerts = alerts::AlertManager::from_config(&config);
+        }
+        if changed("monitor.watchdog") {
+            self.watchdog = watchdog::Watchdog::from_config(config.monitor.watchdog.as_ref());
+        }
+        self.config = config;
+    }
 
-        let mut iteration = 0;
-        loop {
-            iteration += 1;
-            log::monitor_event(&format!("Monitoring cycle #{}", iteration));
+    /// Run continuous monitoring until Ctrl-C or a configured limit is reached
+    pub async fn run_continuous(
+        &mut self,
+        limits: RunLimits,
+        output: OutputFormat,
+        mut watcher: Option<ConfigWatcher>,
+    ) -> Result<String> {
+        log::monitor_event("Starting continuous monitoring (press Ctrl-C to stop)");
+
+        let mut clock = CycleClock::start(
+            self.config.monitor.interval_secs,
+            self.config.monitor.max_backoff_secs,
+            limits,
+        );
+        let mut session = SessionStats::default();
 
-            let status = self.check_status().await?;
-            self.display_status(&status);
+        loop {
+            log::monitor_event(&format!("Monitoring cycle #{}", clock.cycles + 1));
 
-            if iteration >= 10 {
+            let Some(status) = clock.until_shutdown(self.check_status()).await else {
                 break;
+            };
+            let status = status?;
+            match output {
+                OutputFormat::Text => self.display_status(&status),
+                OutputFormat::Json => {
+                    let metrics = self.metrics(&status);
+                    println!("{}", self.document(&status, &metrics).to_json(false)?);
+                }
+            }
+            session.record(&status);
+            self.track_incident(&status);
+
+            if let Some(alerts) = &mut self.alerts {
+                alerts.process(&status).await;
+            }
+            self.run_watchdog(&status).await;
+
+            if let Some(url) = &self.config.monitor.heartbeat_url {
+                heartbeat::ping(&self.http_client, url, status.is_healthy()).await;
             }
+            clock.backoff(status.is_unreachable());
 
-            sleep(Duration::from_secs(self.config.monitor.interval_secs)).await;
+            if !clock.wait_next().await {
+                break;
+            }
+            if let Some(watcher) = &mut watcher
+                && let Some((config, changed)) = reload_config(watcher, &self.config)
+            {
+                clock.set_interval(
+                    config.monitor.interval_secs,
+                    config.monitor.max_backoff_secs,
+                );
+                self.reconfigure(config, &changed);
+            }
         }
 
+        log::monitor_event(&format!(
+            "Monitoring summary: {}",
+            session.summary(clock.elapsed())
+        ));
         Ok("Continuous monitoring completed".to_string())
     }
 
     /// Run one-time monitoring check
-    pub async fn run_once(&mut self, lines: usize) -> Result<String> {
+    pub async fn run_once(&mut self, lines: usize, output: OutputFormat) -> Result<String> {
         log::monitor_event("Running one-time monitoring check");
 
         let status = self.check_status().await?;
+        if output == OutputFormat::Json {
+            let metrics = self.metrics(&status);
+            println!("{}", self.document(&status, &metrics).to_json(true)?);
+            return Ok("Monitoring check completed".to_string());
+        }
+
         self.display_status(&status);
+        self.display_metrics(&self.metrics(&status));
 
         if let Some(log_path) = &self.config.monitor.log_path {
             self.display_logs(log_path, lines).await?;
@@ -84,7 +247,17 @@ impl ApplicationMonitor {
 
     /// Check application status
     pub async fn check_status(&self) -> Result<ApplicationStatus> {
-        let mut status = ApplicationStatus::default();
+        // Measured separately from the health check to tell a slow network from a slow app
+        let network = network::probe(
+            &self.config.deploy.vps_host,
+            self.config.deploy.ssh_port,
+            Duration::from_secs(self.config.monitor.health_timeout_secs),
+        )
+        .await;
+        let mut status = ApplicationStatus {
+            network: Some(network),
+            ..Default::default()
+        };
 
         if let Some(endpoint) = &self.config.monitor.health_endpoint {
             let _health_start = Instant::now();
@@ -92,6 +265,10 @@ impl ApplicationMonitor {
                 Ok(response_time) => {
                     status.health_ok = true;
                     status.response_time = Some(response_time);
+                    self.latency
+                        .lock()
+                        .unwrap()
+                        .push(response_time.as_millis() as u64);
                     log::health_check(endpoint, true, Some(response_time.as_millis()));
                 }
                 Err(e) => {
@@ -102,80 +279,360 @@ impl ApplicationMonitor {
             }
         }
 
+        if let Some(endpoint) = &self.config.monitor.metrics_endpoint {
+            match prometheus::scrape(&self.http_client, endpoint).await {
+                Ok(samples) => {
+                    let mut last_scrape = self.last_scrape.lock().unwrap();
+                    let (summary, snapshot) = prometheus::summarize(
+                        &samples,
+                        &self.config.monitor.metrics_request_counter,
+                        &self.config.monitor.metrics_gauges,
+                        *last_scrape,
+                        Instant::now(),
+                    );
+                    *last_scrape = Some(snapshot);
+                    status.prometheus = Some(summary);
+                }
+                Err(e) => log::monitor_event(&format!("App metrics unavailable: {}", e)),
+            }
+        }
+
+        let (ssh_ports, direct_ports): (Vec<_>, Vec<_>) = self
+            .config
+            .monitor
+            .port_checks
+            .iter()
+            .partition(|check| check.via() == "ssh");
+
         match self.check_ssh_connection().await {
-            Ok(_) => {
+            Ok(host) => {
                 status.ssh_ok = true;
                 status.service_status = self.check_service_status().await.ok();
+                status.system = system::collect(host.as_ref(), &self.config.deploy.deploy_path)
+                    .map_err(|e| log::monitor_event(&format!("System metrics unavailable: {}", e)))
+                    .ok();
+
+                match process::collect(host.as_ref(), &self.config) {
+                    Ok(process) => {
+                        if let Some(process) = &process {
+                            self.process_history.lock().unwrap().push(process.clone());
+                        }
+                        status.process = process;
+                    }
+                    Err(e) => log::monitor_event(&format!("Process metrics unavailable: {}", e)),
+                }
+
+                match crashloop::detect(host.as_ref(), &self.config) {
+                    Ok(crash_loop) => status.crash_loop = crash_loop,
+                    Err(e) => log::monitor_event(&format!("Restart check unavailable: {}", e)),
+                }
+
+                if let Some(path) = &self.config.monitor.access_log_path {
+                    let format = &self.config.monitor.access_log_format;
+                    match self
+                        .access_log
+                        .lock()
+                        .unwrap()
+                        .sample(host.as_ref(), path, format)
+                    {
+                        Ok(histogram) => status.status_codes = Some(histogram),
+                        Err(e) => log::monitor_event(&format!("Access log unavailable: {}", e)),
+                    }
+                }
+
+                status.binary_drift = self
+                    .drift
+                    .lock()
+                    .unwrap()
+                    .sample(host.as_ref(), &self.config);
+                if let Some(drift) = &status.binary_drift {
+                    status.warnings.push(drift.summary());
+                }
+
+                match disk::check(host.as_ref(), &disk::watched_paths(&self.config)) {
+                    Ok(disks) => {
+                        let min_free_mb = self.config.deploy.min_free_disk_mb;
+                        for low in disks.iter().filter(|disk| disk.is_low(min_free_mb)) {
+                            status
+                                .warnings
+                                .push(format!("Low disk space: {}", low.summary()));
+                        }
+                        status.disks = disks;
+                    }
+                    Err(e) => log::monitor_event(&format!("Disk check unavailable: {}", e)),
+                }
+
+                if !ssh_ports.is_empty() {
+                    match ports::check_listening(host.as_ref(), &ssh_ports) {
+                        Ok(results) => status.ports.extend(results),
+                        Err(e) => {
+                            log::monitor_event(&format!("Port checks unavailable: {}", e));
+                            status.ports.extend(ports::unknown(&ssh_ports));
+                            status
+                                .warnings
+                                .push("Ports on the server could not be checked".to_string());
+                        }
+                    }
+                }
+
+                if !self.config.monitor.checks.is_empty() {
+                    status.checks = checks::run(host.as_ref(), &self.config.monitor.checks)
+                        .unwrap_or_else(|e| {
+                            log::monitor_event(&format!("Command checks unavailable: {}", e));
+                            checks::parse("", &self.config.monitor.checks)
+                        });
+                }
             }
             Err(e) => {
                 status.ssh_ok = false;
                 status.last_error = Some(format!("SSH connection failed: {}", e));
+                status.ports.extend(ports::unknown(&ssh_ports));
             }
         }
 
+        if !direct_ports.is_empty() {
+            let timeout = Duration::from_secs(self.config.monitor.health_timeout_secs);
+            status.ports.extend(
+                ports::check_direct(&self.config.deploy.vps_host, &direct_ports, timeout).await,
+            );
+        }
+
+        if let Some(history) = &self.history
+            && let Err(e) = history.lock().unwrap().record(&status)
+        {
+            tracing::warn!("Failed to record monitoring history: {}", e);
+        }
+
         Ok(status)
     }
 
-    /// Check health endpoint
+    /// Let the watchdog restart the service after sustained failures
+    async fn run_watchdog(&mut self, status: &ApplicationStatus) {
+        let Some(watchdog) = &mut self.watchdog else {
+            return;
+        };
+
+        match watchdog.evaluate(status, Instant::now()) {
+            watchdog::Decision::Wait => {}
+            watchdog::Decision::CoolingDown { remaining } => {
+                log::monitor_event(&format!(
+                    "Watchdog: restart due but cooling down for {}",
+                    format_duration(remaining)
+                ));
+            }
+            watchdog::Decision::Restart { failures } => {
+                watchdog.restarted(Instant::now());
+
+                let service = self.config.service_name();
+                let host = &self.config.deploy.vps_host;
+                let message = match self.restart_service().await {
+                    Ok(()) => format!(
+                        "Watchdog restarted {} on {} after {} consecutive failed checks",
+                        service, host, failures
+                    ),
+                    Err(e) => format!("Watchdog failed to restart {} on {}: {}", service, host, e),
+                };
+
+                match &self.alerts {
+                    Some(alerts) => {
+                        alerts
+                            .notify(alerts::AlertKind::WatchdogRestart, message)
+                            .await
+                    }
+                    None => log::monitor_event(&format!("{} {}", glyph("🐕"), message)),
+                }
+            }
+        }
+    }
+
+    /// Restart the systemd service over SSH
+    async fn restart_service(&self) -> Result<()> {
+        let host = self.check_ssh_connection().await?;
+        host.execute_command(&format!(
+            "sudo systemctl restart {}",
+            utils::ssh::shell_quote(&self.config.service_name())
+        ))?;
+        Ok(())
+    }
+
+    /// Open or resolve an incident when health changes, logging the transition
+    fn track_incident(&self, status: &ApplicationStatus) {
+        let Some(history) = &self.history else {
+            return;
+        };
+
+        let host = &self.config.deploy.vps_host;
+        match incidents::track(&history.lock().unwrap(), status, chrono::Utc::now()) {
+            Ok(Some(incidents::Transition::Started(incident))) => {
+                log::monitor_event(&format!(
+                    "{} Incident opened on {}: {}",
+                    glyph("🔥"),
+                    host,
+                    incident.cause
+                ));
+            }
+            Ok(Some(incidents::Transition::Resolved(incident))) => {
+                log::monitor_event(&format!(
+                    "{} Incident on {} resolved after {}",
+                    glyph("✅"),
+                    host,
+                    format_duration(incident.duration(chrono::Utc::now()))
+                ));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to track incident: {}", e),
+        }
+    }
+
+    /// JSON document for a status sample and its metrics
+    fn document<'a>(
+        &'a self,
+        status: &'a ApplicationStatus,
+        metrics: &'a MonitoringMetrics,
+    ) -> StatusDocument<'a> {
+        StatusDocument::new(&self.config.deploy.vps_host, status, metrics)
+    }
+
+    /// Build metrics for a status sample, using recorded history where available
+    pub fn metrics(&self, status: &ApplicationStatus) -> MonitoringMetrics {
+        let since = chrono::Utc::now() - chrono::Duration::hours(UPTIME_WINDOW_HOURS);
+        let summary = self
+            .history
+            .as_ref()
+            .and_then(|history| history.lock().unwrap().summary_since(since).ok())
+            .filter(|summary| summary.total_checks > 0);
+
+        let latency = self.latency.lock().unwrap().percentiles();
+        let (process_trend, rss_history) = {
+            let history = self.process_history.lock().unwrap();
+            (history.rss_trend(), history.rss_series())
+        };
+        let total_requests = status
+            .prometheus
+            .as_ref()
+            .and_then(|prometheus| prometheus.requests_total)
+            .map(|total| total as u64);
+
+        match summary {
+            Some(summary) => MonitoringMetrics {
+                uptime_percentage: summary.uptime_percentage().unwrap_or_default(),
+                average_response_time: summary.average_response_time_ms,
+                total_requests,
+                total_checks: Some(summary.total_checks),
+                error_count: summary.failed_checks,
+                last_check: chrono::Utc::now(),
+                system: status.system.clone(),
+                latency,
+                status_codes: status.status_codes.clone(),
+                process_trend,
+                rss_history,
+            },
+            None => MonitoringMetrics {
+                uptime_percentage: if status.is_healthy() { 100.0 } else { 0.0 },
+                average_response_time: status.response_time.map(|d| d.as_millis() as f64),
+                total_requests,
+                total_checks: None,
+                error_count: if status.last_error.is_some() { 1 } else { 0 },
+                last_check: chrono::Utc::now(),
+                system: status.system.clone(),
+                latency,
+                status_codes: status.status_codes.clone(),
+                process_trend,
+                rss_history,
+            },
+        }
+    }
+
+    /// Check health endpoint, retrying network failures and 5xx responses as `[retry]` says
     async fn check_health_endpoint(&self, endpoint: &str) -> Result<Duration> {
+        let policy = self.config.retry_policy();
+        utils::retry::with_backoff(&policy, "Health check", |_| self.probe_health(endpoint)).await
+    }
+
+    /// One request to the health endpoint; the response time leaves out reading the body
+    async fn probe_health(&self, endpoint: &str) -> Result<Duration> {
+        let mut health_tunnel = self.health_tunnel.lock().await;
+        let request = if tunnel::is_wanted(&self.config) {
+            let health_tunnel = match health_tunnel.take() {
+                Some(open) if open.is_open() => health_tunnel.insert(open),
+                _ => {
+                    health_tunnel.insert(tunnel::HealthTunnel::open(&self.config, endpoint).await?)
+                }
+            };
+            health_tunnel.client.get(health_tunnel.url.clone())
+        } else {
+            self.http_client.get(endpoint)
+        };
         let start = Instant::now();
 
-        let response = self
-            .http_client
-            .get(endpoint)
+        let response = request
             .send()
             .await
             .with_context(|| format!("Failed to connect to health endpoint: {}", endpoint))?;
 
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Health endpoint returned status: {}",
-                response.status()
-            ));
-        }
+        let status = response.status();
+        // Kept as the cause, so a 5xx can be told apart for retrying
+        let response = response
+            .error_for_status()
+            .with_context(|| format!("Health endpoint returned status: {}", status))?;
 
         let elapsed = start.elapsed();
+
+        let contains = self.config.monitor.health_body_contains.as_deref();
+        if contains.is_some() || !self.health_assertions.is_empty() {
+            let body = response
+                .text()
+                .await
+                .context("Failed to read health response body")?;
+            assertions::check_body(&body, contains, &self.health_assertions)?;
+        }
+
         Ok(elapsed)
     }
 
     /// Check SSH connection
-    async fn check_ssh_connection(&self) -> Result<Session> {
-        let ssh_config = utils::ssh::SshConfig {
-            host: self.config.deploy.vps_host.clone(),
-            port: self.config.deploy.ssh_port,
-            username: self.config.deploy.vps_user.clone(),
-            key_path: self.config.deploy.vps_key_path.clone(),
-            password: self.config.deploy.vps_password.clone(),
-        };
-
-        utils::ssh::connect_with_retry(&ssh_config, 2).await
+    async fn check_ssh_connection(&self) -> Result<Box<dyn Host>> {
+        // Monitoring cycles give up sooner than other commands
+        let attempts = self.config.deploy.ssh.max_retries.min(2);
+        utils::host::remote_with_retries(&self.config, attempts).await
     }
 
     /// Check systemd service status
     async fn check_service_status(&self) -> Result<String> {
-        let session = self.check_ssh_connection().await?;
+        let host = self.check_ssh_connection().await?;
         let service_name = self.config.service_name();
 
-        let (output, _) = utils::ssh::execute_command(
-            &session,
-            &format!("sudo systemctl is-active {}", service_name),
-        )?;
+        let (output, _) = host.execute_command(&format!(
+            "sudo systemctl is-active {}",
+            utils::ssh::shell_quote(&service_name)
+        ))?;
 
         Ok(output.trim().to_string())
     }
 
     /// Display logs from remote server
     async fn display_logs(&self, log_path: &str, lines: usize) -> Result<()> {
-        let session = self.check_ssh_connection().await?;
+        let host = self.check_ssh_connection().await?;
 
-        let (output, _) =
-            utils::ssh::execute_command(&session, &format!("tail -n {} {}", lines, log_path))?;
+        let (output, _) = host.execute_command(&format!(
+            "tail -n {} {}",
+            lines,
+            utils::ssh::shell_quote(log_path)
+        ))?;
 
         if output.trim().is_empty() {
             log::monitor_event("No log entries found");
         } else {
+            let converter = logs::TimestampConverter::for_config(
+                &self.config,
+                logs::server_timezone(host.as_ref()),
+            );
             log::monitor_event(&format!("Recent logs (last {} lines):", lines));
             for line in output.lines() {
+                let line = match &converter {
+                    Some(converter) => converter.convert(line),
+                    None => line.into(),
+                };
                 log::monitor_event(&format!("  {}", line));
             }
         }
@@ -183,49 +640,415 @@ impl ApplicationMonitor {
         Ok(())
     }
 
+    /// Display metrics aggregated from monitoring history
+    fn display_metrics(&self, metrics: &MonitoringMetrics) {
+        if let Some(checks) = metrics.total_checks {
+            log::monitor_event(&format!(
+                "Uptime ({}h): {:.2}% over {} checks ({} failed)",
+                UPTIME_WINDOW_HOURS, metrics.uptime_percentage, checks, metrics.error_count
+            ));
+        }
+
+        if let Some(average) = metrics.average_response_time {
+            log::monitor_event(&format!("Average Response Time: {:.0}ms", average));
+        }
+
+        if let Some(latency) = &metrics.latency {
+            log::monitor_event(&format!("Latency: {}", latency.summary()));
+        }
+
+        if let Some(status_codes) = &metrics.status_codes {
+            log::monitor_event(&format!("HTTP Status: {}", status_codes.summary()));
+        }
+
+        if let Some(trend) = &metrics.process_trend {
+            log::monitor_event(&format!("Process RSS trend: {}", trend));
+        }
+    }
+
     /// Display current status
     fn display_status(&self, status: &ApplicationStatus) {
-        log::monitor_event(&format!(
-            "Health Status: {}",
-            if status.health_ok {
-                "✅ OK"
-            } else {
-                "❌ FAIL"
-            }
-        ));
-        log::monitor_event(&format!(
-            "SSH Connection: {}",
-            if status.ssh_ok { "✅ OK" } else { "❌ FAIL" }
-        ));
+        log::monitor_event(&format!("Health Status: {}", pass_fail(status.health_ok)));
+        log::monitor_event(&format!("SSH Connection: {}", pass_fail(status.ssh_ok)));
 
         if let Some(response_time) = status.response_time {
             log::monitor_event(&format!("Response Time: {}ms", response_time.as_millis()));
         }
 
+        if let Some(network) = &status.network {
+            log::monitor_event(&format!("Network Latency: {}", network.summary()));
+        }
+
         if let Some(service_status) = &status.service_status {
             log::monitor_event(&format!("Service Status: {}", service_status));
         }
 
+        if !status.ports.is_empty() {
+            log::monitor_event(&format!("Ports: {}", status.ports_summary()));
+        }
+
+        if !status.checks.is_empty() {
+            log::monitor_event(&format!("Checks: {}", status.checks_summary()));
+            for check in status.checks.iter().filter(|check| !check.passed()) {
+                for line in &check.output {
+                    log::monitor_event(&format!("  {}: {}", check.name, line));
+                }
+            }
+        }
+
+        if let Some(system) = &status.system {
+            log::monitor_event(&format!("System: {}", system.summary()));
+        }
+
+        if let Some(prometheus) = &status.prometheus {
+            log::monitor_event(&format!("App Metrics: {}", prometheus.summary()));
+        }
+
+        if let Some(process) = &status.process {
+            log::monitor_event(&format!("Process: {}", process.summary()));
+        }
+
+        if let Some(crash_loop) = &status.crash_loop {
+            log::monitor_event(&format!("{} {}", glyph("🔁"), crash_loop.summary()));
+            for line in &crash_loop.excerpt {
+                log::monitor_event(&format!("  {}", line));
+            }
+        }
+
+        for disk in &status.disks {
+            log::monitor_event(&format!("Disk: {}", disk.summary()));
+        }
+
+        for warning in &status.warnings {
+            log::monitor_event(&format!("{} {}", glyph("⚠️"), warning));
+        }
+
         if let Some(error) = &status.last_error {
             log::monitor_event(&format!("Last Error: {}", error));
         }
     }
 }
 
+/// HTTP client for health checks, with the configured timeout
+fn http_client(config: &Config) -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(config.monitor.health_timeout_secs))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+fn health_assertions(config: &Config) -> Vec<assertions::JsonAssertion> {
+    config
+        .monitor
+        .health_assertions
+        .iter()
+        .filter_map(|expression| {
+            assertions::JsonAssertion::parse(expression)
+                .map_err(|e| tracing::warn!("Ignoring health assertion: {}", e))
+                .ok()
+        })
+        .collect()
+}
+
+/// Configuration to continue with after the watched config file changed, with the settings
+/// that changed
+///
+/// Changes that need a new connection are only taken after confirming them.
+fn reload_config(watcher: &mut ConfigWatcher, current: &Config) -> Option<(Config, Vec<String>)> {
+    let reload = match watcher.poll()? {
+        Ok(config) => Reload::new(current, config),
+        Err(e) => {
+            log::monitor_event(&format!("{} {:#}", glyph("⚠️"), e));
+            return None;
+        }
+    };
+    let mut changed = reload.applied.clone();
+    let config = if !reload.reconnect.is_empty() && confirm_reconnect(&reload.reconnect) {
+        match reload.clone().with_reconnect() {
+            Ok(config) => {
+                changed.extend(reload.reconnect);
+                config
+            }
+            Err(e) => {
+                log::monitor_event(&format!("{} Not reconnecting: {:#}", glyph("⚠️"), e));
+                reload.without_reconnect()
+            }
+        }
+    } else {
+        reload.without_reconnect()
+    };
+    if changed.is_empty() {
+        return None;
+    }
+    log::monitor_event(&format!("Config reloaded: {}", changed.join(", ")));
+    Some((config, changed))
+}
+
+/// Ask on the terminal whether to use changed connection settings from the next cycle on
+///
+/// Without a terminal they are left for the next start, unless `--yes` accepts them.
+fn confirm_reconnect(keys: &[String]) -> bool {
+    let asked = prompt::can_ask();
+    let confirmed = prompt::confirm(
+        &format!(
+            "Connection settings changed ({}). Reconnect with them?",
+            keys.join(", ")
+        ),
+        false,
+    );
+    if confirmed && !asked {
+        log::monitor_event(&format!(
+            "Reconnecting with the changed connection settings: {}",
+            keys.join(", ")
+        ));
+    } else if !confirmed && asked {
+        log::monitor_event("Keeping the current connection settings");
+    } else if !confirmed {
+        log::monitor_event(&format!(
+            "{} Restart monitoring to use the changed connection settings: {}",
+            glyph("⚠️"),
+            keys.join(", ")
+        ));
+    }
+    confirmed
+}
+
+/// Paces continuous monitoring cycles, stopping on Ctrl-C or a configured limit
+struct CycleClock {
+    interval: Duration,
+    max_delay: Duration,
+    /// Current wait between cycles, above `interval` while backing off
+    delay: Duration,
+    limits: RunLimits,
+    started: Instant,
+    cycles: u64,
+    shutdown: CancellationToken,
+    ctrl_c: tokio::task::JoinHandle<()>,
+    /// Keeps the first Ctrl-C from quitting before the summary is printed
+    _interrupt: utils::interrupt::Handler,
+}
+
+impl CycleClock {
+    fn start(interval_secs: u64, max_backoff_secs: u64, limits: RunLimits) -> Self {
+        let shutdown = CancellationToken::new();
+        let token = shutdown.clone();
+        let ctrl_c = tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                token.cancel();
+            }
+        });
+
+        let interval = Duration::from_secs(interval_secs);
+        Self {
+            interval,
+            max_delay: Duration::from_secs(max_backoff_secs).max(interval),
+            delay: interval,
+            limits,
+            started: Instant::now(),
+            cycles: 0,
+            shutdown,
+            ctrl_c,
+            _interrupt: utils::interrupt::handle(),
+        }
+    }
+
+    /// Use a reloaded interval from the next wait on, ending any backoff
+    fn set_interval(&mut self, interval_secs: u64, max_backoff_secs: u64) {
+        self.interval = Duration::from_secs(interval_secs);
+        self.max_delay = Duration::from_secs(max_backoff_secs).max(self.interval);
+        self.delay = self.interval;
+    }
+
+    /// Run `future` unless Ctrl-C arrives first
+    async fn until_shutdown<F: std::future::Future>(&self, future: F) -> Option<F::Output> {
+        tokio::select! {
+            output = future => Some(output),
+            _ = self.shutdown.cancelled() => None,
+        }
+    }
+
+    /// Double the wait while the target is unreachable, up to the cap, and
+    /// return to the normal interval once it answers again
+    fn backoff(&mut self, unreachable: bool) {
+        let next = if unreachable {
+            (self.delay * 2).min(self.max_delay)
+        } else {
+            self.interval
+        };
+        if next == self.delay {
+            return;
+        }
+
+        if unreachable {
+            log::monitor_event(&format!(
+                "Target unreachable, backing off: next check in {}",
+                format_duration(next)
+            ));
+        } else {
+            log::monitor_event(&format!(
+                "Target reachable again, resuming {} interval",
+                format_duration(next)
+            ));
+        }
+        self.delay = next;
+    }
+
+    /// Count a finished cycle and sleep until the next one; `false` means stop
+    async fn wait_next(&mut self) -> bool {
+        self.cycles += 1;
+        if self
+            .limits
+            .cycles
+            .is_some_and(|cycles| self.cycles >= cycles)
+        {
+            return false;
+        }
+
+        let deadline = self.limits.duration.map(|duration| self.started + duration);
+        let mut wait = self.delay;
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            wait = wait.min(remaining);
+        }
+
+        self.until_shutdown(sleep(wait)).await.is_some()
+            && deadline.is_none_or(|deadline| Instant::now() < deadline)
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+impl Drop for CycleClock {
+    fn drop(&mut self) {
+        self.ctrl_c.abort();
+    }
+}
+
+/// Running totals for a continuous monitoring session
+#[derive(Debug, Clone, Default)]
+struct SessionStats {
+    cycles: u64,
+    healthy: u64,
+    response_time_total: Duration,
+    response_samples: u32,
+}
+
+impl SessionStats {
+    fn record(&mut self, status: &ApplicationStatus) {
+        self.cycles += 1;
+        if status.is_healthy() {
+            self.healthy += 1;
+        }
+        if let Some(response_time) = status.response_time {
+            self.response_time_total += response_time;
+            self.response_samples += 1;
+        }
+    }
+
+    fn summary(&self, elapsed: Duration) -> String {
+        if self.cycles == 0 {
+            return format!("no checks completed in {}", format_duration(elapsed));
+        }
+
+        let uptime = self.healthy as f64 / self.cycles as f64 * 100.0;
+        let latency = if self.response_samples > 0 {
+            format!(
+                "{}ms",
+                (self.response_time_total / self.response_samples).as_millis()
+            )
+        } else {
+            "N/A".to_string()
+        };
+
+        format!(
+            "{} checks over {}, uptime {:.2}%, avg latency {}",
+            self.cycles,
+            format_duration(elapsed),
+            uptime,
+            latency
+        )
+    }
+}
+
 /// Application status information
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ApplicationStatus {
     pub health_ok: bool,
     pub ssh_ok: bool,
+    #[serde(rename = "response_time_ms", serialize_with = "serialize_millis")]
     pub response_time: Option<Duration>,
+    /// TCP round trip to the host, independent of the application
+    pub network: Option<network::NetworkProbe>,
     pub service_status: Option<String>,
     pub last_error: Option<String>,
+    pub system: Option<system::SystemMetrics>,
+    pub prometheus: Option<prometheus::PrometheusSummary>,
+    pub ports: Vec<ports::PortStatus>,
+    pub checks: Vec<checks::CheckResult>,
+    pub disks: Vec<disk::DiskSpace>,
+    pub crash_loop: Option<crashloop::CrashLoop>,
+    pub process: Option<process::ProcessMetrics>,
+    /// HTTP status classes from the access log since the previous check
+    pub status_codes: Option<access::StatusHistogram>,
+    /// Deployed binary changed since the last rzen deploy
+    pub binary_drift: Option<drift::BinaryDrift>,
+    /// Conditions worth attention that do not make the app unhealthy
+    pub warnings: Vec<String>,
 }
 
 impl ApplicationStatus {
     /// Check if application is healthy
     pub fn is_healthy(&self) -> bool {
-        self.health_ok && self.ssh_ok && matches!(self.service_status.as_deref(), Some("active"))
+        self.health_ok
+            && self.ssh_ok
+            && matches!(self.service_status.as_deref(), Some("active"))
+            && self.ports.iter().all(|port| port.listening != Some(false))
+            && self.checks.iter().all(checks::CheckResult::passed)
+            && self.crash_loop.is_none()
+    }
+
+    /// Neither the health endpoint nor SSH answered
+    pub fn is_unreachable(&self) -> bool {
+        !self.health_ok && !self.ssh_ok
+    }
+
+    /// Port check results, e.g. `5432/tcp ✅, 6379/tcp ❌`
+    pub fn ports_summary(&self) -> String {
+        self.ports
+            .iter()
+            .map(|port| {
+                format!(
+                    "{} {}",
+                    port.label(),
+                    glyph(match port.listening {
+                        Some(true) => "✅",
+                        Some(false) => "❌",
+                        None => "❔",
+                    })
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Command check results, e.g. `postgres ✅, queue ❌`
+    pub fn checks_summary(&self) -> String {
+        self.checks
+            .iter()
+            .map(|check| {
+                format!(
+                    "{} {}",
+                    check.name,
+                    glyph(if check.passed() { "✅" } else { "❌" })
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 
     /// Get status summary
@@ -236,13 +1059,31 @@ impl ApplicationStatus {
             let mut issues = Vec::new();
 
             if !self.health_ok {
-                issues.push("Health check failing");
+                issues.push("Health check failing".to_string());
             }
             if !self.ssh_ok {
-                issues.push("SSH connection failed");
+                issues.push("SSH connection failed".to_string());
+            }
+            if let Some(network) = self.network.as_ref().filter(|network| !network.reachable()) {
+                issues.push(format!("Host unreachable on port {}", network.port));
             }
             if !matches!(self.service_status.as_deref(), Some("active")) {
-                issues.push("Service not active");
+                issues.push("Service not active".to_string());
+            }
+            if let Some(crash_loop) = &self.crash_loop {
+                issues.push(crash_loop.summary());
+            }
+            for port in self
+                .ports
+                .iter()
+                .filter(|port| port.listening == Some(false))
+            {
+                issues.push(format!("Port {} not listening", port.label()));
+            }
+            for check in &self.checks {
+                if let Some(failure) = check.failure() {
+                    issues.push(format!("Check '{}' failed ({})", check.name, failure));
+                }
             }
 
             if issues.is_empty() {
@@ -275,87 +1116,184 @@ impl From<&Config> for MonitorConfig {
 }
 
 /// Stream logs in real-time
-pub async fn stream_logs(config: &Config) -> Result<()> {
+pub async fn stream_logs(
+    config: &Config,
+    source: &logs::LogSource,
+    query: &logs::LogQuery,
+    filter: &logs::LogFilter,
+    columns: Option<Vec<structured::LogField>>,
+) -> Result<()> {
     log::operation_start("Streaming logs in real-time");
+    log::monitor_event(&format!("Tailing logs from: {}", source.describe()));
 
-    // Create SSH connection
-    let ssh_config = crate::utils::ssh::SshConfig {
-        host: config.deploy.vps_host.clone(),
-        port: config.deploy.ssh_port,
-        username: config.deploy.vps_user.clone(),
-        key_path: config.deploy.vps_key_path.clone(),
-        password: config.deploy.vps_password.clone(),
-    };
+    follow_logs(config, source, query, filter, columns, |line| {
+        log::monitor_event(line);
+        true
+    })
+    .await?;
 
-    let session = crate::utils::ssh::connect_with_retry(&ssh_config, 3).await?;
+    log::operation_success("Log streaming ended");
+    Ok(())
+}
 
-    // Get log path from config or use default
-    let log_path = config
-        .monitor
-        .log_path
-        .as_deref()
-        .unwrap_or("/var/log/my-rust-app.log");
-
-    log::monitor_event(&format!("Tailing logs from: {}", log_path));
-
-    // Use tail -f to stream logs
-    let command = format!("tail -f -n 50 {}", log_path);
-
-    match session.channel_session() {
-        Ok(mut channel) => {
-            channel.exec(&command)?;
-
-            let mut buf = [0; 1024];
-            loop {
-                match channel.read(&mut buf) {
-                    Ok(0) => break, // EOF
-                    Ok(n) => {
-                        let log_line = String::from_utf8_lossy(&buf[..n]);
-                        for line in log_line.lines() {
-                            if !line.trim().is_empty() {
-                                log::monitor_event(&format!("📜 {}", line));
-                            }
+/// Follow `source` on the server, passing each formatted output line to `on_line`
+///
+/// Rotation notices from `tail -F` are passed as `🔄` markers. Returns when the
+/// stream ends or `on_line` returns false.
+pub async fn follow_logs(
+    config: &Config,
+    source: &logs::LogSource,
+    query: &logs::LogQuery,
+    filter: &logs::LogFilter,
+    columns: Option<Vec<structured::LogField>>,
+    mut on_line: impl FnMut(&str) -> bool,
+) -> Result<()> {
+    // Create SSH connection
+    let ssh_config = config.ssh_config();
+
+    // A session of its own, since following switches it to non-blocking mode
+    let connection = utils::ssh::Connection::new(
+        crate::utils::ssh::connect_with_retry(&ssh_config, ssh_config.options.max_retries).await?,
+        &ssh_config,
+    );
+    let session = &connection.session;
+
+    // Use tail -F to stream logs, filtering on the server where possible
+    let command = filter.remote_command(source, query);
+    let server_timezone = logs::server_timezone(&connection);
+    let converter = logs::TimestampConverter::for_config(config, server_timezone);
+    let mut formatter = structured::LogFormatter::new(columns, converter);
+    let mut range = logs::RangeFilter::new(query.range, server_timezone);
+    let mut levels = logs::SeverityFilter::new(query.level);
+
+    let mut channel = session
+        .channel_session()
+        .map_err(|e| anyhow!("Failed to create SSH channel: {}", e))?;
+    // tail reports rotation on stderr, which bypasses the grep stages
+    channel.handle_extended_data(ssh2::ExtendedData::Merge)?;
+    utils::ssh::exec(&mut channel, &command, connection.sudo_password.as_deref())?;
+    // Non-blocking reads let the task yield while the log is quiet, so it can be cancelled
+    session.set_blocking(false);
+
+    let mut buf = [0; 1024];
+    let mut pending = String::new();
+    loop {
+        match channel.read(&mut buf) {
+            Ok(0) => break, // EOF
+            Ok(n) => {
+                pending.push_str(&String::from_utf8_lossy(&buf[..n]));
+                // Only complete lines can be filtered reliably
+                while let Some(end) = pending.find('\n') {
+                    let line: String = pending.drain(..=end).collect();
+                    let line = line.trim_end();
+                    let outputs = if let Some(event) = logs::RotationEvent::parse(line) {
+                        vec![format!("{} {}", glyph("🔄"), event.marker())]
+                    } else if !line.trim().is_empty()
+                        && levels.accepts(line)
+                        && range.accepts(line)
+                        && filter.matches(line)
+                    {
+                        formatter.format(line)
+                    } else {
+                        Vec::new()
+                    };
+                    for output in outputs {
+                        if !on_line(&output) {
+                            return Ok(());
                         }
                     }
-                    Err(_) => break,
                 }
-
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                // Keeps a quiet stream's connection open; one goes out every `keepalive_interval_secs`
+                let _ = session.keepalive_send();
                 // Small delay to prevent busy waiting
                 tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
             }
-        }
-        Err(e) => {
-            return Err(anyhow!("Failed to create SSH channel: {}", e));
+            Err(_) => break,
         }
     }
 
-    log::operation_success("Log streaming ended");
     Ok(())
 }
 
-/// Get monitoring metrics
-pub async fn get_metrics(config: &Config) -> Result<MonitoringMetrics> {
-    let monitor = ApplicationMonitor::new(config.clone());
-    let status = monitor.check_status().await?;
-
-    Ok(MonitoringMetrics {
-        uptime_percentage: if status.is_healthy() { 100.0 } else { 0.0 }, // Simplified
-        average_response_time: status.response_time.map(|d| d.as_millis() as f64),
-        total_requests: None, // Would need more sophisticated monitoring
-        error_count: if status.last_error.is_some() { 1 } else { 0 },
-        last_check: chrono::Utc::now(),
-    })
-}
-
 /// Monitoring metrics structure
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[allow(dead_code)]
 pub struct MonitoringMetrics {
     pub uptime_percentage: f64,
     pub average_response_time: Option<f64>,
+    /// Requests the app has served, from its metrics endpoint's request counter
     pub total_requests: Option<u64>,
+    /// Health checks recorded in monitoring history over the uptime window
+    pub total_checks: Option<u64>,
     pub error_count: u64,
     pub last_check: chrono::DateTime<chrono::Utc>,
+    pub system: Option<system::SystemMetrics>,
+    pub latency: Option<latency::LatencyPercentiles>,
+    pub status_codes: Option<access::StatusHistogram>,
+    /// RSS change of the service process across recent checks
+    pub process_trend: Option<String>,
+    /// RSS samples (KB) of the service process, oldest first
+    pub rss_history: Vec<u64>,
+}
+
+/// Document printed per host and check by `--output json`
+///
+/// Field names are part of the CLI's interface; add fields rather than
+/// renaming or removing them.
+#[derive(Debug, Serialize)]
+pub struct StatusDocument<'a> {
+    pub host: &'a str,
+    pub healthy: bool,
+    pub summary: String,
+    pub status: &'a ApplicationStatus,
+    pub metrics: &'a MonitoringMetrics,
+}
+
+impl<'a> StatusDocument<'a> {
+    pub fn new(
+        host: &'a str,
+        status: &'a ApplicationStatus,
+        metrics: &'a MonitoringMetrics,
+    ) -> Self {
+        Self {
+            host,
+            healthy: status.is_healthy(),
+            summary: status.summary(),
+            status,
+            metrics,
+        }
+    }
+
+    /// Serialize as pretty JSON, or as a single line for streaming
+    pub fn to_json(&self, pretty: bool) -> Result<String> {
+        let json = if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        };
+        json.context("Failed to serialize status")
+    }
+}
+
+/// `✅ OK` or `❌ FAIL` for a status line
+fn pass_fail(ok: bool) -> String {
+    if ok {
+        format!("{} OK", glyph("✅"))
+    } else {
+        format!("{} FAIL", glyph("❌"))
+    }
+}
+
+/// Serialize an optional duration as whole milliseconds
+fn serialize_millis<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    duration
+        .map(|duration| duration.as_millis() as u64)
+        .serialize(serializer)
 }
 
 #[cfg(test)]
@@ -371,6 +1309,7 @@ mod tests {
             response_time: Some(Duration::from_millis(50)),
             service_status: Some("active".to_string()),
             last_error: None,
+            ..Default::default()
         };
 
         assert!(healthy_status.is_healthy());
@@ -382,15 +1321,150 @@ mod tests {
             response_time: None,
             service_status: Some("failed".to_string()),
             last_error: Some("Health check failed".to_string()),
+            ..Default::default()
         };
 
         assert!(!unhealthy_status.is_healthy());
         assert!(unhealthy_status.summary().contains("Issues"));
+
+        let closed_port = ApplicationStatus {
+            ports: vec![ports::PortStatus {
+                port: 5432,
+                protocol: "tcp".to_string(),
+                via: "ssh".to_string(),
+                listening: Some(false),
+            }],
+            ..healthy_status.clone()
+        };
+
+        assert!(!closed_port.is_healthy());
+        assert_eq!(closed_port.summary(), "Issues: Port 5432/tcp not listening");
+        assert_eq!(closed_port.ports_summary(), "5432/tcp ❌");
+
+        // A port that couldn't be checked is neither closed nor known to be open
+        let mut unknown_port = closed_port.clone();
+        unknown_port.ports[0].listening = None;
+        assert!(unknown_port.is_healthy());
+        assert_eq!(unknown_port.ports_summary(), "5432/tcp ❔");
+
+        let failed_check = ApplicationStatus {
+            checks: vec![checks::CheckResult {
+                name: "postgres".to_string(),
+                exit_code: Some(2),
+                output: Vec::new(),
+            }],
+            ..healthy_status
+        };
+
+        assert!(!failed_check.is_healthy());
+        assert_eq!(
+            failed_check.summary(),
+            "Issues: Check 'postgres' failed (exit 2)"
+        );
+        assert_eq!(failed_check.checks_summary(), "postgres ❌");
+    }
+
+    #[tokio::test]
+    async fn test_cycle_clock_backoff() {
+        let mut clock = CycleClock::start(10, 35, RunLimits::default());
+
+        clock.backoff(true);
+        assert_eq!(clock.delay, Duration::from_secs(20));
+        clock.backoff(true);
+        clock.backoff(true);
+        assert_eq!(clock.delay, Duration::from_secs(35));
+
+        clock.backoff(false);
+        assert_eq!(clock.delay, Duration::from_secs(10));
+
+        let capped = CycleClock::start(60, 30, RunLimits::default());
+        assert_eq!(capped.max_delay, Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn test_check_status_on_mock_host() {
+        let host = utils::host::mock::MockHost::register("monitor.mock.invalid");
+        let dir = tempfile::tempdir().unwrap();
+        let config: Config = toml::from_str(&format!(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "monitor.mock.invalid"
+vps_user = "deploy"
+deploy_path = "/opt/app"
+min_free_disk_mb = 1024
+
+[monitor]
+history_path = "{}"
+"#,
+            dir.path().join("history.db").display()
+        ))
+        .unwrap();
+        host.reply(
+            "df -Pk",
+            "deploy_path /dev/sda1 10485760 9961472 524288 95% /",
+        )
+        .reply("systemctl is-active", "active")
+        .fail("--restarts", "sudo: a password is required");
+
+        let monitor = ApplicationMonitor::new(config);
+        let status = monitor.check_status().await.unwrap();
+        assert!(status.ssh_ok);
+        assert_eq!(status.service_status.as_deref(), Some("active"));
+        assert_eq!(status.disks.len(), 1);
+        assert_eq!(status.disks[0].mount, "/");
+        assert!(
+            status
+                .warnings
+                .iter()
+                .any(|warning| warning.starts_with("Low disk space: deploy_path"))
+        );
+        // A collector that fails leaves its part of the status out
+        assert!(status.crash_loop.is_none());
+        assert!(host.ran("sudo journalctl -u 'app.service'"));
+
+        // History counts checks; requests only come from the app's own metrics
+        let metrics = monitor.metrics(&status);
+        assert_eq!(metrics.total_checks, Some(1));
+        assert_eq!(metrics.total_requests, None);
+    }
+
+    #[test]
+    fn test_session_stats_summary() {
+        let mut session = SessionStats::default();
+        assert_eq!(
+            session.summary(Duration::from_secs(5)),
+            "no checks completed in 5.0s"
+        );
+
+        let healthy = ApplicationStatus {
+            health_ok: true,
+            ssh_ok: true,
+            response_time: Some(Duration::from_millis(100)),
+            service_status: Some("active".to_string()),
+            ..Default::default()
+        };
+        let slow_failure = ApplicationStatus {
+            response_time: Some(Duration::from_millis(300)),
+            ..Default::default()
+        };
+
+        session.record(&healthy);
+        session.record(&healthy);
+        session.record(&healthy);
+        session.record(&slow_failure);
+        assert_eq!(
+            session.summary(Duration::from_secs(90)),
+            "4 checks over 1m 30s, uptime 75.00%, avg latency 150ms"
+        );
     }
 
     #[test]
     fn test_monitor_config_from_config() {
         let config = Config {
+            version: crate::config::CONFIG_VERSION,
             project: crate::config::ProjectConfig {
                 path: ".".to_string(),
                 name: "test".to_string(),
@@ -405,13 +1479,22 @@ mod tests {
                 deploy_path: "/opt/app".to_string(),
                 service_name: None,
                 ssh_port: 22,
+                ..Default::default()
             },
             monitor: crate::config::MonitorConfig {
                 health_endpoint: Some("http://example.com/health".to_string()),
                 log_path: Some("/var/log/app.log".to_string()),
                 interval_secs: 30,
                 health_timeout_secs: 10,
+                ..Default::default()
             },
+            hooks: Default::default(),
+            tui: Default::default(),
+            retry: Default::default(),
+            env: Default::default(),
+            projects: Default::default(),
+            overrides: Default::default(),
+            extends: None,
         };
 
         let monitor_config = MonitorConfig::from(&config);
@@ -429,13 +1512,56 @@ mod tests {
             uptime_percentage: 99.9,
             average_response_time: Some(45.5),
             total_requests: Some(1000),
+            total_checks: Some(288),
             error_count: 2,
             last_check: chrono::Utc::now(),
+            system: None,
+            latency: None,
+            status_codes: None,
+            process_trend: None,
+            rss_history: Vec::new(),
         };
 
         assert_eq!(metrics.uptime_percentage, 99.9);
         assert_eq!(metrics.average_response_time, Some(45.5));
         assert_eq!(metrics.total_requests, Some(1000));
+        assert_eq!(metrics.total_checks, Some(288));
         assert_eq!(metrics.error_count, 2);
     }
+
+    #[test]
+    fn test_status_document_json() {
+        let status = ApplicationStatus {
+            health_ok: true,
+            ssh_ok: true,
+            response_time: Some(Duration::from_millis(42)),
+            service_status: Some("active".to_string()),
+            ..Default::default()
+        };
+        let metrics = MonitoringMetrics {
+            uptime_percentage: 100.0,
+            average_response_time: Some(42.0),
+            total_requests: None,
+            total_checks: None,
+            error_count: 0,
+            last_check: chrono::Utc::now(),
+            system: None,
+            latency: None,
+            status_codes: None,
+            process_trend: None,
+            rss_history: Vec::new(),
+        };
+
+        let json = StatusDocument::new("app.example.com", &status, &metrics)
+            .to_json(false)
+            .unwrap();
+        assert!(!json.contains('\n'));
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["host"], "app.example.com");
+        assert_eq!(value["healthy"], true);
+        assert_eq!(value["status"]["response_time_ms"], 42);
+        assert_eq!(value["status"]["service_status"], "active");
+        assert_eq!(value["metrics"]["uptime_percentage"], 100.0);
+    }
 }
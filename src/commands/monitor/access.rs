@@ -0,0 +1,164 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::time::Instant;
+
+use crate::utils;
+use crate::utils::host::Host;
+
+/// Lines read from the end of the access log on the first sample
+const INITIAL_LINES: usize = 1000;
+
+/// Requests per HTTP status class from the access log
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StatusHistogram {
+    /// Request counts for 1xx through 5xx
+    pub counts: [u64; 5],
+    /// Request rate since the previous sample; `None` on the first sample
+    pub requests_per_sec: Option<f64>,
+}
+
+impl StatusHistogram {
+    /// Requests counted for a status class, e.g. `5` for 5xx
+    pub fn count(&self, class: u8) -> u64 {
+        match class {
+            1..=5 => self.counts[class as usize - 1],
+            _ => 0,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// One-line summary, e.g. `2xx 1520 | 3xx 12 | 4xx 40 | 5xx 3 | 12.3 req/s`
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = (2..=5)
+            .map(|class| format!("{}xx {}", class, self.count(class)))
+            .collect();
+        if let Some(rate) = self.requests_per_sec {
+            parts.push(format!("{:.1} req/s", rate));
+        }
+        parts.join(" | ")
+    }
+}
+
+/// Follows the access log between samples so each request is counted once
+#[derive(Debug, Clone, Default)]
+pub struct AccessLogTracker {
+    inode: Option<u64>,
+    offset: Option<u64>,
+    last_sample: Option<Instant>,
+}
+
+impl AccessLogTracker {
+    /// Count status codes written since the previous sample
+    pub fn sample(&mut self, host: &dyn Host, path: &str, format: &str) -> Result<StatusHistogram> {
+        let (output, _) = host.execute_command(&self.command(path, format))?;
+        self.update(&output, Instant::now())
+    }
+
+    /// Remote command that aggregates status classes server-side
+    ///
+    /// The first sample reads the last [`INITIAL_LINES`] lines; later samples
+    /// read from the previous offset, or from the start when the file was
+    /// rotated (new inode) or truncated.
+    pub fn command(&self, path: &str, format: &str) -> String {
+        let awk = match format {
+            "json" => {
+                r#"awk 'match($0, /"status": *[1-5][0-9][0-9]/) { c[substr($0, RSTART + RLENGTH - 3, 1)]++ } END { for (k in c) print k, c[k] }'"#
+            }
+            _ => {
+                r#"awk 'match($0, /" [1-5][0-9][0-9] /) { c[substr($0, RSTART + 2, 1)]++ } END { for (k in c) print k, c[k] }'"#
+            }
+        };
+        let stat = format!(
+            "f={}; s=$(stat -c '%i %s' \"$f\") || exit 1; set -- $s; echo \"file $1 $2\"",
+            utils::ssh::shell_quote(path)
+        );
+
+        match (self.inode, self.offset) {
+            (Some(inode), Some(offset)) => format!(
+                "{stat}; if [ \"$1\" = \"{inode}\" ] && [ \"$2\" -ge {offset} ]; \
+                 then start={offset}; else start=0; fi; \
+                 tail -c +$((start + 1)) \"$f\" | head -c $(($2 - start)) | {awk}"
+            ),
+            _ => format!("{stat}; tail -n {INITIAL_LINES} \"$f\" | {awk}"),
+        }
+    }
+
+    /// Parse the output of [`command`](Self::command) and advance to the new end of file
+    pub fn update(&mut self, output: &str, now: Instant) -> Result<StatusHistogram> {
+        let mut histogram = StatusHistogram::default();
+        let mut file = None;
+
+        for line in output.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                ["file", inode, size] => {
+                    file = inode.parse::<u64>().ok().zip(size.parse::<u64>().ok());
+                }
+                [class, count] => {
+                    let class: u8 = class.parse().unwrap_or_default();
+                    if let (1..=5, Ok(count)) = (class, count.parse::<u64>()) {
+                        histogram.counts[class as usize - 1] += count;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let (inode, size) =
+            file.ok_or_else(|| anyhow!("Unexpected access log output: {}", output.trim()))?;
+
+        if let Some(last) = self.last_sample {
+            let elapsed = now.duration_since(last).as_secs_f64();
+            if elapsed > 0.0 {
+                histogram.requests_per_sec = Some(histogram.total() as f64 / elapsed);
+            }
+        }
+
+        self.inode = Some(inode);
+        self.offset = Some(size);
+        self.last_sample = Some(now);
+        Ok(histogram)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_histogram_from_samples() {
+        let mut tracker = AccessLogTracker::default();
+        assert!(
+            tracker
+                .command("/var/log/nginx/access.log", "combined")
+                .contains("tail -n 1000")
+        );
+
+        let start = Instant::now();
+        let first = tracker
+            .update("file 42 1000\n2 90\n4 8\n5 2\n", start)
+            .unwrap();
+        assert_eq!(first.total(), 100);
+        assert_eq!(first.count(5), 2);
+        assert_eq!(first.requests_per_sec, None);
+        assert_eq!(first.summary(), "2xx 90 | 3xx 0 | 4xx 8 | 5xx 2");
+
+        let command = tracker.command("/var/log/nginx/access.log", "combined");
+        assert!(command.contains("[ \"$1\" = \"42\" ] && [ \"$2\" -ge 1000 ]"));
+
+        let second = tracker
+            .update("file 42 2000\n2 20\n", start + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(second.requests_per_sec, Some(2.0));
+        assert_eq!(
+            second.summary(),
+            "2xx 20 | 3xx 0 | 4xx 0 | 5xx 0 | 2.0 req/s"
+        );
+
+        assert!(tracker.update("stat: cannot stat", start).is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::ApplicationStatus;
+use crate::config::{AlertChannel, AlertConfig, Config};
+use crate::logging::{glyph, log};
+use crate::utils::timing::format_duration;
+
+/// Kind of condition an alert reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    Down,
+    SlowResponse,
+    DiskUsage,
+    BinaryDrift,
+    WatchdogRestart,
+    Recovered,
+}
+
+impl AlertKind {
+    fn describe(self) -> &'static str {
+        match self {
+            AlertKind::Down => "down",
+            AlertKind::SlowResponse => "slow response",
+            AlertKind::DiskUsage => "disk usage",
+            AlertKind::BinaryDrift => "binary drift",
+            AlertKind::WatchdogRestart => "watchdog restart",
+            AlertKind::Recovered => "recovery",
+        }
+    }
+}
+
+/// A single alert ready to be delivered
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub project: String,
+    pub host: String,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+    /// Condition that cleared, for `recovered` alerts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolves: Option<AlertKind>,
+}
+
+/// A condition that has been notified and has not cleared yet
+#[derive(Debug, Clone, Copy)]
+struct ActiveAlert {
+    since: DateTime<Utc>,
+    last_sent: DateTime<Utc>,
+}
+
+/// Evaluates monitoring results against thresholds and delivers alerts
+pub struct AlertManager {
+    alerts: AlertConfig,
+    project: String,
+    host: String,
+    http_client: Client,
+    consecutive_failures: u32,
+    active: BTreeMap<AlertKind, ActiveAlert>,
+}
+
+impl AlertManager {
+    /// Create an alert manager if alerting is configured
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let alerts = config.monitor.alerts.clone()?;
+        Some(Self {
+            alerts,
+            project: config.project.name.clone(),
+            host: config.deploy.vps_host.clone(),
+            http_client: Client::new(),
+            consecutive_failures: 0,
+            active: BTreeMap::new(),
+        })
+    }
+
+    /// Evaluate a status sample and return the notifications to send
+    ///
+    /// A condition is notified when it starts and then at most once per
+    /// `cooldown_secs` while it lasts; when it clears, a recovery message is
+    /// sent if `notify_recovery` is set.
+    pub fn evaluate(&mut self, status: &ApplicationStatus, now: DateTime<Utc>) -> Vec<Alert> {
+        let raised = self.conditions(status);
+        let cooldown = chrono::Duration::seconds(self.alerts.cooldown_secs as i64);
+        let mut alerts = Vec::new();
+
+        let mut cleared = Vec::new();
+        self.active.retain(|kind, active| {
+            let ongoing = raised.iter().any(|alert| alert.kind == *kind);
+            if !ongoing {
+                cleared.push((*kind, active.since));
+            }
+            ongoing
+        });
+        if self.alerts.notify_recovery {
+            for (kind, since) in cleared {
+                alerts.push(self.recovery(kind, since, now));
+            }
+        }
+
+        for mut alert in raised {
+            match self.active.get_mut(&alert.kind) {
+                None => {
+                    self.active.insert(
+                        alert.kind,
+                        ActiveAlert {
+                            since: now,
+                            last_sent: now,
+                        },
+                    );
+                    alerts.push(alert);
+                }
+                Some(active) if now - active.last_sent >= cooldown => {
+                    alert.message = format!(
+                        "{} (ongoing for {})",
+                        alert.message,
+                        format_duration((now - active.since).to_std().unwrap_or_default())
+                    );
+                    active.last_sent = now;
+                    alerts.push(alert);
+                }
+                Some(_) => {
+                    tracing::debug!("Suppressing repeated {} alert", alert.kind.describe());
+                }
+            }
+        }
+
+        alerts
+    }
+
+    /// Alerts for every threshold the status sample currently violates
+    fn conditions(&mut self, status: &ApplicationStatus) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+
+        if status.is_healthy() {
+            self.consecutive_failures = 0;
+        } else {
+            self.consecutive_failures += 1;
+            if self.consecutive_failures >= self.alerts.consecutive_failures {
+                alerts.push(self.alert(
+                    AlertKind::Down,
+                    format!(
+                        "{} on {} is DOWN ({} consecutive failed checks): {}{}",
+                        self.project,
+                        self.host,
+                        self.consecutive_failures,
+                        status.summary(),
+                        status
+                            .last_error
+                            .as_ref()
+                            .map(|e| format!(" - {}", e))
+                            .unwrap_or_default()
+                    ),
+                ));
+            }
+        }
+
+        if let (Some(threshold), Some(response_time)) =
+            (self.alerts.response_time_ms, status.response_time)
+            && response_time.as_millis() as u64 > threshold
+        {
+            alerts.push(self.alert(
+                AlertKind::SlowResponse,
+                format!(
+                    "{} on {} responded in {}ms (threshold {}ms)",
+                    self.project,
+                    self.host,
+                    response_time.as_millis(),
+                    threshold
+                ),
+            ));
+        }
+
+        if let (Some(threshold), Some(system)) = (self.alerts.disk_usage_percent, &status.system)
+            && system.disk_used_percent() > threshold
+        {
+            alerts.push(self.alert(
+                AlertKind::DiskUsage,
+                format!(
+                    "Disk usage on {} is {:.1}% (threshold {:.0}%)",
+                    self.host,
+                    system.disk_used_percent(),
+                    threshold
+                ),
+            ));
+        }
+
+        if let Some(drift) = &status.binary_drift {
+            alerts.push(self.alert(
+                AlertKind::BinaryDrift,
+                format!("{} on {}: {}", self.project, self.host, drift.summary()),
+            ));
+        }
+
+        alerts
+    }
+
+    /// Evaluate a status sample and deliver the resulting alerts to every channel
+    pub async fn process(&mut self, status: &ApplicationStatus) {
+        for alert in self.evaluate(status, Utc::now()) {
+            self.send(&alert).await;
+        }
+    }
+
+    /// Build and deliver a one-off alert, e.g. for a watchdog intervention
+    pub async fn notify(&self, kind: AlertKind, message: String) {
+        self.send(&self.alert(kind, message)).await;
+    }
+
+    /// Deliver an alert to all configured channels, logging delivery failures
+    pub async fn send(&self, alert: &Alert) {
+        log::monitor_event(&format!("{} Alert: {}", glyph("🚨"), alert.message));
+
+        for channel in &self.alerts.channels {
+            if let Err(e) = self.send_to(channel, alert).await {
+                tracing::warn!("Failed to deliver alert: {}", e);
+            }
+        }
+    }
+
+    fn alert(&self, kind: AlertKind, message: String) -> Alert {
+        Alert {
+            kind,
+            project: self.project.clone(),
+            host: self.host.clone(),
+            message,
+            timestamp: Utc::now(),
+            resolves: None,
+        }
+    }
+
+    fn recovery(&self, kind: AlertKind, since: DateTime<Utc>, now: DateTime<Utc>) -> Alert {
+        let duration = format_duration((now - since).to_std().unwrap_or_default());
+        let message = match kind {
+            AlertKind::Down => format!(
+                "{} on {} is back UP after {}",
+                self.project, self.host, duration
+            ),
+            _ => format!(
+                "{} on {}: {} cleared after {}",
+                self.project,
+                self.host,
+                kind.describe(),
+                duration
+            ),
+        };
+        Alert {
+            resolves: Some(kind),
+            ..self.alert(AlertKind::Recovered, message)
+        }
+    }
+
+    async fn send_to(&self, channel: &AlertChannel, alert: &Alert) -> Result<()> {
+        match channel {
+            AlertChannel::Webhook { url } => {
+                self.http_client
+                    .post(url)
+                    .json(alert)
+                    .send()
+                    .await
+                    .with_context(|| format!("Webhook request failed: {}", url))?
+                    .error_for_status()?;
+            }
+            AlertChannel::Slack { webhook_url } => {
+                self.http_client
+                    .post(webhook_url)
+                    .json(&slack_payload(&alert.message))
+                    .send()
+                    .await
+                    .context("Slack webhook request failed")?
+                    .error_for_status()?;
+            }
+            AlertChannel::Email { to, from, sendmail } => {
+                send_email(sendmail, to, from.as_deref(), alert)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Slack incoming webhooks accept `{"text": "..."}`
+fn slack_payload(message: &str) -> std::collections::HashMap<&'static str, &str> {
+    std::collections::HashMap::from([("text", message)])
+}
+
+/// Pipe a plain-text email into `sendmail -t`
+fn send_email(sendmail: &str, to: &str, from: Option<&str>, alert: &Alert) -> Result<()> {
+    let mut message = String::new();
+    if let Some(from) = from {
+        message.push_str(&format!("From: {}\n", from));
+    }
+    message.push_str(&format!(
+        "To: {}\nSubject: [rzen] {}\n\n{}\n",
+        to, alert.message, alert.message
+    ));
+
+    let mut child = Command::new(sendmail)
+        .arg("-t")
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", sendmail))?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open sendmail stdin"))?
+        .write_all(message.as_bytes())?;
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", sendmail, status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn manager(consecutive_failures: u32) -> AlertManager {
+        AlertManager {
+            alerts: AlertConfig {
+                consecutive_failures,
+                response_time_ms: Some(500),
+                disk_usage_percent: Some(90.0),
+                cooldown_secs: 3600,
+                notify_recovery: true,
+                channels: Vec::new(),
+            },
+            project: "app".to_string(),
+            host: "example.com".to_string(),
+            http_client: Client::new(),
+            consecutive_failures: 0,
+            active: BTreeMap::new(),
+        }
+    }
+
+    fn healthy() -> ApplicationStatus {
+        ApplicationStatus {
+            health_ok: true,
+            ssh_ok: true,
+            response_time: Some(Duration::from_millis(50)),
+            service_status: Some("active".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_down_alert_after_consecutive_failures() {
+        let mut manager = manager(2);
+        let down = ApplicationStatus::default();
+        let now = Utc::now();
+
+        assert!(manager.evaluate(&down, now).is_empty());
+        let alerts = manager.evaluate(&down, now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, AlertKind::Down);
+
+        let recovered = manager.evaluate(&healthy(), now);
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].resolves, Some(AlertKind::Down));
+        assert!(manager.evaluate(&down, now).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_alerts_respect_cooldown() {
+        let mut manager = manager(1);
+        let down = ApplicationStatus::default();
+        let start = Utc::now();
+        let at = |minutes| start + chrono::Duration::minutes(minutes);
+
+        assert_eq!(manager.evaluate(&down, start).len(), 1);
+        assert!(manager.evaluate(&down, at(1)).is_empty());
+        assert!(manager.evaluate(&down, at(59)).is_empty());
+
+        let repeated = manager.evaluate(&down, at(60));
+        assert_eq!(repeated.len(), 1);
+        assert!(repeated[0].message.ends_with("(ongoing for 1h 0m)"));
+        assert!(manager.evaluate(&down, at(90)).is_empty());
+
+        let recovered = manager.evaluate(&healthy(), at(95));
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].kind, AlertKind::Recovered);
+        assert_eq!(
+            recovered[0].message,
+            "app on example.com is back UP after 1h 35m"
+        );
+
+        manager.alerts.notify_recovery = false;
+        manager.evaluate(&down, at(100));
+        assert!(manager.evaluate(&healthy(), at(101)).is_empty());
+    }
+
+    #[test]
+    fn test_threshold_alerts() {
+        let mut manager = manager(1);
+        let mut status = healthy();
+        status.response_time = Some(Duration::from_millis(900));
+        status.system = Some(super::super::system::SystemMetrics {
+            disk_total_kb: 100,
+            disk_used_kb: 95,
+            ..Default::default()
+        });
+
+        let kinds: Vec<AlertKind> = manager
+            .evaluate(&status, Utc::now())
+            .iter()
+            .map(|a| a.kind)
+            .collect();
+        assert_eq!(kinds, vec![AlertKind::SlowResponse, AlertKind::DiskUsage]);
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+/// Check that a JSON health response has an expected value at a path
+///
+/// Expressions look like `$.status == "ok"` or `$.checks[0].healthy == true`;
+/// the right-hand side is any JSON literal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonAssertion {
+    pub path: String,
+    pointer: String,
+    expected: Value,
+}
+
+impl JsonAssertion {
+    /// Parse an assertion expression
+    pub fn parse(expression: &str) -> Result<Self> {
+        let (path, expected) = expression
+            .split_once("==")
+            .ok_or_else(|| anyhow!("Health assertion must have the form `$.path == value`"))?;
+        let path = path.trim();
+        let expected = expected.trim();
+
+        let pointer = to_pointer(path)
+            .ok_or_else(|| anyhow!("Invalid JSON path in health assertion: {}", path))?;
+        let expected = serde_json::from_str(expected)
+            .map_err(|_| anyhow!("Invalid JSON value in health assertion: {}", expected))?;
+
+        Ok(Self {
+            path: path.to_string(),
+            pointer,
+            expected,
+        })
+    }
+
+    /// Check the assertion against a parsed response body
+    pub fn check(&self, body: &Value) -> Result<()> {
+        match body.pointer(&self.pointer) {
+            Some(actual) if *actual == self.expected => Ok(()),
+            Some(actual) => Err(anyhow!(
+                "{} is {}, expected {}",
+                self.path,
+                actual,
+                self.expected
+            )),
+            None => Err(anyhow!("{} is missing from the response", self.path)),
+        }
+    }
+}
+
+/// Check a health response body against the configured assertions
+pub fn check_body(body: &str, contains: Option<&str>, assertions: &[JsonAssertion]) -> Result<()> {
+    if let Some(needle) = contains
+        && !body.contains(needle)
+    {
+        return Err(anyhow!("Health response does not contain {:?}", needle));
+    }
+
+    if assertions.is_empty() {
+        return Ok(());
+    }
+
+    let json: Value = serde_json::from_str(body)
+        .map_err(|e| anyhow!("Health response is not valid JSON: {}", e))?;
+    for assertion in assertions {
+        assertion
+            .check(&json)
+            .map_err(|e| anyhow!("Health assertion failed: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Convert `$.a.b[0]` (or an RFC 6901 pointer such as `/a/b/0`) into a JSON pointer
+fn to_pointer(path: &str) -> Option<String> {
+    if path.starts_with('/') {
+        return Some(path.to_string());
+    }
+
+    let rest = path.strip_prefix('$')?;
+    if !rest.is_empty() && !rest.starts_with('.') {
+        return None;
+    }
+    let mut pointer = String::new();
+
+    for segment in rest.split('.').skip(1) {
+        let (key, indexes) = match segment.find('[') {
+            Some(i) => segment.split_at(i),
+            None => (segment, ""),
+        };
+        if key.is_empty() && indexes.is_empty() {
+            return None;
+        }
+        if !key.is_empty() {
+            pointer.push('/');
+            pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+        }
+
+        let mut indexes = indexes;
+        while let Some(index) = indexes.strip_prefix('[') {
+            let (index, remaining) = index.split_once(']')?;
+            index.parse::<usize>().ok()?;
+            pointer.push('/');
+            pointer.push_str(index);
+            indexes = remaining;
+        }
+        if !indexes.is_empty() {
+            return None;
+        }
+    }
+
+    Some(pointer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_assertions() {
+        let body =
+            r#"{"status": "degraded", "db": {"connected": true}, "checks": [{"ok": false}]}"#;
+
+        let status = JsonAssertion::parse(r#"$.status == "ok""#).unwrap();
+        let db = JsonAssertion::parse("$.db.connected == true").unwrap();
+        let check = JsonAssertion::parse("$.checks[0].ok == false").unwrap();
+        let missing = JsonAssertion::parse("/version == 2").unwrap();
+
+        assert!(check_body(body, None, &[db.clone(), check]).is_ok());
+        let error = check_body(body, None, &[db, status]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"Health assertion failed: $.status is "degraded", expected "ok""#
+        );
+        assert!(check_body(body, None, &[missing]).is_err());
+    }
+
+    #[test]
+    fn test_body_contains() {
+        assert!(check_body("status: ok", Some("ok"), &[]).is_ok());
+        assert!(check_body("status: degraded", Some("ok"), &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(JsonAssertion::parse("$.status").is_err());
+        assert!(JsonAssertion::parse("status == 1").is_err());
+        assert!(JsonAssertion::parse("$.items[x] == 1").is_err());
+        assert!(JsonAssertion::parse("$.status == ok").is_err());
+    }
+}
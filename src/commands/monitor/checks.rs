@@ -0,0 +1,147 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::config::CommandCheck;
+use crate::utils;
+use crate::utils::host::Host;
+
+/// Output lines kept per check
+const OUTPUT_LINES: usize = 5;
+
+/// Exit code of `timeout` when the command ran too long
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// Marker separating the results of individual checks in the combined output
+const MARKER: &str = "--rzen-check";
+
+/// Outcome of a command check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    /// Exit code of the command; `None` when no result came back
+    pub exit_code: Option<i32>,
+    /// Last lines of combined stdout and stderr
+    pub output: Vec<String>,
+}
+
+impl CheckResult {
+    pub fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Why the check failed, e.g. `exit 2` or `timed out`
+    pub fn failure(&self) -> Option<String> {
+        match self.exit_code {
+            Some(0) => None,
+            Some(TIMEOUT_EXIT_CODE) => Some("timed out".to_string()),
+            Some(code) => Some(format!("exit {}", code)),
+            None => Some("no result".to_string()),
+        }
+    }
+}
+
+/// Run all checks in one SSH command, each with its own timeout
+pub fn run(host: &dyn Host, checks: &[CommandCheck]) -> Result<Vec<CheckResult>> {
+    let (output, _) = host.execute_command(&script(checks))?;
+    Ok(parse(&output, checks))
+}
+
+/// Shell script printing a marker with the exit code, then the output tail, per check
+pub fn script(checks: &[CommandCheck]) -> String {
+    checks
+        .iter()
+        .enumerate()
+        .map(|(index, check)| {
+            format!(
+                "out=$(timeout {} sh -c {} 2>&1 </dev/null); code=$?; \
+                 echo \"{} {} $code\"; printf '%s\\n' \"$out\" | tail -n {}",
+                check.timeout_secs,
+                utils::ssh::shell_quote(&check.command),
+                MARKER,
+                index,
+                OUTPUT_LINES
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Split the output of [`script`] into per-check results
+pub fn parse(output: &str, checks: &[CommandCheck]) -> Vec<CheckResult> {
+    let mut results: Vec<CheckResult> = checks
+        .iter()
+        .map(|check| CheckResult {
+            name: check.label().to_string(),
+            exit_code: None,
+            output: Vec::new(),
+        })
+        .collect();
+    let mut current = None;
+
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix(MARKER) {
+            let mut fields = rest.split_whitespace();
+            let index = fields.next().and_then(|i| i.parse::<usize>().ok());
+            let code = fields.next().and_then(|c| c.parse::<i32>().ok());
+            current = index.filter(|&i| i < results.len());
+            if let Some(i) = current {
+                results[i].exit_code = code;
+            }
+        } else if let Some(i) = current
+            && !line.trim().is_empty()
+        {
+            results[i].output.push(line.to_string());
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: Option<&str>, command: &str) -> CommandCheck {
+        CommandCheck {
+            name: name.map(str::to_string),
+            command: command.to_string(),
+            timeout_secs: 5,
+        }
+    }
+
+    #[test]
+    fn test_script() {
+        let checks = [check(None, "pg_isready -h 'localhost'")];
+        assert_eq!(
+            script(&checks),
+            "out=$(timeout 5 sh -c 'pg_isready -h '\\''localhost'\\''' 2>&1 </dev/null); \
+             code=$?; echo \"--rzen-check 0 $code\"; printf '%s\\n' \"$out\" | tail -n 5"
+        );
+    }
+
+    #[test]
+    fn test_parse_results() {
+        let checks = [
+            check(Some("postgres"), "pg_isready"),
+            check(None, "redis-cli ping"),
+            check(Some("queue"), "sleep 60"),
+        ];
+        let output = "\
+--rzen-check 0 0
+/var/run/postgresql:5432 - accepting connections
+--rzen-check 1 1
+Could not connect to Redis at 127.0.0.1:6379: Connection refused
+
+";
+        let results = parse(output, &checks);
+        assert!(results[0].passed());
+        assert_eq!(results[0].name, "postgres");
+        assert_eq!(results[1].name, "redis-cli ping");
+        assert_eq!(results[1].failure().as_deref(), Some("exit 1"));
+        assert_eq!(results[1].output.len(), 1);
+        assert_eq!(results[2].failure().as_deref(), Some("no result"));
+
+        let timed_out = parse("--rzen-check 0 124\n", &checks[..1]);
+        assert_eq!(timed_out[0].failure().as_deref(), Some("timed out"));
+    }
+}
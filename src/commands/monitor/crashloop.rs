@@ -0,0 +1,180 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::host::Host;
+use crate::utils::ssh::shell_quote;
+
+/// Journal lines kept as the crash excerpt
+const EXCERPT_LINES: usize = 10;
+
+/// Evidence that systemd keeps restarting the service
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CrashLoop {
+    /// Automatic restarts within the detection window
+    pub recent_restarts: u32,
+    /// `NRestarts` since the unit was last started manually
+    pub total_restarts: u32,
+    pub last_exit_status: Option<i32>,
+    pub window_secs: u64,
+    /// Most recent error-level journal lines
+    pub excerpt: Vec<String>,
+}
+
+impl CrashLoop {
+    pub fn summary(&self) -> String {
+        let exit = self
+            .last_exit_status
+            .map(|status| format!(", last exit status {}", status))
+            .unwrap_or_default();
+        format!(
+            "Service crash-looping ({} restarts in {}{})",
+            self.recent_restarts,
+            utils::timing::format_duration(std::time::Duration::from_secs(self.window_secs)),
+            exit
+        )
+    }
+}
+
+/// Restart counters and recent errors read from systemd and the journal
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestartInfo {
+    pub total_restarts: u32,
+    pub recent_restarts: u32,
+    pub last_exit_status: Option<i32>,
+    pub errors: Vec<String>,
+}
+
+/// Download the newest core dump of the deployed binary to `local_path`
+///
+/// systemd-coredump keeps dumps compressed and readable by root, so `coredumpctl` writes one
+/// to a temporary file the SSH user can read, which is removed after the download.
+pub fn download_core_dump(
+    host: &dyn Host,
+    config: &Config,
+    local_path: &Path,
+    on_progress: Option<&dyn Fn(&utils::progress::Transfer)>,
+) -> Result<()> {
+    let binary = format!("{}/{}", config.deploy.deploy_path, config.binary_name());
+    let (output, _) = host
+        .execute_command(&format!(
+            "f=$(mktemp /tmp/rzen-core.XXXXXX) || exit 1; \
+             if sudo coredumpctl --no-pager --output=\"$f\" dump {} >/dev/null \
+                 && sudo chown \"$(id -u)\" \"$f\"; \
+             then echo \"$f\"; else rm -f \"$f\"; exit 1; fi",
+            shell_quote(&binary)
+        ))
+        .map_err(|e| {
+            anyhow!(
+                "No core dump of {} could be read with coredumpctl: {}",
+                binary,
+                e
+            )
+        })?;
+    let remote = output.trim();
+
+    let download = host.download_file(remote, local_path, on_progress);
+    let _ = host.execute_command(&format!("rm -f {}", shell_quote(remote)));
+    download
+}
+
+/// Read restart counters for the service and report a crash loop if it is flapping
+pub fn detect(host: &dyn Host, config: &Config) -> Result<Option<CrashLoop>> {
+    let service = config.service_name();
+    let window = config.monitor.crash_loop_window_secs;
+    let command = format!(
+        "echo '--show'; sudo systemctl show {service} -p NRestarts -p ExecMainStatus; \
+         echo '--restarts'; sudo journalctl -u {service} --since '-{window}s' -o cat --no-pager \
+             | grep -c 'Scheduled restart job' || true; \
+         echo '--errors'; sudo journalctl -u {service} --since '-{window}s' -p err -o cat \
+             --no-pager -n {lines}",
+        service = utils::ssh::shell_quote(&service),
+        window = window,
+        lines = EXCERPT_LINES,
+    );
+
+    let (output, _) = host.execute_command(&command)?;
+    let info = parse(&output);
+    Ok(evaluate(&info, config.monitor.crash_loop_restarts, window))
+}
+
+/// Decide whether restart activity amounts to a crash loop
+pub fn evaluate(info: &RestartInfo, threshold: u32, window_secs: u64) -> Option<CrashLoop> {
+    (info.recent_restarts >= threshold).then(|| CrashLoop {
+        recent_restarts: info.recent_restarts,
+        total_restarts: info.total_restarts,
+        last_exit_status: info.last_exit_status,
+        window_secs,
+        excerpt: info.errors.clone(),
+    })
+}
+
+/// Parse the sectioned output produced by [`detect`]
+pub fn parse(output: &str) -> RestartInfo {
+    let mut info = RestartInfo::default();
+    let mut section = "";
+
+    for line in output.lines().map(str::trim_end).filter(|l| !l.is_empty()) {
+        if let Some(name) = line.strip_prefix("--") {
+            section = name;
+            continue;
+        }
+
+        match section {
+            "show" => match line.split_once('=') {
+                Some(("NRestarts", value)) => {
+                    info.total_restarts = value.trim().parse().unwrap_or_default();
+                }
+                Some(("ExecMainStatus", value)) => {
+                    info.last_exit_status = value.trim().parse().ok();
+                }
+                _ => {}
+            },
+            "restarts" => info.recent_restarts = line.trim().parse().unwrap_or_default(),
+            "errors" => info.errors.push(line.to_string()),
+            _ => {}
+        }
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "\
+--show
+NRestarts=12
+ExecMainStatus=101
+--restarts
+4
+--errors
+thread 'main' panicked at src/main.rs:10:5:
+DATABASE_URL must be set
+";
+
+    #[test]
+    fn test_parse_restart_info() {
+        let info = parse(OUTPUT);
+        assert_eq!(info.total_restarts, 12);
+        assert_eq!(info.recent_restarts, 4);
+        assert_eq!(info.last_exit_status, Some(101));
+        assert_eq!(info.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_evaluate_crash_loop() {
+        let info = parse(OUTPUT);
+        let crash_loop = evaluate(&info, 3, 300).unwrap();
+        assert_eq!(
+            crash_loop.summary(),
+            "Service crash-looping (4 restarts in 5m 0s, last exit status 101)"
+        );
+        assert_eq!(crash_loop.excerpt[1], "DATABASE_URL must be set");
+
+        assert_eq!(evaluate(&info, 5, 300), None);
+    }
+}
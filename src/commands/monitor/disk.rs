@@ -0,0 +1,193 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::host::Host;
+
+/// Free space on the filesystem holding one or more watched paths
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiskSpace {
+    pub labels: Vec<String>,
+    pub mount: String,
+    pub total_kb: u64,
+    pub available_kb: u64,
+}
+
+impl DiskSpace {
+    pub fn used_percent(&self) -> f64 {
+        if self.total_kb == 0 {
+            0.0
+        } else {
+            (self.total_kb - self.available_kb) as f64 / self.total_kb as f64 * 100.0
+        }
+    }
+
+    /// Whether available space is under `min_free_mb`
+    pub fn is_low(&self, min_free_mb: u64) -> bool {
+        self.available_kb < min_free_mb * 1024
+    }
+
+    /// One-line summary, e.g. `deploy_path, log_dir (/): 1.2 GB free (85.0% used)`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} ({}): {} free ({:.1}% used)",
+            self.labels.join(", "),
+            self.mount,
+            utils::fs::format_size(self.available_kb * 1024),
+            self.used_percent()
+        )
+    }
+}
+
+/// Paths whose filesystems must not fill up: the deploy path and the log directory
+pub fn watched_paths(config: &Config) -> Vec<(String, String)> {
+    let mut paths = vec![("deploy_path".to_string(), config.deploy.deploy_path.clone())];
+    if let Some(log_dir) = config
+        .monitor
+        .log_path
+        .as_deref()
+        .and_then(|path| path.rsplit_once('/'))
+        .map(|(dir, _)| if dir.is_empty() { "/" } else { dir })
+    {
+        paths.push(("log_dir".to_string(), log_dir.to_string()));
+    }
+    paths
+}
+
+/// Measure free space for the watched paths, grouping paths on the same mount
+pub fn check(host: &dyn Host, paths: &[(String, String)]) -> Result<Vec<DiskSpace>> {
+    // Paths that do not exist yet (e.g. before the first deploy) are measured at
+    // their nearest existing parent
+    let command = paths
+        .iter()
+        .map(|(label, path)| {
+            format!(
+                "p={}; while [ ! -e \"$p\" ]; do p=$(dirname \"$p\"); done; \
+                 echo {} $(df -Pk \"$p\" | tail -n1)",
+                utils::ssh::shell_quote(path),
+                label
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let (output, _) = host.execute_command(&command)?;
+    parse(&output)
+}
+
+/// Parse `<label> <df -Pk line>` records produced by [`check`]
+pub fn parse(output: &str) -> Result<Vec<DiskSpace>> {
+    let mut disks: Vec<DiskSpace> = Vec::new();
+
+    for line in output.lines().filter(|l| !l.trim().is_empty()) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [
+            label,
+            _filesystem,
+            total,
+            _used,
+            available,
+            _capacity,
+            mount,
+            ..,
+        ] = fields[..]
+        else {
+            return Err(anyhow!("Unexpected df output: {}", line));
+        };
+        let parse_kb = |value: &str| {
+            value
+                .parse::<u64>()
+                .map_err(|_| anyhow!("Unexpected df output: {}", line))
+        };
+
+        match disks.iter_mut().find(|disk| disk.mount == mount) {
+            Some(disk) => disk.labels.push(label.to_string()),
+            None => disks.push(DiskSpace {
+                labels: vec![label.to_string()],
+                mount: mount.to_string(),
+                total_kb: parse_kb(total)?,
+                available_kb: parse_kb(available)?,
+            }),
+        }
+    }
+
+    Ok(disks)
+}
+
+/// Refuse to deploy when the deploy filesystem cannot hold the new binary and
+/// its backup while keeping `min_free_disk_mb` free
+pub fn ensure_deploy_space(host: &dyn Host, config: &Config, binary_size: u64) -> Result<()> {
+    let disks = check(host, &watched_paths(config))?;
+    let min_free_kb = config.deploy.min_free_disk_mb * 1024;
+
+    for disk in &disks {
+        let needed_kb = if disk.labels.iter().any(|label| label == "deploy_path") {
+            // New binary plus a backup copy of the current one
+            min_free_kb + binary_size.div_ceil(1024) * 2
+        } else {
+            min_free_kb
+        };
+
+        if disk.available_kb < needed_kb {
+            return Err(anyhow!(
+                "Not enough disk space for {} on {}: {} free, {} required",
+                disk.labels.join(", "),
+                disk.mount,
+                utils::fs::format_size(disk.available_kb * 1024),
+                utils::fs::format_size(needed_kb * 1024)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_records() {
+        let output = "\
+deploy_path /dev/sda1 41152736 35000000 4000000 90% /
+log_dir /dev/sda1 41152736 35000000 4000000 90% /
+data /dev/sdb1 1000000 990000 10000 99% /data
+";
+        let disks = parse(output).unwrap();
+        assert_eq!(disks.len(), 2);
+        assert_eq!(disks[0].labels, vec!["deploy_path", "log_dir"]);
+        assert_eq!(disks[0].mount, "/");
+        assert!(!disks[0].is_low(512));
+        assert!(disks[1].is_low(512));
+        assert!((disks[1].used_percent() - 99.0).abs() < 0.01);
+
+        assert!(parse("deploy_path df: cannot read").is_err());
+    }
+
+    #[test]
+    fn test_watched_paths() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            [deploy]
+            vps_host = "example.com"
+            vps_user = "deploy"
+            deploy_path = "/opt/app"
+            [monitor]
+            log_path = "/var/log/app/app.log"
+            "#,
+        )
+        .unwrap();
+
+        let paths = watched_paths(&config);
+        assert_eq!(
+            paths[1],
+            ("log_dir".to_string(), "/var/log/app".to_string())
+        );
+
+        config.monitor.log_path = None;
+        assert_eq!(watched_paths(&config).len(), 1);
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::commands::deploy::{DeployManifest, MANIFEST_FILE};
+use crate::config::Config;
+use crate::logging::log;
+use crate::utils;
+use crate::utils::host::Host;
+
+/// Separates the manifest from the checksum in the combined output
+const MARKER: &str = "--rzen-sha256";
+
+/// Deployed binary that no longer matches the checksum recorded at deploy time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BinaryDrift {
+    pub binary: String,
+    /// Checksum recorded by the last rzen deploy
+    pub expected: String,
+    /// Current checksum; `None` when the binary is missing
+    pub actual: Option<String>,
+    pub deployed_at: DateTime<Utc>,
+}
+
+impl BinaryDrift {
+    /// One-line summary, e.g. `app changed outside rzen deploy (sha256 1a2b3c4d5e6f, expected ...)`
+    pub fn summary(&self) -> String {
+        match &self.actual {
+            Some(actual) => format!(
+                "{} changed outside rzen deploy (sha256 {}, expected {} from {})",
+                self.binary,
+                short(actual),
+                short(&self.expected),
+                self.deployed_at.format("%Y-%m-%d %H:%M UTC")
+            ),
+            None => format!(
+                "{} is missing from the deploy path (deployed {})",
+                self.binary,
+                self.deployed_at.format("%Y-%m-%d %H:%M UTC")
+            ),
+        }
+    }
+}
+
+fn short(checksum: &str) -> &str {
+    &checksum[..checksum.len().min(12)]
+}
+
+/// Runs the drift check at most once per interval and remembers the last result
+#[derive(Debug, Default)]
+pub struct DriftTracker {
+    last_check: Option<Instant>,
+    drift: Option<BinaryDrift>,
+}
+
+impl DriftTracker {
+    /// Current drift, re-checking the binary when the interval has passed
+    pub fn sample(&mut self, host: &dyn Host, config: &Config) -> Option<BinaryDrift> {
+        let interval = Duration::from_secs(config.monitor.drift_check_interval_secs);
+        let now = Instant::now();
+
+        if !interval.is_zero()
+            && self
+                .last_check
+                .is_none_or(|last| now.duration_since(last) >= interval)
+        {
+            match check(host, config) {
+                Ok(drift) => self.drift = drift,
+                Err(e) => log::monitor_event(&format!("Binary drift check unavailable: {}", e)),
+            }
+            self.last_check = Some(now);
+        }
+
+        self.drift.clone()
+    }
+}
+
+/// Compare the deployed binary with the deploy manifest
+pub fn check(host: &dyn Host, config: &Config) -> Result<Option<BinaryDrift>> {
+    let (output, _) = host.execute_command(&command(config))?;
+    parse(&output)
+}
+
+/// Remote command printing the manifest, a marker, then the binary's checksum
+pub fn command(config: &Config) -> String {
+    let deploy_path = &config.deploy.deploy_path;
+    format!(
+        "cat {manifest} 2>/dev/null; echo '{marker}'; sha256sum {binary} 2>/dev/null",
+        manifest = utils::ssh::shell_quote(&format!("{}/{}", deploy_path, MANIFEST_FILE)),
+        marker = MARKER,
+        binary = utils::ssh::shell_quote(&format!("{}/{}", deploy_path, config.binary_name())),
+    )
+}
+
+/// Parse the output of [`command`]; no manifest means nothing to compare against
+pub fn parse(output: &str) -> Result<Option<BinaryDrift>> {
+    let (manifest, checksum) = output
+        .split_once(MARKER)
+        .ok_or_else(|| anyhow!("Unexpected drift check output: {}", output.trim()))?;
+    if manifest.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let manifest: DeployManifest =
+        toml::from_str(manifest).context("Failed to parse deploy manifest")?;
+    let actual = checksum.split_whitespace().next().map(str::to_string);
+    if actual
+        .as_deref()
+        .is_some_and(|actual| actual.eq_ignore_ascii_case(&manifest.sha256))
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(BinaryDrift {
+        binary: manifest.binary,
+        expected: manifest.sha256,
+        actual,
+        deployed_at: manifest.deployed_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"binary = "app"
+sha256 = "aaaabbbbccccddddeeeeffff0000111122223333444455556666777788889999"
+source = "local build"
+deployed_at = "2024-06-01T12:00:00Z"
+"#;
+
+    #[test]
+    fn test_parse_drift() {
+        let matching = format!(
+            "{}--rzen-sha256\naaaabbbbccccddddeeeeffff0000111122223333444455556666777788889999  /opt/app/app\n",
+            MANIFEST
+        );
+        assert_eq!(parse(&matching).unwrap(), None);
+
+        let changed = format!(
+            "{}--rzen-sha256\n0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef  /opt/app/app\n",
+            MANIFEST
+        );
+        let drift = parse(&changed).unwrap().unwrap();
+        assert_eq!(drift.actual.as_deref().map(short), Some("0123456789ab"));
+        assert_eq!(
+            drift.summary(),
+            "app changed outside rzen deploy (sha256 0123456789ab, expected aaaabbbbcccc from 2024-06-01 12:00 UTC)"
+        );
+
+        let missing = parse(&format!("{}--rzen-sha256\n", MANIFEST))
+            .unwrap()
+            .unwrap();
+        assert_eq!(missing.actual, None);
+
+        assert_eq!(parse("--rzen-sha256\n").unwrap(), None);
+        assert!(parse("permission denied").is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use anyhow::{Context, Result};
+use std::sync::Arc;
+
+use super::{
+    ApplicationMonitor, ApplicationStatus, CycleClock, RunLimits, SessionStats, heartbeat,
+    reload_config,
+};
+use crate::cli::OutputFormat;
+use crate::config::Config;
+use crate::config::reload::ConfigWatcher;
+use crate::logging::{glyph, log};
+use crate::utils;
+
+/// Monitors every host listed in `deploy.hosts` concurrently
+pub struct Fleet {
+    /// Shared with the checks while they run, and only with them
+    monitors: Vec<Arc<ApplicationMonitor>>,
+    /// Configuration the per-host ones are derived from
+    config: Config,
+}
+
+impl Fleet {
+    /// Create one monitor per configured host
+    pub fn new(config: &Config) -> Self {
+        Self {
+            monitors: config
+                .hosts()
+                .iter()
+                .map(|host| Arc::new(ApplicationMonitor::new(config.for_host(host))))
+                .collect(),
+            config: config.clone(),
+        }
+    }
+
+    /// Take over a reloaded configuration; hosts that stay keep their monitor and `sessions`
+    /// entry, in the new host order
+    fn reconfigure(
+        &mut self,
+        config: Config,
+        changed: &[String],
+        sessions: &mut Vec<SessionStats>,
+    ) {
+        let mut monitors = std::mem::take(&mut self.monitors);
+        let mut stats = std::mem::take(sessions);
+        for host in config.hosts() {
+            let host_config = config.for_host(&host);
+            match monitors
+                .iter()
+                .position(|monitor| monitor.config.deploy.vps_host == host)
+            {
+                Some(index) => {
+                    let mut monitor = monitors.remove(index);
+                    exclusive(&mut monitor).reconfigure(host_config, changed);
+                    self.monitors.push(monitor);
+                    sessions.push(stats.remove(index));
+                }
+                None => {
+                    self.monitors
+                        .push(Arc::new(ApplicationMonitor::new(host_config)));
+                    sessions.push(SessionStats::default());
+                }
+            }
+        }
+        self.config = config;
+    }
+
+    /// Check all hosts, `max_parallel_hosts` at a time, returning statuses in host order
+    ///
+    /// Each check runs on a thread of its own, so a host that hangs only delays its own row.
+    pub async fn check_all(&self) -> Result<Vec<(String, ApplicationStatus)>> {
+        let checks = self.monitors.iter().map(|monitor| {
+            let monitor = Arc::clone(monitor);
+            (monitor.config.deploy.vps_host.clone(), async move {
+                monitor.check_status().await
+            })
+        });
+        utils::ssh::on_all(checks, self.config.deploy.ssh.max_parallel_hosts)
+            .await
+            .into_result()
+    }
+
+    /// Check every host once and print the table, or a JSON array of documents
+    pub async fn run_once(&self, output: OutputFormat) -> Result<String> {
+        log::monitor_event(&format!(
+            "Checking {} hosts concurrently",
+            self.monitors.len()
+        ));
+
+        let results = self.check_all().await?;
+        match output {
+            OutputFormat::Text => display_table(&results),
+            OutputFormat::Json => {
+                let metrics: Vec<_> = self
+                    .monitors
+                    .iter()
+                    .zip(&results)
+                    .map(|(monitor, (_, status))| monitor.metrics(status))
+                    .collect();
+                let documents: Vec<_> = self
+                    .monitors
+                    .iter()
+                    .zip(&results)
+                    .zip(&metrics)
+                    .map(|((monitor, (_, status)), metrics)| monitor.document(status, metrics))
+                    .collect();
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&documents)
+                        .context("Failed to serialize status")?
+                );
+            }
+        }
+        Ok("Monitoring check completed".to_string())
+    }
+
+    /// Check every host each cycle until Ctrl-C or a configured limit is reached
+    pub async fn run_continuous(
+        &mut self,
+        limits: RunLimits,
+        output: OutputFormat,
+        mut watcher: Option<ConfigWatcher>,
+    ) -> Result<String> {
+        log::monitor_event(&format!(
+            "Starting continuous monitoring of {} hosts (press Ctrl-C to stop)",
+            self.monitors.len()
+        ));
+
+        let monitor = &self.config.monitor;
+        let mut clock = CycleClock::start(monitor.interval_secs, monitor.max_backoff_secs, limits);
+        let mut sessions = vec![SessionStats::default(); self.monitors.len()];
+
+        loop {
+            log::monitor_event(&format!("Monitoring cycle #{}", clock.cycles + 1));
+
+            let Some(results) = clock.until_shutdown(self.check_all()).await else {
+                break;
+            };
+            let results = results?;
+            match output {
+                OutputFormat::Text => display_table(&results),
+                OutputFormat::Json => {
+                    // One line per host so the stream can be consumed as NDJSON
+                    for (monitor, (_, status)) in self.monitors.iter().zip(&results) {
+                        let metrics = monitor.metrics(status);
+                        println!("{}", monitor.document(status, &metrics).to_json(false)?);
+                    }
+                }
+            }
+
+            for ((monitor, session), (_, status)) in
+                self.monitors.iter_mut().zip(&mut sessions).zip(&results)
+            {
+                let monitor = exclusive(monitor);
+                session.record(status);
+                monitor.track_incident(status);
+                if let Some(alerts) = &mut monitor.alerts {
+                    alerts.process(status).await;
+                }
+                monitor.run_watchdog(status).await;
+            }
+
+            if let Some(monitor) = self.monitors.first()
+                && let Some(url) = &monitor.config.monitor.heartbeat_url
+            {
+                let healthy = results.iter().all(|(_, status)| status.is_healthy());
+                heartbeat::ping(&monitor.http_client, url, healthy).await;
+            }
+            clock.backoff(results.iter().all(|(_, status)| status.is_unreachable()));
+
+            if !clock.wait_next().await {
+                break;
+            }
+            if let Some(watcher) = &mut watcher
+                && let Some((config, changed)) = reload_config(watcher, &self.config)
+            {
+                clock.set_interval(
+                    config.monitor.interval_secs,
+                    config.monitor.max_backoff_secs,
+                );
+                self.reconfigure(config, &changed, &mut sessions);
+            }
+        }
+
+        log::monitor_event("Monitoring summary:");
+        for (monitor, session) in self.monitors.iter().zip(&sessions) {
+            log::monitor_event(&format!(
+                "  {}: {}",
+                monitor.config.deploy.vps_host,
+                session.summary(clock.elapsed())
+            ));
+        }
+        Ok("Continuous monitoring completed".to_string())
+    }
+}
+
+/// The monitor itself, which no check still holds once `check_all` has returned
+fn exclusive(monitor: &mut Arc<ApplicationMonitor>) -> &mut ApplicationMonitor {
+    Arc::get_mut(monitor).expect("host checks have finished")
+}
+
+/// Aggregate health verdict across hosts
+pub fn verdict(healthy: usize, total: usize) -> String {
+    if healthy == total {
+        format!("{} All {} hosts healthy", glyph("✅"), total)
+    } else if healthy == 0 {
+        format!("{} All {} hosts unhealthy", glyph("❌"), total)
+    } else {
+        format!(
+            "{} Degraded: {}/{} hosts healthy",
+            glyph("⚠️"),
+            healthy,
+            total
+        )
+    }
+}
+
+/// Print a per-host status table followed by the aggregate verdict
+pub fn display_table(results: &[(String, ApplicationStatus)]) {
+    for line in table_lines(results) {
+        log::monitor_event(&line);
+    }
+}
+
+fn table_lines(results: &[(String, ApplicationStatus)]) -> Vec<String> {
+    let width = results
+        .iter()
+        .map(|(host, _)| host.len())
+        .chain(["HOST".len()])
+        .max()
+        .unwrap_or_default();
+    let mark = |ok: bool| if ok { "OK" } else { "FAIL" };
+
+    let mut lines = vec![format!(
+        "{:<width$}  {:<6}  {:<4}  {:<10}  {:>8}  STATUS",
+        "HOST", "HEALTH", "SSH", "SERVICE", "LATENCY"
+    )];
+    for (host, status) in results {
+        lines.push(format!(
+            "{:<width$}  {:<6}  {:<4}  {:<10}  {:>8}  {}",
+            host,
+            mark(status.health_ok),
+            mark(status.ssh_ok),
+            status.service_status.as_deref().unwrap_or("unknown"),
+            status
+                .response_time
+                .map(|d| format!("{}ms", d.as_millis()))
+                .unwrap_or_else(|| "N/A".to_string()),
+            status.summary()
+        ));
+    }
+
+    let healthy = results.iter().filter(|(_, s)| s.is_healthy()).count();
+    lines.push(verdict(healthy, results.len()));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(verdict(3, 3), "✅ All 3 hosts healthy");
+        assert_eq!(verdict(2, 3), "⚠️ Degraded: 2/3 hosts healthy");
+        assert_eq!(verdict(0, 2), "❌ All 2 hosts unhealthy");
+    }
+
+    #[test]
+    fn test_table_lines() {
+        let healthy = ApplicationStatus {
+            health_ok: true,
+            ssh_ok: true,
+            response_time: Some(Duration::from_millis(42)),
+            service_status: Some("active".to_string()),
+            ..Default::default()
+        };
+        let results = vec![
+            ("web1.example.com".to_string(), healthy),
+            ("web2".to_string(), ApplicationStatus::default()),
+        ];
+
+        let lines = table_lines(&results);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("HOST              HEALTH"));
+        assert!(lines[1].starts_with("web1.example.com  OK      OK    active          42ms"));
+        assert!(lines[2].starts_with("web2              FAIL    FAIL  unknown          N/A"));
+        assert_eq!(lines[3], "⚠️ Degraded: 1/2 hosts healthy");
+    }
+}
@@ -0,0 +1,49 @@
+use reqwest::Client;
+
+/// URL to ping for a cycle; failed cycles go to the `/fail` variant
+pub fn ping_url(base: &str, healthy: bool) -> String {
+    if healthy {
+        return base.to_string();
+    }
+
+    let (path, query) = match base.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (base, None),
+    };
+    let mut url = format!("{}/fail", path.trim_end_matches('/'));
+    if let Some(query) = query {
+        url.push('?');
+        url.push_str(query);
+    }
+    url
+}
+
+/// Tell the heartbeat service that a monitoring cycle ran
+///
+/// Failures are only logged: a missed ping is exactly what the service alerts on.
+pub async fn ping(client: &Client, base: &str, healthy: bool) {
+    let url = ping_url(base, healthy);
+    match client.get(&url).send().await {
+        Ok(response) if !response.status().is_success() => {
+            tracing::warn!("Heartbeat ping returned {}", response.status());
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Heartbeat ping failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_url() {
+        let base = "https://hc-ping.com/5f2c1b7e";
+        assert_eq!(ping_url(base, true), base);
+        assert_eq!(ping_url(base, false), "https://hc-ping.com/5f2c1b7e/fail");
+        assert_eq!(
+            ping_url("https://hb.example.com/ping/app/?rid=1", false),
+            "https://hb.example.com/ping/app/fail?rid=1"
+        );
+    }
+}
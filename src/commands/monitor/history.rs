@@ -0,0 +1,437 @@
+use anyhow::{Context, Result, anyhow};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::ApplicationStatus;
+use crate::config::Config;
+
+/// One persisted health check result
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckRecord {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub healthy: bool,
+    pub response_time_ms: Option<u64>,
+    pub service_status: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Aggregated statistics over a window of history
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HistorySummary {
+    pub total_checks: u64,
+    pub failed_checks: u64,
+    pub average_response_time_ms: Option<f64>,
+}
+
+impl HistorySummary {
+    /// Share of healthy checks, or `None` without data
+    pub fn uptime_percentage(&self) -> Option<f64> {
+        if self.total_checks == 0 {
+            None
+        } else {
+            let healthy = self.total_checks - self.failed_checks;
+            Some(healthy as f64 / self.total_checks as f64 * 100.0)
+        }
+    }
+}
+
+/// A period during which the application was unhealthy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Incident {
+    pub id: i64,
+    pub host: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub cause: String,
+}
+
+impl Incident {
+    /// Downtime so far, or in total once resolved
+    pub fn duration(&self, now: chrono::DateTime<chrono::Utc>) -> std::time::Duration {
+        (self.ended_at.unwrap_or(now) - self.started_at)
+            .to_std()
+            .unwrap_or_default()
+    }
+}
+
+/// Local SQLite store of monitoring results, scoped to one project and host
+pub struct HistoryStore {
+    conn: Connection,
+    project: String,
+    host: String,
+}
+
+impl HistoryStore {
+    /// Open the store configured for this project, pruning expired records
+    pub fn open(config: &Config) -> Result<Self> {
+        let path = history_path(config)?;
+        let store = Self::open_at(&path, &config.project.name, &config.deploy.vps_host)?;
+
+        let cutoff = chrono::Utc::now()
+            - chrono::Duration::days(config.monitor.history_retention_days as i64);
+        store.prune_before(cutoff)?;
+        Ok(store)
+    }
+
+    /// Open (creating if needed) a store at an explicit path
+    pub fn open_at(path: &Path, project: &str, host: &str) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create history directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open history database: {}", path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS checks (
+                 id INTEGER PRIMARY KEY,
+                 project TEXT NOT NULL,
+                 host TEXT NOT NULL,
+                 timestamp INTEGER NOT NULL,
+                 healthy INTEGER NOT NULL,
+                 response_time_ms INTEGER,
+                 service_status TEXT,
+                 error TEXT
+             );
+             CREATE INDEX IF NOT EXISTS checks_by_target
+                 ON checks (project, host, timestamp);
+             CREATE TABLE IF NOT EXISTS incidents (
+                 id INTEGER PRIMARY KEY,
+                 project TEXT NOT NULL,
+                 host TEXT NOT NULL,
+                 started_at INTEGER NOT NULL,
+                 ended_at INTEGER,
+                 cause TEXT NOT NULL
+             );",
+        )
+        .context("Failed to initialize history database")?;
+
+        Ok(Self {
+            conn,
+            project: project.to_string(),
+            host: host.to_string(),
+        })
+    }
+
+    /// Persist a status sample
+    pub fn record(&self, status: &ApplicationStatus) -> Result<()> {
+        self.insert(&CheckRecord {
+            timestamp: chrono::Utc::now(),
+            healthy: status.is_healthy(),
+            response_time_ms: status.response_time.map(|d| d.as_millis() as u64),
+            service_status: status.service_status.clone(),
+            error: status.last_error.clone(),
+        })
+    }
+
+    /// Persist a check record
+    pub fn insert(&self, record: &CheckRecord) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO checks
+                     (project, host, timestamp, healthy, response_time_ms, service_status, error)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    self.project,
+                    self.host,
+                    record.timestamp.timestamp(),
+                    record.healthy,
+                    record.response_time_ms.map(|ms| ms as i64),
+                    record.service_status,
+                    record.error,
+                ],
+            )
+            .context("Failed to record health check")?;
+        Ok(())
+    }
+
+    /// Summarize checks recorded since `since`
+    pub fn summary_since(&self, since: chrono::DateTime<chrono::Utc>) -> Result<HistorySummary> {
+        self.conn
+            .query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(CASE WHEN healthy THEN 0 ELSE 1 END), 0),
+                        AVG(response_time_ms)
+                 FROM checks
+                 WHERE project = ?1 AND host = ?2 AND timestamp >= ?3",
+                params![self.project, self.host, since.timestamp()],
+                |row| {
+                    Ok(HistorySummary {
+                        total_checks: row.get::<_, i64>(0)? as u64,
+                        failed_checks: row.get::<_, i64>(1)? as u64,
+                        average_response_time_ms: row.get(2)?,
+                    })
+                },
+            )
+            .context("Failed to query monitoring history")
+    }
+
+    /// Response times of the most recent `limit` checks, oldest first
+    pub fn recent_response_times(&self, limit: usize) -> Result<Vec<u64>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT response_time_ms FROM checks
+                 WHERE project = ?1 AND host = ?2 AND response_time_ms IS NOT NULL
+                 ORDER BY timestamp DESC, id DESC LIMIT ?3",
+            )
+            .context("Failed to query response times")?;
+
+        let mut times = statement
+            .query_map(params![self.project, self.host, limit as i64], |row| {
+                row.get::<_, i64>(0)
+            })?
+            .map(|time| time.map(|ms| ms as u64))
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read response times")?;
+        times.reverse();
+        Ok(times)
+    }
+
+    /// All checks recorded since `since`, oldest first
+    pub fn checks_since(&self, since: chrono::DateTime<chrono::Utc>) -> Result<Vec<CheckRecord>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT timestamp, healthy, response_time_ms, service_status, error FROM checks
+                 WHERE project = ?1 AND host = ?2 AND timestamp >= ?3
+                 ORDER BY timestamp, id",
+            )
+            .context("Failed to query monitoring history")?;
+
+        let checks = statement
+            .query_map(params![self.project, self.host, since.timestamp()], |row| {
+                Ok(CheckRecord {
+                    timestamp: chrono::DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+                    healthy: row.get(1)?,
+                    response_time_ms: row.get::<_, Option<i64>>(2)?.map(|ms| ms as u64),
+                    service_status: row.get(3)?,
+                    error: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read monitoring history")?;
+        Ok(checks)
+    }
+
+    /// The incident that is still open for this target, if any
+    pub fn open_incident(&self) -> Result<Option<Incident>> {
+        self.conn
+            .query_row(
+                "SELECT id, started_at, ended_at, cause FROM incidents
+                 WHERE project = ?1 AND host = ?2 AND ended_at IS NULL
+                 ORDER BY started_at DESC LIMIT 1",
+                params![self.project, self.host],
+                |row| self.incident_from_row(row),
+            )
+            .optional()
+            .context("Failed to query open incident")
+    }
+
+    /// Record the start of an incident
+    pub fn start_incident(
+        &self,
+        started_at: chrono::DateTime<chrono::Utc>,
+        cause: &str,
+    ) -> Result<Incident> {
+        self.conn
+            .execute(
+                "INSERT INTO incidents (project, host, started_at, cause) VALUES (?1, ?2, ?3, ?4)",
+                params![self.project, self.host, started_at.timestamp(), cause],
+            )
+            .context("Failed to record incident")?;
+
+        Ok(Incident {
+            id: self.conn.last_insert_rowid(),
+            host: self.host.clone(),
+            started_at,
+            ended_at: None,
+            cause: cause.to_string(),
+        })
+    }
+
+    /// Mark an incident as resolved
+    pub fn resolve_incident(
+        &self,
+        incident: &Incident,
+        ended_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Incident> {
+        self.conn
+            .execute(
+                "UPDATE incidents SET ended_at = ?1 WHERE id = ?2",
+                params![ended_at.timestamp(), incident.id],
+            )
+            .context("Failed to resolve incident")?;
+
+        Ok(Incident {
+            ended_at: Some(ended_at),
+            ..incident.clone()
+        })
+    }
+
+    /// Most recent incidents for this project, newest first
+    pub fn incidents(&self, limit: usize) -> Result<Vec<Incident>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT id, started_at, ended_at, cause, host FROM incidents
+                 WHERE project = ?1
+                 ORDER BY started_at DESC LIMIT ?2",
+            )
+            .context("Failed to query incidents")?;
+
+        let incidents = statement
+            .query_map(params![self.project, limit as i64], |row| {
+                Ok(Incident {
+                    host: row.get(4)?,
+                    ..self.incident_from_row(row)?
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read incidents")?;
+        Ok(incidents)
+    }
+
+    /// Incidents for this project that were open at any point since `since`, newest first
+    pub fn incidents_since(&self, since: chrono::DateTime<chrono::Utc>) -> Result<Vec<Incident>> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT id, started_at, ended_at, cause, host FROM incidents
+                 WHERE project = ?1 AND (ended_at IS NULL OR ended_at >= ?2)
+                 ORDER BY started_at DESC",
+            )
+            .context("Failed to query incidents")?;
+
+        let incidents = statement
+            .query_map(params![self.project, since.timestamp()], |row| {
+                Ok(Incident {
+                    host: row.get(4)?,
+                    ..self.incident_from_row(row)?
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read incidents")?;
+        Ok(incidents)
+    }
+
+    fn incident_from_row(&self, row: &rusqlite::Row) -> rusqlite::Result<Incident> {
+        let timestamp = |secs: i64| chrono::DateTime::from_timestamp(secs, 0).unwrap_or_default();
+        Ok(Incident {
+            id: row.get(0)?,
+            host: self.host.clone(),
+            started_at: timestamp(row.get(1)?),
+            ended_at: row.get::<_, Option<i64>>(2)?.map(timestamp),
+            cause: row.get(3)?,
+        })
+    }
+
+    /// Delete records older than `cutoff`
+    pub fn prune_before(&self, cutoff: chrono::DateTime<chrono::Utc>) -> Result<usize> {
+        self.conn
+            .execute(
+                "DELETE FROM checks WHERE timestamp < ?1",
+                params![cutoff.timestamp()],
+            )
+            .context("Failed to prune monitoring history")
+    }
+}
+
+/// Resolve the history database location
+pub fn history_path(config: &Config) -> Result<PathBuf> {
+    if let Some(path) = &config.monitor.history_path {
+        return Ok(PathBuf::from(shellexpand::tilde(path).to_string()));
+    }
+
+    dirs::data_local_dir()
+        .map(|dir| dir.join("rzen").join("history.db"))
+        .ok_or_else(|| anyhow!("Could not determine local data directory for monitoring history"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn record(offset_secs: i64, healthy: bool, response_time_ms: Option<u64>) -> CheckRecord {
+        CheckRecord {
+            timestamp: chrono::Utc::now() + chrono::Duration::seconds(offset_secs),
+            healthy,
+            response_time_ms,
+            service_status: Some("active".to_string()),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_history_summary() {
+        let temp_dir = tempdir().unwrap();
+        let store = HistoryStore::open_at(&temp_dir.path().join("h.db"), "app", "host").unwrap();
+        let other = HistoryStore::open_at(&temp_dir.path().join("h.db"), "other", "host").unwrap();
+
+        store.insert(&record(-7200, false, None)).unwrap();
+        store.insert(&record(-30, true, Some(100))).unwrap();
+        store.insert(&record(-20, true, Some(200))).unwrap();
+        store.insert(&record(-10, false, None)).unwrap();
+        other.insert(&record(-10, false, None)).unwrap();
+
+        let summary = store
+            .summary_since(chrono::Utc::now() - chrono::Duration::hours(1))
+            .unwrap();
+        assert_eq!(summary.total_checks, 3);
+        assert_eq!(summary.failed_checks, 1);
+        assert_eq!(summary.average_response_time_ms, Some(150.0));
+        assert!((summary.uptime_percentage().unwrap() - 66.666).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_recent_response_times() {
+        let temp_dir = tempdir().unwrap();
+        let store = HistoryStore::open_at(&temp_dir.path().join("h.db"), "app", "host").unwrap();
+
+        store.insert(&record(-30, true, Some(100))).unwrap();
+        store.insert(&record(-20, false, None)).unwrap();
+        store.insert(&record(-10, true, Some(200))).unwrap();
+        store.insert(&record(0, true, Some(300))).unwrap();
+
+        assert_eq!(store.recent_response_times(2).unwrap(), vec![200, 300]);
+        assert_eq!(
+            store.recent_response_times(10).unwrap(),
+            vec![100, 200, 300]
+        );
+    }
+
+    #[test]
+    fn test_checks_since() {
+        let temp_dir = tempdir().unwrap();
+        let store = HistoryStore::open_at(&temp_dir.path().join("h.db"), "app", "host").unwrap();
+
+        store.insert(&record(-7200, true, Some(50))).unwrap();
+        store.insert(&record(-20, false, None)).unwrap();
+        store.insert(&record(-10, true, Some(200))).unwrap();
+
+        let checks = store
+            .checks_since(chrono::Utc::now() - chrono::Duration::hours(1))
+            .unwrap();
+        assert_eq!(checks.len(), 2);
+        assert!(!checks[0].healthy);
+        assert_eq!(checks[1].response_time_ms, Some(200));
+    }
+
+    #[test]
+    fn test_history_prune() {
+        let temp_dir = tempdir().unwrap();
+        let store = HistoryStore::open_at(&temp_dir.path().join("h.db"), "app", "host").unwrap();
+
+        store.insert(&record(-86400 * 40, true, Some(10))).unwrap();
+        store.insert(&record(0, true, Some(10))).unwrap();
+
+        let removed = store
+            .prune_before(chrono::Utc::now() - chrono::Duration::days(30))
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(HistorySummary::default().uptime_percentage(), None);
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::Result;
+
+use super::ApplicationStatus;
+use super::history::{HistoryStore, Incident};
+use crate::config::Config;
+use crate::logging::log;
+use crate::utils::timing::format_duration;
+
+/// Change in incident state caused by a status sample
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transition {
+    Started(Incident),
+    Resolved(Incident),
+}
+
+/// Open an incident when the target goes down and resolve it on recovery
+pub fn track(
+    store: &HistoryStore,
+    status: &ApplicationStatus,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Option<Transition>> {
+    let transition = match (status.is_healthy(), store.open_incident()?) {
+        (false, None) => Some(Transition::Started(
+            store.start_incident(now, &cause(status))?,
+        )),
+        (true, Some(incident)) => Some(Transition::Resolved(
+            store.resolve_incident(&incident, now)?,
+        )),
+        _ => None,
+    };
+    Ok(transition)
+}
+
+/// Describe why a status sample is unhealthy
+fn cause(status: &ApplicationStatus) -> String {
+    match &status.last_error {
+        Some(error) => format!("{} - {}", status.summary(), error),
+        None => status.summary(),
+    }
+}
+
+/// Print recent incidents from the local history
+pub fn show_incidents(config: &Config, limit: usize) -> Result<String> {
+    let store = HistoryStore::open(config)?;
+    let incidents = store.incidents(limit)?;
+
+    if incidents.is_empty() {
+        log::monitor_event("No incidents recorded");
+        return Ok("No incidents".to_string());
+    }
+
+    let now = chrono::Utc::now();
+    let timezone = config.display_timezone();
+    log::monitor_event(&format!("Recent incidents for {}:", config.project.name));
+    for incident in &incidents {
+        let state = match incident.ended_at {
+            Some(_) => "resolved",
+            None => "ONGOING",
+        };
+        log::monitor_event(&format!(
+            "  {}  {:<8}  {:>8}  {}  {}",
+            timezone.format(incident.started_at),
+            state,
+            format_duration(incident.duration(now)),
+            incident.host,
+            incident.cause
+        ));
+    }
+
+    Ok(format!("{} incidents", incidents.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_incident_lifecycle() {
+        let temp_dir = tempdir().unwrap();
+        let store = HistoryStore::open_at(&temp_dir.path().join("h.db"), "app", "host").unwrap();
+        let start = chrono::Utc::now();
+
+        let healthy = ApplicationStatus {
+            health_ok: true,
+            ssh_ok: true,
+            service_status: Some("active".to_string()),
+            ..Default::default()
+        };
+        let down = ApplicationStatus {
+            ssh_ok: true,
+            service_status: Some("failed".to_string()),
+            last_error: Some("connection refused".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(track(&store, &healthy, start).unwrap(), None);
+
+        let Some(Transition::Started(incident)) = track(&store, &down, start).unwrap() else {
+            panic!("expected an incident to start");
+        };
+        assert!(incident.cause.ends_with("connection refused"));
+        assert_eq!(track(&store, &down, start).unwrap(), None);
+
+        let later = start + chrono::Duration::seconds(90);
+        let Some(Transition::Resolved(resolved)) = track(&store, &healthy, later).unwrap() else {
+            panic!("expected the incident to resolve");
+        };
+        assert_eq!(resolved.id, incident.id);
+        assert_eq!(resolved.duration(later).as_secs(), 90);
+
+        let incidents = store.incidents(10).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert!(incidents[0].ended_at.is_some());
+        assert_eq!(store.open_incident().unwrap(), None);
+    }
+}
@@ -0,0 +1,103 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Health-check latency percentiles in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LatencyPercentiles {
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub samples: usize,
+}
+
+impl LatencyPercentiles {
+    /// One-line summary for CLI and TUI display
+    pub fn summary(&self) -> String {
+        format!(
+            "p50 {}ms | p95 {}ms | p99 {}ms (last {} checks)",
+            self.p50, self.p95, self.p99, self.samples
+        )
+    }
+}
+
+/// Sliding window over the most recent health-check response times
+#[derive(Debug, Clone)]
+pub struct LatencyWindow {
+    samples: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl LatencyWindow {
+    /// Create a window holding at most `capacity` samples, oldest first in `initial`
+    pub fn new(capacity: usize, initial: impl IntoIterator<Item = u64>) -> Self {
+        let mut window = Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        };
+        for sample in initial {
+            window.push(sample);
+        }
+        window
+    }
+
+    /// Add a sample, evicting the oldest once full
+    pub fn push(&mut self, millis: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(millis);
+    }
+
+    /// Nearest-rank percentiles over the window, or `None` when empty
+    pub fn percentiles(&self) -> Option<LatencyPercentiles> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = |percentile: f64| {
+            let index = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[index.clamp(1, sorted.len()) - 1]
+        };
+
+        Some(LatencyPercentiles {
+            p50: rank(50.0),
+            p95: rank(95.0),
+            p99: rank(99.0),
+            samples: sorted.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let window = LatencyWindow::new(100, 1..=100);
+        let percentiles = window.percentiles().unwrap();
+        assert_eq!(
+            (percentiles.p50, percentiles.p95, percentiles.p99),
+            (50, 95, 99)
+        );
+        assert_eq!(percentiles.samples, 100);
+
+        assert_eq!(LatencyWindow::new(10, []).percentiles(), None);
+    }
+
+    #[test]
+    fn test_window_evicts_oldest() {
+        let mut window = LatencyWindow::new(3, [1000, 10, 20]);
+        window.push(30);
+
+        let percentiles = window.percentiles().unwrap();
+        assert_eq!(percentiles.samples, 3);
+        assert_eq!(percentiles.p99, 30);
+        assert_eq!(percentiles.p50, 20);
+    }
+}
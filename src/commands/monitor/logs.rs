@@ -0,0 +1,624 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use regex::Regex;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use super::structured::Severity;
+use crate::config::Config;
+use crate::utils;
+use crate::utils::host::Host;
+use crate::utils::ssh::shell_quote;
+use crate::utils::timing::DisplayTimezone;
+
+/// Include/exclude patterns applied to log lines
+///
+/// Patterns are pushed to the server as `grep -E` stages to cut bandwidth and
+/// re-checked locally, so output is correct even where grep's dialect differs.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    grep: Option<Regex>,
+    exclude: Option<Regex>,
+}
+
+impl LogFilter {
+    /// Compile the `--grep` and `--exclude` expressions
+    pub fn new(grep: Option<&str>, exclude: Option<&str>) -> Result<Self> {
+        let compile = |pattern: &str| {
+            Regex::new(pattern).with_context(|| format!("Invalid log filter regex: {}", pattern))
+        };
+
+        Ok(Self {
+            grep: grep.map(compile).transpose()?,
+            exclude: exclude.map(compile).transpose()?,
+        })
+    }
+
+    /// Whether a line passes both filters
+    pub fn matches(&self, line: &str) -> bool {
+        self.grep.as_ref().is_none_or(|re| re.is_match(line))
+            && !self.exclude.as_ref().is_some_and(|re| re.is_match(line))
+    }
+
+    /// `grep -E` stages applied on the server, each prefixed with ` | `
+    fn remote_filters(&self, follow: bool) -> String {
+        let buffered = if follow { " --line-buffered" } else { "" };
+        let mut filters = String::new();
+        if let Some(re) = &self.grep {
+            filters.push_str(&format!(
+                " | grep{} -E -- {}",
+                buffered,
+                shell_quote(re.as_str())
+            ));
+        }
+        if let Some(re) = &self.exclude {
+            filters.push_str(&format!(
+                " | grep{} -v -E -- {}",
+                buffered,
+                shell_quote(re.as_str())
+            ));
+        }
+        filters
+    }
+
+    /// Remote command that prints (or follows) the log with filters applied server-side
+    pub fn remote_command(&self, source: &LogSource, query: &LogQuery) -> String {
+        let filters = self.remote_filters(query.follow);
+        let range = &query.range;
+
+        match source {
+            LogSource::File(path) => {
+                if query.follow {
+                    // -F reopens the file by name after logrotate moves or truncates it.
+                    // With --since the whole file is read and trimmed locally.
+                    let start = match range.since {
+                        Some(_) => "+1".to_string(),
+                        None => query.lines.to_string(),
+                    };
+                    format!("tail -F -n {} {}{}", start, shell_quote(path), filters)
+                } else if !range.is_unbounded() || query.level.is_some() {
+                    // Timestamps and levels are checked locally, so fetch every candidate line
+                    format!("cat {}{}", shell_quote(path), filters)
+                } else if filters.is_empty() {
+                    format!("tail -n {} {}", query.lines, shell_quote(path))
+                } else {
+                    // Filter the whole file so `lines` counts matching lines
+                    format!(
+                        "cat {}{} | tail -n {}",
+                        shell_quote(path),
+                        filters,
+                        query.lines
+                    )
+                }
+            }
+            LogSource::Journal(unit) => {
+                let mut command = format!(
+                    "sudo journalctl -u {} --no-pager -o short-iso",
+                    shell_quote(unit)
+                );
+                for (flag, bound) in [("--since", range.since), ("--until", range.until)] {
+                    if let Some(time) = bound {
+                        command.push_str(&format!(
+                            " {} {}",
+                            flag,
+                            shell_quote(&time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                        ));
+                    }
+                }
+
+                if query.follow {
+                    if range.since.is_none() {
+                        command.push_str(&format!(" -n {}", query.lines));
+                    }
+                    format!("{} -f{}", command, filters)
+                } else if !range.is_unbounded() || query.level.is_some() {
+                    format!("{}{}", command, filters)
+                } else if filters.is_empty() {
+                    format!("{} -n {}", command, query.lines)
+                } else {
+                    format!("{}{} | tail -n {}", command, filters, query.lines)
+                }
+            }
+        }
+    }
+}
+
+/// Where application logs are read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogSource {
+    /// Log file written by the application (`monitor.log_path`)
+    File(String),
+    /// systemd journal of a unit
+    Journal(String),
+}
+
+impl LogSource {
+    /// The configured log file, or the service's journal when none is set
+    pub fn from_config(config: &Config) -> Self {
+        match &config.monitor.log_path {
+            Some(path) => Self::File(path.clone()),
+            None => Self::Journal(config.service_name()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::File(path) => path.clone(),
+            Self::Journal(unit) => format!("journal of {}", unit),
+        }
+    }
+}
+
+/// What part of the log to show
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogQuery {
+    /// Trailing lines to show when no time range is given
+    pub lines: usize,
+    pub follow: bool,
+    pub range: TimeRange,
+    /// Lowest severity shown, checked locally
+    pub level: Option<Severity>,
+}
+
+/// Optional `--since`/`--until` bounds, both inclusive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    /// Parse bounds given as durations before `now` (`2h`) or timestamps
+    /// (`2024-06-01 12:00`, read in `timezone`)
+    pub fn parse(
+        since: Option<&str>,
+        until: Option<&str>,
+        now: DateTime<Utc>,
+        timezone: DisplayTimezone,
+    ) -> Result<Self> {
+        let parse = |input| parse_time(input, now, timezone);
+        let range = Self {
+            since: since.map(parse).transpose()?,
+            until: until.map(parse).transpose()?,
+        };
+        if let (Some(since), Some(until)) = (range.since, range.until)
+            && since > until
+        {
+            return Err(anyhow!("--since must be earlier than --until"));
+        }
+        Ok(range)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| time >= since) && self.until.is_none_or(|until| time <= until)
+    }
+}
+
+/// Applies a [`TimeRange`] to a stream of log lines
+///
+/// Lines without a timestamp (stack traces, wrapped messages) belong to the
+/// entry above them.
+#[derive(Debug, Clone)]
+pub struct RangeFilter {
+    range: TimeRange,
+    server: DisplayTimezone,
+    last: Option<DateTime<Utc>>,
+}
+
+impl RangeFilter {
+    /// Filter lines whose timestamps lack an offset are read in `server` time
+    pub fn new(range: TimeRange, server: DisplayTimezone) -> Self {
+        Self {
+            range,
+            server,
+            last: None,
+        }
+    }
+
+    pub fn accepts(&mut self, line: &str) -> bool {
+        if self.range.is_unbounded() {
+            return true;
+        }
+        if let Some((_, time)) = find_timestamp(line, self.server) {
+            self.last = Some(time);
+        }
+        self.last.is_some_and(|time| self.range.contains(time))
+    }
+}
+
+/// Keeps log lines at or above a severity
+///
+/// Lines without a level of their own (stack traces, the `-->` lines under
+/// a compiler error) belong to the last line that had one.
+#[derive(Debug, Clone)]
+pub struct SeverityFilter {
+    minimum: Option<Severity>,
+    current: Option<Severity>,
+}
+
+impl SeverityFilter {
+    pub fn new(minimum: Option<Severity>) -> Self {
+        Self {
+            minimum,
+            current: None,
+        }
+    }
+
+    pub fn accepts(&mut self, line: &str) -> bool {
+        let Some(minimum) = self.minimum else {
+            return true;
+        };
+        if let Some(severity) = Severity::of_line(line) {
+            self.current = Some(severity);
+        }
+        self.current.is_some_and(|severity| severity >= minimum)
+    }
+}
+
+/// Rewrites log timestamps from server time into the display timezone
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampConverter {
+    server: DisplayTimezone,
+    display: DisplayTimezone,
+    keep_original: bool,
+}
+
+impl TimestampConverter {
+    /// Converter for the configured `monitor.timezone`; `None` leaves lines untouched
+    pub fn for_config(config: &Config, server: DisplayTimezone) -> Option<Self> {
+        config.monitor.timezone.as_ref().map(|_| Self {
+            server,
+            display: config.display_timezone(),
+            keep_original: config.monitor.show_original_timestamps,
+        })
+    }
+
+    /// Replace the first timestamp in `line`, keeping the original alongside on request
+    pub fn convert<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        let Some((span, time)) = find_timestamp(line, self.server) else {
+            return Cow::Borrowed(line);
+        };
+
+        let mut converted = self.display.format(time);
+        if self.keep_original {
+            converted = format!("{} ({})", converted, &line[span.clone()]);
+        }
+        Cow::Owned(format!(
+            "{}{}{}",
+            &line[..span.start],
+            converted,
+            &line[span.end..]
+        ))
+    }
+}
+
+/// Save the whole log to `local_path`: a log file is downloaded as is, a journal written out as text
+pub fn download(
+    host: &dyn Host,
+    source: &LogSource,
+    local_path: &Path,
+    on_progress: Option<&dyn Fn(&utils::progress::Transfer)>,
+) -> Result<()> {
+    match source {
+        LogSource::File(path) => host.download_file(path, local_path, on_progress),
+        LogSource::Journal(unit) => {
+            let (output, _) = host.execute_command(&format!(
+                "sudo journalctl -u {} --no-pager -o short-iso",
+                shell_quote(unit)
+            ))?;
+            std::fs::write(local_path, output)
+                .with_context(|| format!("Failed to write {}", local_path.display()))
+        }
+    }
+}
+
+/// Timezone of the server clock, used for log timestamps without an offset
+pub fn server_timezone(host: &dyn Host) -> DisplayTimezone {
+    host.execute_command("date +%:z")
+        .ok()
+        .and_then(|(output, _)| DisplayTimezone::parse(output.trim()).ok())
+        .unwrap_or_else(|| {
+            tracing::warn!("Could not read server timezone, assuming UTC");
+            DisplayTimezone::Utc
+        })
+}
+
+/// Parse a time bound; timestamps without an offset are read in `timezone`
+pub fn parse_time(
+    input: &str,
+    now: DateTime<Utc>,
+    timezone: DisplayTimezone,
+) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(ago) = utils::timing::parse_duration(input) {
+        return chrono::Duration::from_std(ago)
+            .ok()
+            .and_then(|ago| now.checked_sub_signed(ago))
+            .ok_or_else(|| anyhow!("Time is out of range: {}", input));
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(input, "%Y-%m-%d")
+            .ok()
+            .map(|date| date.and_time(NaiveTime::MIN))
+    });
+    if let Some(time) = naive.and_then(|naive| timezone.resolve_naive(naive)) {
+        return Ok(time);
+    }
+
+    Err(anyhow!(
+        "Invalid time: {}. Use a duration such as 2h or a timestamp such as \"2024-06-01 12:00\"",
+        input
+    ))
+}
+
+/// Byte span and instant of the first ISO-8601 style timestamp in a log line;
+/// timestamps without an offset are read in `server` time
+pub fn find_timestamp(
+    line: &str,
+    server: DisplayTimezone,
+) -> Option<(Range<usize>, DateTime<Utc>)> {
+    static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(\d{4}-\d{2}-\d{2})[T ](\d{2}:\d{2}:\d{2}(?:\.\d+)?)(Z|[+-]\d{2}:?\d{2})?")
+            .expect("timestamp regex is valid")
+    });
+
+    let captures = TIMESTAMP.captures(line)?;
+    let span = captures.get(0)?.range();
+    let naive = format!("{}T{}", &captures[1], &captures[2]);
+    let time = match captures.get(3).map(|offset| offset.as_str()) {
+        Some("Z") => NaiveDateTime::parse_from_str(&naive, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .map(|time| time.and_utc()),
+        None => NaiveDateTime::parse_from_str(&naive, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .and_then(|time| server.resolve_naive(time)),
+        Some(offset) => {
+            let offset = offset.replace(':', "");
+            DateTime::parse_from_str(&format!("{}{}", naive, offset), "%Y-%m-%dT%H:%M:%S%.f%z")
+                .ok()
+                .map(|time| time.with_timezone(&Utc))
+        }
+    }?;
+    Some((span, time))
+}
+
+/// Log file changes that `tail -F` reports on stderr while following
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationEvent {
+    /// The file was moved away and a new one created (logrotate `create`)
+    Replaced,
+    /// The file was truncated in place (logrotate `copytruncate`)
+    Truncated,
+    /// The file disappeared and has not been recreated yet
+    Inaccessible,
+    /// A missing file was created again
+    Appeared,
+}
+
+impl RotationEvent {
+    /// Recognize a `tail -F` diagnostic line
+    pub fn parse(line: &str) -> Option<Self> {
+        if !line.starts_with("tail: ") {
+            return None;
+        }
+        if line.contains("has been replaced") {
+            Some(Self::Replaced)
+        } else if line.ends_with("file truncated") {
+            Some(Self::Truncated)
+        } else if line.contains("has become inaccessible") {
+            Some(Self::Inaccessible)
+        } else if line.contains("has appeared") {
+            Some(Self::Appeared)
+        } else {
+            None
+        }
+    }
+
+    /// Marker shown in the log stream
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Self::Replaced => "log rotated, following new file",
+            Self::Truncated => "log truncated, following from the start",
+            Self::Inaccessible => "log file missing, waiting for it to reappear",
+            Self::Appeared => "log file reappeared, following new file",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_filter_matches() {
+        let filter = LogFilter::new(Some("ERROR|WARN"), Some("/health")).unwrap();
+        assert!(filter.matches("2024-01-01 ERROR db timeout"));
+        assert!(!filter.matches("2024-01-01 INFO started"));
+        assert!(!filter.matches("2024-01-01 WARN GET /health 200"));
+
+        assert!(LogFilter::default().matches("anything"));
+        assert!(LogFilter::new(Some("("), None).is_err());
+    }
+
+    #[test]
+    fn test_rotation_event() {
+        assert_eq!(
+            RotationEvent::parse("tail: '/var/log/app.log' has been replaced;  following new file"),
+            Some(RotationEvent::Replaced)
+        );
+        assert_eq!(
+            RotationEvent::parse("tail: /var/log/app.log: file truncated"),
+            Some(RotationEvent::Truncated)
+        );
+        assert_eq!(
+            RotationEvent::parse(
+                "tail: '/var/log/app.log' has become inaccessible: No such file or directory"
+            ),
+            Some(RotationEvent::Inaccessible)
+        );
+        assert_eq!(
+            RotationEvent::parse("INFO file truncated by user request"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_remote_command() {
+        let file = LogSource::File("/var/log/app.log".to_string());
+        let query = |lines, follow| LogQuery {
+            lines,
+            follow,
+            ..Default::default()
+        };
+        let filter = LogFilter::new(Some("ERROR"), Some("it's noisy")).unwrap();
+        assert_eq!(
+            filter.remote_command(&file, &query(20, true)),
+            "tail -F -n 20 '/var/log/app.log' | grep --line-buffered -E -- 'ERROR' \
+             | grep --line-buffered -v -E -- 'it'\\''s noisy'"
+        );
+        assert_eq!(
+            filter.remote_command(&file, &query(20, false)),
+            "cat '/var/log/app.log' | grep -E -- 'ERROR' | grep -v -E -- 'it'\\''s noisy' \
+             | tail -n 20"
+        );
+        assert_eq!(
+            LogFilter::default().remote_command(&file, &query(5, false)),
+            "tail -n 5 '/var/log/app.log'"
+        );
+    }
+
+    #[test]
+    fn test_time_range_commands() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T14:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let range = TimeRange::parse(
+            Some("2h"),
+            Some("2024-06-01 13:30"),
+            now,
+            DisplayTimezone::Utc,
+        )
+        .unwrap();
+        let query = LogQuery {
+            lines: 50,
+            follow: false,
+            range,
+            level: None,
+        };
+
+        let journal = LogSource::Journal("app".to_string());
+        assert_eq!(
+            LogFilter::default().remote_command(&journal, &query),
+            "sudo journalctl -u 'app' --no-pager -o short-iso \
+             --since '2024-06-01 12:00:00 UTC' --until '2024-06-01 13:30:00 UTC'"
+        );
+
+        let file = LogSource::File("/var/log/app.log".to_string());
+        assert_eq!(
+            LogFilter::default().remote_command(&file, &query),
+            "cat '/var/log/app.log'"
+        );
+
+        assert!(TimeRange::parse(Some("1h"), Some("3h"), now, DisplayTimezone::Utc).is_err());
+        assert!(TimeRange::parse(Some("yesterday-ish"), None, now, DisplayTimezone::Utc).is_err());
+    }
+
+    #[test]
+    fn test_severity_filter() {
+        let query = LogQuery {
+            lines: 20,
+            follow: false,
+            range: TimeRange::default(),
+            level: Some(Severity::Warn),
+        };
+        let journal = LogSource::Journal("nginx".to_string());
+        assert_eq!(
+            LogFilter::default().remote_command(&journal, &query),
+            "sudo journalctl -u 'nginx' --no-pager -o short-iso"
+        );
+        let query = LogQuery {
+            follow: true,
+            ..query
+        };
+        assert_eq!(
+            LogFilter::default().remote_command(&journal, &query),
+            "sudo journalctl -u 'nginx' --no-pager -o short-iso -n 20 -f"
+        );
+
+        let mut filter = SeverityFilter::new(Some(Severity::Warn));
+        assert!(!filter.accepts("starting"));
+        assert!(!filter.accepts(r#"{"level":"info","msg":"listening"}"#));
+        assert!(filter.accepts("2024-06-01T12:00:00Z ERROR request failed"));
+        assert!(filter.accepts("    at handler (src/api.rs:42)"));
+        assert!(!filter.accepts("level=debug msg=retrying"));
+        assert!(SeverityFilter::new(None).accepts("anything"));
+    }
+
+    #[test]
+    fn test_range_filter() {
+        let utc = DisplayTimezone::Utc;
+        let range = TimeRange::parse(
+            Some("2024-06-01T12:00:00Z"),
+            Some("2024-06-01 12:30"),
+            Utc::now(),
+            utc,
+        )
+        .unwrap();
+        let mut filter = RangeFilter::new(range, utc);
+
+        assert!(!filter.accepts("2024-06-01 11:59:59 INFO starting"));
+        assert!(filter.accepts("2024-06-01T14:05:00+02:00 ERROR boom"));
+        assert!(filter.accepts("    at src/main.rs:10"));
+        assert!(!filter.accepts(r#"{"ts":"2024-06-01T12:30:00.250Z","level":"info"}"#));
+        assert!(!filter.accepts("    continuation of a late entry"));
+
+        // Server clock two hours ahead of UTC
+        let server = DisplayTimezone::parse("+02:00").unwrap();
+        let mut filter = RangeFilter::new(range, server);
+        assert!(filter.accepts("2024-06-01 14:10:00 INFO in range"));
+
+        assert!(RangeFilter::new(TimeRange::default(), utc).accepts("no timestamp"));
+    }
+
+    #[test]
+    fn test_timestamp_converter() {
+        let converter = TimestampConverter {
+            server: DisplayTimezone::parse("-05:00").unwrap(),
+            display: DisplayTimezone::Utc,
+            keep_original: false,
+        };
+        assert_eq!(
+            converter.convert("2024-06-01 07:00:00 INFO started"),
+            "2024-06-01 12:00:00 UTC INFO started"
+        );
+        assert_eq!(converter.convert("no timestamp"), "no timestamp");
+
+        let converter = TimestampConverter {
+            display: DisplayTimezone::parse("+02:00").unwrap(),
+            keep_original: true,
+            ..converter
+        };
+        assert_eq!(
+            converter.convert("[2024-06-01T12:00:00Z] WARN slow"),
+            "[2024-06-01 14:00:00 +02:00 (2024-06-01T12:00:00Z)] WARN slow"
+        );
+    }
+}
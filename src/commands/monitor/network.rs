@@ -0,0 +1,107 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::utils;
+
+/// TCP connects per probe; the fastest one estimates the round trip, like ping's min RTT
+const ATTEMPTS: usize = 3;
+
+/// Reachability and round-trip estimate to the host, independent of the application
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NetworkProbe {
+    pub port: u16,
+    /// Fastest TCP handshake in milliseconds; `None` when no attempt connected
+    pub rtt_ms: Option<f64>,
+    pub attempts: usize,
+    pub failures: usize,
+}
+
+impl NetworkProbe {
+    /// Build a probe result from per-attempt connect times (`None` for failures)
+    pub fn from_samples(port: u16, samples: &[Option<Duration>]) -> Self {
+        Self {
+            port,
+            rtt_ms: samples
+                .iter()
+                .flatten()
+                .map(|duration| duration.as_secs_f64() * 1000.0)
+                .min_by(f64::total_cmp),
+            attempts: samples.len(),
+            failures: samples.iter().filter(|sample| sample.is_none()).count(),
+        }
+    }
+
+    pub fn reachable(&self) -> bool {
+        self.rtt_ms.is_some()
+    }
+
+    /// One-line summary, e.g. `12.3ms (TCP connect to port 22)`
+    pub fn summary(&self) -> String {
+        match self.rtt_ms {
+            Some(rtt) if self.failures > 0 => format!(
+                "{:.1}ms (TCP connect to port {}, {}/{} attempts failed)",
+                rtt, self.port, self.failures, self.attempts
+            ),
+            Some(rtt) => format!("{:.1}ms (TCP connect to port {})", rtt, self.port),
+            None => format!("port {} unreachable", self.port),
+        }
+    }
+}
+
+/// Time TCP handshakes to the host, giving up after the first failed attempt
+pub async fn probe(host: &str, port: u16, timeout: Duration) -> NetworkProbe {
+    let mut samples = Vec::with_capacity(ATTEMPTS);
+
+    for _ in 0..ATTEMPTS {
+        let start = Instant::now();
+        match utils::net::connect_async(host, port, timeout).await {
+            Ok(_) => samples.push(Some(start.elapsed())),
+            _ => {
+                samples.push(None);
+                break;
+            }
+        }
+    }
+
+    NetworkProbe::from_samples(port, &samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_from_samples() {
+        let probe = NetworkProbe::from_samples(
+            22,
+            &[
+                Some(Duration::from_micros(15_400)),
+                Some(Duration::from_micros(12_300)),
+                Some(Duration::from_millis(20)),
+            ],
+        );
+        assert!(probe.reachable());
+        assert_eq!(probe.summary(), "12.3ms (TCP connect to port 22)");
+
+        let lossy = NetworkProbe::from_samples(22, &[Some(Duration::from_millis(40)), None]);
+        assert_eq!(
+            lossy.summary(),
+            "40.0ms (TCP connect to port 22, 1/2 attempts failed)"
+        );
+
+        let down = NetworkProbe::from_samples(22, &[None]);
+        assert!(!down.reachable());
+        assert_eq!(down.summary(), "port 22 unreachable");
+    }
+
+    #[tokio::test]
+    async fn test_probe_local_listener() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let probe = probe("127.0.0.1", port, Duration::from_secs(1)).await;
+        assert_eq!(probe.attempts, ATTEMPTS);
+        assert_eq!(probe.failures, 0);
+        assert!(probe.reachable());
+    }
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::config::PortCheck;
+use crate::utils;
+use crate::utils::host::Host;
+
+/// Outcome of a single configured port check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortStatus {
+    pub port: u16,
+    pub protocol: String,
+    pub via: String,
+    /// `None` when the port could not be checked, e.g. without SSH or `ss`/`netstat`
+    pub listening: Option<bool>,
+}
+
+impl PortStatus {
+    /// Short label such as `5432/tcp`
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.port, self.protocol)
+    }
+}
+
+/// Check ports against the sockets listening on the server
+///
+/// Fails rather than reporting every port closed when neither `ss` nor `netstat` works there.
+pub fn check_listening(host: &dyn Host, checks: &[&PortCheck]) -> Result<Vec<PortStatus>> {
+    let (output, _) = host.execute_command(
+        "ss -H -lntu 2>/dev/null || netstat -lntu 2>/dev/null \
+         || { echo 'neither ss nor netstat works' >&2; exit 1; }",
+    )?;
+    let listening = parse_listening(&output);
+
+    Ok(checks
+        .iter()
+        .map(|check| PortStatus {
+            listening: Some(listening.contains(&(check.protocol().to_string(), check.port()))),
+            ..unchecked(check)
+        })
+        .collect())
+}
+
+/// Ports whose state is unknown, such as the ones checked over SSH when it is down
+pub fn unknown(checks: &[&PortCheck]) -> Vec<PortStatus> {
+    checks.iter().map(|check| unchecked(check)).collect()
+}
+
+fn unchecked(check: &PortCheck) -> PortStatus {
+    PortStatus {
+        port: check.port(),
+        protocol: check.protocol().to_string(),
+        via: check.via().to_string(),
+        listening: None,
+    }
+}
+
+/// Check TCP ports by connecting to the host from this machine
+pub async fn check_direct(host: &str, checks: &[&PortCheck], timeout: Duration) -> Vec<PortStatus> {
+    let mut results = Vec::new();
+
+    for check in checks {
+        let listening = utils::net::connect_async(host, check.port(), timeout)
+            .await
+            .is_ok();
+        results.push(PortStatus {
+            listening: Some(listening),
+            ..unchecked(check)
+        });
+    }
+
+    results
+}
+
+/// Parse `ss -lntu` or `netstat -lntu` output into (protocol, port) pairs
+pub fn parse_listening(output: &str) -> HashSet<(String, u16)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let protocol = fields.next()?.trim_end_matches('6');
+            if protocol != "tcp" && protocol != "udp" {
+                return None;
+            }
+
+            // The local address is the first field with a port, after the queue sizes
+            let local = fields.find(|field| field.contains(':'))?;
+            let port = local.rsplit(':').next()?.parse().ok()?;
+            Some((protocol.to_string(), port))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::host::mock::MockHost;
+
+    #[test]
+    fn test_check_listening() {
+        let host = MockHost::default();
+        let checks = [PortCheck::Port(5432), PortCheck::Port(6379)];
+        let checks: Vec<_> = checks.iter().collect();
+        host.reply("ss -H -lntu", "tcp LISTEN 0 244 127.0.0.1:5432 0.0.0.0:*\n");
+        let results = check_listening(&host, &checks).unwrap();
+        assert_eq!(results[0].listening, Some(true));
+        assert_eq!(results[1].listening, Some(false));
+
+        // Without ss or netstat nothing is known, rather than every port being closed
+        host.fail("ss -H -lntu", "neither ss nor netstat works");
+        assert!(check_listening(&host, &checks).is_err());
+        assert!(unknown(&checks).iter().all(|port| port.listening.is_none()));
+    }
+
+    #[test]
+    fn test_parse_ss_output() {
+        let output = "\
+tcp   LISTEN 0      128          0.0.0.0:22        0.0.0.0:*
+tcp   LISTEN 0      244        127.0.0.1:5432      0.0.0.0:*
+tcp   LISTEN 0      511             [::]:6379         [::]:*
+udp   UNCONN 0      0      127.0.0.53%lo:53        0.0.0.0:*
+";
+        let listening = parse_listening(output);
+        assert!(listening.contains(&("tcp".to_string(), 5432)));
+        assert!(listening.contains(&("tcp".to_string(), 6379)));
+        assert!(listening.contains(&("udp".to_string(), 53)));
+        assert!(!listening.contains(&("tcp".to_string(), 53)));
+    }
+
+    #[test]
+    fn test_parse_netstat_output() {
+        let output = "\
+Active Internet connections (only servers)
+Proto Recv-Q Send-Q Local Address           Foreign Address         State
+tcp        0      0 0.0.0.0:8080            0.0.0.0:*               LISTEN
+tcp6       0      0 :::5432                 :::*                    LISTEN
+udp        0      0 0.0.0.0:68              0.0.0.0:*
+";
+        let listening = parse_listening(output);
+        assert_eq!(listening.len(), 3);
+        assert!(listening.contains(&("tcp".to_string(), 5432)));
+        assert!(listening.contains(&("udp".to_string(), 68)));
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::host::Host;
+
+/// Resource usage of the service's main process
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProcessMetrics {
+    pub pid: u32,
+    pub rss_kb: u64,
+    pub cpu_percent: f64,
+    pub open_fds: Option<u64>,
+    pub threads: u64,
+}
+
+impl ProcessMetrics {
+    /// One-line summary for CLI and TUI display
+    pub fn summary(&self) -> String {
+        format!(
+            "PID {} | RSS {} | CPU {:.1}% | FDs {} | Threads {}",
+            self.pid,
+            utils::fs::format_size(self.rss_kb * 1024),
+            self.cpu_percent,
+            self.open_fds
+                .map(|fds| fds.to_string())
+                .unwrap_or_else(|| "N/A".to_string()),
+            self.threads
+        )
+    }
+}
+
+/// Sample the service MainPID; `None` when the service has no running process
+pub fn collect(host: &dyn Host, config: &Config) -> Result<Option<ProcessMetrics>> {
+    // Fields after the `(comm)` entry of /proc/<pid>/stat start at `state`, so
+    // utime and stime (fields 14 and 15) become the 12th and 13th
+    let command = format!(
+        "pid=$(sudo systemctl show -p MainPID --value {}); echo '--pid'; echo \"$pid\"; \
+         [ \"${{pid:-0}}\" -gt 0 ] || exit 0; \
+         echo '--status'; grep -E '^(VmRSS|Threads):' /proc/$pid/status; \
+         echo '--fds'; sudo ls /proc/$pid/fd 2>/dev/null | wc -l; \
+         echo '--cpu'; getconf CLK_TCK; \
+         sed 's/.*) //' /proc/$pid/stat | cut -d' ' -f12,13; sleep 1; \
+         sed 's/.*) //' /proc/$pid/stat | cut -d' ' -f12,13",
+        utils::ssh::shell_quote(&config.service_name())
+    );
+
+    let (output, _) = host.execute_command(&command)?;
+    parse(&output, 1.0)
+}
+
+/// Parse the sectioned output produced by [`collect`]; `interval_secs` is the CPU sample gap
+pub fn parse(output: &str, interval_secs: f64) -> Result<Option<ProcessMetrics>> {
+    let mut metrics = ProcessMetrics::default();
+    let mut section = "";
+    let mut cpu_lines = Vec::new();
+
+    for line in output.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(name) = line.strip_prefix("--") {
+            section = name;
+            continue;
+        }
+
+        match section {
+            "pid" => metrics.pid = line.parse().unwrap_or_default(),
+            "status" => {
+                let mut fields = line.split_whitespace();
+                let value = fields
+                    .nth(1)
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_default();
+                match line.split(':').next() {
+                    Some("VmRSS") => metrics.rss_kb = value,
+                    Some("Threads") => metrics.threads = value,
+                    _ => {}
+                }
+            }
+            "fds" => metrics.open_fds = line.parse().ok(),
+            "cpu" => cpu_lines.push(line),
+            _ => {}
+        }
+    }
+
+    if metrics.pid == 0 {
+        return Ok(None);
+    }
+
+    if let [clock_ticks, before, after] = cpu_lines[..] {
+        let ticks = |line: &str| -> Result<u64> {
+            line.split_whitespace()
+                .map(|v| v.parse::<u64>())
+                .sum::<Result<u64, _>>()
+                .map_err(|_| anyhow!("Unexpected /proc stat output: {}", line))
+        };
+        let clock_ticks: f64 = clock_ticks
+            .parse()
+            .map_err(|_| anyhow!("Unexpected CLK_TCK: {}", clock_ticks))?;
+        let used = ticks(after)?.saturating_sub(ticks(before)?) as f64 / clock_ticks;
+        metrics.cpu_percent = used / interval_secs * 100.0;
+    }
+
+    Ok(Some(metrics))
+}
+
+/// Recent process samples used to spot trends such as memory leaks
+#[derive(Debug, Clone)]
+pub struct ProcessHistory {
+    samples: VecDeque<ProcessMetrics>,
+    capacity: usize,
+}
+
+impl ProcessHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Add a sample; a new PID (restart) starts a fresh trend
+    pub fn push(&mut self, sample: ProcessMetrics) {
+        if self
+            .samples
+            .back()
+            .is_some_and(|last| last.pid != sample.pid)
+        {
+            self.samples.clear();
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// RSS values, oldest first
+    pub fn rss_series(&self) -> Vec<u64> {
+        self.samples.iter().map(|s| s.rss_kb).collect()
+    }
+
+    /// Change in RSS across the window, e.g. `+15.0 MB over 12 checks`
+    pub fn rss_trend(&self) -> Option<String> {
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        if self.samples.len() < 2 {
+            return None;
+        }
+
+        let delta = last.rss_kb as i64 - first.rss_kb as i64;
+        let sign = if delta >= 0 { "+" } else { "-" };
+        Some(format!(
+            "{}{} over {} checks",
+            sign,
+            utils::fs::format_size(delta.unsigned_abs() * 1024),
+            self.samples.len()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_process_metrics() {
+        let output = "\
+--pid
+4242
+--status
+VmRSS:\t  204800 kB
+Threads:\t12
+--fds
+37
+--cpu
+100
+1500 300
+1520 305
+";
+        let metrics = parse(output, 1.0).unwrap().unwrap();
+        assert_eq!(metrics.pid, 4242);
+        assert_eq!(metrics.rss_kb, 204800);
+        assert_eq!(metrics.threads, 12);
+        assert_eq!(metrics.open_fds, Some(37));
+        assert!((metrics.cpu_percent - 25.0).abs() < 0.001);
+
+        assert_eq!(parse("--pid\n0\n", 1.0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_process_history_trend() {
+        let sample = |pid, rss_kb| ProcessMetrics {
+            pid,
+            rss_kb,
+            ..Default::default()
+        };
+
+        let mut history = ProcessHistory::new(3);
+        history.push(sample(1, 1024));
+        assert_eq!(history.rss_trend(), None);
+
+        history.push(sample(1, 2048));
+        history.push(sample(1, 4096));
+        history.push(sample(1, 11264));
+        assert_eq!(history.rss_series(), vec![2048, 4096, 11264]);
+        assert_eq!(
+            history.rss_trend().as_deref(),
+            Some("+9.0 MB over 3 checks")
+        );
+
+        history.push(sample(2, 512));
+        assert_eq!(history.rss_series(), vec![512]);
+    }
+}
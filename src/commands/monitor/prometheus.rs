@@ -0,0 +1,306 @@
+use anyhow::{Context, Result, anyhow};
+use reqwest::Client;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// A single sample from the Prometheus text exposition format
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+/// Key series extracted from an application's `/metrics` endpoint
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PrometheusSummary {
+    pub requests_total: Option<f64>,
+    pub errors_total: Option<f64>,
+    pub request_rate: Option<f64>,
+    pub error_rate: Option<f64>,
+    pub gauges: Vec<(String, f64)>,
+}
+
+impl PrometheusSummary {
+    /// Share of requests that failed, based on rates when known
+    pub fn error_percentage(&self) -> Option<f64> {
+        match (self.request_rate, self.error_rate) {
+            (Some(requests), Some(errors)) if requests > 0.0 => Some(errors / requests * 100.0),
+            _ => None,
+        }
+    }
+
+    /// One-line summary for CLI and TUI display
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+
+        match (self.request_rate, self.requests_total) {
+            (Some(rate), _) => parts.push(format!("{:.2} req/s", rate)),
+            (None, Some(total)) => parts.push(format!("{} requests", total)),
+            _ => {}
+        }
+        if let Some(rate) = self.error_rate {
+            let percentage = self
+                .error_percentage()
+                .map(|p| format!(" ({:.1}%)", p))
+                .unwrap_or_default();
+            parts.push(format!("{:.2} err/s{}", rate, percentage));
+        }
+        for (name, value) in &self.gauges {
+            parts.push(format!("{}={}", name, value));
+        }
+
+        if parts.is_empty() {
+            "no matching series".to_string()
+        } else {
+            parts.join(" | ")
+        }
+    }
+}
+
+/// Previous counter values used to turn totals into rates
+#[derive(Debug, Clone, Copy)]
+pub struct ScrapeSnapshot {
+    pub at: Instant,
+    pub requests_total: Option<f64>,
+    pub errors_total: Option<f64>,
+}
+
+/// Fetch and parse the exposition text from a metrics endpoint
+pub async fn scrape(client: &Client, endpoint: &str) -> Result<Vec<Sample>> {
+    let body = client
+        .get(endpoint)
+        .send()
+        .await
+        .with_context(|| format!("Failed to connect to metrics endpoint: {}", endpoint))?
+        .error_for_status()
+        .with_context(|| format!("Metrics endpoint returned an error: {}", endpoint))?
+        .text()
+        .await
+        .context("Failed to read metrics response")?;
+
+    parse(&body)
+}
+
+/// Summarize samples, computing rates against the previous snapshot when available
+pub fn summarize(
+    samples: &[Sample],
+    request_counter: &str,
+    gauges: &[String],
+    previous: Option<ScrapeSnapshot>,
+    now: Instant,
+) -> (PrometheusSummary, ScrapeSnapshot) {
+    let requests: Vec<&Sample> = samples
+        .iter()
+        .filter(|s| s.name == request_counter)
+        .collect();
+
+    let requests_total = (!requests.is_empty()).then(|| requests.iter().map(|s| s.value).sum());
+    let errors_total = (!requests.is_empty()).then(|| {
+        requests
+            .iter()
+            .filter(|s| is_error(s))
+            .map(|s| s.value)
+            .sum()
+    });
+
+    let rate = |current: Option<f64>, earlier: Option<f64>, previous: &ScrapeSnapshot| {
+        let elapsed = now.duration_since(previous.at).as_secs_f64();
+        match (current, earlier) {
+            // A counter that went backwards was reset (e.g. the app restarted)
+            (Some(current), Some(earlier)) if elapsed > 0.0 && current >= earlier => {
+                Some((current - earlier) / elapsed)
+            }
+            _ => None,
+        }
+    };
+
+    let (request_rate, error_rate) = match &previous {
+        Some(previous) => (
+            rate(requests_total, previous.requests_total, previous),
+            rate(errors_total, previous.errors_total, previous),
+        ),
+        None => (None, None),
+    };
+
+    let gauges = gauges
+        .iter()
+        .filter_map(|name| {
+            let matching: Vec<f64> = samples
+                .iter()
+                .filter(|s| &s.name == name)
+                .map(|s| s.value)
+                .collect();
+            (!matching.is_empty()).then(|| (name.clone(), matching.iter().sum()))
+        })
+        .collect();
+
+    (
+        PrometheusSummary {
+            requests_total,
+            errors_total,
+            request_rate,
+            error_rate,
+            gauges,
+        },
+        ScrapeSnapshot {
+            at: now,
+            requests_total,
+            errors_total,
+        },
+    )
+}
+
+/// A request sample counts as an error when its status label is 5xx
+fn is_error(sample: &Sample) -> bool {
+    ["status", "code", "status_code"]
+        .iter()
+        .filter_map(|label| sample.labels.get(*label))
+        .any(|status| status.starts_with('5'))
+}
+
+/// Parse the Prometheus text exposition format
+pub fn parse(text: &str) -> Result<Vec<Sample>> {
+    let mut samples = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let sample = parse_line(line)
+            .ok_or_else(|| anyhow!("Invalid metrics line {}: {}", index + 1, line))?;
+        samples.push(sample);
+    }
+
+    Ok(samples)
+}
+
+fn parse_line(line: &str) -> Option<Sample> {
+    let name_end = line
+        .find(|c: char| c == '{' || c.is_whitespace())
+        .unwrap_or(line.len());
+    let name = &line[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut labels = BTreeMap::new();
+    let mut rest = &line[name_end..];
+
+    if let Some(body) = rest.strip_prefix('{') {
+        let mut chars = body.char_indices().peekable();
+        let mut consumed = None;
+
+        loop {
+            while chars
+                .next_if(|(_, c)| *c == ',' || c.is_whitespace())
+                .is_some()
+            {}
+            match chars.peek() {
+                Some((i, '}')) => {
+                    consumed = Some(i + 1);
+                    break;
+                }
+                None => break,
+                _ => {}
+            }
+
+            let mut key = String::new();
+            while let Some((_, c)) = chars.next_if(|(_, c)| *c != '=') {
+                key.push(c);
+            }
+            chars.next()?; // '='
+            if chars.next()?.1 != '"' {
+                return None;
+            }
+
+            let mut value = String::new();
+            loop {
+                match chars.next()?.1 {
+                    '\\' => match chars.next()?.1 {
+                        'n' => value.push('\n'),
+                        other => value.push(other),
+                    },
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+            labels.insert(key.trim().to_string(), value);
+        }
+
+        rest = &body[consumed?..];
+    }
+
+    let value = match rest.split_whitespace().next()? {
+        "+Inf" => f64::INFINITY,
+        "-Inf" => f64::NEG_INFINITY,
+        value => value.parse().ok()?,
+    };
+
+    Some(Sample {
+        name: name.to_string(),
+        labels,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const EXPOSITION: &str = r#"
+# HELP http_requests_total Total HTTP requests
+# TYPE http_requests_total counter
+http_requests_total{method="GET",status="200"} 90
+http_requests_total{method="POST",status="500"} 10
+queue_depth 4
+build_info{version="1.2.3",path="C:\\app \"x\""} 1 1700000000000
+"#;
+
+    #[test]
+    fn test_parse_exposition() {
+        let samples = parse(EXPOSITION).unwrap();
+        assert_eq!(samples.len(), 4);
+        assert_eq!(
+            samples[1].labels.get("status").map(String::as_str),
+            Some("500")
+        );
+        assert_eq!(samples[2].name, "queue_depth");
+        assert_eq!(samples[2].value, 4.0);
+        assert_eq!(
+            samples[3].labels.get("path").map(String::as_str),
+            Some("C:\\app \"x\"")
+        );
+
+        assert!(parse("http_requests_total{status=\"200\" 5").is_err());
+    }
+
+    #[test]
+    fn test_summarize_rates() {
+        let start = Instant::now();
+        let first = parse(EXPOSITION).unwrap();
+        let gauges = vec!["queue_depth".to_string()];
+        let (summary, snapshot) = summarize(&first, "http_requests_total", &gauges, None, start);
+
+        assert_eq!(summary.requests_total, Some(100.0));
+        assert_eq!(summary.errors_total, Some(10.0));
+        assert_eq!(summary.request_rate, None);
+        assert_eq!(summary.gauges, vec![("queue_depth".to_string(), 4.0)]);
+
+        let second = parse(&EXPOSITION.replace("} 90", "} 170").replace("} 10", "} 30")).unwrap();
+        let (summary, _) = summarize(
+            &second,
+            "http_requests_total",
+            &gauges,
+            Some(snapshot),
+            start + Duration::from_secs(10),
+        );
+        assert_eq!(summary.request_rate, Some(10.0));
+        assert_eq!(summary.error_rate, Some(2.0));
+        assert_eq!(summary.error_percentage(), Some(20.0));
+    }
+}
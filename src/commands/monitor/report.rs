@@ -0,0 +1,349 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+use super::history::{CheckRecord, HistoryStore, HistorySummary, Incident};
+use super::latency::{LatencyPercentiles, LatencyWindow};
+use crate::config::Config;
+use crate::logging::{glyph, log};
+use crate::utils::timing::{DisplayTimezone, format_duration};
+
+/// Failed checks listed per host under "Recent errors"
+const RECENT_ERRORS: usize = 20;
+
+/// Latency chart size in SVG units
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 160.0;
+
+/// Output format of a monitoring report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Json,
+}
+
+impl ReportFormat {
+    /// Pick the format from the output file extension
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("html" | "htm") => Ok(Self::Html),
+            Some("json") => Ok(Self::Json),
+            _ => Err(anyhow!(
+                "Unsupported report format for {}: use a .html or .json file",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Monitoring history of a project over a reporting period
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub project: String,
+    pub since: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub hosts: Vec<HostReport>,
+    /// Incidents open at any point in the period, newest first
+    pub incidents: Vec<Incident>,
+}
+
+/// Checks and derived statistics for one host
+#[derive(Debug, Clone, Serialize)]
+pub struct HostReport {
+    pub host: String,
+    pub summary: HistorySummary,
+    pub uptime_percentage: Option<f64>,
+    pub latency: Option<LatencyPercentiles>,
+    /// Most recent failed checks, newest first
+    pub recent_errors: Vec<CheckRecord>,
+    /// Every check in the period, oldest first
+    pub checks: Vec<CheckRecord>,
+}
+
+impl HostReport {
+    /// Derive statistics from the checks recorded for a host
+    pub fn from_checks(host: &str, checks: Vec<CheckRecord>) -> Self {
+        let response_times: Vec<u64> = checks.iter().filter_map(|c| c.response_time_ms).collect();
+        let summary = HistorySummary {
+            total_checks: checks.len() as u64,
+            failed_checks: checks.iter().filter(|c| !c.healthy).count() as u64,
+            average_response_time_ms: (!response_times.is_empty())
+                .then(|| response_times.iter().sum::<u64>() as f64 / response_times.len() as f64),
+        };
+        let latency =
+            LatencyWindow::new(response_times.len(), response_times.iter().copied()).percentiles();
+        let recent_errors = checks
+            .iter()
+            .rev()
+            .filter(|c| !c.healthy)
+            .take(RECENT_ERRORS)
+            .cloned()
+            .collect();
+
+        Self {
+            host: host.to_string(),
+            uptime_percentage: summary.uptime_percentage(),
+            summary,
+            latency,
+            recent_errors,
+            checks,
+        }
+    }
+}
+
+/// Collect the report for every configured host from the local history
+pub fn build(config: &Config, period: Duration, now: DateTime<Utc>) -> Result<Report> {
+    let since = now - chrono::Duration::from_std(period).context("Report period is too long")?;
+
+    let mut hosts = Vec::new();
+    for host in config.hosts() {
+        let store = HistoryStore::open(&config.for_host(&host))?;
+        hosts.push(HostReport::from_checks(&host, store.checks_since(since)?));
+    }
+    let incidents = HistoryStore::open(config)?.incidents_since(since)?;
+
+    Ok(Report {
+        project: config.project.name.clone(),
+        since,
+        generated_at: now,
+        hosts,
+        incidents,
+    })
+}
+
+/// Build a report for the last `period` and write it to `path`
+pub fn write_report(config: &Config, path: &Path, period: Duration) -> Result<String> {
+    let format = ReportFormat::from_path(path)?;
+    let report = build(config, period, Utc::now())?;
+
+    let content = match format {
+        ReportFormat::Html => render_html(&report, config.display_timezone()),
+        ReportFormat::Json => {
+            serde_json::to_string_pretty(&report).context("Failed to serialize report")?
+        }
+    };
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write report: {}", path.display()))?;
+
+    let checks: u64 = report.hosts.iter().map(|h| h.summary.total_checks).sum();
+    log::monitor_event(&format!(
+        "{} Report written to {} ({} checks, {} incidents over {})",
+        glyph("📄"),
+        path.display(),
+        checks,
+        report.incidents.len(),
+        format_duration(period)
+    ));
+    Ok(format!("Report written to {}", path.display()))
+}
+
+/// Render a self-contained HTML page with inline styles and SVG charts
+pub fn render_html(report: &Report, timezone: DisplayTimezone) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{project} monitoring report</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{project} monitoring report</h1>\n\
+         <p class=\"muted\">{since} to {until}</p>\n",
+        project = escape_html(&report.project),
+        since = timezone.format(report.since),
+        until = timezone.format(report.generated_at),
+    );
+
+    for host in &report.hosts {
+        let uptime = host
+            .uptime_percentage
+            .map(|uptime| format!("{:.2}%", uptime))
+            .unwrap_or_else(|| "N/A".to_string());
+        let latency = host
+            .latency
+            .map(|latency| latency.summary())
+            .unwrap_or_else(|| "N/A".to_string());
+        let _ = write!(
+            html,
+            "<section>\n<h2>{}</h2>\n<table class=\"stats\">\n\
+             <tr><th>Uptime</th><td>{}</td></tr>\n\
+             <tr><th>Checks</th><td>{} ({} failed)</td></tr>\n\
+             <tr><th>Latency</th><td>{}</td></tr>\n</table>\n{}\n",
+            escape_html(&host.host),
+            uptime,
+            host.summary.total_checks,
+            host.summary.failed_checks,
+            latency,
+            latency_chart(host, report.since, report.generated_at),
+        );
+
+        if !host.recent_errors.is_empty() {
+            html.push_str(
+                "<h3>Recent errors</h3>\n<table>\n<tr><th>Time</th><th>Error</th></tr>\n",
+            );
+            for check in &host.recent_errors {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    timezone.format(check.timestamp),
+                    escape_html(check.error.as_deref().unwrap_or("Unhealthy"))
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</section>\n");
+    }
+
+    html.push_str("<section>\n<h2>Incidents</h2>\n");
+    if report.incidents.is_empty() {
+        html.push_str("<p>No incidents in this period.</p>\n");
+    } else {
+        html.push_str(
+            "<table>\n<tr><th>Started</th><th>Duration</th><th>Host</th><th>Cause</th></tr>\n",
+        );
+        for incident in &report.incidents {
+            let duration = match incident.ended_at {
+                Some(_) => format_duration(incident.duration(report.generated_at)),
+                None => format!(
+                    "ongoing ({})",
+                    format_duration(incident.duration(report.generated_at))
+                ),
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                timezone.format(incident.started_at),
+                duration,
+                escape_html(&incident.host),
+                escape_html(&incident.cause)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</section>\n</body>\n</html>\n");
+    html
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:860px;margin:2em auto;color:#222}\
+    table{border-collapse:collapse;margin:0.5em 0}\
+    th,td{text-align:left;padding:4px 12px;border-bottom:1px solid #ddd;vertical-align:top}\
+    .muted{color:#777}\
+    .chart{width:100%;height:auto;background:#fafafa;border:1px solid #ddd}\
+    .chart polyline{fill:none;stroke:#2b6cb0;stroke-width:1.5}\
+    .chart .failure{stroke:#e53e3e;stroke-opacity:0.5}";
+
+/// Response times as an SVG line, with failed checks marked in red
+fn latency_chart(host: &HostReport, since: DateTime<Utc>, until: DateTime<Utc>) -> String {
+    if host.checks.is_empty() {
+        return "<p class=\"muted\">No checks recorded in this period.</p>".to_string();
+    }
+
+    let span = (until - since).num_seconds().max(1) as f64;
+    let peak = host
+        .checks
+        .iter()
+        .filter_map(|c| c.response_time_ms)
+        .max()
+        .unwrap_or_default();
+    let x =
+        |check: &CheckRecord| (check.timestamp - since).num_seconds() as f64 / span * CHART_WIDTH;
+
+    let points: Vec<String> = host
+        .checks
+        .iter()
+        .filter_map(|check| {
+            let ms = check.response_time_ms?;
+            let y = CHART_HEIGHT - ms as f64 / peak.max(1) as f64 * CHART_HEIGHT;
+            Some(format!("{:.1},{:.1}", x(check), y))
+        })
+        .collect();
+    let failures: String = host
+        .checks
+        .iter()
+        .filter(|check| !check.healthy)
+        .map(|check| {
+            let x = x(check);
+            format!(
+                "<line class=\"failure\" x1=\"{x:.1}\" y1=\"0\" x2=\"{x:.1}\" y2=\"{CHART_HEIGHT}\"/>"
+            )
+        })
+        .collect();
+
+    format!(
+        "<svg class=\"chart\" viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\" role=\"img\" \
+         aria-label=\"Response times\">{failures}<polyline points=\"{}\"/></svg>\n\
+         <p class=\"muted\">Response time, peak {}ms; red lines mark failed checks</p>",
+        points.join(" "),
+        peak
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(offset_secs: i64, healthy: bool, response_time_ms: Option<u64>) -> CheckRecord {
+        CheckRecord {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + offset_secs, 0).unwrap(),
+            healthy,
+            response_time_ms,
+            service_status: Some("active".to_string()),
+            error: (!healthy).then(|| "HTTP 503 <Service Unavailable>".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_report_format_from_path() {
+        assert_eq!(
+            ReportFormat::from_path(Path::new("out.HTML")).unwrap(),
+            ReportFormat::Html
+        );
+        assert_eq!(
+            ReportFormat::from_path(Path::new("reports/week.json")).unwrap(),
+            ReportFormat::Json
+        );
+        assert!(ReportFormat::from_path(Path::new("out.pdf")).is_err());
+    }
+
+    #[test]
+    fn test_host_report_and_html() {
+        let host = HostReport::from_checks(
+            "example.com",
+            vec![
+                check(0, true, Some(100)),
+                check(60, false, None),
+                check(120, true, Some(300)),
+            ],
+        );
+        assert_eq!(host.summary.total_checks, 3);
+        assert_eq!(host.summary.failed_checks, 1);
+        assert_eq!(host.summary.average_response_time_ms, Some(200.0));
+        assert_eq!(host.latency.unwrap().p99, 300);
+        assert_eq!(host.recent_errors.len(), 1);
+
+        let report = Report {
+            project: "app".to_string(),
+            since: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            generated_at: DateTime::from_timestamp(1_700_000_240, 0).unwrap(),
+            hosts: vec![host],
+            incidents: Vec::new(),
+        };
+        let html = render_html(&report, DisplayTimezone::Utc);
+        assert!(html.contains("<h2>example.com</h2>"));
+        assert!(html.contains("66.67%"));
+        assert!(html.contains("points=\"0.0,106.7 400.0,0.0\""));
+        assert!(html.contains("HTTP 503 &lt;Service Unavailable&gt;"));
+        assert!(html.contains("No incidents in this period."));
+    }
+}
@@ -0,0 +1,423 @@
+use serde_json::{Map, Value};
+
+use super::logs::{self, TimestampConverter};
+use crate::logging::glyph;
+use crate::utils::timing::DisplayTimezone;
+
+/// Column shown by `rzen logs` for structured (JSON or logfmt) lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogField {
+    Timestamp,
+    Level,
+    Target,
+    Message,
+    /// Any other key, looked up verbatim
+    Other(String),
+}
+
+impl LogField {
+    pub fn parse(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "timestamp" | "time" | "ts" => Self::Timestamp,
+            "level" | "lvl" | "severity" => Self::Level,
+            "target" | "logger" | "module" => Self::Target,
+            "message" | "msg" => Self::Message,
+            _ => Self::Other(name.trim().to_string()),
+        }
+    }
+
+    /// Keys that common loggers use for this field, in order of preference
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            Self::Timestamp => vec!["timestamp", "time", "ts", "@timestamp", "t"],
+            Self::Level => vec!["level", "lvl", "severity", "log.level"],
+            Self::Target => vec!["target", "logger", "module", "caller"],
+            Self::Message => vec!["message", "msg", "fields.message", "@message"],
+            Self::Other(key) => vec![key.as_str()],
+        }
+    }
+
+    fn header(&self) -> String {
+        match self {
+            Self::Timestamp => "TIMESTAMP".to_string(),
+            Self::Level => "LEVEL".to_string(),
+            Self::Target => "TARGET".to_string(),
+            Self::Message => "MESSAGE".to_string(),
+            Self::Other(key) => key.to_uppercase(),
+        }
+    }
+
+    /// Column width; longer values are truncated so streamed rows stay aligned
+    fn width(&self) -> usize {
+        match self {
+            Self::Timestamp => 26,
+            Self::Level => 5,
+            Self::Target => 24,
+            Self::Message => 60,
+            Self::Other(_) => 16,
+        }
+    }
+}
+
+/// Key/value pairs of a JSON or logfmt log line, plus any text before them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuredLine {
+    /// Text before the structured part, e.g. the journal's timestamp and unit
+    pub prefix: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl StructuredLine {
+    /// Parse a line as a JSON object or logfmt, allowing a journal-style prefix
+    pub fn parse(line: &str) -> Option<Self> {
+        if let Some(start) = line.find('{')
+            && let Ok(object) = serde_json::from_str::<Map<String, Value>>(line[start..].trim())
+        {
+            let mut fields = Vec::new();
+            flatten("", &object, &mut fields);
+            return Some(Self {
+                prefix: line[..start].trim().trim_end_matches(':').to_string(),
+                fields,
+            });
+        }
+
+        // journalctl prints `<time> <host> <unit>[<pid>]: <message>`
+        let (prefix, rest) = line
+            .split_once("]: ")
+            .map(|(prefix, rest)| (format!("{}]", prefix), rest))
+            .unwrap_or_default();
+        [(String::new(), line), (prefix, rest)]
+            .into_iter()
+            .find_map(|(prefix, text)| parse_logfmt(text).map(|fields| Self { prefix, fields }))
+    }
+
+    /// Value of a field under any of its common keys
+    pub fn get(&self, field: &LogField) -> Option<&str> {
+        field.keys().into_iter().find_map(|key| {
+            self.fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str())
+        })
+    }
+}
+
+/// Log severity, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    /// Parse a level name as loggers and syslog write it
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "trace" => Some(Self::Trace),
+            "debug" | "dbg" => Some(Self::Debug),
+            "info" | "notice" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "err" | "fatal" | "critical" | "crit" | "panic" | "alert" | "emerg" => {
+                Some(Self::Error)
+            }
+            _ => None,
+        }
+    }
+
+    /// Severity of a log line, from its structured level field or a leading marker
+    ///
+    /// Markers are the first words of the line written as `ERROR`, `[warn]`,
+    /// `error:` or `error[E0308]:`; a plain lowercase word such as "error" in a
+    /// sentence is not taken as a level.
+    pub fn of_line(line: &str) -> Option<Self> {
+        if let Some(structured) = StructuredLine::parse(line) {
+            return structured.get(&LogField::Level).and_then(Self::parse);
+        }
+
+        // Leave room for a timestamp, host and unit before the marker
+        line.split_whitespace().take(5).find_map(|token| {
+            let word = token.trim_start_matches('[');
+            let end = word
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(word.len());
+            let name = &word[..end];
+            let marked = name.len() < token.len() || name.chars().all(|c| c.is_ascii_uppercase());
+            if marked { Self::parse(name) } else { None }
+        })
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::parse(name).ok_or_else(|| {
+            format!(
+                "unknown level `{}`; use error, warn, info, debug or trace",
+                name
+            )
+        })
+    }
+}
+
+/// Collect nested JSON values under dotted keys, e.g. tracing's `fields.message`
+fn flatten(prefix: &str, object: &Map<String, Value>, fields: &mut Vec<(String, String)>) {
+    for (key, value) in object {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Object(nested) => flatten(&key, nested, fields),
+            Value::String(text) => fields.push((key, text.clone())),
+            Value::Null => fields.push((key, String::new())),
+            other => fields.push((key, other.to_string())),
+        }
+    }
+}
+
+/// Parse `key=value key="quoted value"` pairs; `None` unless the whole text is logfmt
+fn parse_logfmt(text: &str) -> Option<Vec<(String, String)>> {
+    let mut fields = Vec::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=')?;
+        if key.is_empty() || key.contains(char::is_whitespace) || key.contains('"') {
+            return None;
+        }
+
+        let (value, after) = if let Some(quoted) = after.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let mut end = None;
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next().map(|(_, c)| c)),
+                    '"' => {
+                        end = Some(i + 1);
+                        break;
+                    }
+                    c => value.push(c),
+                }
+            }
+            (value, &quoted[end?..])
+        } else {
+            let end = after.find(char::is_whitespace).unwrap_or(after.len());
+            (after[..end].to_string(), &after[end..])
+        };
+
+        fields.push((key.to_string(), value));
+        rest = after.trim_start();
+    }
+
+    // Require a level or message so prose containing `a=b` is not mistaken for logfmt
+    let structured = fields
+        .iter()
+        .any(|(key, _)| matches!(key.as_str(), "level" | "lvl" | "msg" | "message"));
+    (fields.len() >= 2 && structured).then_some(fields)
+}
+
+/// Formats log lines for the terminal, as table rows when they are structured
+#[derive(Debug, Clone)]
+pub struct LogFormatter {
+    /// Columns for structured lines; `None` prints every line as-is
+    columns: Option<Vec<LogField>>,
+    converter: Option<TimestampConverter>,
+    header_shown: bool,
+}
+
+impl LogFormatter {
+    pub fn new(columns: Option<Vec<LogField>>, converter: Option<TimestampConverter>) -> Self {
+        Self {
+            columns,
+            converter,
+            header_shown: false,
+        }
+    }
+
+    /// Output lines for one log line: the header before the first row, then the row
+    pub fn format(&mut self, line: &str) -> Vec<String> {
+        let structured = StructuredLine::parse(line);
+        let (Some(columns), Some(structured)) = (&self.columns, structured) else {
+            let line = match &self.converter {
+                Some(converter) => converter.convert(line),
+                None => line.into(),
+            };
+            return vec![format!("{} {}", glyph("📜"), line)];
+        };
+
+        let mut output = Vec::new();
+        if !self.header_shown {
+            self.header_shown = true;
+            let headers: Vec<String> = columns.iter().map(LogField::header).collect();
+            output.push(row(columns, &headers));
+        }
+
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|field| {
+                let value = match field {
+                    LogField::Timestamp => {
+                        structured.get(field).map(str::to_string).or_else(|| {
+                            // Fall back to the journal's own timestamp
+                            logs::find_timestamp(&structured.prefix, DisplayTimezone::Utc)
+                                .map(|(span, _)| structured.prefix[span].to_string())
+                        })
+                    }
+                    LogField::Level => structured.get(field).map(str::to_uppercase),
+                    _ => structured.get(field).map(str::to_string),
+                }
+                .unwrap_or_default();
+
+                match (field, &self.converter) {
+                    (LogField::Timestamp, Some(converter)) => {
+                        converter.convert(&value).into_owned()
+                    }
+                    _ => value,
+                }
+            })
+            .collect();
+        output.push(row(columns, &cells));
+        output
+    }
+}
+
+/// Pad or truncate each cell to its column width; the last column is left open
+fn row(columns: &[LogField], cells: &[String]) -> String {
+    let last = columns.len().saturating_sub(1);
+    columns
+        .iter()
+        .zip(cells)
+        .enumerate()
+        .map(|(i, (field, cell))| {
+            let cell = cell.replace('\n', " ");
+            if i == last {
+                cell
+            } else {
+                let width = field.width();
+                if cell.chars().count() > width {
+                    let truncated: String = cell.chars().take(width - 1).collect();
+                    format!("{}…", truncated)
+                } else {
+                    format!("{:<width$}", cell)
+                }
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<LogField> {
+        ["timestamp", "level", "target", "message"]
+            .iter()
+            .map(|name| LogField::parse(name))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_json_lines() {
+        let line = r#"{"timestamp":"2024-06-01T12:00:00.5Z","level":"INFO","fields":{"message":"listening","port":8080},"target":"app::server"}"#;
+        let parsed = StructuredLine::parse(line).unwrap();
+        assert_eq!(parsed.get(&LogField::Message), Some("listening"));
+        assert_eq!(parsed.get(&LogField::Target), Some("app::server"));
+        assert_eq!(
+            parsed.get(&LogField::Other("fields.port".to_string())),
+            Some("8080")
+        );
+
+        let journal = r#"2024-06-01T12:00:00+0000 vps app[42]: {"lvl":"warn","msg":"slow query"}"#;
+        let parsed = StructuredLine::parse(journal).unwrap();
+        assert_eq!(parsed.prefix, "2024-06-01T12:00:00+0000 vps app[42]");
+        assert_eq!(parsed.get(&LogField::Level), Some("warn"));
+    }
+
+    #[test]
+    fn test_parse_logfmt_lines() {
+        let line = r#"time=2024-06-01T12:00:00Z level=error msg="db \"main\" unreachable" retry=3"#;
+        let parsed = StructuredLine::parse(line).unwrap();
+        assert_eq!(
+            parsed.get(&LogField::Message),
+            Some("db \"main\" unreachable")
+        );
+        assert_eq!(parsed.get(&LogField::Other("retry".to_string())), Some("3"));
+
+        let journal = "2024-06-01T12:00:00+0000 vps app[42]: level=info msg=started";
+        let parsed = StructuredLine::parse(journal).unwrap();
+        assert_eq!(parsed.prefix, "2024-06-01T12:00:00+0000 vps app[42]");
+        assert_eq!(parsed.get(&LogField::Level), Some("info"));
+
+        assert_eq!(StructuredLine::parse("Started server on port 8080"), None);
+        assert_eq!(StructuredLine::parse("config a=b loaded"), None);
+        assert_eq!(StructuredLine::parse("a=1 b=2"), None);
+    }
+
+    #[test]
+    fn test_line_severity() {
+        let severity = Severity::of_line;
+        assert_eq!(
+            severity(r#"{"level":"WARN","msg":"slow"}"#),
+            Some(Severity::Warn)
+        );
+        assert_eq!(
+            severity("2024-06-01T12:00:00+0000 vps app[42]: level=error msg=boom"),
+            Some(Severity::Error)
+        );
+        assert_eq!(
+            severity("2024-06-01T12:00:00Z ERROR app::db: connection lost"),
+            Some(Severity::Error)
+        );
+        assert_eq!(severity("[warn] disk almost full"), Some(Severity::Warn));
+        assert_eq!(
+            severity("error[E0308]: mismatched types"),
+            Some(Severity::Error)
+        );
+        assert_eq!(severity("warning: unused variable"), Some(Severity::Warn));
+        assert_eq!(severity("retrying after error in handler"), None);
+        assert_eq!(severity("   --> src/main.rs:4:5"), None);
+        assert!(Severity::Warn > Severity::Info);
+    }
+
+    #[test]
+    fn test_format_table_rows() {
+        let mut formatter = LogFormatter::new(Some(columns()), None);
+
+        let output = formatter.format(
+            "2024-06-01T12:00:00+0000 vps app[42]: level=info target=app::db msg=\"connected to postgres\"",
+        );
+        assert_eq!(output.len(), 2);
+        assert!(output[0].starts_with("TIMESTAMP "));
+        assert!(output[0].ends_with("  MESSAGE"));
+        assert_eq!(
+            output[1],
+            format!(
+                "{:<26}  {:<5}  {:<24}  connected to postgres",
+                "2024-06-01T12:00:00+0000", "INFO", "app::db"
+            )
+        );
+
+        let output = formatter
+            .format(r#"{"level":"warn","target":"app::some::very::long::module::path","msg":"x"}"#);
+        assert_eq!(output.len(), 1);
+        assert!(output[0].contains("app::some::very::long::…  x"));
+
+        assert_eq!(
+            formatter.format("plain text line"),
+            vec!["📜 plain text line".to_string()]
+        );
+
+        let mut raw = LogFormatter::new(None, None);
+        assert_eq!(
+            raw.format("level=info msg=started"),
+            vec!["📜 level=info msg=started".to_string()]
+        );
+    }
+}
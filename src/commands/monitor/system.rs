@@ -0,0 +1,170 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+
+use crate::utils;
+use crate::utils::host::Host;
+
+/// Host-level resource metrics sampled over SSH
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SystemMetrics {
+    pub cpu_percent: f64,
+    pub memory_total_kb: u64,
+    pub memory_available_kb: u64,
+    pub disk_total_kb: u64,
+    pub disk_used_kb: u64,
+    pub load_average: [f64; 3],
+}
+
+impl SystemMetrics {
+    /// Percentage of physical memory in use
+    pub fn memory_used_percent(&self) -> f64 {
+        percent(
+            self.memory_total_kb
+                .saturating_sub(self.memory_available_kb),
+            self.memory_total_kb,
+        )
+    }
+
+    /// Percentage of the deploy filesystem in use
+    pub fn disk_used_percent(&self) -> f64 {
+        percent(self.disk_used_kb, self.disk_total_kb)
+    }
+
+    /// One-line summary for CLI and TUI display
+    pub fn summary(&self) -> String {
+        format!(
+            "CPU {:.1}% | Mem {} / {} ({:.0}%) | Disk {:.0}% | Load {:.2} {:.2} {:.2}",
+            self.cpu_percent,
+            utils::fs::format_size(
+                self.memory_total_kb
+                    .saturating_sub(self.memory_available_kb)
+                    * 1024
+            ),
+            utils::fs::format_size(self.memory_total_kb * 1024),
+            self.memory_used_percent(),
+            self.disk_used_percent(),
+            self.load_average[0],
+            self.load_average[1],
+            self.load_average[2]
+        )
+    }
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
+
+/// Sample CPU, memory, disk, and load from `/proc` and `df` on the remote host
+pub fn collect(host: &dyn Host, disk_path: &str) -> Result<SystemMetrics> {
+    let command = format!(
+        "echo '--loadavg'; cat /proc/loadavg; \
+         echo '--stat'; head -n1 /proc/stat; sleep 1; head -n1 /proc/stat; \
+         echo '--meminfo'; grep -E '^(MemTotal|MemAvailable):' /proc/meminfo; \
+         echo '--df'; df -Pk {} | tail -n1",
+        utils::ssh::shell_quote(disk_path)
+    );
+    let (output, _) = host.execute_command(&command)?;
+    parse(&output)
+}
+
+/// Parse the sectioned output produced by [`collect`]
+pub fn parse(output: &str) -> Result<SystemMetrics> {
+    let mut metrics = SystemMetrics::default();
+    let mut section = "";
+    let mut cpu_samples = Vec::new();
+
+    for line in output.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(name) = line.strip_prefix("--") {
+            section = name;
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match section {
+            "loadavg" => {
+                for (slot, value) in metrics.load_average.iter_mut().zip(&fields) {
+                    *slot = value.parse().unwrap_or_default();
+                }
+            }
+            "stat" if fields.first() == Some(&"cpu") => {
+                let values: Vec<u64> = fields[1..].iter().filter_map(|v| v.parse().ok()).collect();
+                cpu_samples.push(values);
+            }
+            "meminfo" => {
+                let value = fields
+                    .get(1)
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_default();
+                match fields.first() {
+                    Some(&"MemTotal:") => metrics.memory_total_kb = value,
+                    Some(&"MemAvailable:") => metrics.memory_available_kb = value,
+                    _ => {}
+                }
+            }
+            "df" if fields.len() >= 3 => {
+                metrics.disk_total_kb = fields[1].parse().unwrap_or_default();
+                metrics.disk_used_kb = fields[2].parse().unwrap_or_default();
+            }
+            _ => {}
+        }
+    }
+
+    if let [first, second] = cpu_samples.as_slice() {
+        metrics.cpu_percent = cpu_usage(first, second);
+    }
+
+    if metrics.memory_total_kb == 0 {
+        return Err(anyhow!("Could not read system metrics from remote host"));
+    }
+
+    Ok(metrics)
+}
+
+/// CPU utilisation between two `/proc/stat` samples (idle includes iowait)
+fn cpu_usage(first: &[u64], second: &[u64]) -> f64 {
+    let idle =
+        |sample: &[u64]| sample.get(3).copied().unwrap_or(0) + sample.get(4).copied().unwrap_or(0);
+    let total_delta = second
+        .iter()
+        .sum::<u64>()
+        .saturating_sub(first.iter().sum::<u64>());
+    let idle_delta = idle(second).saturating_sub(idle(first));
+    percent(total_delta.saturating_sub(idle_delta), total_delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "--loadavg
+0.52 0.34 0.20 1/123 4567
+--stat
+cpu  100 0 100 700 100 0 0 0 0 0
+cpu  150 0 150 750 150 0 0 0 0 0
+--meminfo
+MemTotal:        2048000 kB
+MemAvailable:    1536000 kB
+--df
+/dev/vda1 41152736 10288184 28751120 27% /
+";
+
+    #[test]
+    fn test_parse_system_metrics() {
+        let metrics = parse(SAMPLE).unwrap();
+
+        assert_eq!(metrics.load_average, [0.52, 0.34, 0.20]);
+        assert_eq!(metrics.cpu_percent, 50.0);
+        assert_eq!(metrics.memory_used_percent(), 25.0);
+        assert_eq!(metrics.disk_total_kb, 41152736);
+        assert!((metrics.disk_used_percent() - 25.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_parse_system_metrics_missing_memory() {
+        assert!(parse("--loadavg\n0.1 0.1 0.1 1/1 1\n").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{Context, Result, anyhow};
+use reqwest::{Client, Url};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::utils;
+
+/// Whether `config` checks its health endpoint through an SSH tunnel
+///
+/// A local target reaches its endpoint directly either way.
+pub fn is_wanted(config: &Config) -> bool {
+    config.monitor.health_via == "ssh" && !config.deploy.is_local()
+}
+
+/// Health endpoint reached through a port forwarded over SSH, for `health_via = "ssh"`
+pub struct HealthTunnel {
+    forward: utils::ssh::LocalForward,
+    /// Client that sends requests for the endpoint's host name to the forwarded port
+    pub client: Client,
+    /// Endpoint URL on the forwarded port
+    pub url: Url,
+}
+
+impl HealthTunnel {
+    /// Forward a local port to the endpoint's host and port as the server sees them
+    pub async fn open(config: &Config, endpoint: &str) -> Result<Self> {
+        let url = Url::parse(endpoint)
+            .with_context(|| format!("Invalid health endpoint: {}", endpoint))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow!("Health endpoint has no host: {}", endpoint))?;
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| anyhow!("Health endpoint has no port: {}", endpoint))?;
+        // `host_str` keeps the brackets of an IPv6 address
+        let target = utils::net::bare_host(host);
+        let forward = utils::ssh::forward_local(&config.ssh_config(), target, port)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to open SSH tunnel to {}",
+                    utils::net::host_port(host, port)
+                )
+            })?;
+
+        let (url, domain) = tunneled_url(&url, forward.local_addr.port());
+        let mut client =
+            Client::builder().timeout(Duration::from_secs(config.monitor.health_timeout_secs));
+        if let Some(domain) = domain {
+            client = client.resolve(&domain, forward.local_addr);
+        }
+        Ok(Self {
+            forward,
+            client: client.build().context("Failed to create HTTP client")?,
+            url,
+        })
+    }
+
+    /// Whether the SSH connection carrying the tunnel is still up
+    pub fn is_open(&self) -> bool {
+        self.forward.is_open()
+    }
+}
+
+/// `url` on the local `port`, and the host name to resolve to 127.0.0.1 for it
+///
+/// A host name is kept, so TLS checks the certificate against it and the `Host` header still
+/// names it; an IP address is replaced.
+fn tunneled_url(url: &Url, port: u16) -> (Url, Option<String>) {
+    let mut tunneled = url.clone();
+    let domain = url.domain().map(str::to_string);
+    if domain.is_none() {
+        let _ = tunneled.set_host(Some("127.0.0.1"));
+    }
+    let _ = tunneled.set_port(Some(port));
+    (tunneled, domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tunneled_url() {
+        let url = |text: &str| Url::parse(text).unwrap();
+
+        let (tunneled, domain) = tunneled_url(&url("http://127.0.0.1:8080/health?full=1"), 40000);
+        assert_eq!(tunneled.as_str(), "http://127.0.0.1:40000/health?full=1");
+        assert_eq!(domain, None);
+
+        let (tunneled, domain) = tunneled_url(&url("http://[::1]:8080/health"), 40000);
+        assert_eq!(tunneled.as_str(), "http://127.0.0.1:40000/health");
+        assert_eq!(domain, None);
+
+        let (tunneled, domain) = tunneled_url(&url("https://api.internal/health"), 40000);
+        assert_eq!(tunneled.as_str(), "https://api.internal:40000/health");
+        assert_eq!(domain.as_deref(), Some("api.internal"));
+    }
+}
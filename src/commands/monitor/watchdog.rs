@@ -0,0 +1,117 @@
+use std::time::{Duration, Instant};
+
+use super::ApplicationStatus;
+use crate::config::WatchdogConfig;
+
+/// What the watchdog wants to do after a status sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Healthy, or not failing for long enough yet
+    Wait,
+    /// Failures persisted long enough; restart the service now
+    Restart { failures: u32 },
+    /// A restart is due but the previous one was too recent
+    CoolingDown { remaining: Duration },
+}
+
+/// Counts consecutive failures and decides when to restart the service
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    config: WatchdogConfig,
+    consecutive_failures: u32,
+    last_restart: Option<Instant>,
+}
+
+impl Watchdog {
+    /// Create a watchdog if one is configured and enabled
+    pub fn from_config(config: Option<&WatchdogConfig>) -> Option<Self> {
+        config.filter(|c| c.enabled).map(|config| Self {
+            config: config.clone(),
+            consecutive_failures: 0,
+            last_restart: None,
+        })
+    }
+
+    /// Record a status sample and decide whether to intervene
+    pub fn evaluate(&mut self, status: &ApplicationStatus, now: Instant) -> Decision {
+        if status.is_healthy() {
+            self.consecutive_failures = 0;
+            return Decision::Wait;
+        }
+
+        self.consecutive_failures += 1;
+        if self.consecutive_failures < self.config.failures_before_restart {
+            return Decision::Wait;
+        }
+
+        let cooldown = Duration::from_secs(self.config.cooldown_secs);
+        if let Some(last) = self.last_restart {
+            let since = now.duration_since(last);
+            if since < cooldown {
+                return Decision::CoolingDown {
+                    remaining: cooldown - since,
+                };
+            }
+        }
+
+        Decision::Restart {
+            failures: self.consecutive_failures,
+        }
+    }
+
+    /// Note that a restart was attempted, starting the cooldown
+    pub fn restarted(&mut self, now: Instant) {
+        self.last_restart = Some(now);
+        self.consecutive_failures = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_after_failures_with_cooldown() {
+        let config = WatchdogConfig {
+            enabled: true,
+            failures_before_restart: 2,
+            cooldown_secs: 60,
+        };
+        let mut watchdog = Watchdog::from_config(Some(&config)).unwrap();
+        let down = ApplicationStatus::default();
+        let start = Instant::now();
+
+        assert_eq!(watchdog.evaluate(&down, start), Decision::Wait);
+        assert_eq!(
+            watchdog.evaluate(&down, start),
+            Decision::Restart { failures: 2 }
+        );
+        watchdog.restarted(start);
+
+        let later = start + Duration::from_secs(20);
+        assert_eq!(watchdog.evaluate(&down, later), Decision::Wait);
+        assert_eq!(
+            watchdog.evaluate(&down, later),
+            Decision::CoolingDown {
+                remaining: Duration::from_secs(40)
+            }
+        );
+
+        let after_cooldown = start + Duration::from_secs(61);
+        assert_eq!(
+            watchdog.evaluate(&down, after_cooldown),
+            Decision::Restart { failures: 3 }
+        );
+    }
+
+    #[test]
+    fn test_disabled_watchdog() {
+        let config = WatchdogConfig {
+            enabled: false,
+            failures_before_restart: 1,
+            cooldown_secs: 0,
+        };
+        assert!(Watchdog::from_config(Some(&config)).is_none());
+        assert!(Watchdog::from_config(None).is_none());
+    }
+}
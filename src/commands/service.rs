@@ -0,0 +1,303 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+
+use crate::cli::{OutputFormat, ServiceAction};
+use crate::config::Config;
+use crate::logging::{glyph, log};
+use crate::utils;
+use crate::utils::host::Host;
+use crate::utils::ssh::shell_quote;
+
+/// Properties read with `systemctl show`, in the order they are printed
+const PROPERTIES: [&str; 5] = [
+    "LoadState",
+    "ActiveState",
+    "SubState",
+    "MainPID",
+    "ActiveEnterTimestamp",
+];
+
+/// State of a systemd unit on one host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnitState {
+    pub host: String,
+    pub service: String,
+    /// `loaded`, or `not-found` for a unit that isn't installed
+    pub load_state: String,
+    /// `active`, `inactive`, `failed`, ...
+    pub active_state: String,
+    /// `running`, `dead`, `exited`, ...
+    pub sub_state: String,
+    pub main_pid: Option<u32>,
+    /// When the unit last became active
+    pub since: Option<String>,
+}
+
+impl UnitState {
+    /// One-line summary, e.g. `app.service on example.com: active (running), PID 1234, since ...`
+    pub fn line(&self) -> String {
+        let mark = match self.active_state.as_str() {
+            "active" => glyph("✅"),
+            "failed" => glyph("❌"),
+            _ => glyph("🔴"),
+        };
+        let mut line = format!(
+            "{} {} on {}: {} ({})",
+            mark, self.service, self.host, self.active_state, self.sub_state
+        );
+        if self.load_state != "loaded" {
+            line.push_str(&format!(", {}", self.load_state));
+        }
+        if let Some(pid) = self.main_pid {
+            line.push_str(&format!(", PID {}", pid));
+        }
+        if let Some(since) = &self.since
+            && self.active_state == "active"
+        {
+            line.push_str(&format!(", since {}", since));
+        }
+        line
+    }
+}
+
+/// Apply `action` to `service` (default: the configured service) on every host,
+/// `max_parallel_hosts` at a time, and print the unit's state on each (as JSON with `--output json`)
+///
+/// In a dry run the systemctl command is only listed and the current state is shown.
+pub async fn manage_service(
+    config: &Config,
+    action: ServiceAction,
+    service: Option<&str>,
+    dry_run: bool,
+    output: OutputFormat,
+) -> Result<Vec<UnitState>> {
+    let service = service
+        .map(str::to_string)
+        .unwrap_or_else(|| config.service_name());
+    let results = utils::ssh::run_on_all(config, move |target, host_config| {
+        apply(
+            target,
+            &host_config.deploy.vps_host,
+            action,
+            &service,
+            dry_run,
+        )
+    })
+    .await;
+    let states = results
+        .0
+        .iter()
+        .filter_map(|(_, result)| result.as_ref().ok())
+        .collect::<Vec<_>>();
+    if output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&states)?);
+    } else {
+        for state in states {
+            println!("{}", state.line());
+        }
+    }
+    Ok(results
+        .into_result()?
+        .into_iter()
+        .map(|(_, state)| state)
+        .collect())
+}
+
+/// Run the systemctl command of `action` on `host` and read the resulting state
+fn apply(
+    host: &dyn Host,
+    name: &str,
+    action: ServiceAction,
+    service: &str,
+    dry_run: bool,
+) -> Result<UnitState> {
+    if let Some(verb) = action.verb() {
+        let command = format!("sudo systemctl {} {}", verb, shell_quote(service));
+        if dry_run {
+            log::dry_run(&format!("Would run on {}: {}", name, command));
+        } else {
+            log::deploy_step(&format!("Running on {}: {}", name, command));
+            host.execute_command(&command)?;
+        }
+    }
+
+    let state = unit_state(host, name, service)?;
+    if state.load_state == "not-found" {
+        return Err(anyhow!("Service {} is not installed", service));
+    }
+    // A service that exits with an error when stopped ends up `failed`, which counts as stopped
+    let reached = match action {
+        ServiceAction::Start | ServiceAction::Restart => state.active_state == "active",
+        ServiceAction::Stop => matches!(state.active_state.as_str(), "inactive" | "failed"),
+        ServiceAction::Status => true,
+    };
+    if !reached && !dry_run {
+        return Err(anyhow!(
+            "Service {} is {} ({}) after {}",
+            service,
+            state.active_state,
+            state.sub_state,
+            action.verb().unwrap_or_default()
+        ));
+    }
+    Ok(state)
+}
+
+/// Read the state of `service` with `systemctl show`
+fn unit_state(host: &dyn Host, name: &str, service: &str) -> Result<UnitState> {
+    let properties = PROPERTIES
+        .iter()
+        .map(|property| format!("-p {}", property))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (output, _) = host.execute_command(&format!(
+        "systemctl show {} {}",
+        properties,
+        shell_quote(service)
+    ))?;
+    Ok(parse_show(name, service, &output))
+}
+
+/// Parse the `Property=value` lines of `systemctl show`
+fn parse_show(host: &str, service: &str, output: &str) -> UnitState {
+    let value = |property: &str| {
+        output
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .find(|(key, _)| *key == property)
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    UnitState {
+        host: host.to_string(),
+        service: service.to_string(),
+        load_state: value("LoadState").unwrap_or_else(|| "unknown".to_string()),
+        active_state: value("ActiveState").unwrap_or_else(|| "unknown".to_string()),
+        sub_state: value("SubState").unwrap_or_else(|| "unknown".to_string()),
+        main_pid: value("MainPID")
+            .and_then(|pid| pid.parse().ok())
+            .filter(|pid| *pid != 0),
+        since: value("ActiveEnterTimestamp"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::host::mock::MockHost;
+
+    const RUNNING: &str = "LoadState=loaded\nActiveState=active\nSubState=running\nMainPID=1234\n\
+                           ActiveEnterTimestamp=Thu 2026-10-15 10:00:00 UTC\n";
+
+    fn config(host: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "{}"
+vps_user = "deploy"
+
+[monitor]
+"#,
+            host
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_manage_service() {
+        let host = MockHost::register("service.mock.invalid");
+        let config = config("service.mock.invalid");
+        host.reply("systemctl show", RUNNING);
+
+        let states = manage_service(
+            &config,
+            ServiceAction::Restart,
+            None,
+            false,
+            OutputFormat::Text,
+        )
+        .await
+        .unwrap();
+        assert!(host.ran("sudo systemctl restart 'app.service'"));
+        assert_eq!(
+            states[0].line(),
+            format!(
+                "{} app.service on service.mock.invalid: active (running), PID 1234, since Thu 2026-10-15 10:00:00 UTC",
+                glyph("✅")
+            )
+        );
+
+        // Status only reads the state; a dry run only lists the command
+        let commands = host.commands().len();
+        manage_service(
+            &config,
+            ServiceAction::Status,
+            Some("worker"),
+            false,
+            OutputFormat::Text,
+        )
+        .await
+        .unwrap();
+        manage_service(&config, ServiceAction::Stop, None, true, OutputFormat::Text)
+            .await
+            .unwrap();
+        assert!(
+            host.commands()[commands..]
+                .iter()
+                .all(|command| command.starts_with("systemctl show"))
+        );
+        assert!(host.ran("'worker'"));
+
+        // A unit that doesn't reach the state asked for fails the command
+        let error = manage_service(
+            &config,
+            ServiceAction::Stop,
+            None,
+            false,
+            OutputFormat::Text,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Service app.service is active (running) after stop")
+        );
+
+        host.reply(
+            "systemctl show",
+            "LoadState=not-found\nActiveState=inactive\nSubState=dead\nMainPID=0\n",
+        );
+        let error = manage_service(
+            &config,
+            ServiceAction::Status,
+            Some("nope"),
+            false,
+            OutputFormat::Text,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("Service nope is not installed"));
+    }
+
+    #[test]
+    fn test_parse_show() {
+        let state = parse_show(
+            "example.com",
+            "app.service",
+            "LoadState=loaded\nActiveState=failed\nSubState=failed\nMainPID=0\nActiveEnterTimestamp=\n",
+        );
+        assert_eq!(state.main_pid, None);
+        assert_eq!(state.since, None);
+        assert_eq!(
+            state.line(),
+            format!(
+                "{} app.service on example.com: failed (failed)",
+                glyph("❌")
+            )
+        );
+    }
+}
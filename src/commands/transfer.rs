@@ -0,0 +1,316 @@
+use anyhow::{Context, Result, anyhow};
+use indicatif::{MultiProgress, ProgressBar};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::logging::log;
+use crate::utils;
+use crate::utils::host::Host;
+use crate::utils::progress::{self, Transfer};
+use crate::utils::ssh::shell_quote;
+
+/// What a path on the server is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RemoteKind {
+    File,
+    Dir,
+}
+
+/// A finished copy between this machine and one host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Copied {
+    pub host: String,
+    /// Path on the server
+    pub remote: String,
+    pub local: PathBuf,
+    /// Bytes transferred
+    pub size: u64,
+}
+
+/// Copy the local file or directory `local` to `remote` on every host, `max_parallel_hosts`
+/// at a time
+///
+/// As with `cp`, a `remote` that is a directory on the server receives `local` under its own
+/// name. Directories are only copied with `recursive`. In a dry run the copies are only listed.
+pub async fn upload(
+    config: &Config,
+    local: &Path,
+    remote: &str,
+    recursive: bool,
+    dry_run: bool,
+) -> Result<Vec<Copied>> {
+    let metadata = std::fs::metadata(local)
+        .with_context(|| format!("Failed to read local file: {}", local.display()))?;
+    if metadata.is_dir() && !recursive {
+        return Err(anyhow!(
+            "{} is a directory; pass -r to copy it with everything in it",
+            local.display()
+        ));
+    }
+    if dry_run {
+        for host in config.hosts() {
+            log::dry_run(&format!(
+                "Upload {} to {}:{}",
+                local.display(),
+                host,
+                remote
+            ));
+        }
+        return Ok(Vec::new());
+    }
+
+    let bars = MultiProgress::new();
+    let (local, remote) = (local.to_path_buf(), remote.to_string());
+    let copied = utils::ssh::run_on_all(config, move |target, host_config| {
+        let name = host_config.deploy.vps_host.clone();
+        let bar = bars.add(transfer_bar(&name));
+        let on_progress = |transfer: &Transfer| progress::show_transfer(&bar, transfer);
+        let remote = match remote_kind(target, &remote)? {
+            Some(RemoteKind::Dir) => join_remote(&remote, &file_name(&local)?),
+            _ => remote.clone(),
+        };
+        if local.is_dir() {
+            target.upload_dir(&local, &remote, Some(&on_progress))?;
+        } else {
+            target.upload_file(&local, &remote, Some(&on_progress))?;
+        }
+        bar.finish_and_clear();
+        Ok(Copied {
+            host: name,
+            remote,
+            local: local.clone(),
+            size: bar.position(),
+        })
+    })
+    .await
+    .into_result()?;
+    Ok(copied.into_iter().map(|(_, copied)| copied).collect())
+}
+
+/// Copy the file or directory `remote` from every host to `local`, `max_parallel_hosts` at a
+/// time
+///
+/// With several hosts, each host's copy goes under `<local>/<host>/`. As with `cp`, a `local`
+/// that is a directory receives the copy under its own name. Directories are only copied with
+/// `recursive`. In a dry run the copies are only listed.
+pub async fn fetch(
+    config: &Config,
+    remote: &str,
+    local: &Path,
+    recursive: bool,
+    dry_run: bool,
+) -> Result<Vec<Copied>> {
+    let hosts = config.hosts();
+    let per_host = hosts.len() > 1;
+    if dry_run {
+        for host in &hosts {
+            let destination = if per_host {
+                local.join(host)
+            } else {
+                local.to_path_buf()
+            };
+            log::dry_run(&format!(
+                "Download {}:{} to {}",
+                host,
+                remote,
+                destination.display()
+            ));
+        }
+        return Ok(Vec::new());
+    }
+
+    let bars = MultiProgress::new();
+    let (remote, local) = (remote.to_string(), local.to_path_buf());
+    let copied = utils::ssh::run_on_all(config, move |target, host_config| {
+        let name = host_config.deploy.vps_host.clone();
+        let bar = bars.add(transfer_bar(&name));
+        let on_progress = |transfer: &Transfer| progress::show_transfer(&bar, transfer);
+        let kind = remote_kind(target, &remote)?
+            .ok_or_else(|| anyhow!("{} does not exist on {}", remote, name))?;
+        if kind == RemoteKind::Dir && !recursive {
+            return Err(anyhow!(
+                "{} is a directory on {}; pass -r to copy it with everything in it",
+                remote,
+                name
+            ));
+        }
+        let local = if per_host {
+            local.join(&name)
+        } else {
+            local.clone()
+        };
+        let local = if per_host || local.is_dir() {
+            local.join(remote_file_name(&remote)?)
+        } else {
+            local
+        };
+        if let Some(parent) = local.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        match kind {
+            RemoteKind::Dir => target.download_dir(&remote, &local, Some(&on_progress))?,
+            RemoteKind::File => target.download_file(&remote, &local, Some(&on_progress))?,
+        }
+        bar.finish_and_clear();
+        Ok(Copied {
+            host: name,
+            remote: remote.clone(),
+            local,
+            size: bar.position(),
+        })
+    })
+    .await
+    .into_result()?;
+    Ok(copied.into_iter().map(|(_, copied)| copied).collect())
+}
+
+/// Progress bar of one host's transfer, labelled with its name
+fn transfer_bar(host: &str) -> ProgressBar {
+    let bar = progress::transfer_progress(0);
+    bar.set_message(host.to_string());
+    bar
+}
+
+/// Whether `path` is a file or a directory on `host`, or `None` when it doesn't exist
+fn remote_kind(host: &dyn Host, path: &str) -> Result<Option<RemoteKind>> {
+    let path = shell_quote(path);
+    let (output, _) = host.execute_command(&format!(
+        "if [ -d {} ]; then echo dir; elif [ -e {} ]; then echo file; fi",
+        path, path
+    ))?;
+    Ok(match output.trim() {
+        "dir" => Some(RemoteKind::Dir),
+        "file" => Some(RemoteKind::File),
+        _ => None,
+    })
+}
+
+fn join_remote(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
+fn file_name(path: &Path) -> Result<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| anyhow!("{} has no file name", path.display()))
+}
+
+fn remote_file_name(path: &str) -> Result<&str> {
+    path.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .ok_or_else(|| anyhow!("{} has no file name", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::host::mock::MockHost;
+    use tempfile::tempdir;
+
+    fn config(hosts: &[&str]) -> Config {
+        toml::from_str(&format!(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "{}"
+hosts = {:?}
+vps_user = "deploy"
+
+[monitor]
+"#,
+            hosts[0], hosts
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_upload() {
+        let host = MockHost::register("cp.mock.invalid");
+        let config = config(&["cp.mock.invalid"]);
+        host.reply("[ -d '/etc/app' ]", "dir");
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("app.toml");
+        std::fs::write(&file, "port = 80").unwrap();
+        let copied = upload(&config, &file, "/etc/app", false, false)
+            .await
+            .unwrap();
+        assert_eq!(copied[0].remote, "/etc/app/app.toml");
+        assert_eq!(copied[0].size, 9);
+        assert_eq!(host.file("/etc/app/app.toml").unwrap().0, b"port = 80");
+
+        std::fs::create_dir_all(dir.path().join("conf/nested")).unwrap();
+        std::fs::write(dir.path().join("conf/nested/extra.toml"), "a = 1").unwrap();
+        let conf = dir.path().join("conf");
+        let error = upload(&config, &conf, "/srv/conf", false, false)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("pass -r"));
+        upload(&config, &conf, "/srv/conf", true, false)
+            .await
+            .unwrap();
+        assert!(host.file("/srv/conf/nested/extra.toml").is_some());
+
+        // A dry run doesn't reach the server
+        let commands = host.commands().len();
+        upload(&config, &file, "/tmp", false, true).await.unwrap();
+        assert_eq!(host.commands().len(), commands);
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let web1 = MockHost::register("fetch1.mock.invalid");
+        let web2 = MockHost::register("fetch2.mock.invalid");
+        for host in [&web1, &web2] {
+            host.add_file("/var/log/app.log", b"started\n")
+                .reply("[ -e '/var/log/app.log' ]", "file");
+        }
+
+        let dir = tempdir().unwrap();
+        let config = config(&["fetch1.mock.invalid", "fetch2.mock.invalid"]);
+        let copied = fetch(&config, "/var/log/app.log", dir.path(), false, false)
+            .await
+            .unwrap();
+        assert_eq!(copied.len(), 2);
+        assert_eq!(
+            std::fs::read(dir.path().join("fetch2.mock.invalid/app.log")).unwrap(),
+            b"started\n"
+        );
+
+        // One host saves to the path given, or into it when it is a directory
+        let config = self::config(&["fetch1.mock.invalid"]);
+        let local = dir.path().join("saved.log");
+        fetch(&config, "/var/log/app.log", &local, false, false)
+            .await
+            .unwrap();
+        assert!(local.is_file());
+        let copied = fetch(&config, "/var/log/app.log", dir.path(), false, false)
+            .await
+            .unwrap();
+        assert_eq!(copied[0].local, dir.path().join("app.log"));
+
+        let error = fetch(&config, "/var/log/missing", dir.path(), false, false)
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("/var/log/missing does not exist on fetch1.mock.invalid")
+        );
+    }
+
+    #[test]
+    fn test_remote_file_name() {
+        assert_eq!(remote_file_name("/etc/app/").unwrap(), "app");
+        assert_eq!(remote_file_name("app.log").unwrap(), "app.log");
+        assert!(remote_file_name("/").is_err());
+        assert_eq!(join_remote("/etc/", "app.toml"), "/etc/app.toml");
+    }
+}
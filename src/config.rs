@@ -1,19 +1,124 @@
 use anyhow::{Context, Result, anyhow};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::utils::timing::DisplayTimezone;
+
+pub mod reload;
+
 /// Configuration for the rzen application
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
+    /// Layout version of the file; older files are upgraded when loaded
+    #[serde(default = "default_version")]
+    pub version: u32,
+
     pub project: ProjectConfig,
     pub deploy: DeployConfig,
     pub monitor: MonitorConfig,
+
+    /// Commands run around builds, deploys and rollbacks (optional)
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+
+    /// Terminal UI settings (optional)
+    #[serde(default, skip_serializing_if = "TuiConfig::is_default")]
+    pub tui: TuiConfig,
+
+    /// Attempts and backoff for SSH connections, health checks and uploads (optional)
+    #[serde(default, skip_serializing_if = "RetryConfig::is_default")]
+    pub retry: RetryConfig,
+
+    /// Per-environment overrides layered over the sections above, e.g. `[env.staging.deploy]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[schemars(with = "BTreeMap<String, BTreeMap<String, serde_json::Value>>")]
+    pub env: BTreeMap<String, toml::Table>,
+
+    /// Projects sharing this file, selected with `--project <name>`: each entry's keys go to
+    /// `[project]` and its `deploy`, `monitor` and `tui` tables override those sections
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(with = "Vec<BTreeMap<String, serde_json::Value>>")]
+    pub projects: Vec<toml::Table>,
+
+    /// Settings that did not come from the config files
+    #[serde(skip)]
+    pub overrides: Overrides,
+
+    /// Config file whose settings this one overrides, relative to this file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+}
+
+/// Layout version of rzen.toml written by this build; files without `version` are version 1
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Upgrade of the settings from one layout version to the next
+type Migration = fn(&mut dyn toml_edit::TableLike);
+
+/// `MIGRATIONS[i]` upgrades version `i + 1` to `i + 2`
+///
+/// Add one (and bump `CONFIG_VERSION`) whenever a setting is renamed or moved, so files
+/// written for an older rzen keep loading.
+const MIGRATIONS: [Migration; CONFIG_VERSION as usize - 1] = [];
+
+/// Sections a `[[projects]]` entry can override; its other keys belong to `[project]`
+const PROJECT_SECTIONS: [&str; 4] = ["deploy", "monitor", "hooks", "tui"];
+
+/// Environment variable selecting an `[env.<name>]` section when `--env` is not given
+pub const ENV_VAR: &str = "RZEN_ENV";
+
+/// Prefix of environment variables overriding single settings, e.g. `RZEN_DEPLOY_VPS_HOST`
+const VAR_PREFIX: &str = "RZEN_";
+
+/// `RZEN_*` variables that don't override a setting
+const OTHER_VARS: [&str; 4] = [ENV_VAR, "RZEN_AGE_IDENTITY", "RZEN_HOOK", "RZEN_HOOK_HOST"];
+
+/// Placeholder host written by `rzen init` and new default configs
+pub const EXAMPLE_HOST: &str = "your-vps.example.com";
+
+/// Where settings were overridden after the config files were read
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    /// `[[projects]]` entry that was applied (`--project`)
+    pub project: Option<String>,
+    /// Environment whose `[env.<name>]` section was applied (`--env` or `RZEN_ENV`)
+    pub environment: Option<String>,
+    /// Settings taken from `RZEN_*` variables, e.g. `deploy.vps_host`
+    pub variables: Vec<String>,
+    /// `enc:` values decrypted with the age identity
+    pub encrypted: Vec<EncryptedValue>,
+    /// Strings built from `{section.key}` references to other settings
+    pub interpolated: Vec<InterpolatedValue>,
+    /// Files upgraded from an older layout, with the version they had
+    pub migrated: Vec<(PathBuf, u32)>,
+}
+
+/// Config value decrypted at load time, kept to write its ciphertext back on save
+#[derive(Debug, Clone)]
+pub struct EncryptedValue {
+    /// Keys leading to the value, e.g. `["deploy", "vps_password"]`; array items by index
+    pub path: Vec<String>,
+    pub ciphertext: String,
+    pub plaintext: String,
+}
+
+/// Config string with `{section.key}` references resolved at load time, kept to write the
+/// references back on save
+#[derive(Debug, Clone)]
+pub struct InterpolatedValue {
+    /// Keys leading to the value, e.g. `["deploy", "deploy_path"]`; array items by index
+    pub path: Vec<String>,
+    /// The string as written in the file, e.g. `/opt/{project.name}`
+    pub template: String,
+    pub value: String,
 }
 
 /// Project-specific configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectConfig {
     /// Path to the Rust project (relative to config file or absolute)
     #[serde(default = "default_project_path")]
@@ -28,16 +133,22 @@ pub struct ProjectConfig {
 }
 
 /// Deployment configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DeployConfig {
-    /// Deployment target type
+    /// Where to deploy: "vps" over SSH, or "local" for the machine rzen runs on
     #[serde(default = "default_target")]
     pub target: String,
 
     /// VPS host address
+    #[serde(default)]
     pub vps_host: String,
 
+    /// Every host running the application (defaults to `vps_host` alone)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+
     /// SSH username
+    #[serde(default)]
     pub vps_user: String,
 
     /// Path to SSH private key (optional, falls back to password auth)
@@ -46,6 +157,48 @@ pub struct DeployConfig {
     /// SSH password (optional, used if key_path not provided)
     pub vps_password: Option<String>,
 
+    /// Where the SSH password or key passphrase comes from: "config" (`vps_password`, default)
+    /// or "keyring", the OS keyring entry stored with `rzen auth set`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_source: Option<String>,
+
+    /// Command printing the SSH password or key passphrase, run each time rzen needs it,
+    /// e.g. `op read op://vault/vps/password`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_command: Option<String>,
+
+    /// With `password_source = "keyring"`, hosts in `hosts` without a keyring entry of their
+    /// own use the one stored for `vps_host`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keyring_shared: bool,
+
+    /// Secret read from the keyring or `password_command`; never saved
+    #[serde(skip)]
+    pub runtime_password: Option<String>,
+
+    /// Keyring secrets by host, for `for_host`; never saved
+    #[serde(skip)]
+    pub host_passwords: BTreeMap<String, String>,
+
+    /// Passphrase of an encrypted `vps_key_path`; asked for on the terminal when left out.
+    /// Without either, the SSH password is tried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_passphrase: Option<String>,
+
+    /// Key passphrase entered at the prompt; never saved
+    #[serde(skip)]
+    pub prompted_passphrase: Option<String>,
+
+    /// Where the password for `sudo` on the server comes from when it asks for one: "prompt"
+    /// asks on the terminal once per command, "ssh" reuses the SSH password. Unset means
+    /// passwordless sudo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sudo_password_source: Option<String>,
+
+    /// Sudo password entered at the prompt; never saved
+    #[serde(skip)]
+    pub prompted_sudo_password: Option<String>,
+
     /// Remote directory for deployment
     #[serde(default = "default_deploy_path")]
     pub deploy_path: String,
@@ -56,10 +209,169 @@ pub struct DeployConfig {
     /// SSH port
     #[serde(default = "default_ssh_port")]
     pub ssh_port: u16,
+
+    /// How to replace a running service: "restart", "reload", or "stop-start"
+    ///
+    /// "reload" keeps the running process on units with `ExecReload=`, so it doesn't pick up a
+    /// newly deployed binary there.
+    #[serde(default = "default_restart_strategy")]
+    pub restart_strategy: String,
+
+    /// Seconds the old process gets to exit after SIGTERM before it is killed
+    #[serde(default = "default_shutdown_grace")]
+    pub shutdown_grace_secs: u64,
+
+    /// Free space (MB) to keep on the deploy and log filesystems
+    #[serde(default = "default_min_free_disk")]
+    pub min_free_disk_mb: u64,
+
+    /// Binary backups kept for rollback after each deploy (0 keeps them all)
+    #[serde(default = "default_keep_backups")]
+    pub keep_backups: usize,
+
+    /// GitHub Release to pull prebuilt binaries from (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_release: Option<GithubReleaseConfig>,
+
+    /// SSH connection tuning
+    #[serde(default, skip_serializing_if = "SshOptions::is_default")]
+    pub ssh: SshOptions,
+
+    /// Config files rendered from local templates and uploaded on every deploy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub templates: Vec<TemplateConfig>,
+}
+
+/// Local template rendered with the config's settings and uploaded to the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TemplateConfig {
+    /// Template file, relative to the project directory
+    pub source: String,
+
+    /// Path on the server; relative paths are under `deploy_path`
+    pub destination: String,
+
+    /// Permissions of the uploaded file in octal, e.g. "0640"
+    #[serde(default = "default_template_mode")]
+    pub mode: String,
+}
+
+impl TemplateConfig {
+    /// `mode` as permission bits
+    pub fn mode_bits(&self) -> Result<i32> {
+        i32::from_str_radix(&self.mode, 8)
+            .ok()
+            .filter(|bits| (0..=0o7777).contains(bits))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Template mode must be octal permissions like \"0644\", got: {}",
+                    self.mode
+                )
+            })
+    }
+}
+
+/// Timeouts, retries and authentication of SSH connections (`[deploy.ssh]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SshOptions {
+    /// Seconds to wait for the TCP connection, handshake and authentication
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout_secs: u64,
+
+    /// Seconds a read or write on an open connection may block (0 waits indefinitely)
+    #[serde(default)]
+    pub read_timeout_secs: u64,
+
+    /// Seconds a remote command may run before it is stopped (0 lets it run indefinitely)
+    #[serde(default = "default_command_timeout")]
+    pub command_timeout_secs: u64,
+
+    /// Seconds between keepalive messages on a connection (0 disables them)
+    #[serde(default = "default_keepalive_interval")]
+    pub keepalive_interval_secs: u32,
+
+    /// Connection attempts, including the first, before giving up
+    #[serde(default = "default_ssh_retries")]
+    pub max_retries: u32,
+
+    /// Compress the SSH transport
+    #[serde(default)]
+    pub compression: bool,
+
+    /// Authentication methods to try, in order: "publickey", "agent", "password"
+    #[serde(default = "default_auth_order")]
+    pub auth_order: Vec<String>,
+
+    /// How server keys are checked against known_hosts: "ask" (trust a new key after
+    /// confirming it on the terminal), "accept-new", "strict" or "off"
+    #[serde(default = "default_host_key_checking")]
+    pub host_key_checking: String,
+
+    /// known_hosts file to check and add keys to (defaults to ~/.ssh/known_hosts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub known_hosts_file: Option<String>,
+
+    /// How deploys copy files to the server: "scp" or "sftp"
+    #[serde(default = "default_transfer")]
+    pub transfer: String,
+
+    /// Hosts that deploys, status checks and monitoring work on at the same time
+    #[serde(default = "default_max_parallel_hosts")]
+    pub max_parallel_hosts: usize,
+}
+
+impl Default for SshOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: default_connect_timeout(),
+            read_timeout_secs: 0,
+            command_timeout_secs: default_command_timeout(),
+            keepalive_interval_secs: default_keepalive_interval(),
+            max_retries: default_ssh_retries(),
+            compression: false,
+            auth_order: default_auth_order(),
+            host_key_checking: default_host_key_checking(),
+            known_hosts_file: None,
+            transfer: default_transfer(),
+            max_parallel_hosts: default_max_parallel_hosts(),
+        }
+    }
+}
+
+impl SshOptions {
+    /// Authentication methods `auth_order` accepts
+    pub const AUTH_METHODS: [&str; 3] = ["publickey", "agent", "password"];
+
+    /// Values `host_key_checking` accepts
+    pub const HOST_KEY_CHECKING: [&str; 4] = ["ask", "accept-new", "strict", "off"];
+
+    /// Values `transfer` accepts
+    pub const TRANSFERS: [&str; 2] = ["scp", "sftp"];
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// GitHub Release artifact source
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GithubReleaseConfig {
+    /// Repository in "owner/name" form
+    pub repo: String,
+
+    /// Name of the release asset containing the binary
+    pub asset: String,
+
+    /// Name of the asset holding the SHA-256 checksum (defaults to "<asset>.sha256")
+    pub checksum_asset: Option<String>,
+
+    /// Environment variable holding an API token for private repositories
+    #[serde(default = "default_github_token_env")]
+    pub token_env: String,
 }
 
 /// Monitoring configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MonitorConfig {
     /// Health check endpoint URL
     pub health_endpoint: Option<String>,
@@ -71,200 +383,2227 @@ pub struct MonitorConfig {
     #[serde(default = "default_monitor_interval")]
     pub interval_secs: u64,
 
+    /// Longest poll interval while the target is unreachable, in seconds
+    #[serde(default = "default_max_backoff")]
+    pub max_backoff_secs: u64,
+
     /// Timeout for health checks in seconds
     #[serde(default = "default_health_timeout")]
     pub health_timeout_secs: u64,
-}
 
-// Default value functions
-fn default_project_path() -> String {
-    ".".to_string()
+    /// Text the health response body must contain (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_body_contains: Option<String>,
+
+    /// JSON assertions on the health response, e.g. `['$.status == "ok"']`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub health_assertions: Vec<String>,
+
+    /// How the health endpoint is reached: "direct", or "ssh" through a port forwarded over
+    /// the SSH connection, for an endpoint only the server can reach
+    #[serde(default = "default_health_via")]
+    pub health_via: String,
+
+    /// Prometheus metrics endpoint exposed by the application (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_endpoint: Option<String>,
+
+    /// Counter used to derive request and error rates from the metrics endpoint
+    #[serde(default = "default_request_counter")]
+    pub metrics_request_counter: String,
+
+    /// Additional metric names to display from the metrics endpoint
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metrics_gauges: Vec<String>,
+
+    /// Remote HTTP access log to aggregate status codes from (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_log_path: Option<String>,
+
+    /// Access log format: "combined" (nginx/Apache) or "json"
+    #[serde(default = "default_access_log_format")]
+    pub access_log_format: String,
+
+    /// Automatic restarts within `crash_loop_window_secs` that mark the service as flapping
+    #[serde(default = "default_crash_loop_restarts")]
+    pub crash_loop_restarts: u32,
+
+    /// Window for crash-loop detection in seconds
+    #[serde(default = "default_crash_loop_window")]
+    pub crash_loop_window_secs: u64,
+
+    /// Seconds between checks of the deployed binary against the deploy manifest (0 disables)
+    #[serde(default = "default_drift_check_interval")]
+    pub drift_check_interval_secs: u64,
+
+    /// Number of recent checks used for latency percentiles
+    #[serde(default = "default_latency_window")]
+    pub latency_window: usize,
+
+    /// Path to the local monitoring history database (defaults to the user data dir)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_path: Option<String>,
+
+    /// Days of monitoring history to keep
+    #[serde(default = "default_history_retention")]
+    pub history_retention_days: u64,
+
+    /// Ports that must be listening, e.g. `[5432, 6379]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_checks: Vec<PortCheck>,
+
+    /// Commands run over SSH whose exit codes count toward health
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<CommandCheck>,
+
+    /// Timezone for displayed timestamps: "utc", "local", or an offset like "+02:00".
+    /// When set, log timestamps are converted from server time as well.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// Keep the original log timestamp next to the converted one
+    #[serde(default)]
+    pub show_original_timestamps: bool,
+
+    /// URL pinged after every continuous monitoring cycle, `<url>/fail` when unhealthy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_url: Option<String>,
+
+    /// Alert thresholds and notification channels (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alerts: Option<AlertConfig>,
+
+    /// Automatic restarts after sustained failures (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchdog: Option<WatchdogConfig>,
 }
 
-fn default_build_mode() -> String {
-    "release".to_string()
+/// Attempts and backoff for operations that can fail on a flaky network (`[retry]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RetryConfig {
+    /// Attempts, including the first, for health checks and uploads; SSH connections
+    /// make `[deploy.ssh] max_retries`
+    #[serde(default = "default_retry_attempts")]
+    pub max_attempts: u32,
+
+    /// Milliseconds before the second attempt, doubling for each one after
+    #[serde(default = "default_retry_base_delay")]
+    pub base_delay_ms: u64,
+
+    /// Longest wait between attempts, in milliseconds
+    #[serde(default = "default_retry_max_delay")]
+    pub max_delay_ms: u64,
+
+    /// Random spread of each wait, as a fraction of it from 0.0 to 1.0
+    #[serde(default = "default_retry_jitter")]
+    pub jitter: f64,
+
+    /// Failures worth another attempt: "connect", "timeout", "disconnect", "server"
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<String>,
 }
 
-fn default_target() -> String {
-    "vps".to_string()
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_attempts(),
+            base_delay_ms: default_retry_base_delay(),
+            max_delay_ms: default_retry_max_delay(),
+            jitter: default_retry_jitter(),
+            retry_on: default_retry_on(),
+        }
+    }
 }
 
-fn default_deploy_path() -> String {
-    "/opt/rzen-app".to_string()
+impl RetryConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
-fn default_ssh_port() -> u16 {
-    22
+/// Terminal UI settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TuiConfig {
+    /// Keys per action, e.g. `build = "B"` or `quit = ["q", "ctrl+c"]`; unset actions keep their defaults
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, KeyList>,
+
+    /// Color scheme: a preset plus optional color overrides
+    #[serde(default, skip_serializing_if = "ThemeConfig::is_default")]
+    pub theme: ThemeConfig,
+
+    /// Vim-style navigation: j/k, gg/G and Ctrl-d/Ctrl-u in log panes, `:` commands
+    #[serde(default)]
+    pub vim: bool,
+
+    /// Lines kept in each log pane (Build, Deploy and Monitor); older lines are dropped
+    #[serde(default = "default_log_lines")]
+    pub log_lines: usize,
 }
 
-fn default_monitor_interval() -> u64 {
-    10
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+            theme: ThemeConfig::default(),
+            vim: false,
+            log_lines: default_log_lines(),
+        }
+    }
 }
 
-fn default_health_timeout() -> u64 {
-    5
+impl TuiConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
-impl Config {
-    /// Load configuration from a TOML file
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref();
-        let contents = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+/// TUI color scheme
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ThemeConfig {
+    /// "dark" (default), "light", "solarized" or "monochrome"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
 
-        let config: Config = toml::from_str(&contents)
-            .with_context(|| format!("Failed to parse TOML config file: {}", path.display()))?;
+    /// Colors replacing the preset's, e.g. `accent = "#268bd2"` or `warning = "magenta"`
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
 
-        config.validate()?;
-        Ok(config)
+impl ThemeConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
     }
+}
 
-    /// Load configuration from the default location (rzen.toml in current directory)
-    pub fn from_default_location() -> Result<Self> {
-        let paths = [
-            "rzen.toml",
-            ".rzen.toml",
-            &format!(
-                "{}/.rzen.toml",
-                dirs::home_dir()
-                    .ok_or_else(|| anyhow!("Could not determine home directory"))?
-                    .display()
-            ),
-        ];
+/// One key or several keys bound to the same action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
 
-        for path in &paths {
-            if Path::new(path).exists() {
-                return Self::from_file(path);
-            }
+impl KeyList {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
         }
-
-        Err(anyhow!(
-            "No configuration file found. Create rzen.toml in the current directory or provide --config path"
-        ))
     }
+}
 
-    /// Create a default configuration file
-    pub fn create_default<P: AsRef<Path>>(path: P) -> Result<()> {
-        let default_config = Config {
-            project: ProjectConfig {
-                path: ".".to_string(),
-                name: "my-rust-app".to_string(),
-                build_mode: "release".to_string(),
-            },
-            deploy: DeployConfig {
-                target: "vps".to_string(),
-                vps_host: "your-vps.example.com".to_string(),
-                vps_user: "deploy".to_string(),
-                vps_key_path: Some("~/.ssh/id_rsa".to_string()),
-                vps_password: None,
-                deploy_path: "/opt/rzen-app".to_string(),
-                service_name: Some("my-rust-app.service".to_string()),
-                ssh_port: 22,
-            },
-            monitor: MonitorConfig {
-                health_endpoint: Some("http://your-vps.example.com:8080/health".to_string()),
-                log_path: Some("/var/log/my-rust-app.log".to_string()),
-                interval_secs: 10,
-                health_timeout_secs: 5,
-            },
-        };
+/// Watchdog that restarts the service when continuous monitoring keeps failing
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WatchdogConfig {
+    /// Whether the watchdog may restart the service
+    #[serde(default)]
+    pub enabled: bool,
 
-        let toml_string = toml::to_string_pretty(&default_config)
-            .context("Failed to serialize default config to TOML")?;
+    /// Consecutive failed checks before the service is restarted
+    #[serde(default = "default_consecutive_failures")]
+    pub failures_before_restart: u32,
 
-        fs::write(path.as_ref(), toml_string).with_context(|| {
-            format!(
-                "Failed to write default config to: {}",
-                path.as_ref().display()
-            )
-        })?;
+    /// Minimum seconds between two restarts
+    #[serde(default = "default_watchdog_cooldown")]
+    pub cooldown_secs: u64,
+}
 
-        Ok(())
-    }
+/// Port that must be listening for the application to count as healthy
+///
+/// A bare number is a TCP port checked on the server over SSH. The table form
+/// selects UDP or a direct connection from this machine:
+/// `{ port = 53, protocol = "udp" }`, `{ port = 443, via = "direct" }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PortCheck {
+    Port(u16),
+    Detailed {
+        port: u16,
+        #[serde(default = "default_port_protocol")]
+        protocol: String,
+        #[serde(default = "default_port_via")]
+        via: String,
+    },
+}
 
-    /// Validate the configuration
-    pub fn validate(&self) -> Result<()> {
-        // Validate project config
-        if self.project.name.trim().is_empty() {
-            return Err(anyhow!("Project name cannot be empty"));
+impl PortCheck {
+    pub fn port(&self) -> u16 {
+        match self {
+            PortCheck::Port(port) | PortCheck::Detailed { port, .. } => *port,
         }
+    }
 
-        if !matches!(self.project.build_mode.as_str(), "debug" | "release") {
-            return Err(anyhow!(
-                "Build mode must be 'debug' or 'release', got: {}",
-                self.project.build_mode
-            ));
+    /// `tcp` or `udp`
+    pub fn protocol(&self) -> &str {
+        match self {
+            PortCheck::Port(_) => "tcp",
+            PortCheck::Detailed { protocol, .. } => protocol,
         }
+    }
 
-        // Validate deploy config
-        if self.deploy.vps_host.trim().is_empty() {
-            return Err(anyhow!("VPS host cannot be empty"));
+    /// `ssh` to inspect listening sockets on the server, `direct` to connect from here
+    pub fn via(&self) -> &str {
+        match self {
+            PortCheck::Port(_) => "ssh",
+            PortCheck::Detailed { via, .. } => via,
         }
+    }
+}
 
-        if self.deploy.vps_user.trim().is_empty() {
-            return Err(anyhow!("VPS user cannot be empty"));
-        }
+/// Health check that runs a command on the server and passes on exit code 0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CommandCheck {
+    /// Display name (defaults to the command)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 
-        if self.deploy.vps_key_path.is_none() && self.deploy.vps_password.is_none() {
-            return Err(anyhow!("Either SSH key path or password must be provided"));
-        }
+    /// Shell command, e.g. `pg_isready -h localhost`
+    pub command: String,
 
-        if let Some(ref key_path) = self.deploy.vps_key_path {
-            if key_path.trim().is_empty() {
-                return Err(anyhow!("SSH key path cannot be empty"));
-            }
-        }
+    /// Seconds before the command is killed and the check fails
+    #[serde(default = "default_check_timeout")]
+    pub timeout_secs: u64,
+}
 
-        // Validate monitor config
-        if let Some(ref endpoint) = self.monitor.health_endpoint {
-            if endpoint.trim().is_empty() {
-                return Err(anyhow!("Health endpoint URL cannot be empty"));
-            }
-            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
-                return Err(anyhow!("Health endpoint must be a valid HTTP/HTTPS URL"));
-            }
-        }
+impl CommandCheck {
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.command)
+    }
+}
 
-        if self.monitor.interval_secs == 0 {
-            return Err(anyhow!("Monitor interval must be greater than 0 seconds"));
-        }
+/// Commands run at points of the build and deploy pipelines
+///
+/// Each list runs in order and stops at the first failing command; a failing `pre_*` hook
+/// stops the pipeline.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct HooksConfig {
+    /// Before `cargo build`, e.g. generating assets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_build: Vec<HookCommand>,
 
-        if self.monitor.health_timeout_secs == 0 {
-            return Err(anyhow!("Health timeout must be greater than 0 seconds"));
-        }
+    /// After a successful (or skipped, up-to-date) build
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_build: Vec<HookCommand>,
 
-        Ok(())
-    }
+    /// After connecting to a host, before the binary is uploaded to it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_deploy: Vec<HookCommand>,
 
-    /// Get the absolute project path
-    pub fn project_path(&self) -> Result<PathBuf> {
-        let config_dir = Path::new(".")
-            .canonicalize()
-            .context("Failed to get current directory")?;
-        let project_path = Path::new(&self.project.path);
+    /// After the service was (re)started on a host
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_deploy: Vec<HookCommand>,
 
-        if project_path.is_absolute() {
-            Ok(project_path.to_path_buf())
-        } else {
-            Ok(config_dir.join(project_path))
-        }
+    /// After a rollback restarted the previous binary
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_rollback: Vec<HookCommand>,
+
+    /// When a build or deploy fails; their own failures are only logged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<HookCommand>,
+}
+
+impl HooksConfig {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
     }
+}
 
-    /// Get the binary name based on project configuration
-    pub fn binary_name(&self) -> String {
-        self.project.name.clone()
+/// Hook command
+///
+/// A string runs locally in the project directory; the table form can run it on the
+/// server over SSH instead: `{ command = "sudo systemctl reload nginx", remote = true }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum HookCommand {
+    Local(String),
+    Detailed {
+        command: String,
+        #[serde(default)]
+        remote: bool,
+    },
+}
+
+impl HookCommand {
+    pub fn command(&self) -> &str {
+        match self {
+            HookCommand::Local(command) | HookCommand::Detailed { command, .. } => command,
+        }
     }
 
-    /// Get the systemd service name
-    pub fn service_name(&self) -> String {
-        self.deploy
-            .service_name
-            .clone()
-            .unwrap_or_else(|| format!("{}.service", self.project.name))
+    pub fn is_remote(&self) -> bool {
+        matches!(self, HookCommand::Detailed { remote: true, .. })
     }
 }
 
-#[cfg(test)]
+/// Alerting configuration for continuous monitoring
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AlertConfig {
+    /// Number of consecutive failed checks before the app is reported down
+    #[serde(default = "default_consecutive_failures")]
+    pub consecutive_failures: u32,
+
+    /// Alert when the health endpoint responds slower than this (milliseconds)
+    pub response_time_ms: Option<u64>,
+
+    /// Alert when disk usage on the deploy filesystem exceeds this percentage
+    pub disk_usage_percent: Option<f64>,
+
+    /// Minimum seconds between repeated notifications for an ongoing condition
+    #[serde(default = "default_alert_cooldown")]
+    pub cooldown_secs: u64,
+
+    /// Send a message when an alerted condition clears
+    #[serde(default = "default_notify_recovery")]
+    pub notify_recovery: bool,
+
+    /// Where alerts are delivered
+    #[serde(default)]
+    pub channels: Vec<AlertChannel>,
+}
+
+/// Alert delivery channel
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AlertChannel {
+    /// POST a JSON payload to an arbitrary URL
+    Webhook { url: String },
+    /// Post a message to a Slack incoming webhook
+    Slack { webhook_url: String },
+    /// Send an email through the local sendmail binary
+    Email {
+        to: String,
+        from: Option<String>,
+        #[serde(default = "default_sendmail")]
+        sendmail: String,
+    },
+}
+
+// Default value functions
+fn default_version() -> u32 {
+    CONFIG_VERSION
+}
+
+fn default_project_path() -> String {
+    ".".to_string()
+}
+
+fn default_build_mode() -> String {
+    "release".to_string()
+}
+
+fn default_target() -> String {
+    "vps".to_string()
+}
+
+fn default_deploy_path() -> String {
+    "/opt/rzen-app".to_string()
+}
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+fn default_restart_strategy() -> String {
+    "stop-start".to_string()
+}
+
+fn default_shutdown_grace() -> u64 {
+    10
+}
+
+fn default_min_free_disk() -> u64 {
+    256
+}
+
+fn default_keep_backups() -> usize {
+    5
+}
+
+fn default_log_lines() -> usize {
+    10_000
+}
+
+fn default_github_token_env() -> String {
+    "GITHUB_TOKEN".to_string()
+}
+
+fn default_request_counter() -> String {
+    "http_requests_total".to_string()
+}
+
+fn default_check_timeout() -> u64 {
+    10
+}
+
+fn default_access_log_format() -> String {
+    "combined".to_string()
+}
+
+fn default_crash_loop_restarts() -> u32 {
+    3
+}
+
+fn default_crash_loop_window() -> u64 {
+    300
+}
+
+fn default_drift_check_interval() -> u64 {
+    300
+}
+
+fn default_latency_window() -> usize {
+    100
+}
+
+fn default_history_retention() -> u64 {
+    30
+}
+
+fn default_consecutive_failures() -> u32 {
+    3
+}
+
+fn default_alert_cooldown() -> u64 {
+    3600
+}
+
+fn default_notify_recovery() -> bool {
+    true
+}
+
+fn default_watchdog_cooldown() -> u64 {
+    300
+}
+
+fn default_sendmail() -> String {
+    "sendmail".to_string()
+}
+
+fn default_port_protocol() -> String {
+    "tcp".to_string()
+}
+
+fn default_port_via() -> String {
+    "ssh".to_string()
+}
+
+fn default_health_via() -> String {
+    "direct".to_string()
+}
+
+fn default_monitor_interval() -> u64 {
+    10
+}
+
+fn default_max_backoff() -> u64 {
+    300
+}
+
+fn default_health_timeout() -> u64 {
+    5
+}
+
+fn default_template_mode() -> String {
+    "0644".to_string()
+}
+
+fn default_connect_timeout() -> u64 {
+    10
+}
+
+fn default_ssh_retries() -> u32 {
+    3
+}
+
+fn default_auth_order() -> Vec<String> {
+    vec!["publickey".to_string(), "password".to_string()]
+}
+
+fn default_command_timeout() -> u64 {
+    300
+}
+
+fn default_keepalive_interval() -> u32 {
+    30
+}
+
+fn default_retry_attempts() -> u32 {
+    3
+}
+
+fn default_retry_base_delay() -> u64 {
+    1000
+}
+
+fn default_retry_max_delay() -> u64 {
+    30_000
+}
+
+fn default_retry_jitter() -> f64 {
+    0.2
+}
+
+fn default_retry_on() -> Vec<String> {
+    crate::utils::retry::ErrorClass::RETRYABLE
+        .iter()
+        .map(|class| class.name().to_string())
+        .collect()
+}
+
+fn default_transfer() -> String {
+    "scp".to_string()
+}
+
+fn default_max_parallel_hosts() -> usize {
+    8
+}
+
+fn default_host_key_checking() -> String {
+    "ask".to_string()
+}
+
+impl DeployConfig {
+    /// Whether deploys install on the machine rzen runs on instead of over SSH
+    pub fn is_local(&self) -> bool {
+        self.target == "local"
+    }
+}
+
+impl Default for DeployConfig {
+    fn default() -> Self {
+        Self {
+            target: default_target(),
+            vps_host: String::new(),
+            hosts: Vec::new(),
+            vps_user: String::new(),
+            vps_key_path: None,
+            vps_password: None,
+            password_source: None,
+            password_command: None,
+            keyring_shared: false,
+            runtime_password: None,
+            host_passwords: BTreeMap::new(),
+            key_passphrase: None,
+            prompted_passphrase: None,
+            sudo_password_source: None,
+            prompted_sudo_password: None,
+            deploy_path: default_deploy_path(),
+            service_name: None,
+            ssh_port: default_ssh_port(),
+            restart_strategy: default_restart_strategy(),
+            shutdown_grace_secs: default_shutdown_grace(),
+            min_free_disk_mb: default_min_free_disk(),
+            keep_backups: default_keep_backups(),
+            github_release: None,
+            ssh: SshOptions::default(),
+            templates: Vec::new(),
+        }
+    }
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            health_endpoint: None,
+            log_path: None,
+            interval_secs: default_monitor_interval(),
+            max_backoff_secs: default_max_backoff(),
+            health_timeout_secs: default_health_timeout(),
+            health_body_contains: None,
+            health_assertions: Vec::new(),
+            health_via: default_health_via(),
+            metrics_endpoint: None,
+            metrics_request_counter: default_request_counter(),
+            metrics_gauges: Vec::new(),
+            access_log_path: None,
+            access_log_format: default_access_log_format(),
+            crash_loop_restarts: default_crash_loop_restarts(),
+            crash_loop_window_secs: default_crash_loop_window(),
+            drift_check_interval_secs: default_drift_check_interval(),
+            latency_window: default_latency_window(),
+            history_path: None,
+            history_retention_days: default_history_retention(),
+            port_checks: Vec::new(),
+            checks: Vec::new(),
+            timezone: None,
+            show_original_timestamps: false,
+            heartbeat_url: None,
+            alerts: None,
+            watchdog: None,
+        }
+    }
+}
+
+impl Config {
+    /// JSON Schema of rzen.toml, generated from these structs
+    pub fn schema() -> schemars::schema::RootSchema {
+        use schemars::schema::{InstanceType, Schema, SchemaObject};
+
+        let mut schema = schemars::schema_for!(Config);
+        // schemars leaves out the colors flattened into [tui.theme]
+        if let Some(Schema::Object(theme)) = schema.definitions.get_mut("ThemeConfig") {
+            let color = SchemaObject {
+                instance_type: Some(InstanceType::String.into()),
+                ..Default::default()
+            };
+            theme.object().additional_properties = Some(Box::new(color.into()));
+        }
+        schema
+    }
+
+    /// Load configuration from a TOML file for the `[[projects]]` entry `project`, with the
+    /// `[env.<name>]` overrides of `environment` applied
+    pub fn from_file<P: AsRef<Path>>(
+        path: P,
+        environment: Option<&str>,
+        project: Option<&str>,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        Self::from_document(path, read_document(path)?, environment, project)
+    }
+
+    /// Load configuration from the parsed contents of the file at `path`, e.g. edited ones
+    pub fn from_document(
+        path: &Path,
+        document: toml_edit::DocumentMut,
+        environment: Option<&str>,
+        project: Option<&str>,
+    ) -> Result<Self> {
+        let mut migrated = Vec::new();
+        let mut table = resolve_table(path, document, &mut Vec::new(), &mut migrated)?;
+        match project {
+            Some(name) => apply_project(&mut table, name)?,
+            // Without a selection, the base [project] must be complete on its own
+            None => {
+                let names = project_names(&table);
+                let named = table
+                    .get("project")
+                    .and_then(|project| project.get("name"))
+                    .is_some();
+                if !names.is_empty() && !named {
+                    return Err(anyhow!(
+                        "Select a project with --project (defined: {})",
+                        names.join(", ")
+                    ));
+                }
+            }
+        }
+        if let Some(name) = environment {
+            apply_environment(&mut table, name)?;
+        }
+        // Variables that aren't valid UTF-8 can't name a setting
+        let vars = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+        let variables = apply_variables(&mut table, vars)?;
+        let encrypted = decrypt_values(&mut table, crate::utils::secrets::identity_path)?;
+        let parse = |table: toml::Table| -> Result<Config> {
+            toml::Value::Table(table)
+                .try_into()
+                .map_err(|e| anyhow!("Invalid settings in {}: {}", path.display(), e))
+        };
+        let mut config = parse(table.clone())?;
+        // References to settings the file leaves out resolve to their defaults
+        let defaults = toml::Table::try_from(&config).context("Failed to serialize config")?;
+        let interpolated = interpolate_values(&mut table, &defaults)?;
+        if !interpolated.is_empty() {
+            config = parse(table)?;
+        }
+        // A local target has no server to name
+        if config.deploy.is_local() && config.deploy.vps_host.is_empty() {
+            config.deploy.vps_host = "localhost".to_string();
+        }
+        config.overrides = Overrides {
+            project: project.map(str::to_string),
+            environment: environment.map(str::to_string),
+            variables,
+            encrypted,
+            interpolated,
+            migrated,
+        };
+
+        let selection = match (project, environment) {
+            (Some(project), Some(env)) => {
+                Some(format!("project '{}', environment '{}'", project, env))
+            }
+            (Some(project), None) => Some(format!("project '{}'", project)),
+            (None, Some(env)) => Some(format!("environment '{}'", env)),
+            (None, None) => None,
+        };
+        match selection {
+            Some(selection) => config
+                .validate()
+                .map_err(|e| anyhow!("Invalid configuration for {}: {}", selection, e))?,
+            None => config.validate()?,
+        }
+        Ok(config)
+    }
+
+    /// Names of the `[[projects]]` in a config file, including those of the files it `extends`
+    pub fn projects_in(path: &Path) -> Result<Vec<String>> {
+        let table = resolve_table(path, read_document(path)?, &mut Vec::new(), &mut Vec::new())?;
+        Ok(project_names(&table)
+            .into_iter()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Load configuration from the default location (rzen.toml in current directory)
+    pub fn from_default_location(environment: Option<&str>, project: Option<&str>) -> Result<Self> {
+        Self::from_file(Self::default_location()?, environment, project)
+    }
+
+    /// Names of the `[env.<name>]` sections
+    pub fn environments(&self) -> Vec<&str> {
+        self.env.keys().map(String::as_str).collect()
+    }
+
+    /// Value of the setting `key`, e.g. `deploy.ssh_port`, with defaults filled in; `None` when unset
+    pub fn setting(&self, key: &str) -> Result<Option<toml::Value>> {
+        setting_schema(key)?;
+        let mut table =
+            toml::Table::try_from(self).context("Failed to serialize config to TOML")?;
+        let path: Vec<String> = key.split('.').map(str::to_string).collect();
+        match lookup_mut(&mut table, &path) {
+            Some(value) => Ok(Some(value.clone())),
+            // Sections left at their defaults, like [deploy.ssh], aren't serialized
+            None => setting_default(key),
+        }
+    }
+
+    /// First existing configuration file among the default locations
+    pub fn default_location() -> Result<PathBuf> {
+        let paths = [
+            "rzen.toml",
+            ".rzen.toml",
+            &format!(
+                "{}/.rzen.toml",
+                dirs::home_dir()
+                    .ok_or_else(|| anyhow!("Could not determine home directory"))?
+                    .display()
+            ),
+        ];
+
+        for path in &paths {
+            if Path::new(path).exists() {
+                return Ok(PathBuf::from(path));
+            }
+        }
+
+        Err(anyhow!(
+            "No configuration file found. Create rzen.toml in the current directory or provide --config path"
+        ))
+    }
+
+    /// Validate and write the configuration to a TOML file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let contents = self.to_toml()?;
+        fs::write(path.as_ref(), contents)
+            .with_context(|| format!("Failed to write config file: {}", path.as_ref().display()))
+    }
+
+    /// Validate and serialize the configuration as it would be saved
+    pub fn to_toml(&self) -> Result<String> {
+        // Saving would write the project's or environment's values over the base sections
+        if let Some(name) = &self.overrides.project {
+            return Err(anyhow!(
+                "Settings are resolved for project '{}'; edit the file to change them",
+                name
+            ));
+        }
+        if let Some(name) = &self.overrides.environment {
+            return Err(anyhow!(
+                "Settings are resolved for environment '{}'; edit the file to change them",
+                name
+            ));
+        }
+        if !self.overrides.variables.is_empty() {
+            return Err(anyhow!(
+                "Settings include overrides from RZEN_* variables ({}); unset them to save",
+                self.overrides.variables.join(", ")
+            ));
+        }
+        // ...or copy every inherited setting into this file
+        if let Some(extends) = &self.extends {
+            return Err(anyhow!(
+                "Settings include those inherited from {}; edit the files to change them",
+                extends
+            ));
+        }
+        self.validate()?;
+        if self.overrides.encrypted.is_empty() && self.overrides.interpolated.is_empty() {
+            return toml::to_string_pretty(self).context("Failed to serialize config to TOML");
+        }
+
+        let mut table =
+            toml::Table::try_from(self).context("Failed to serialize config to TOML")?;
+        // Unchanged interpolated strings go back as the references they were built from
+        for value in &self.overrides.interpolated {
+            if let Some(toml::Value::String(current)) = lookup_mut(&mut table, &value.path)
+                && *current == value.value
+            {
+                *current = value.template.clone();
+            }
+        }
+        // Decrypted values go back to the file as the ciphertext they were read from
+        for value in &self.overrides.encrypted {
+            match lookup_mut(&mut table, &value.path) {
+                Some(toml::Value::String(current)) if *current == value.plaintext => {
+                    *current = value.ciphertext.clone();
+                }
+                None => {}
+                Some(_) => {
+                    return Err(anyhow!(
+                        "{} is encrypted in the file; encrypt the new value with `rzen config encrypt`",
+                        value.path.join(".")
+                    ));
+                }
+            }
+        }
+        toml::to_string_pretty(&table).context("Failed to serialize config to TOML")
+    }
+
+    /// Create a default configuration file
+    pub fn create_default<P: AsRef<Path>>(path: P) -> Result<()> {
+        let default_config = Config {
+            version: CONFIG_VERSION,
+            project: ProjectConfig {
+                path: ".".to_string(),
+                name: "my-rust-app".to_string(),
+                build_mode: "release".to_string(),
+            },
+            deploy: DeployConfig {
+                target: "vps".to_string(),
+                vps_host: EXAMPLE_HOST.to_string(),
+                vps_user: "deploy".to_string(),
+                vps_key_path: Some("~/.ssh/id_rsa".to_string()),
+                vps_password: None,
+                deploy_path: "/opt/rzen-app".to_string(),
+                service_name: Some("my-rust-app.service".to_string()),
+                ssh_port: 22,
+                ..Default::default()
+            },
+            monitor: MonitorConfig {
+                health_endpoint: Some(format!("http://{}:8080/health", EXAMPLE_HOST)),
+                log_path: Some("/var/log/my-rust-app.log".to_string()),
+                interval_secs: 10,
+                health_timeout_secs: 5,
+                ..Default::default()
+            },
+            hooks: Default::default(),
+            tui: Default::default(),
+            retry: Default::default(),
+            env: Default::default(),
+            projects: Default::default(),
+            overrides: Default::default(),
+            extends: None,
+        };
+
+        let toml_string = toml::to_string_pretty(&default_config)
+            .context("Failed to serialize default config to TOML")?;
+
+        fs::write(path.as_ref(), toml_string).with_context(|| {
+            format!(
+                "Failed to write default config to: {}",
+                path.as_ref().display()
+            )
+        })?;
+
+        Ok(())
+    }
+
+    /// Checks for `target = "vps"`: the server, user and a way to authenticate
+    fn validate_ssh_target(&self) -> Result<()> {
+        if self.deploy.vps_host.trim().is_empty() {
+            return Err(anyhow!("VPS host cannot be empty"));
+        }
+
+        if self.deploy.hosts.iter().any(|host| host.trim().is_empty()) {
+            return Err(anyhow!("Deploy hosts cannot contain empty entries"));
+        }
+
+        // An alias in ~/.ssh/config may supply the user and key
+        let openssh = crate::utils::openssh::lookup(&self.deploy.vps_host);
+        if self.deploy.vps_user.trim().is_empty() && openssh.user.is_none() {
+            return Err(anyhow!("VPS user cannot be empty"));
+        }
+
+        let keyring = match self.deploy.password_source.as_deref() {
+            None | Some("config") => false,
+            Some("keyring") => true,
+            Some(other) => {
+                return Err(anyhow!(
+                    "Password source must be 'config' or 'keyring', got: {}",
+                    other
+                ));
+            }
+        };
+        if keyring && self.deploy.vps_password.is_some() {
+            return Err(anyhow!(
+                "vps_password must not be set with password_source = \"keyring\""
+            ));
+        }
+        if self.deploy.keyring_shared && !keyring {
+            return Err(anyhow!(
+                "keyring_shared needs password_source = \"keyring\""
+            ));
+        }
+
+        let command = self.deploy.password_command.is_some();
+        if let Some(password_command) = &self.deploy.password_command {
+            if password_command.trim().is_empty() {
+                return Err(anyhow!("password_command cannot be empty"));
+            }
+            if keyring || self.deploy.vps_password.is_some() {
+                return Err(anyhow!(
+                    "password_command replaces vps_password and password_source = \"keyring\"; set only one"
+                ));
+            }
+        }
+
+        match self.deploy.sudo_password_source.as_deref() {
+            None | Some("prompt") => {}
+            Some("ssh") => {
+                if self.deploy.vps_password.is_none() && !keyring && !command {
+                    return Err(anyhow!(
+                        "sudo_password_source = \"ssh\" needs vps_password, password_command or password_source = \"keyring\""
+                    ));
+                }
+            }
+            Some(other) => {
+                return Err(anyhow!(
+                    "Sudo password source must be 'prompt' or 'ssh', got: {}",
+                    other
+                ));
+            }
+        }
+
+        let ssh = &self.deploy.ssh;
+        let agent = ssh.auth_order.iter().any(|method| method == "agent");
+        if self.deploy.vps_key_path.is_none()
+            && openssh.identity_file.is_none()
+            && self.deploy.vps_password.is_none()
+            && !keyring
+            && !command
+            && !agent
+        {
+            return Err(anyhow!("Either SSH key path or password must be provided"));
+        }
+
+        if ssh.auth_order.is_empty() {
+            return Err(anyhow!("SSH auth_order cannot be empty"));
+        }
+        if let Some(method) = ssh
+            .auth_order
+            .iter()
+            .find(|method| !SshOptions::AUTH_METHODS.contains(&method.as_str()))
+        {
+            return Err(anyhow!(
+                "SSH auth method must be one of {}, got: {}",
+                SshOptions::AUTH_METHODS.join(", "),
+                method
+            ));
+        }
+        if ssh.connect_timeout_secs == 0 {
+            return Err(anyhow!("SSH connect timeout must be greater than 0"));
+        }
+        if ssh.max_retries == 0 {
+            return Err(anyhow!("SSH max_retries must be at least 1"));
+        }
+        if ssh.max_parallel_hosts == 0 {
+            return Err(anyhow!("SSH max_parallel_hosts must be at least 1"));
+        }
+        if !SshOptions::HOST_KEY_CHECKING.contains(&ssh.host_key_checking.as_str()) {
+            return Err(anyhow!(
+                "SSH host_key_checking must be one of {}, got: {}",
+                SshOptions::HOST_KEY_CHECKING.join(", "),
+                ssh.host_key_checking
+            ));
+        }
+        if !SshOptions::TRANSFERS.contains(&ssh.transfer.as_str()) {
+            return Err(anyhow!(
+                "SSH transfer must be one of {}, got: {}",
+                SshOptions::TRANSFERS.join(", "),
+                ssh.transfer
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<()> {
+        // Validate project config
+        if self.project.name.trim().is_empty() {
+            return Err(anyhow!("Project name cannot be empty"));
+        }
+
+        if !matches!(self.project.build_mode.as_str(), "debug" | "release") {
+            return Err(anyhow!(
+                "Build mode must be 'debug' or 'release', got: {}",
+                self.project.build_mode
+            ));
+        }
+
+        // Validate deploy config
+        if !matches!(self.deploy.target.as_str(), "vps" | "local") {
+            return Err(anyhow!(
+                "Deploy target must be 'vps' or 'local', got: {}",
+                self.deploy.target
+            ));
+        }
+
+        if self.deploy.is_local() {
+            if !self.deploy.hosts.is_empty() {
+                return Err(anyhow!("hosts cannot be set with target = \"local\""));
+            }
+        } else {
+            self.validate_ssh_target()?;
+        }
+
+        if let Some(ref key_path) = self.deploy.vps_key_path
+            && key_path.trim().is_empty()
+        {
+            return Err(anyhow!("SSH key path cannot be empty"));
+        }
+
+        if self.deploy.key_passphrase.is_some() && self.deploy.vps_key_path.is_none() {
+            return Err(anyhow!("key_passphrase needs vps_key_path"));
+        }
+
+        for template in &self.deploy.templates {
+            if template.source.trim().is_empty() || template.destination.trim().is_empty() {
+                return Err(anyhow!("Templates need a source and a destination"));
+            }
+            template.mode_bits()?;
+        }
+
+        if !matches!(
+            self.deploy.restart_strategy.as_str(),
+            "restart" | "reload" | "stop-start"
+        ) {
+            return Err(anyhow!(
+                "Restart strategy must be 'restart', 'reload', or 'stop-start', got: {}",
+                self.deploy.restart_strategy
+            ));
+        }
+
+        // Validate monitor config
+        if let Some(ref endpoint) = self.monitor.health_endpoint {
+            if endpoint.trim().is_empty() {
+                return Err(anyhow!("Health endpoint URL cannot be empty"));
+            }
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                return Err(anyhow!("Health endpoint must be a valid HTTP/HTTPS URL"));
+            }
+        }
+        if !matches!(self.monitor.health_via.as_str(), "direct" | "ssh") {
+            return Err(anyhow!(
+                "Invalid health_via: {}. Must be 'direct' or 'ssh'",
+                self.monitor.health_via
+            ));
+        }
+
+        if let Some(ref endpoint) = self.monitor.metrics_endpoint
+            && !endpoint.starts_with("http://")
+            && !endpoint.starts_with("https://")
+        {
+            return Err(anyhow!("Metrics endpoint must be a valid HTTP/HTTPS URL"));
+        }
+
+        if let Some(ref timezone) = self.monitor.timezone {
+            DisplayTimezone::parse(timezone)?;
+        }
+
+        if let Some(ref url) = self.monitor.heartbeat_url
+            && !url.starts_with("http://")
+            && !url.starts_with("https://")
+        {
+            return Err(anyhow!("Heartbeat URL must be a valid HTTP/HTTPS URL"));
+        }
+
+        if self.monitor.interval_secs == 0 {
+            return Err(anyhow!("Monitor interval must be greater than 0 seconds"));
+        }
+
+        if self.monitor.health_timeout_secs == 0 {
+            return Err(anyhow!("Health timeout must be greater than 0 seconds"));
+        }
+
+        if self.monitor.crash_loop_restarts == 0 || self.monitor.crash_loop_window_secs == 0 {
+            return Err(anyhow!(
+                "Crash-loop restarts and window must be greater than 0"
+            ));
+        }
+
+        if !matches!(self.monitor.access_log_format.as_str(), "combined" | "json") {
+            return Err(anyhow!(
+                "Invalid access log format: {}. Must be 'combined' or 'json'",
+                self.monitor.access_log_format
+            ));
+        }
+
+        for assertion in &self.monitor.health_assertions {
+            crate::commands::monitor::assertions::JsonAssertion::parse(assertion)?;
+        }
+
+        for check in &self.monitor.checks {
+            if check.command.trim().is_empty() {
+                return Err(anyhow!("Command checks must have a command"));
+            }
+            if check.timeout_secs == 0 {
+                return Err(anyhow!(
+                    "Timeout for check '{}' must be greater than 0",
+                    check.label()
+                ));
+            }
+        }
+
+        for check in &self.monitor.port_checks {
+            if check.port() == 0 {
+                return Err(anyhow!("Port checks must use a port between 1 and 65535"));
+            }
+            if !matches!(check.protocol(), "tcp" | "udp") {
+                return Err(anyhow!(
+                    "Invalid protocol for port check {}: {}. Must be 'tcp' or 'udp'",
+                    check.port(),
+                    check.protocol()
+                ));
+            }
+            match check.via() {
+                "ssh" => {}
+                "direct" if check.protocol() == "tcp" => {}
+                "direct" => {
+                    return Err(anyhow!(
+                        "UDP port {} can only be checked via ssh",
+                        check.port()
+                    ));
+                }
+                via => {
+                    return Err(anyhow!(
+                        "Invalid via for port check {}: {}. Must be 'ssh' or 'direct'",
+                        check.port(),
+                        via
+                    ));
+                }
+            }
+        }
+
+        let retry = &self.retry;
+        if retry.max_attempts == 0 {
+            return Err(anyhow!("Retry max_attempts must be at least 1"));
+        }
+        if !(0.0..=1.0).contains(&retry.jitter) {
+            return Err(anyhow!(
+                "Retry jitter must be between 0.0 and 1.0, got: {}",
+                retry.jitter
+            ));
+        }
+        for class in &retry.retry_on {
+            crate::utils::retry::ErrorClass::parse(class)?;
+        }
+
+        let hooks = &self.hooks;
+        let hook_lists = [
+            &hooks.pre_build,
+            &hooks.post_build,
+            &hooks.pre_deploy,
+            &hooks.post_deploy,
+            &hooks.on_rollback,
+            &hooks.on_failure,
+        ];
+        if hook_lists
+            .iter()
+            .any(|hooks| hooks.iter().any(|hook| hook.command().trim().is_empty()))
+        {
+            return Err(anyhow!("Hook commands cannot be empty"));
+        }
+
+        if let Some(ref watchdog) = self.monitor.watchdog
+            && watchdog.failures_before_restart == 0
+        {
+            return Err(anyhow!(
+                "Watchdog failures_before_restart must be greater than 0"
+            ));
+        }
+
+        if let Some(ref alerts) = self.monitor.alerts {
+            if alerts.consecutive_failures == 0 {
+                return Err(anyhow!("Alert consecutive_failures must be greater than 0"));
+            }
+
+            for channel in &alerts.channels {
+                let url = match channel {
+                    AlertChannel::Webhook { url } => url,
+                    AlertChannel::Slack { webhook_url } => webhook_url,
+                    AlertChannel::Email { to, .. } => {
+                        if !to.contains('@') {
+                            return Err(anyhow!("Alert email address is invalid: {}", to));
+                        }
+                        continue;
+                    }
+                };
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(anyhow!("Alert channel URL must be HTTP/HTTPS: {}", url));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Settings that load but are probably a mistake, e.g. a plaintext password
+    ///
+    /// Unlike [`Config::validate`] errors, these don't stop any command.
+    pub fn warnings(&self) -> Vec<String> {
+        let deploy = &self.deploy;
+        let mut warnings = Vec::new();
+
+        // Values from the file itself, not decrypted or taken from RZEN_* variables
+        let plaintext = |key: &str, value: &Option<String>| {
+            let encrypted = self
+                .overrides
+                .encrypted
+                .iter()
+                .any(|value| value.path == ["deploy", key]);
+            let from_variable = self
+                .overrides
+                .variables
+                .iter()
+                .any(|name| *name == format!("deploy.{}", key));
+            value.is_some() && !encrypted && !from_variable
+        };
+        if plaintext("vps_password", &deploy.vps_password) {
+            warnings.push(
+                "vps_password is stored in plain text; encrypt it with `rzen config encrypt`, \
+                 or use password_source = \"keyring\" or password_command"
+                    .to_string(),
+            );
+        }
+        if plaintext("key_passphrase", &deploy.key_passphrase) {
+            warnings.push(
+                "key_passphrase is stored in plain text; encrypt it with `rzen config encrypt`, \
+                 or leave it out to be asked for it"
+                    .to_string(),
+            );
+        }
+
+        if deploy.vps_host == EXAMPLE_HOST || deploy.hosts.iter().any(|host| host == EXAMPLE_HOST) {
+            warnings.push(format!(
+                "vps_host is still the example host {}",
+                EXAMPLE_HOST
+            ));
+        }
+
+        if let Some(environment) = &self.overrides.environment
+            && matches!(environment.as_str(), "production" | "prod")
+            && self.project.build_mode == "debug"
+        {
+            warnings.push(format!(
+                "Environment '{}' deploys a debug build; set build_mode = \"release\"",
+                environment
+            ));
+        }
+
+        let password_auth = deploy.vps_key_path.is_none()
+            && (deploy.vps_password.is_some()
+                || deploy.password_source.as_deref() == Some("keyring")
+                || deploy.password_command.is_some())
+            && deploy
+                .ssh
+                .auth_order
+                .iter()
+                .any(|method| method == "password");
+        if !deploy.is_local() && deploy.ssh_port == 22 && password_auth {
+            warnings.push(
+                "SSH logs in with a password on port 22, where most brute-force attempts land; \
+                 use vps_key_path or another ssh_port"
+                    .to_string(),
+            );
+        }
+
+        if !deploy.is_local() && deploy.ssh.host_key_checking == "off" {
+            warnings.push(
+                "host_key_checking = \"off\" accepts any server key, so a man-in-the-middle goes unnoticed"
+                    .to_string(),
+            );
+        }
+
+        warnings
+    }
+
+    /// Get the absolute project path
+    pub fn project_path(&self) -> Result<PathBuf> {
+        let config_dir = Path::new(".")
+            .canonicalize()
+            .context("Failed to get current directory")?;
+        let project_path = Path::new(&self.project.path);
+
+        if project_path.is_absolute() {
+            Ok(project_path.to_path_buf())
+        } else {
+            Ok(config_dir.join(project_path))
+        }
+    }
+
+    /// Get the binary name based on project configuration
+    pub fn binary_name(&self) -> String {
+        self.project.name.clone()
+    }
+
+    /// Get the systemd service name
+    pub fn service_name(&self) -> String {
+        self.deploy
+            .service_name
+            .clone()
+            .unwrap_or_else(|| format!("{}.service", self.project.name))
+    }
+
+    /// Timezone for displayed timestamps (UTC unless configured)
+    pub fn display_timezone(&self) -> DisplayTimezone {
+        self.monitor
+            .timezone
+            .as_deref()
+            .and_then(|timezone| DisplayTimezone::parse(timezone).ok())
+            .unwrap_or(DisplayTimezone::Utc)
+    }
+
+    /// SSH password or key passphrase: `vps_password`, or the keyring secret once loaded
+    pub fn ssh_password(&self) -> Option<String> {
+        self.deploy
+            .vps_password
+            .clone()
+            .or_else(|| self.deploy.runtime_password.clone())
+    }
+
+    /// Policy for retrying health checks and uploads, from `[retry]`
+    pub fn retry_policy(&self) -> crate::utils::retry::RetryPolicy {
+        crate::utils::retry::RetryPolicy::from_config(&self.retry)
+    }
+
+    /// Connection settings for `deploy.vps_host`
+    ///
+    /// `vps_host` may be an alias from `~/.ssh/config`, whose `HostName`, `Port`, `User`,
+    /// `IdentityFile`, `ProxyJump` and `ProxyCommand` fill in what rzen.toml leaves unset.
+    pub fn ssh_config(&self) -> crate::utils::ssh::SshConfig {
+        let openssh = crate::utils::openssh::lookup(&self.deploy.vps_host);
+        let host = openssh
+            .hostname
+            .clone()
+            .unwrap_or_else(|| self.deploy.vps_host.clone());
+        // 22 is also the default, so only a different ssh_port overrides the alias
+        let port = match openssh.port {
+            Some(port) if self.deploy.ssh_port == 22 => port,
+            _ => self.deploy.ssh_port,
+        };
+        let username = match &openssh.user {
+            Some(user) if self.deploy.vps_user.is_empty() => user.clone(),
+            _ => self.deploy.vps_user.clone(),
+        };
+        let proxy_command = openssh.proxy(crate::utils::net::bare_host(&host), port, &username);
+        crate::utils::ssh::SshConfig {
+            // known_hosts and the connection take an IPv6 address without brackets
+            host: crate::utils::net::bare_host(&host).to_string(),
+            port,
+            username,
+            key_path: self.deploy.vps_key_path.clone().or(openssh.identity_file),
+            password: self.ssh_password(),
+            passphrase: self.key_passphrase(),
+            proxy_command,
+            sudo_password: self.sudo_password(),
+            options: self.deploy.ssh.clone(),
+            retry: self.retry_policy(),
+        }
+    }
+
+    /// Read the SSH password from `password_command`, or from the OS keyring when
+    /// `password_source = "keyring"`, and ask for the passphrase of an encrypted key
+    ///
+    /// Keyring secrets are read for every host, each from its own `vps_user@<host>` entry.
+    pub fn load_password(&mut self) -> Result<()> {
+        if let Some(command) = &self.deploy.password_command {
+            let secret = crate::utils::secrets::from_command(command)?;
+            self.deploy.runtime_password = Some(secret);
+        } else if self.deploy.password_source.as_deref() == Some("keyring") {
+            let primary = self.deploy.vps_host.clone();
+            let mut hosts = self.hosts();
+            if self.deploy.keyring_shared && !hosts.contains(&primary) {
+                hosts.push(primary.clone());
+            }
+            for host in hosts {
+                match crate::utils::secrets::get(&self.deploy.vps_user, &host) {
+                    Ok(secret) => {
+                        self.deploy.host_passwords.insert(host, secret);
+                    }
+                    Err(_) if self.deploy.keyring_shared && host != primary => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            self.deploy.runtime_password = self.deploy.host_passwords.get(&primary).cloned();
+        }
+
+        // An encrypted key without a passphrase is unlocked at the terminal, once per command
+        if let Some(key_path) = &self.deploy.vps_key_path
+            && !self.deploy.is_local()
+            && self.key_passphrase().is_none()
+            && crate::utils::prompt::can_ask()
+            && crate::utils::ssh::key_is_encrypted(Path::new(shellexpand::tilde(key_path).as_ref()))
+        {
+            let prompt = format!("Passphrase for {}: ", key_path);
+            let passphrase =
+                rpassword::prompt_password(prompt).context("Failed to read the key passphrase")?;
+            self.deploy.prompted_passphrase = Some(passphrase);
+        }
+
+        // Without a terminal sudo fails with its own "a password is required"
+        if self.deploy.sudo_password_source.as_deref() == Some("prompt")
+            && !self.deploy.is_local()
+            && self.deploy.prompted_sudo_password.is_none()
+            && crate::utils::prompt::can_ask()
+        {
+            let prompt = format!(
+                "Sudo password for {}@{}: ",
+                self.deploy.vps_user, self.deploy.vps_host
+            );
+            let password =
+                rpassword::prompt_password(prompt).context("Failed to read the sudo password")?;
+            self.deploy.prompted_sudo_password = Some(password);
+        }
+        Ok(())
+    }
+
+    /// Password for `sudo` on the server, from `sudo_password_source`
+    pub fn sudo_password(&self) -> Option<String> {
+        match self.deploy.sudo_password_source.as_deref() {
+            Some("ssh") => self.ssh_password(),
+            Some("prompt") => self.deploy.prompted_sudo_password.clone(),
+            _ => None,
+        }
+    }
+
+    /// Passphrase for `vps_key_path`: `key_passphrase`, the one entered at the prompt, or
+    /// the SSH password
+    pub fn key_passphrase(&self) -> Option<String> {
+        self.deploy
+            .key_passphrase
+            .clone()
+            .or_else(|| self.deploy.prompted_passphrase.clone())
+            .or_else(|| self.ssh_password())
+    }
+
+    /// All hosts the application runs on
+    pub fn hosts(&self) -> Vec<String> {
+        if self.deploy.hosts.is_empty() {
+            vec![self.deploy.vps_host.clone()]
+        } else {
+            self.deploy.hosts.clone()
+        }
+    }
+
+    /// Configuration targeting a single host
+    ///
+    /// Monitoring endpoints that point at `vps_host` are rewritten to the same
+    /// URL on `host`, so one config covers every machine behind the app.
+    pub fn for_host(&self, host: &str) -> Config {
+        let mut config = self.clone();
+        let primary = &self.deploy.vps_host;
+        let retarget = |endpoint: &mut Option<String>| {
+            if let Some(url) = endpoint {
+                *url = replace_url_host(url, primary, host);
+            }
+        };
+
+        retarget(&mut config.monitor.health_endpoint);
+        retarget(&mut config.monitor.metrics_endpoint);
+        config.deploy.vps_host = host.to_string();
+        // Each host has its own keyring entry; another host's secret is only sent when shared
+        if self.deploy.password_source.as_deref() == Some("keyring") {
+            config.deploy.runtime_password = match self.deploy.host_passwords.get(host) {
+                Some(secret) => Some(secret.clone()),
+                None if self.deploy.keyring_shared => self.deploy.runtime_password.clone(),
+                None => None,
+            };
+        }
+        config
+    }
+}
+
+/// Settings of the config file at `path` with the files it `extends` merged underneath, base files first
+///
+/// `chain` holds the files being read, to report include cycles; files upgraded from an older
+/// layout are added to `migrated`.
+fn resolve_table(
+    path: &Path,
+    mut document: toml_edit::DocumentMut,
+    chain: &mut Vec<PathBuf>,
+    migrated: &mut Vec<(PathBuf, u32)>,
+) -> Result<toml::Table> {
+    let version = migrate(&mut document).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    if version < CONFIG_VERSION {
+        migrated.push((path.to_path_buf(), version));
+    }
+    let table: toml::Table = toml::from_str(&document.to_string())
+        .with_context(|| format!("Failed to parse TOML config file: {}", path.display()))?;
+    let Some(extends) = table.get("extends").cloned() else {
+        return Ok(table);
+    };
+    let extends = extends
+        .as_str()
+        .ok_or_else(|| anyhow!("`extends` in {} must be a file path", path.display()))?;
+
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if let Some(start) = chain.iter().position(|seen| *seen == canonical) {
+        let cycle: Vec<String> = chain[start..]
+            .iter()
+            .chain([&canonical])
+            .map(|path| path.display().to_string())
+            .collect();
+        return Err(anyhow!("Config include cycle: {}", cycle.join(" -> ")));
+    }
+
+    let base_path = path.parent().unwrap_or(Path::new(".")).join(extends);
+    chain.push(canonical);
+    let mut base = resolve_table(&base_path, read_document(&base_path)?, chain, migrated)?;
+    chain.pop();
+    // `extends` of the base is resolved already; only this file's is kept
+    base.remove("extends");
+    merge_tables(&mut base, table);
+    Ok(base)
+}
+
+/// Parse a config file keeping its comments and layout
+pub fn read_document(path: &Path) -> Result<toml_edit::DocumentMut> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    contents
+        .parse()
+        .with_context(|| format!("Failed to parse TOML config file: {}", path.display()))
+}
+
+/// Set `key`, e.g. `deploy.ssh_port`, to the value written as `text` in a parsed config file
+///
+/// String settings take `text` as is; others read it as a TOML value, like `RZEN_*` variables.
+/// With `environment`, the setting goes into its `[env.<name>]` section, and with `project` into
+/// that `[[projects]]` entry. Comments are kept.
+pub fn set_value(
+    document: &mut toml_edit::DocumentMut,
+    environment: Option<&str>,
+    project: Option<&str>,
+    key: &str,
+    text: &str,
+) -> Result<()> {
+    let schema = setting_schema(key)?;
+    let is_string = match schema.get("type") {
+        Some(serde_json::Value::String(kind)) => kind == "string",
+        Some(serde_json::Value::Array(kinds)) => {
+            kinds.iter().all(|kind| kind == "string" || kind == "null")
+        }
+        _ => false,
+    };
+    let mut value = match text.parse::<toml_edit::Value>() {
+        Ok(value) if !is_string => value,
+        _ => text.into(),
+    };
+
+    let parts: Vec<&str> = key.split('.').collect();
+    let (name, mut sections) = parts.split_last().unwrap_or((&key, &[]));
+    let mut table: &mut dyn toml_edit::TableLike = document.as_table_mut();
+    if let Some(project) = project {
+        if environment.is_some() {
+            return Err(anyhow!(
+                "Set a value for either a project or an environment, not both"
+            ));
+        }
+        // An entry holds the [project] keys itself, and overrides only some sections
+        match sections.split_first() {
+            Some((&"project", rest)) => sections = rest,
+            Some((section, _)) if !PROJECT_SECTIONS.contains(section) => {
+                return Err(anyhow!(
+                    "[[projects]] entries cannot override [{}]",
+                    section
+                ));
+            }
+            _ => {}
+        }
+        table = table
+            .get_mut("projects")
+            .and_then(toml_edit::Item::as_array_of_tables_mut)
+            .and_then(|entries| {
+                entries.iter_mut().find(|entry| {
+                    entry.get("name").and_then(toml_edit::Item::as_str) == Some(project)
+                })
+            })
+            .ok_or_else(|| anyhow!("Unknown project '{}'", project))?;
+    }
+    let environment = environment.map(|env| ["env", env]);
+    let sections = environment.iter().flatten().chain(sections);
+    for &section in sections {
+        table = table
+            .entry(section)
+            .or_insert_with(|| {
+                let mut table = toml_edit::Table::new();
+                table.set_implicit(true);
+                toml_edit::Item::Table(table)
+            })
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("Cannot set {}: {} is not a table", key, section))?;
+    }
+
+    if let Some(current) = table.get(name).and_then(toml_edit::Item::as_value) {
+        let encrypted = |value: &toml_edit::Value| {
+            value
+                .as_str()
+                .is_some_and(|value| value.starts_with(crate::utils::secrets::ENCRYPTED_PREFIX))
+        };
+        if encrypted(current) && !encrypted(&value) {
+            return Err(anyhow!(
+                "{} is encrypted in the file; set it to the output of `rzen config encrypt`",
+                key
+            ));
+        }
+        // Keep the comment after the old value
+        *value.decor_mut() = current.decor().clone();
+    }
+    table.insert(name, toml_edit::Item::Value(value));
+    Ok(())
+}
+
+/// JSON Schema of the setting `key`, e.g. `deploy.ssh_port`; errors when there is no such setting
+fn setting_schema(key: &str) -> Result<serde_json::Value> {
+    let root = serde_json::to_value(Config::schema()).context("Failed to build config schema")?;
+    let mut schema = &root;
+    for part in key.split('.') {
+        let object = resolve_schema(&root, schema);
+        schema = match object
+            .get("properties")
+            .and_then(|properties| properties.get(part))
+        {
+            Some(property) => property,
+            None => match object.get("additionalProperties") {
+                Some(serde_json::Value::Bool(true)) => return Ok(serde_json::json!({})),
+                Some(property @ serde_json::Value::Object(_)) => property,
+                _ => return Err(anyhow!("Unknown setting '{}'", key)),
+            },
+        };
+    }
+    Ok(resolve_schema(&root, schema).clone())
+}
+
+/// Default of the setting `key` according to the schema; `None` when it has none
+///
+/// A field's own default wins over the one its section has as a whole.
+fn setting_default(key: &str) -> Result<Option<toml::Value>> {
+    let root = serde_json::to_value(Config::schema()).context("Failed to build config schema")?;
+    let mut schema = &root;
+    let mut default: Option<&serde_json::Value> = None;
+    for part in key.split('.') {
+        let object = resolve_schema(&root, schema);
+        default = default.and_then(|default| default.get(part));
+        schema = match object
+            .get("properties")
+            .and_then(|properties| properties.get(part))
+        {
+            Some(property) => property,
+            None => match object.get("additionalProperties") {
+                Some(property @ serde_json::Value::Object(_)) => property,
+                _ => break,
+            },
+        };
+        if let Some(own) = schema.get("default") {
+            default = Some(own);
+        }
+    }
+    default
+        .filter(|default| !default.is_null())
+        .map(|default| {
+            toml::Value::try_from(default)
+                .map_err(|e| anyhow!("Failed to convert the default of {}: {}", key, e))
+        })
+        .transpose()
+}
+
+/// `schema` with its `$ref` followed and optional (`null`) alternatives dropped
+fn resolve_schema<'a>(
+    root: &'a serde_json::Value,
+    schema: &'a serde_json::Value,
+) -> &'a serde_json::Value {
+    let reference = schema
+        .get("$ref")
+        .and_then(serde_json::Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/definitions/"));
+    if let Some(definition) = reference.and_then(|name| root["definitions"].get(name)) {
+        return resolve_schema(root, definition);
+    }
+    for keyword in ["allOf", "anyOf"] {
+        let alternatives: Vec<&serde_json::Value> = schema
+            .get(keyword)
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|alternative| alternative["type"] != "null")
+            .collect();
+        if let [alternative] = alternatives[..] {
+            return resolve_schema(root, alternative);
+        }
+    }
+    schema
+}
+
+/// Upgrade a parsed config file, `[env.*]` sections included, to `CONFIG_VERSION`
+///
+/// Returns the version the file had; `version` is set to the current one.
+pub fn migrate(document: &mut toml_edit::DocumentMut) -> Result<u32> {
+    upgrade(document, &MIGRATIONS)
+}
+
+fn upgrade(document: &mut toml_edit::DocumentMut, migrations: &[Migration]) -> Result<u32> {
+    let current = migrations.len() + 1;
+    let version = match document.get("version") {
+        None => 1,
+        Some(item) => item
+            .as_integer()
+            .and_then(|version| usize::try_from(version).ok())
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| anyhow!("`version` must be a positive integer"))?,
+    };
+    if version > current {
+        return Err(anyhow!(
+            "Config version {} is newer than this rzen supports ({}); upgrade rzen to load it",
+            version,
+            current
+        ));
+    }
+
+    for migration in &migrations[version - 1..] {
+        migration(document.as_table_mut());
+        let envs = document
+            .get_mut("env")
+            .and_then(toml_edit::Item::as_table_like_mut);
+        for (_, env) in envs.into_iter().flat_map(|envs| envs.iter_mut()) {
+            if let Some(env) = env.as_table_like_mut() {
+                migration(env);
+            }
+        }
+    }
+    if version < current || !document.contains_key("version") {
+        document["version"] = toml_edit::value(current as i64);
+    }
+    Ok(version as u32)
+}
+
+/// Merge the `[[projects]]` entry named `name` over the base sections
+fn apply_project(table: &mut toml::Table, name: &str) -> Result<()> {
+    let mut entries = project_entries(table)
+        .into_iter()
+        .filter(|entry| entry.get("name").and_then(toml::Value::as_str) == Some(name));
+    let (Some(entry), None) = (entries.next(), entries.next()) else {
+        let names = project_names(table);
+        if names.contains(&name) {
+            return Err(anyhow!("Project '{}' is defined more than once", name));
+        }
+        let defined = Some(names.join(", "))
+            .filter(|defined| !defined.is_empty())
+            .unwrap_or_else(|| "none".to_string());
+        return Err(anyhow!("Unknown project '{}' (defined: {})", name, defined));
+    };
+
+    let mut project = toml::Table::new();
+    let mut overrides = toml::Table::new();
+    for (key, value) in entry.clone() {
+        if PROJECT_SECTIONS.contains(&key.as_str()) && value.is_table() {
+            overrides.insert(key, value);
+        } else {
+            project.insert(key, value);
+        }
+    }
+    overrides.insert("project".to_string(), toml::Value::Table(project));
+    merge_tables(table, overrides);
+    Ok(())
+}
+
+/// Entries of the `[[projects]]` array
+fn project_entries(table: &toml::Table) -> Vec<&toml::Table> {
+    table
+        .get("projects")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_table)
+        .collect()
+}
+
+/// Names of the `[[projects]]` entries, in file order
+fn project_names(table: &toml::Table) -> Vec<&str> {
+    project_entries(table)
+        .into_iter()
+        .filter_map(|entry| entry.get("name").and_then(toml::Value::as_str))
+        .collect()
+}
+
+/// Merge the tables of `[env.<name>]` over the base sections of a parsed config file
+fn apply_environment(table: &mut toml::Table, name: &str) -> Result<()> {
+    let envs = table.get("env").and_then(toml::Value::as_table);
+    let Some(overrides) = envs.and_then(|envs| envs.get(name)) else {
+        let mut defined: Vec<&str> = envs
+            .map(|envs| envs.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        defined.sort();
+        let defined = Some(defined.join(", "))
+            .filter(|defined| !defined.is_empty())
+            .unwrap_or_else(|| "none".to_string());
+        return Err(anyhow!(
+            "Unknown environment '{}' (defined: {})",
+            name,
+            defined
+        ));
+    };
+    let overrides = overrides
+        .as_table()
+        .cloned()
+        .ok_or_else(|| anyhow!("[env.{}] must be a table", name))?;
+    merge_tables(table, overrides);
+    Ok(())
+}
+
+/// Set `RZEN_<SECTION>_<KEY>` variables in the parsed settings; returns the settings they set
+///
+/// Values replacing a string stay strings; others are read as TOML values (numbers, booleans,
+/// arrays, quoted strings) when they parse as one and as plain strings otherwise. A variable
+/// naming no setting is an error, so a typo isn't silently ignored.
+fn apply_variables(
+    table: &mut toml::Table,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<Vec<String>> {
+    let mut applied = Vec::new();
+    let root = serde_json::to_value(Config::schema()).context("Failed to build config schema")?;
+    for (name, value) in vars {
+        let Some(rest) = name.strip_prefix(VAR_PREFIX) else {
+            continue;
+        };
+        if OTHER_VARS.contains(&name.as_str()) {
+            continue;
+        }
+        let mut path = variable_setting(&root, &root, &rest.to_lowercase())
+            .filter(|path| path.len() > 1 && !matches!(path[0].as_str(), "env" | "projects"))
+            .ok_or_else(|| anyhow!("{} doesn't name a setting", name))?;
+        let key = path.pop().unwrap_or_default();
+        let section = path.join(".");
+
+        let mut target = &mut *table;
+        for part in &path {
+            target = target
+                .entry(part)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| anyhow!("Cannot apply {}: [{}] is not a table", name, section))?;
+        }
+        let value = match target.get(&key) {
+            Some(toml::Value::String(_)) => toml::Value::String(value),
+            _ => parse_value(value),
+        };
+        target.insert(key.clone(), value);
+        applied.push(format!("{}.{}", section, key));
+    }
+    applied.sort();
+    Ok(applied)
+}
+
+/// Path of the setting that the `RZEN_*` variable `name` (without the prefix, lowercased) names
+/// in the table described by `schema`
+///
+/// Names are matched against the schema, so `deploy_ssh_port` is `deploy.ssh_port` while
+/// `deploy_ssh_connect_timeout_secs` is `deploy.ssh.connect_timeout_secs`.
+fn variable_setting(
+    root: &serde_json::Value,
+    schema: &serde_json::Value,
+    name: &str,
+) -> Option<Vec<String>> {
+    let object = resolve_schema(root, schema);
+    if let Some(properties) = object
+        .get("properties")
+        .and_then(serde_json::Value::as_object)
+    {
+        // A key of this table wins over the keys of a table inside it
+        if properties.contains_key(name) {
+            return Some(vec![name.to_string()]);
+        }
+        for (key, property) in properties {
+            if let Some(rest) = name
+                .strip_prefix(key.as_str())
+                .and_then(|rest| rest.strip_prefix('_'))
+                && let Some(mut path) = variable_setting(root, property, rest)
+            {
+                path.insert(0, key.clone());
+                return Some(path);
+            }
+        }
+    }
+    // Tables such as [tui.theme] take keys of any name
+    match object.get("additionalProperties") {
+        Some(serde_json::Value::Bool(true) | serde_json::Value::Object(_)) if !name.is_empty() => {
+            Some(vec![name.to_string()])
+        }
+        _ => None,
+    }
+}
+
+/// Replace the `enc:` strings in the parsed settings with their values decrypted by the age identity
+///
+/// `[env.*]` sections are left alone: the selected one is merged in already, and the others may
+/// be encrypted for identities this machine doesn't have.
+fn decrypt_values(
+    table: &mut toml::Table,
+    identity: impl FnOnce() -> Result<PathBuf>,
+) -> Result<Vec<EncryptedValue>> {
+    let mut paths = Vec::new();
+    for (key, value) in table.iter().filter(|(key, _)| *key != "env") {
+        encrypted_paths(value, &mut vec![key.clone()], &mut paths);
+    }
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let identity = identity()?;
+    paths
+        .into_iter()
+        .map(|path| {
+            let Some(toml::Value::String(value)) = lookup_mut(table, &path) else {
+                unreachable!("encrypted_paths only returns strings");
+            };
+            let plaintext = crate::utils::secrets::decrypt(value, &identity)
+                .map_err(|e| anyhow!("Failed to decrypt {}: {}", path.join("."), e))?;
+            let ciphertext = std::mem::replace(value, plaintext.clone());
+            Ok(EncryptedValue {
+                path,
+                ciphertext,
+                plaintext,
+            })
+        })
+        .collect()
+}
+
+/// Collect the paths of `enc:` strings under `value`
+fn encrypted_paths(value: &toml::Value, path: &mut Vec<String>, found: &mut Vec<Vec<String>>) {
+    match value {
+        toml::Value::String(text) if text.starts_with(crate::utils::secrets::ENCRYPTED_PREFIX) => {
+            found.push(path.clone());
+        }
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                path.push(key.clone());
+                encrypted_paths(value, path, found);
+                path.pop();
+            }
+        }
+        toml::Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                path.push(i.to_string());
+                encrypted_paths(value, path, found);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace `{section.key}` references in the settings' strings with the values they name
+///
+/// References resolve against the merged settings, then `defaults` for keys the file leaves
+/// out. Braces that don't name a setting of a known section, like `{}` or `{a,b}`, are kept.
+fn interpolate_values(
+    table: &mut toml::Table,
+    defaults: &toml::Table,
+) -> Result<Vec<InterpolatedValue>> {
+    let mut paths = Vec::new();
+    for (key, value) in table
+        .iter()
+        .filter(|(key, _)| !UNREFERENCED.contains(&key.as_str()))
+    {
+        string_paths(value, &mut vec![key.clone()], &mut paths);
+    }
+
+    let source = table.clone();
+    let mut interpolated = Vec::new();
+    for path in paths {
+        let Some(toml::Value::String(template)) = lookup(&source, &path) else {
+            continue;
+        };
+        let value = resolve_references(template, &source, defaults, &mut vec![path.join(".")])?;
+        if value != *template {
+            if let Some(current) = lookup_mut(table, &path) {
+                *current = toml::Value::String(value.clone());
+            }
+            interpolated.push(InterpolatedValue {
+                path,
+                template: template.clone(),
+                value,
+            });
+        }
+    }
+    Ok(interpolated)
+}
+
+/// Top-level keys that references can't name: raw sections already merged into the others
+const UNREFERENCED: [&str; 2] = ["env", "projects"];
+
+/// `text` with its references replaced; `stack` holds the settings being resolved, to catch cycles
+fn resolve_references(
+    text: &str,
+    source: &toml::Table,
+    defaults: &toml::Table,
+    stack: &mut Vec<String>,
+) -> Result<String> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(name) = after
+            .find('}')
+            .map(|end| &after[..end])
+            .filter(|name| is_reference(name, source, defaults))
+        else {
+            output.push('{');
+            rest = after;
+            continue;
+        };
+
+        if stack.iter().any(|key| key == name) {
+            return Err(anyhow!(
+                "Circular reference: {} -> {}",
+                stack.join(" -> "),
+                name
+            ));
+        }
+        let path: Vec<String> = name.split('.').map(str::to_string).collect();
+        let value = match lookup(source, &path).or_else(|| lookup(defaults, &path)) {
+            Some(toml::Value::String(text)) => {
+                stack.push(name.to_string());
+                let value = resolve_references(text, source, defaults, stack)?;
+                stack.pop();
+                value
+            }
+            Some(toml::Value::Table(_) | toml::Value::Array(_)) => {
+                return Err(anyhow!(
+                    "{{{}}} in {} is not a single value",
+                    name,
+                    stack[0]
+                ));
+            }
+            Some(value) => value.to_string(),
+            None => return Err(anyhow!("Unknown setting {{{}}} in {}", name, stack[0])),
+        };
+        output.push_str(&value);
+        rest = &after[name.len() + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Whether `name` has the form `section.key` for a section of the settings
+fn is_reference(name: &str, source: &toml::Table, defaults: &toml::Table) -> bool {
+    let Some((section, _)) = name.split_once('.') else {
+        return false;
+    };
+    !UNREFERENCED.contains(&section)
+        && (source.contains_key(section) || defaults.contains_key(section))
+        && name.split('.').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+/// Paths of every string inside `value`
+fn string_paths(value: &toml::Value, path: &mut Vec<String>, found: &mut Vec<Vec<String>>) {
+    match value {
+        toml::Value::String(_) => found.push(path.clone()),
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                path.push(key.clone());
+                string_paths(value, path, found);
+                path.pop();
+            }
+        }
+        toml::Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                path.push(i.to_string());
+                string_paths(value, path, found);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Value at `path` (table keys, array indices) inside `table`
+fn lookup<'a>(table: &'a toml::Table, path: &[String]) -> Option<&'a toml::Value> {
+    let (first, rest) = path.split_first()?;
+    let mut value = table.get(first)?;
+    for key in rest {
+        value = match value {
+            toml::Value::Table(table) => table.get(key)?,
+            toml::Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Value at `path` (table keys, array indices) inside `table`
+fn lookup_mut<'a>(table: &'a mut toml::Table, path: &[String]) -> Option<&'a mut toml::Value> {
+    let (first, rest) = path.split_first()?;
+    let mut value = table.get_mut(first)?;
+    for key in rest {
+        value = match value {
+            toml::Value::Table(table) => table.get_mut(key)?,
+            toml::Value::Array(items) => items.get_mut(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// A TOML value written as `text`, or `text` as a string when it isn't one
+fn parse_value(text: String) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", text))
+        .ok()
+        .filter(|table| table.len() == 1)
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or(toml::Value::String(text))
+}
+
+/// Deep-merge `overrides` into `base`: nested tables merge key by key, other values replace
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => {
+                merge_tables(base, value)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Swap the host part of `url` when it is exactly `from`
+fn replace_url_host(url: &str, from: &str, to: &str) -> String {
+    let Some(scheme_end) = url.find("://").map(|i| i + 3) else {
+        return url.to_string();
+    };
+    let rest = &url[scheme_end..];
+    match rest.strip_prefix(from) {
+        Some(tail) if tail.is_empty() || tail.starts_with([':', '/', '?']) => {
+            format!("{}{}{}", &url[..scheme_end], to, tail)
+        }
+        _ => url.to_string(),
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::tempdir;
@@ -272,6 +2611,7 @@ mod tests {
     #[test]
     fn test_config_validation() {
         let valid_config = Config {
+            version: CONFIG_VERSION,
             project: ProjectConfig {
                 path: ".".to_string(),
                 name: "test-app".to_string(),
@@ -286,21 +2626,82 @@ mod tests {
                 deploy_path: "/opt/app".to_string(),
                 service_name: Some("test-app.service".to_string()),
                 ssh_port: 22,
+                ..Default::default()
             },
             monitor: MonitorConfig {
                 health_endpoint: Some("http://example.com/health".to_string()),
                 log_path: Some("/var/log/app.log".to_string()),
                 interval_secs: 10,
                 health_timeout_secs: 5,
+                ..Default::default()
             },
+            hooks: Default::default(),
+            tui: Default::default(),
+            retry: Default::default(),
+            env: Default::default(),
+            projects: Default::default(),
+            overrides: Default::default(),
+            extends: None,
         };
 
         assert!(valid_config.validate().is_ok());
+
+        let mut keyring = valid_config.clone();
+        keyring.deploy.vps_key_path = None;
+        keyring.deploy.password_source = Some("keyring".to_string());
+        assert!(keyring.validate().is_ok());
+        keyring.deploy.runtime_password = Some("secret".to_string());
+        assert_eq!(keyring.ssh_password().as_deref(), Some("secret"));
+        assert!(!keyring.to_toml().unwrap().contains("secret"));
+
+        keyring.deploy.vps_password = Some("plain".to_string());
+        assert!(keyring.validate().is_err());
+        keyring.deploy.vps_password = None;
+        keyring.deploy.password_source = Some("vault".to_string());
+        assert!(keyring.validate().is_err());
+
+        let mut command = valid_config.clone();
+        command.deploy.vps_key_path = None;
+        command.deploy.password_command = Some("printf 'secret\\n'".to_string());
+        assert!(command.validate().is_ok());
+        command.load_password().unwrap();
+        assert_eq!(command.ssh_password().as_deref(), Some("secret"));
+        let saved = command.to_toml().unwrap();
+        assert!(saved.contains("password_command") && !saved.contains("runtime_password"));
+
+        command.deploy.password_command = Some("echo secret; exit 3".to_string());
+        let error = command.load_password().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "password_command exited with exit status: 3: echo secret; exit 3"
+        );
+        command.deploy.password_source = Some("keyring".to_string());
+        assert!(command.validate().is_err());
+
+        let mut sudo = valid_config.clone();
+        assert_eq!(sudo.sudo_password(), None);
+        sudo.deploy.sudo_password_source = Some("prompt".to_string());
+        assert!(sudo.validate().is_ok());
+        sudo.deploy.prompted_sudo_password = Some("sudo-secret".to_string());
+        assert_eq!(
+            sudo.ssh_config().sudo_password.as_deref(),
+            Some("sudo-secret")
+        );
+        assert!(!sudo.to_toml().unwrap().contains("sudo-secret"));
+        // Reusing the SSH password needs one to reuse
+        sudo.deploy.sudo_password_source = Some("ssh".to_string());
+        assert!(sudo.validate().is_err());
+        sudo.deploy.vps_password = Some("secret".to_string());
+        assert!(sudo.validate().is_ok());
+        assert_eq!(sudo.sudo_password().as_deref(), Some("secret"));
+        sudo.deploy.sudo_password_source = Some("askpass".to_string());
+        assert!(sudo.validate().is_err());
     }
 
     #[test]
     fn test_config_validation_empty_name() {
         let invalid_config = Config {
+            version: CONFIG_VERSION,
             project: ProjectConfig {
                 path: ".".to_string(),
                 name: "".to_string(),
@@ -315,18 +2716,426 @@ mod tests {
                 deploy_path: "/opt/app".to_string(),
                 service_name: Some("test-app.service".to_string()),
                 ssh_port: 22,
+                ..Default::default()
             },
             monitor: MonitorConfig {
                 health_endpoint: Some("http://example.com/health".to_string()),
                 log_path: Some("/var/log/app.log".to_string()),
                 interval_secs: 10,
                 health_timeout_secs: 5,
+                ..Default::default()
             },
+            hooks: Default::default(),
+            tui: Default::default(),
+            retry: Default::default(),
+            env: Default::default(),
+            projects: Default::default(),
+            overrides: Default::default(),
+            extends: None,
         };
 
         assert!(invalid_config.validate().is_err());
     }
 
+    #[test]
+    fn test_port_checks() {
+        let monitor: MonitorConfig = toml::from_str(
+            r#"port_checks = [5432, { port = 53, protocol = "udp" }, { port = 443, via = "direct" }]"#,
+        )
+        .unwrap();
+
+        let checks: Vec<(u16, &str, &str)> = monitor
+            .port_checks
+            .iter()
+            .map(|c| (c.port(), c.protocol(), c.via()))
+            .collect();
+        assert_eq!(
+            checks,
+            vec![
+                (5432, "tcp", "ssh"),
+                (53, "udp", "ssh"),
+                (443, "tcp", "direct")
+            ]
+        );
+
+        let mut config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            [deploy]
+            vps_host = "example.com"
+            vps_user = "deploy"
+            vps_key_path = "~/.ssh/id_rsa"
+            [monitor]
+            port_checks = [5432]
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        config.monitor.port_checks = vec![PortCheck::Detailed {
+            port: 53,
+            protocol: "udp".to_string(),
+            via: "direct".to_string(),
+        }];
+        assert!(config.validate().is_err());
+
+        config.monitor.port_checks.clear();
+        config.monitor.health_via = "ssh".to_string();
+        assert!(config.validate().is_ok());
+        config.monitor.health_via = "tunnel".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_ssh_options() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            [deploy]
+            vps_host = "example.com"
+            vps_user = "deploy"
+            [deploy.ssh]
+            connect_timeout_secs = 5
+            compression = true
+            auth_order = ["agent"]
+            [monitor]
+            "#,
+        )
+        .unwrap();
+        // The agent stands in for a key path or password
+        assert!(config.validate().is_ok());
+        let ssh_config = config.ssh_config();
+        assert_eq!(ssh_config.options.connect_timeout_secs, 5);
+        assert_eq!(ssh_config.options.max_retries, 3);
+        assert!(ssh_config.options.compression);
+        assert!(config.to_toml().unwrap().contains("[deploy.ssh]"));
+
+        // RZEN_DEPLOY_SSH_* variables override [deploy.ssh] like any other section
+        let mut table = toml::Table::try_from(&config).unwrap();
+        let variables = [
+            ("RZEN_DEPLOY_SSH_MAX_RETRIES", "7"),
+            ("RZEN_DEPLOY_SSH_COMPRESSION", "false"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        apply_variables(&mut table, variables).unwrap();
+        let overridden: Config = toml::Value::Table(table).try_into().unwrap();
+        assert_eq!(overridden.deploy.ssh.max_retries, 7);
+        assert!(!overridden.deploy.ssh.compression);
+        assert_eq!(overridden.deploy.ssh.connect_timeout_secs, 5);
+
+        config.deploy.ssh.auth_order = vec!["keyboard-interactive".to_string()];
+        let error = config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Either SSH key path or password must be provided"
+        );
+        config.deploy.vps_password = Some("secret".to_string());
+        let error = config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "SSH auth method must be one of publickey, agent, password, got: keyboard-interactive"
+        );
+
+        config.deploy.ssh = SshOptions::default();
+        assert!(!config.to_toml().unwrap().contains("[deploy.ssh]"));
+    }
+
+    #[test]
+    fn test_retry_config() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            [deploy]
+            vps_host = "example.com"
+            vps_user = "deploy"
+            vps_password = "secret"
+            [deploy.ssh]
+            max_retries = 5
+            [monitor]
+            [retry]
+            base_delay_ms = 250
+            jitter = 0.0
+            retry_on = ["timeout"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        let policy = config.retry_policy();
+        assert_eq!(policy.max_attempts, 3);
+        assert_eq!(policy.delay(2), std::time::Duration::from_millis(500));
+        assert_eq!(policy.retry_on, [crate::utils::retry::ErrorClass::Timeout]);
+        // SSH connections keep their own number of attempts
+        assert_eq!(config.ssh_config().options.max_retries, 5);
+        assert!(config.to_toml().unwrap().contains("[retry]"));
+
+        // RZEN_RETRY_* variables override [retry] like any other section
+        let mut table = toml::Table::try_from(&config).unwrap();
+        let variables = [
+            ("RZEN_RETRY_MAX_ATTEMPTS", "9"),
+            ("RZEN_RETRY_RETRY_ON", r#"["connect", "server"]"#),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        apply_variables(&mut table, variables).unwrap();
+        let overridden: Config = toml::Value::Table(table).try_into().unwrap();
+        assert_eq!(overridden.retry.max_attempts, 9);
+        assert_eq!(overridden.retry.retry_on, ["connect", "server"]);
+        assert_eq!(overridden.retry.base_delay_ms, 250);
+
+        config.retry.retry_on.push("always".to_string());
+        let error = config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Retry class must be one of connect, timeout, disconnect, server, got: always"
+        );
+        config.retry = RetryConfig {
+            jitter: 2.0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        config.retry = RetryConfig::default();
+        assert!(!config.to_toml().unwrap().contains("[retry]"));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            build_mode = "debug"
+            [deploy]
+            vps_host = "your-vps.example.com"
+            vps_user = "deploy"
+            vps_password = "secret"
+            [monitor]
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        let warnings = config.warnings();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].starts_with("vps_password is stored in plain text"));
+        assert_eq!(
+            warnings[1],
+            "vps_host is still the example host your-vps.example.com"
+        );
+        assert!(warnings[2].starts_with("SSH logs in with a password on port 22"));
+
+        config.overrides.environment = Some("production".to_string());
+        config.overrides.encrypted.push(EncryptedValue {
+            path: vec!["deploy".to_string(), "vps_password".to_string()],
+            ciphertext: "enc:...".to_string(),
+            plaintext: "secret".to_string(),
+        });
+        config.deploy.vps_host = "example.com".to_string();
+        config.deploy.ssh_port = 2222;
+        assert_eq!(
+            config.warnings(),
+            ["Environment 'production' deploys a debug build; set build_mode = \"release\""]
+        );
+
+        config.project.build_mode = "release".to_string();
+        assert!(config.warnings().is_empty());
+
+        config.deploy.ssh.host_key_checking = "off".to_string();
+        assert_eq!(config.warnings().len(), 1);
+        assert!(config.warnings()[0].contains("man-in-the-middle"));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rzen.toml");
+        let contents = r#"[project]
+name = "app"
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_password = "secret"
+deploy_path = "/opt/{project.name}"
+
+[monitor]
+health_endpoint = "http://{deploy.vps_host}:8080/health"
+log_path = "{deploy.deploy_path}/{project.name}.log"
+
+[hooks]
+post_deploy = ["ssh -p {deploy.ssh_port} host awk '{print $1}' {}"]
+"#;
+        fs::write(&path, contents).unwrap();
+
+        let config = Config::from_file(&path, None, None).unwrap();
+        assert_eq!(config.deploy.deploy_path, "/opt/app");
+        assert_eq!(
+            config.monitor.health_endpoint.as_deref(),
+            Some("http://example.com:8080/health")
+        );
+        assert_eq!(config.monitor.log_path.as_deref(), Some("/opt/app/app.log"));
+        // Defaults resolve too, and braces that aren't references stay
+        assert_eq!(
+            config.hooks.post_deploy[0].command(),
+            "ssh -p 22 host awk '{print $1}' {}"
+        );
+
+        // Saving keeps the references
+        let saved = config.to_toml().unwrap();
+        assert!(saved.contains(r#"deploy_path = "/opt/{project.name}""#));
+        let mut renamed = config.clone();
+        renamed.monitor.log_path = Some("/var/log/app.log".to_string());
+        assert!(
+            renamed
+                .to_toml()
+                .unwrap()
+                .contains(r#"log_path = "/var/log/app.log""#)
+        );
+
+        fs::write(
+            &path,
+            contents.replace("{project.name}.log", "{project.title}.log"),
+        )
+        .unwrap();
+        let error = Config::from_file(&path, None, None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown setting {project.title} in monitor.log_path"
+        );
+
+        fs::write(
+            &path,
+            contents.replace("/opt/{project.name}", "{monitor.log_path}"),
+        )
+        .unwrap();
+        let error = Config::from_file(&path, None, None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Circular reference: deploy.deploy_path -> monitor.log_path -> deploy.deploy_path"
+        );
+    }
+
+    #[test]
+    fn test_local_target() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("rzen.toml");
+        fs::write(
+            &path,
+            "[project]\nname = \"app\"\n\n[deploy]\ntarget = \"local\"\n\n[monitor]\n",
+        )
+        .unwrap();
+
+        // No server, user or credentials needed
+        let mut config = Config::from_file(&path, None, None).unwrap();
+        assert!(config.deploy.is_local());
+        assert_eq!(config.deploy.vps_host, "localhost");
+
+        config.deploy.hosts = vec!["web1".to_string()];
+        let error = config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "hosts cannot be set with target = \"local\""
+        );
+
+        config.deploy.hosts.clear();
+        config.deploy.target = "docker".to_string();
+        let error = config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Deploy target must be 'vps' or 'local', got: docker"
+        );
+    }
+
+    #[test]
+    fn test_for_host_rewrites_endpoints() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            [deploy]
+            vps_host = "web1.example.com"
+            hosts = ["web1.example.com", "web2.example.com"]
+            vps_user = "deploy"
+            [monitor]
+            health_endpoint = "http://web1.example.com:8080/health"
+            metrics_endpoint = "https://metrics.example.com/web1.example.com"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.hosts().len(), 2);
+        let web2 = config.for_host("web2.example.com");
+        assert_eq!(web2.deploy.vps_host, "web2.example.com");
+        assert_eq!(
+            web2.monitor.health_endpoint.as_deref(),
+            Some("http://web2.example.com:8080/health")
+        );
+        assert_eq!(
+            web2.monitor.metrics_endpoint.as_deref(),
+            Some("https://metrics.example.com/web1.example.com")
+        );
+
+        config.deploy.hosts.clear();
+        assert_eq!(config.hosts(), vec!["web1.example.com".to_string()]);
+    }
+
+    #[test]
+    fn test_for_host_keyring_secrets() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "app"
+            [deploy]
+            vps_host = "web1.example.com"
+            hosts = ["web1.example.com", "web2.example.com", "web3.example.com"]
+            vps_user = "deploy"
+            password_source = "keyring"
+            sudo_password_source = "ssh"
+            [monitor]
+            "#,
+        )
+        .unwrap();
+        // As load_password leaves them when web3 has no entry of its own
+        config.deploy.runtime_password = Some("web1-secret".to_string());
+        for host in ["web1", "web2"] {
+            config
+                .deploy
+                .host_passwords
+                .insert(format!("{}.example.com", host), format!("{}-secret", host));
+        }
+
+        let web2 = config.for_host("web2.example.com");
+        assert_eq!(web2.ssh_password().as_deref(), Some("web2-secret"));
+        assert_eq!(web2.sudo_password().as_deref(), Some("web2-secret"));
+        // A host without an entry never gets the first host's secret
+        let web3 = config.for_host("web3.example.com");
+        assert_eq!(web3.ssh_password(), None);
+        assert_eq!(web3.ssh_config().sudo_password, None);
+        assert_eq!(
+            config
+                .for_host("web1.example.com")
+                .ssh_password()
+                .as_deref(),
+            Some("web1-secret")
+        );
+
+        // Unless sharing it was asked for
+        config.deploy.keyring_shared = true;
+        assert!(config.validate().is_ok());
+        let web3 = config.for_host("web3.example.com");
+        assert_eq!(web3.ssh_password().as_deref(), Some("web1-secret"));
+        assert!(!config.to_toml().unwrap().contains("secret\""));
+
+        config.deploy.password_source = None;
+        config.deploy.vps_password = Some("plain".to_string());
+        let error = config.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "keyring_shared needs password_source = \"keyring\""
+        );
+    }
+
     #[test]
     fn test_create_default_config() {
         let temp_dir = tempdir().unwrap();
@@ -335,8 +3144,541 @@ mod tests {
         Config::create_default(&config_path).unwrap();
 
         // Should be able to load the created config
-        let loaded_config = Config::from_file(&config_path).unwrap();
+        let loaded_config = Config::from_file(&config_path, None, None).unwrap();
         assert_eq!(loaded_config.project.name, "my-rust-app");
         assert_eq!(loaded_config.deploy.vps_host, "your-vps.example.com");
     }
+
+    #[test]
+    fn test_save_config() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("rzen.toml");
+        Config::create_default(&config_path).unwrap();
+
+        let mut config = Config::from_file(&config_path, None, None).unwrap();
+        config.deploy.vps_host = "web1.example.com".to_string();
+        config.save(&config_path).unwrap();
+        assert_eq!(
+            Config::from_file(&config_path, None, None)
+                .unwrap()
+                .deploy
+                .vps_host,
+            "web1.example.com"
+        );
+
+        config.deploy.vps_host = String::new();
+        assert!(config.save(&config_path).is_err());
+    }
+
+    #[test]
+    fn test_tui_section() {
+        let config: Config = toml::from_str(
+            r##"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_key_path = "~/.ssh/id_ed25519"
+
+[monitor]
+
+[tui]
+vim = true
+log_lines = 500
+
+[tui.keys]
+build = "B"
+quit = ["q", "ctrl+c"]
+
+[tui.theme]
+preset = "solarized"
+accent = "#268bd2"
+"##,
+        )
+        .unwrap();
+        assert_eq!(config.tui.keys["build"].keys(), vec!["B"]);
+        assert_eq!(config.tui.keys["quit"].keys(), vec!["q", "ctrl+c"]);
+        assert_eq!(config.tui.theme.preset.as_deref(), Some("solarized"));
+        assert_eq!(config.tui.theme.colors["accent"], "#268bd2");
+        assert!(config.tui.vim);
+        assert_eq!(config.tui.log_lines, 500);
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        assert!(saved.contains("[tui.theme]"));
+        let reloaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.tui, config.tui);
+    }
+
+    #[test]
+    fn test_environments() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("rzen.toml");
+        fs::write(
+            &config_path,
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "dev.example.com"
+vps_user = "deploy"
+vps_key_path = "~/.ssh/id_ed25519"
+
+[monitor]
+interval_secs = 5
+
+[env.production.deploy]
+vps_host = "prod.example.com"
+
+[env.production.monitor]
+health_endpoint = "https://prod.example.com/health"
+
+[env.broken.project]
+build_mode = "fast"
+"#,
+        )
+        .unwrap();
+
+        let base = Config::from_file(&config_path, None, None).unwrap();
+        assert_eq!(base.deploy.vps_host, "dev.example.com");
+        assert_eq!(base.environments(), ["broken", "production"]);
+        assert!(base.to_toml().unwrap().contains("[env.production.deploy]"));
+
+        let production = Config::from_file(&config_path, Some("production"), None).unwrap();
+        assert_eq!(production.deploy.vps_host, "prod.example.com");
+        assert_eq!(production.deploy.vps_user, "deploy");
+        assert_eq!(production.monitor.interval_secs, 5);
+        assert_eq!(
+            production.monitor.health_endpoint.as_deref(),
+            Some("https://prod.example.com/health")
+        );
+        assert_eq!(
+            production.overrides.environment.as_deref(),
+            Some("production")
+        );
+        assert!(production.to_toml().is_err());
+
+        let error = Config::from_file(&config_path, Some("broken"), None).unwrap_err();
+        assert!(error.to_string().contains("environment 'broken'"));
+        let error = Config::from_file(&config_path, Some("qa"), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown environment 'qa' (defined: broken, production)"
+        );
+    }
+
+    #[test]
+    fn test_projects() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("rzen.toml");
+        let contents = r#"
+[project]
+path = "."
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_key_path = "~/.ssh/id_ed25519"
+
+[monitor]
+
+[[projects]]
+name = "api"
+
+[projects.deploy]
+deploy_path = "/opt/api"
+
+[[projects]]
+name = "worker"
+build_mode = "debug"
+"#;
+        fs::write(&config_path, contents).unwrap();
+
+        let error = Config::from_file(&config_path, None, None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Select a project with --project (defined: api, worker)"
+        );
+        assert_eq!(
+            Config::projects_in(&config_path).unwrap(),
+            ["api", "worker"]
+        );
+
+        let api = Config::from_file(&config_path, None, Some("api")).unwrap();
+        assert_eq!(api.project.name, "api");
+        assert_eq!(api.deploy.deploy_path, "/opt/api");
+        assert_eq!(api.deploy.vps_host, "example.com");
+        assert!(api.to_toml().is_err());
+
+        let worker = Config::from_file(&config_path, None, Some("worker")).unwrap();
+        assert_eq!(worker.project.build_mode, "debug");
+        assert_eq!(worker.deploy.deploy_path, default_deploy_path());
+
+        let error = Config::from_file(&config_path, None, Some("cron")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown project 'cron' (defined: api, worker)"
+        );
+
+        let mut document: toml_edit::DocumentMut = contents.parse().unwrap();
+        set_value(
+            &mut document,
+            None,
+            Some("worker"),
+            "project.build_mode",
+            "release",
+        )
+        .unwrap();
+        set_value(&mut document, None, Some("api"), "deploy.ssh_port", "2222").unwrap();
+        assert!(set_value(&mut document, None, Some("api"), "tui.vim", "true").is_ok());
+        let edited = document.to_string();
+        assert!(
+            edited.contains("[projects.deploy]\ndeploy_path = \"/opt/api\"\nssh_port = 2222"),
+            "{}",
+            edited
+        );
+        assert!(
+            edited.contains("name = \"worker\"\nbuild_mode = \"release\""),
+            "{}",
+            edited
+        );
+    }
+
+    #[test]
+    fn test_extends() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("rzen.base.toml"),
+            r#"
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_key_path = "~/.ssh/id_ed25519"
+
+[monitor]
+interval_secs = 30
+"#,
+        )
+        .unwrap();
+        let service_dir = temp_dir.path().join("api");
+        fs::create_dir(&service_dir).unwrap();
+        let config_path = service_dir.join("rzen.toml");
+        fs::write(
+            &config_path,
+            r#"
+extends = "../rzen.base.toml"
+
+[project]
+name = "api"
+
+[deploy]
+deploy_path = "/opt/api"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::from_file(&config_path, None, None).unwrap();
+        assert_eq!(config.project.name, "api");
+        assert_eq!(config.deploy.vps_host, "example.com");
+        assert_eq!(config.deploy.deploy_path, "/opt/api");
+        assert_eq!(config.monitor.interval_secs, 30);
+        assert_eq!(config.extends.as_deref(), Some("../rzen.base.toml"));
+        assert!(config.to_toml().is_err());
+
+        fs::write(temp_dir.path().join("a.toml"), "extends = \"b.toml\"").unwrap();
+        fs::write(temp_dir.path().join("b.toml"), "extends = \"a.toml\"").unwrap();
+        let error = Config::from_file(temp_dir.path().join("a.toml"), None, None).unwrap_err();
+        let message = error.to_string();
+        assert!(message.starts_with("Config include cycle: "), "{}", message);
+        assert!(message.contains("a.toml -> "), "{}", message);
+        assert!(message.ends_with("a.toml"), "{}", message);
+    }
+
+    #[test]
+    fn test_variable_overrides() {
+        let mut table: toml::Table = toml::from_str(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_key_path = "~/.ssh/id_ed25519"
+
+[monitor]
+"#,
+        )
+        .unwrap();
+        let vars = [
+            ("RZEN_DEPLOY_VPS_HOST", "ci.example.com"),
+            ("RZEN_PROJECT_BUILD_MODE", "debug"),
+            ("RZEN_DEPLOY_SSH_PORT", "2222"),
+            ("RZEN_DEPLOY_HOSTS", r#"["a.example.com", "b.example.com"]"#),
+            ("RZEN_MONITOR_ALERTS_RESPONSE_TIME_MS", "500"),
+            ("RZEN_DEPLOY_SSH_CONNECT_TIMEOUT_SECS", "99"),
+            ("RZEN_ENV", "staging"),
+            ("RZEN_HOOK", "pre_deploy"),
+            ("HOME", "/root"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        let applied = apply_variables(&mut table, vars).unwrap();
+        assert_eq!(
+            applied,
+            [
+                "deploy.hosts",
+                "deploy.ssh.connect_timeout_secs",
+                "deploy.ssh_port",
+                "deploy.vps_host",
+                "monitor.alerts.response_time_ms",
+                "project.build_mode",
+            ]
+        );
+        let config: Config = toml::Value::Table(table).try_into().unwrap();
+        assert_eq!(config.deploy.vps_host, "ci.example.com");
+        assert_eq!(config.project.build_mode, "debug");
+        assert_eq!(config.deploy.ssh_port, 2222);
+        assert_eq!(config.deploy.hosts, ["a.example.com", "b.example.com"]);
+        assert_eq!(config.monitor.alerts.unwrap().response_time_ms, Some(500));
+        // Keys of a nested table match by the table's name, next to a key sharing its prefix
+        assert_eq!(config.deploy.ssh.connect_timeout_secs, 99);
+
+        // A name that matches no setting is a mistake, not something to skip
+        for name in [
+            "RZEN_DEPLOY_TYPO",
+            "RZEN_UNKNOWN",
+            "RZEN_DEPLOY",
+            "RZEN_ENV_STAGING_DEPLOY_X",
+        ] {
+            let variable = [(name.to_string(), "1".to_string())];
+            let error = apply_variables(&mut toml::Table::new(), variable).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("{} doesn't name a setting", name)
+            );
+        }
+
+        assert_eq!(parse_value("true".to_string()), toml::Value::Boolean(true));
+        assert_eq!(
+            parse_value("\"1234\"".to_string()),
+            toml::Value::String("1234".to_string())
+        );
+        assert_eq!(
+            parse_value("not toml".to_string()),
+            toml::Value::String("not toml".to_string())
+        );
+    }
+
+    #[test]
+    fn test_encrypted_values() {
+        use age::secrecy::ExposeSecret;
+
+        let temp_dir = tempdir().unwrap();
+        let identity = age::x25519::Identity::generate();
+        let identity_path = temp_dir.path().join("identity.txt");
+        fs::write(&identity_path, identity.to_string().expose_secret()).unwrap();
+        let recipient = identity.to_public().to_string();
+        let password = crate::utils::secrets::encrypt("hunter2", Some(&recipient)).unwrap();
+
+        let mut table: toml::Table = toml::from_str(&format!(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_password = "{}"
+
+[monitor]
+
+[env.production.deploy]
+vps_password = "enc:for-another-identity"
+"#,
+            password
+        ))
+        .unwrap();
+        let encrypted = decrypt_values(&mut table, || Ok(identity_path.clone())).unwrap();
+        assert_eq!(encrypted.len(), 1);
+        assert_eq!(encrypted[0].path, ["deploy", "vps_password"]);
+
+        let mut config: Config = toml::Value::Table(table).try_into().unwrap();
+        assert_eq!(config.deploy.vps_password.as_deref(), Some("hunter2"));
+        config.overrides.encrypted = encrypted;
+        let saved = config.to_toml().unwrap();
+        assert!(saved.contains(&password));
+        assert!(!saved.contains("hunter2"));
+
+        config.deploy.vps_password = Some("changed".to_string());
+        assert!(config.to_toml().is_err());
+
+        let mut plain: toml::Table =
+            toml::from_str("[deploy]\nvps_host = \"example.com\"").unwrap();
+        let none = decrypt_values(&mut plain, || Err(anyhow!("no identity needed"))).unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema = serde_json::to_value(Config::schema()).unwrap();
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["deploy", "monitor", "project"])
+        );
+        let deploy = &schema["definitions"]["DeployConfig"]["properties"];
+        assert_eq!(deploy["ssh_port"]["default"], 22);
+        assert!(deploy["vps_host"]["description"].is_string());
+        // Runtime-only fields stay out of the file format
+        assert!(deploy.get("runtime_password").is_none());
+        assert!(schema["properties"].get("overrides").is_none());
+        assert!(schema["properties"]["env"].is_object());
+        assert!(schema["definitions"]["AlertChannel"].is_object());
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut document: toml_edit::DocumentMut = r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_key_path = "~/.ssh/id_ed25519"
+ssh_port = 22 # behind the bastion
+vps_password = "enc:abc"
+
+[monitor]
+"#
+        .parse()
+        .unwrap();
+        set_value(&mut document, None, None, "deploy.ssh_port", "2222").unwrap();
+        set_value(&mut document, None, None, "deploy.vps_host", "1234").unwrap();
+        set_value(&mut document, None, None, "deploy.hosts", r#"["a", "b"]"#).unwrap();
+        set_value(&mut document, None, None, "tui.theme.accent", "red").unwrap();
+        set_value(
+            &mut document,
+            Some("staging"),
+            None,
+            "monitor.interval_secs",
+            "5",
+        )
+        .unwrap();
+        let edited = document.to_string();
+        assert!(
+            edited.contains("ssh_port = 2222 # behind the bastion"),
+            "{}",
+            edited
+        );
+        assert!(edited.contains(r#"vps_host = "1234""#), "{}", edited);
+        assert!(edited.contains(r#"hosts = ["a", "b"]"#), "{}", edited);
+        assert!(
+            edited.contains("[tui.theme]\naccent = \"red\""),
+            "{}",
+            edited
+        );
+        assert!(
+            edited.contains("[env.staging.monitor]\ninterval_secs = 5"),
+            "{}",
+            edited
+        );
+
+        let error = set_value(&mut document, None, None, "deploy.sshport", "22").unwrap_err();
+        assert_eq!(error.to_string(), "Unknown setting 'deploy.sshport'");
+        assert!(set_value(&mut document, None, None, "deploy.vps_password", "hunter2").is_err());
+
+        let config: Config = toml::from_str(&edited).unwrap();
+        assert_eq!(
+            config.setting("deploy.ssh_port").unwrap(),
+            Some(toml::Value::Integer(2222))
+        );
+        assert_eq!(
+            config.setting("deploy.restart_strategy").unwrap(),
+            Some(toml::Value::String("stop-start".to_string()))
+        );
+        assert_eq!(config.setting("deploy.service_name").unwrap(), None);
+        assert!(config.setting("monitor.bogus").is_err());
+        // Nested sections the file leaves out report their defaults too
+        assert_eq!(
+            config.setting("deploy.ssh.connect_timeout_secs").unwrap(),
+            Some(toml::Value::Integer(10))
+        );
+        assert_eq!(
+            config.setting("deploy.ssh.read_timeout_secs").unwrap(),
+            Some(toml::Value::Integer(0))
+        );
+        assert_eq!(
+            config.setting("retry.max_attempts").unwrap(),
+            Some(toml::Value::Integer(3))
+        );
+    }
+
+    #[test]
+    fn test_migrations() {
+        // Stand-in for a future layout change: `monitor.interval` renamed to `interval_secs`
+        fn rename_interval(table: &mut dyn toml_edit::TableLike) {
+            let monitor = table
+                .get_mut("monitor")
+                .and_then(toml_edit::Item::as_table_like_mut);
+            let Some(monitor) = monitor else { return };
+            // The key's decor holds the comments above it
+            let Some(decor) = monitor.key("interval").map(|key| key.leaf_decor().clone()) else {
+                return;
+            };
+            if let Some(value) = monitor.remove("interval") {
+                let key = toml_edit::Key::new("interval_secs").with_leaf_decor(decor);
+                monitor.entry_format(&key).or_insert(value);
+            }
+        }
+
+        let mut document: toml_edit::DocumentMut = r#"
+[monitor]
+# Poll twice a minute
+interval = 30
+
+[env.staging.monitor]
+interval = 5
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(upgrade(&mut document, &[rename_interval]).unwrap(), 1);
+        let migrated = document.to_string();
+        assert!(migrated.contains("version = 2"), "{}", migrated);
+        assert!(migrated.contains("# Poll twice a minute"), "{}", migrated);
+        assert!(migrated.contains("interval_secs = 30"), "{}", migrated);
+        assert!(migrated.contains("interval_secs = 5"), "{}", migrated);
+        assert!(!migrated.contains("interval ="), "{}", migrated);
+        // Already current: nothing left to do
+        assert_eq!(upgrade(&mut document, &[rename_interval]).unwrap(), 2);
+        assert_eq!(document.to_string(), migrated);
+
+        let mut newer: toml_edit::DocumentMut = "version = 3".parse().unwrap();
+        let error = upgrade(&mut newer, &[rename_interval]).unwrap_err();
+        assert!(error.to_string().contains("newer than this rzen supports"));
+
+        // Files without `version` load as the first layout
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("rzen.toml");
+        fs::write(
+            &config_path,
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_key_path = "~/.ssh/id_ed25519"
+
+[monitor]
+"#,
+        )
+        .unwrap();
+        let config = Config::from_file(&config_path, None, None).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(config.to_toml().unwrap().starts_with("version = 1\n"));
+    }
 }
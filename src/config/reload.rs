@@ -0,0 +1,264 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::{Config, DeployConfig, SshOptions};
+
+/// Settings that only take effect on a new SSH connection
+const CONNECTION_KEYS: [&str; 11] = [
+    "deploy.vps_host",
+    "deploy.hosts",
+    "deploy.vps_user",
+    "deploy.vps_key_path",
+    "deploy.key_passphrase",
+    "deploy.vps_password",
+    "deploy.password_source",
+    "deploy.password_command",
+    "deploy.ssh_port",
+    "deploy.sudo_password_source",
+    "deploy.ssh.compression",
+];
+
+/// Sections that are already merged into the resolved settings, so their raw contents aren't compared
+const MERGED_SECTIONS: [&str; 4] = ["version", "env", "projects", "extends"];
+
+/// Reloads a config file when it is modified, with the environment and project it was loaded for
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    environment: Option<String>,
+    project: Option<String>,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    /// Watch the file `config` was loaded from
+    pub fn new(path: PathBuf, config: &Config) -> Self {
+        let modified = modified(&path);
+        Self {
+            path,
+            environment: config.overrides.environment.clone(),
+            project: config.overrides.project.clone(),
+            modified,
+        }
+    }
+
+    /// The file's configuration when it changed since the last call
+    ///
+    /// A file that doesn't load is reported once, and read again on its next change.
+    pub fn poll(&mut self) -> Option<Result<Config>> {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(
+            Config::from_file(
+                &self.path,
+                self.environment.as_deref(),
+                self.project.as_deref(),
+            )
+            .with_context(|| format!("Not reloaded: {}", self.path.display())),
+        )
+    }
+}
+
+fn modified(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Reloaded configuration and the settings that differ from the running one
+#[derive(Debug, Clone)]
+pub struct Reload {
+    pub config: Config,
+    /// Changed settings that take effect right away, e.g. `monitor.interval_secs`
+    pub applied: Vec<String>,
+    /// Changed settings that need a new connection, e.g. `deploy.vps_host`
+    pub reconnect: Vec<String>,
+    /// Deploy settings of the running configuration
+    previous: DeployConfig,
+}
+
+impl Reload {
+    pub fn new(current: &Config, config: Config) -> Self {
+        let (reconnect, applied) = changed_keys(current, &config)
+            .into_iter()
+            .partition(|key| CONNECTION_KEYS.contains(&key.as_str()));
+        Self {
+            config,
+            applied,
+            reconnect,
+            previous: current.deploy.clone(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.reconnect.is_empty()
+    }
+
+    /// Every change, reading the password for the new connection from the keyring or
+    /// `password_command` if it is kept there
+    pub fn with_reconnect(mut self) -> Result<Config> {
+        if self.reconnect.is_empty() {
+            return Ok(self.without_reconnect());
+        }
+        // Don't ask again for the passphrase of the same key, or the same sudo password
+        if self.config.deploy.vps_key_path == self.previous.vps_key_path {
+            self.config.deploy.prompted_passphrase = self.previous.prompted_passphrase.clone();
+        }
+        let deploy = &self.config.deploy;
+        if deploy.sudo_password_source == self.previous.sudo_password_source
+            && deploy.vps_host == self.previous.vps_host
+            && deploy.vps_user == self.previous.vps_user
+        {
+            self.config.deploy.prompted_sudo_password =
+                self.previous.prompted_sudo_password.clone();
+        }
+        self.config.load_password()?;
+        Ok(self.config)
+    }
+
+    /// The changes that don't need a new connection; the connection settings stay as they were
+    pub fn without_reconnect(self) -> Config {
+        let mut config = self.config;
+        let previous = self.previous;
+        config.deploy = DeployConfig {
+            vps_host: previous.vps_host,
+            hosts: previous.hosts,
+            vps_user: previous.vps_user,
+            vps_key_path: previous.vps_key_path,
+            vps_password: previous.vps_password,
+            password_source: previous.password_source,
+            password_command: previous.password_command,
+            runtime_password: previous.runtime_password,
+            key_passphrase: previous.key_passphrase,
+            prompted_passphrase: previous.prompted_passphrase,
+            sudo_password_source: previous.sudo_password_source,
+            prompted_sudo_password: previous.prompted_sudo_password,
+            ssh_port: previous.ssh_port,
+            ssh: SshOptions {
+                compression: previous.ssh.compression,
+                ..config.deploy.ssh
+            },
+            ..config.deploy
+        };
+        config
+    }
+}
+
+/// Dotted keys of the settings that differ between two configurations, in order
+fn changed_keys(current: &Config, reloaded: &Config) -> Vec<String> {
+    let flatten = |config: &Config| {
+        let mut settings = Vec::new();
+        if let Ok(mut table) = toml::Table::try_from(config) {
+            // Default `[deploy.ssh]` settings are left out, so compare them in full
+            if let Some(toml::Value::Table(deploy)) = table.get_mut("deploy")
+                && let Ok(ssh) = toml::Value::try_from(&config.deploy.ssh)
+            {
+                deploy.insert("ssh".to_string(), ssh);
+            }
+            for (key, value) in table {
+                if !MERGED_SECTIONS.contains(&key.as_str()) {
+                    flatten_value(key, value, &mut settings);
+                }
+            }
+        }
+        settings
+    };
+    let before = flatten(current);
+    let after = flatten(reloaded);
+
+    let mut keys: Vec<String> = before
+        .iter()
+        .filter(|setting| !after.contains(setting))
+        .chain(after.iter().filter(|setting| !before.contains(setting)))
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Leaf settings of `value` with their dotted keys; arrays count as one setting
+fn flatten_value(key: String, value: toml::Value, settings: &mut Vec<(String, toml::Value)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (name, value) in table {
+                flatten_value(format!("{}.{}", key, name), value, settings);
+            }
+        }
+        value => settings.push((key, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+
+    const CONFIG: &str = r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "example.com"
+vps_user = "deploy"
+vps_password = "secret"
+
+[monitor]
+interval_secs = 30
+"#;
+
+    #[test]
+    fn test_reload() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("rzen.toml");
+        fs::write(&path, CONFIG).unwrap();
+        let current = Config::from_file(&path, None, None).unwrap();
+
+        let mut watcher = ConfigWatcher::new(path.clone(), &current);
+        assert!(watcher.poll().is_none());
+
+        let edited = CONFIG
+            .replace(
+                "interval_secs = 30",
+                "interval_secs = 10\nlog_path = \"/var/log/app.log\"",
+            )
+            .replace("example.com", "other.example.com")
+            + "\n[deploy.ssh]\ncompression = true\n";
+        fs::write(&path, edited).unwrap();
+        // Bump the time in case the file system's resolution hides the rewrite
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        let reloaded = watcher.poll().unwrap().unwrap();
+        assert!(watcher.poll().is_none());
+
+        let reload = Reload::new(&current, reloaded);
+        assert_eq!(
+            reload.applied,
+            ["monitor.interval_secs", "monitor.log_path"]
+        );
+        assert_eq!(
+            reload.reconnect,
+            ["deploy.ssh.compression", "deploy.vps_host"]
+        );
+
+        let config = reload.clone().without_reconnect();
+        assert_eq!(config.deploy.vps_host, "example.com");
+        assert!(!config.deploy.ssh.compression);
+        assert_eq!(config.monitor.interval_secs, 10);
+        let config = reload.with_reconnect().unwrap();
+        assert_eq!(config.deploy.vps_host, "other.example.com");
+
+        assert!(Reload::new(&current, current.clone()).is_empty());
+    }
+}
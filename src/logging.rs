@@ -1,5 +1,7 @@
 use anyhow::Result;
+use std::env;
 use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -46,9 +48,88 @@ pub fn init_with_level(level: LogLevel) -> Result<()> {
     Ok(())
 }
 
+/// Plain-text markers replace emoji in all output (`--ascii`)
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// Plain-text marker for every glyph passed to [`glyph`]
+const GLYPHS: &[(&str, &str)] = &[
+    ("✅", "[OK]"),
+    ("❌", "[FAIL]"),
+    ("⚠️", "[WARN]"),
+    ("❔", "[?]"),
+    ("💚", "[OK]"),
+    ("💔", "[FAIL]"),
+    ("🟢", "[UP]"),
+    ("🔴", "[DOWN]"),
+    ("🔥", "[!]"),
+    ("🚨", "[!]"),
+    ("🔁", "[!]"),
+    ("🔄", "[~]"),
+    ("📜", "|"),
+    ("🚀", "*"),
+    ("🔨", "*"),
+    ("👀", "*"),
+    ("🔐", "*"),
+    ("📁", "*"),
+    ("🌵", "*"),
+    ("📋", "*"),
+    ("📄", "*"),
+    ("🐕", "*"),
+    ("●", "*"),
+    ("▏", "_"),
+    ("←", "Left"),
+    ("→", "Right"),
+    ("↑", "Up"),
+    ("↓", "Down"),
+];
+
+/// Switch all CLI and TUI output to plain-text markers
+pub fn set_ascii(ascii: bool) {
+    ASCII.store(ascii, Ordering::Relaxed);
+}
+
+/// `emoji` as given, or its plain-text marker in ASCII mode
+pub fn glyph(emoji: &'static str) -> &'static str {
+    if !ASCII.load(Ordering::Relaxed) {
+        return emoji;
+    }
+    plain(emoji)
+}
+
+fn plain(emoji: &'static str) -> &'static str {
+    GLYPHS
+        .iter()
+        .find(|(glyph, _)| *glyph == emoji)
+        .map_or(emoji, |(_, plain)| plain)
+}
+
+/// Whether the terminal is unlikely to show emoji: the Linux console, serial
+/// and dumb terminals, or a locale without UTF-8
+pub fn ascii_terminal() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+    // The first of these that is set decides the character set, as in setlocale(3)
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()));
+    needs_ascii(&term, locale.as_deref())
+}
+
+fn needs_ascii(term: &str, locale: Option<&str>) -> bool {
+    let plain_term = matches!(
+        term,
+        "linux" | "dumb" | "vt100" | "vt102" | "vt220" | "ansi" | "cons25"
+    );
+    let plain_locale = locale.is_some_and(|locale| {
+        let locale = locale.to_ascii_lowercase();
+        !locale.contains("utf-8") && !locale.contains("utf8")
+    });
+    plain_term || plain_locale
+}
+
 /// Log levels for CLI display
 #[derive(Debug, Clone, Copy)]
 pub enum LogLevel {
+    Off,
     Error,
     Warn,
     Info,
@@ -60,7 +141,7 @@ impl LogLevel {
     /// Convert numeric level to LogLevel enum
     pub fn from_number(level: u8) -> Self {
         match level {
-            0 => LogLevel::Error,
+            0 => LogLevel::Off,
             1 => LogLevel::Error,
             2 => LogLevel::Warn,
             3 => LogLevel::Info,
@@ -73,6 +154,7 @@ impl LogLevel {
     /// Get the string representation for filtering
     pub fn as_filter(&self) -> &'static str {
         match self {
+            LogLevel::Off => "off",
             LogLevel::Error => "error",
             LogLevel::Warn => "warn",
             LogLevel::Info => "info",
@@ -85,6 +167,7 @@ impl LogLevel {
     #[allow(dead_code)]
     pub fn as_number(&self) -> u8 {
         match self {
+            LogLevel::Off => 0,
             LogLevel::Error => 1,
             LogLevel::Warn => 2,
             LogLevel::Info => 3,
@@ -96,20 +179,21 @@ impl LogLevel {
 
 /// Utility functions for consistent logging
 pub mod log {
+    use super::glyph;
 
     /// Log an operation start
     pub fn operation_start(operation: &str) {
-        tracing::info!("🚀 Starting: {}", operation);
+        tracing::info!("{} Starting: {}", glyph("🚀"), operation);
     }
 
     /// Log an operation success
     pub fn operation_success(operation: &str) {
-        tracing::info!("✅ Completed: {}", operation);
+        tracing::info!("{} Completed: {}", glyph("✅"), operation);
     }
 
     /// Log an operation failure
     pub fn operation_failed(operation: &str, error: &str) {
-        tracing::error!("❌ Failed: {} - {}", operation, error);
+        tracing::error!("{} Failed: {} - {}", glyph("❌"), operation, error);
     }
 
     // /// Log progress with percentage
@@ -124,55 +208,79 @@ pub mod log {
 
     /// Log build step
     pub fn build_step(step: &str) {
-        tracing::info!("🔨 Build: {}", step);
+        tracing::info!("{} Build: {}", glyph("🔨"), step);
     }
 
     /// Log deployment step
     pub fn deploy_step(step: &str) {
-        tracing::info!("🚀 Deploy: {}", step);
+        tracing::info!("{} Deploy: {}", glyph("🚀"), step);
     }
 
     /// Log monitoring event
     pub fn monitor_event(event: &str) {
-        tracing::info!("👀 Monitor: {}", event);
+        tracing::info!("{} Monitor: {}", glyph("👀"), event);
     }
 
     /// Log SSH operation
     pub fn ssh_operation(operation: &str, host: &str) {
-        tracing::debug!("🔐 SSH {} on {}", operation, host);
+        tracing::debug!("{} SSH {} on {}", glyph("🔐"), operation, host);
+    }
+
+    /// Log a failed attempt that is tried again after `delay`
+    pub fn retrying(
+        operation: &str,
+        attempt: u32,
+        max_attempts: u32,
+        delay: std::time::Duration,
+        error: &str,
+    ) {
+        tracing::warn!(
+            "{} {} failed (attempt {}/{}), retrying in {}: {}",
+            glyph("🔄"),
+            operation,
+            attempt,
+            max_attempts,
+            crate::utils::timing::format_duration(delay),
+            error
+        );
     }
 
     /// Log file transfer
     pub fn file_transfer(file: &str, direction: &str) {
-        tracing::info!("📁 {}: {}", direction, file);
+        tracing::info!("{} {}: {}", glyph("📁"), direction, file);
     }
 
     /// Log health check result
     pub fn health_check(endpoint: &str, status: bool, response_time_ms: Option<u128>) {
         if status {
             if let Some(ms) = response_time_ms {
-                tracing::info!("💚 Health OK: {} ({}ms)", endpoint, ms);
+                tracing::info!("{} Health OK: {} ({}ms)", glyph("💚"), endpoint, ms);
             } else {
-                tracing::info!("💚 Health OK: {}", endpoint);
+                tracing::info!("{} Health OK: {}", glyph("💚"), endpoint);
             }
         } else {
-            tracing::warn!("💔 Health FAIL: {}", endpoint);
+            tracing::warn!("{} Health FAIL: {}", glyph("💔"), endpoint);
         }
     }
 
     /// Log dry run message
     pub fn dry_run(operation: &str) {
-        tracing::info!("🌵 DRY RUN: Would execute '{}'", operation);
+        tracing::info!("{} DRY RUN: Would execute '{}'", glyph("🌵"), operation);
     }
 
     /// Log configuration loading
     pub fn config_loaded(path: &str) {
-        tracing::info!("📋 Configuration loaded from: {}", path);
+        tracing::info!("{} Configuration loaded from: {}", glyph("📋"), path);
+    }
+
+    /// Log a setting that is probably a mistake but doesn't stop the command
+    pub fn config_warning(warning: &str) {
+        tracing::warn!("{} Config: {}", glyph("⚠️"), warning);
     }
 
     /// Log configuration validation
     pub fn config_validated() {
-        tracing::debug!("✅ Configuration validation passed");
+        tracing::debug!("{} Configuration validation passed", glyph("✅"));
     }
 }
 
@@ -188,6 +296,18 @@ mod tests {
         assert_eq!(LogLevel::from_number(10).as_filter(), "info"); // default
     }
 
+    #[test]
+    fn test_ascii_glyphs() {
+        assert!(GLYPHS.iter().all(|(_, plain)| plain.is_ascii()));
+        assert_eq!(plain("⚠️"), "[WARN]");
+        assert_eq!(plain("🟢"), "[UP]");
+
+        assert!(needs_ascii("linux", Some("en_US.UTF-8")));
+        assert!(needs_ascii("xterm-256color", Some("C")));
+        assert!(!needs_ascii("xterm-256color", Some("en_US.utf8")));
+        assert!(!needs_ascii("xterm-256color", None));
+    }
+
     #[test]
     fn test_log_level_numbers() {
         assert_eq!(LogLevel::Error.as_number(), 1);
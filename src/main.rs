@@ -9,7 +9,8 @@ mod tui;
 mod utils;
 
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{AuthAction, Cli, Commands, ConfigAction, TuiTab};
+use logging::glyph;
 
 #[tokio::main]
 async fn main() {
@@ -20,11 +21,15 @@ async fn main() {
         process::exit(1);
     }
 
+    logging::set_ascii(cli.ascii || logging::ascii_terminal());
+    utils::prompt::configure(cli.non_interactive, cli.yes);
+
     if let Err(e) = init_logging(&cli) {
         eprintln!("Failed to initialize logging: {}", e);
         process::exit(1);
     }
 
+    utils::interrupt::install();
     if let Err(e) = run(cli).await {
         logging::log::operation_failed("Application", &e.to_string());
         eprintln!("Error: {}", e);
@@ -40,11 +45,29 @@ fn init_logging(cli: &Cli) -> Result<()> {
 
 /// Main application logic
 async fn run(cli: Cli) -> Result<()> {
-    let config = load_configuration(&cli)?;
+    // `rzen config` only needs its arguments, and helps fix a config that doesn't load
+    if let Some(Commands::Config { action }) = &cli.command {
+        return manage_config_values(action.clone(), &cli);
+    }
+
+    let mut config = load_configuration(&cli)?;
+    // `rzen validate` lists them with its results
+    if !matches!(cli.command, Some(Commands::Validate { .. })) {
+        for warning in config.warnings() {
+            logging::log::config_warning(&warning);
+        }
+    }
+    // `rzen auth` puts the password into the keyring, so it can't need it yet
+    let needs_secret = !matches!(
+        cli.command,
+        Some(Commands::Auth { .. } | Commands::Init { .. } | Commands::Validate { .. })
+    );
+    if needs_secret {
+        config.load_password()?;
+    }
 
     if cli.should_run_tui() {
-        logging::log::operation_start("Starting TUI interface");
-        tui::run_tui(config).await?;
+        start_tui(&cli, config, None).await?;
     } else if let Some(ref command) = cli.command {
         handle_command(command.clone(), config, &cli).await?;
     }
@@ -52,22 +75,46 @@ async fn run(cli: Cli) -> Result<()> {
     Ok(())
 }
 
+/// Start the TUI, optionally on a specific tab
+async fn start_tui(cli: &Cli, config: config::Config, tab: Option<TuiTab>) -> Result<()> {
+    if utils::prompt::is_non_interactive() {
+        return Err(anyhow::anyhow!(
+            "The TUI can't run with prompts turned off (--non-interactive, --yes or CI); run a subcommand instead"
+        ));
+    }
+    logging::log::operation_start("Starting TUI interface");
+    tui::run_tui(config, config_path(cli)?, tab.map(tui::Tab::from)).await
+}
+
+/// Config file given with --config, or the first one found in the default locations
+fn config_path(cli: &Cli) -> Result<std::path::PathBuf> {
+    match &cli.config {
+        Some(path) => Ok(path.clone()),
+        None => config::Config::default_location(),
+    }
+}
+
 /// Load configuration from file or create default
 fn load_configuration(cli: &Cli) -> Result<config::Config> {
     let config_path = cli.config.as_ref();
+    let environment = cli.environment();
+    let project = cli.project.as_deref();
 
     match config_path {
         Some(path) => {
             logging::log::config_loaded(&path.display().to_string());
-            config::Config::from_file(path)
-        }
-        None => config::Config::from_default_location().or_else(|_| {
-            println!(
-                "No configuration file found. Would you like to create a default rzen.toml? (y/N): "
-            );
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if input.trim().to_lowercase() == "y" {
+            config::Config::from_file(path, environment.as_deref(), project)
+        }
+        // Errors in an existing file (like an unknown environment) are reported as they are
+        None if config::Config::default_location().is_ok() => {
+            config::Config::from_default_location(environment.as_deref(), project)
+        }
+        // Creating a placeholder config unattended would only hide the missing one
+        None if !utils::prompt::can_ask() => Err(anyhow::anyhow!(
+            "No configuration file found; create one with `rzen init` or pass --config"
+        )),
+        None => {
+            if utils::prompt::confirm("No configuration file found. Would you like to create a default rzen.toml?", false) {
                 config::Config::create_default("rzen.toml")?;
                 println!("Created default configuration file: rzen.toml");
                 println!("Please edit it with your project settings before running rzen again.");
@@ -75,7 +122,7 @@ fn load_configuration(cli: &Cli) -> Result<config::Config> {
             } else {
                 Err(anyhow::anyhow!("Configuration required"))
             }
-        }),
+        }
     }
 }
 
@@ -89,76 +136,280 @@ async fn handle_command(command: Commands, config: config::Config, cli: &Cli) ->
             let build_mode = mode.as_deref();
             commands::build::build_project(&config, build_mode, cli.dry_run).await?;
         }
-        Commands::Deploy { skip_build, force } => {
-            commands::deploy::deploy_project(&config, skip_build, force, cli.dry_run).await?;
+        Commands::Deploy {
+            skip_build,
+            force,
+            artifact,
+            github_release,
+            sha256,
+            skip_checksum,
+            git_ref,
+        } => {
+            use commands::deploy::artifact::ArtifactSource;
+
+            let source = match (artifact, github_release) {
+                (Some(artifact), _) => Some(ArtifactSource::parse(&artifact)),
+                (None, Some(tag)) => Some(ArtifactSource::GithubRelease(tag)),
+                (None, None) => None,
+            };
+
+            let started = std::time::Instant::now();
+            let (origin, message) = match (source, git_ref) {
+                (Some(source), _) => {
+                    let (source, message) = commands::deploy::artifact::deploy_artifact(
+                        &config,
+                        &source,
+                        sha256.as_deref(),
+                        skip_checksum,
+                        cli.dry_run,
+                    )
+                    .await?;
+                    (source.describe(), message)
+                }
+                (None, Some(git_ref)) => {
+                    let message = commands::deploy::git_ref::deploy_ref(&config, &git_ref, cli.dry_run).await?;
+                    (format!("git ref {}", git_ref), message)
+                }
+                (None, None) => {
+                    let message = commands::deploy::deploy_project(&config, skip_build, force, cli.dry_run).await?;
+                    ("local build".to_string(), message)
+                }
+            };
+            if cli.output == cli::OutputFormat::Json {
+                let summary = commands::deploy::DeploySummary {
+                    project: config.project.name.clone(),
+                    binary: config.binary_name(),
+                    hosts: config.hosts(),
+                    source: origin,
+                    dry_run: cli.dry_run,
+                    duration_secs: started.elapsed().as_secs_f64(),
+                    message,
+                };
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            }
         }
-        Commands::Monitor { continuous, lines } => {
-            if continuous {
-                commands::monitor::monitor_application(&config, continuous, lines).await?;
+        Commands::Monitor {
+            continuous,
+            duration,
+            cycles,
+            lines,
+            report,
+            period,
+        } => {
+            if let Some(path) = report {
+                commands::monitor::report::write_report(&config, &path, period)?;
             } else {
-                commands::monitor::monitor_application(&config, false, lines).await?;
+                let limits = commands::monitor::RunLimits { duration, cycles };
+                // Continuous monitoring takes over edits to the config file as it runs
+                let watcher = if continuous {
+                    Some(config::reload::ConfigWatcher::new(config_path(cli)?, &config))
+                } else {
+                    None
+                };
+                commands::monitor::monitor_application(&config, continuous, lines, limits, cli.output, watcher).await?;
             }
         }
         Commands::Init { path, name, host } => {
             init_configuration(path, name, host)?;
         }
-        Commands::Validate { path } => {
-            validate_configuration(path)?;
+        Commands::Validate { path, remote } => {
+            validate_configuration(path, cli.environment(), cli.project.clone(), remote, cli.output).await?;
+        }
+        Commands::Auth { action } => {
+            manage_keyring_password(action, &config)?;
+        }
+        Commands::Config { action } => {
+            manage_config_values(action, cli)?;
         }
         Commands::Clean { cargo_args: _ } => {
             commands::build::clean_project(&config, cli.dry_run).await?;
         }
         Commands::Rollback => {
-            commands::deploy::rollback_deployment(&config).await?;
+            commands::deploy::rollback_deployment(&config, None).await?;
+        }
+        Commands::Releases { action: cli::ReleasesAction::Prune { keep } } => {
+            let keep = keep.map_or(config.deploy.keep_backups, |keep| keep as usize);
+            if keep == 0 {
+                return Err(anyhow::anyhow!("deploy.keep_backups is 0, so nothing is pruned; pass --keep N"));
+            }
+            let verb = if cli.dry_run { "Would remove" } else { "Removed" };
+            for (host, backups) in commands::deploy::prune_releases(&config, keep, cli.dry_run).await? {
+                if backups.is_empty() {
+                    println!("{} {}: no more than {} backups to keep", glyph("✅"), host, keep);
+                    continue;
+                }
+                let size = backups.iter().map(|backup| backup.size).sum();
+                println!(
+                    "{} {}: {} {} old backups ({}), keeping the newest {}",
+                    glyph("✅"), host, verb, backups.len(), utils::fs::format_size(size), keep
+                );
+            }
         }
-        Commands::Logs { lines, follow } => {
+        Commands::Logs {
+            lines,
+            follow,
+            grep,
+            exclude,
+            level,
+            service,
+            since,
+            until,
+            fields,
+            raw,
+            download,
+        } => {
+            use commands::monitor::{logs, structured};
+
+            let source = match service {
+                Some(unit) => logs::LogSource::Journal(unit),
+                None => logs::LogSource::from_config(&config),
+            };
+
+            if let Some(local_path) = download {
+                let host = utils::host::remote(&config).await?;
+                let bar = utils::progress::transfer_progress(0);
+                let on_progress = |transfer: &utils::progress::Transfer| utils::progress::show_transfer(&bar, transfer);
+                logs::download(host.as_ref(), &source, &local_path, Some(&on_progress))?;
+                bar.finish_and_clear();
+                println!("{} Saved {} to {}", glyph("✅"), source.describe(), local_path.display());
+                return Ok(());
+            }
+
+            let filter = logs::LogFilter::new(grep.as_deref(), exclude.as_deref())?;
+            let query = logs::LogQuery {
+                lines,
+                follow,
+                range: logs::TimeRange::parse(
+                    since.as_deref(),
+                    until.as_deref(),
+                    chrono::Utc::now(),
+                    config.display_timezone(),
+                )?,
+                level,
+            };
+            let columns = (!raw).then(|| fields.iter().map(|f| structured::LogField::parse(f)).collect());
             if follow {
-                commands::monitor::stream_logs(&config).await?;
+                commands::monitor::stream_logs(&config, &source, &query, &filter, columns).await?;
             } else {
-                // Show last N lines without following
-                let ssh_config = utils::ssh::SshConfig {
-                    host: config.deploy.vps_host.clone(),
-                    port: config.deploy.ssh_port,
-                    username: config.deploy.vps_user.clone(),
-                    key_path: config.deploy.vps_key_path.clone(),
-                    password: config.deploy.vps_password.clone(),
-                };
+                // Show last N lines, or the requested time range, without following
+                let ssh_config = config.ssh_config();
 
-                let session = utils::ssh::connect_with_retry(&ssh_config, 3).await?;
-                let log_path = config.monitor.log_path.as_deref()
-                    .unwrap_or("/var/log/my-rust-app.log");
+                let connection = utils::ssh::connect(&ssh_config).await?;
 
                 let (output, _) = utils::ssh::execute_command(
-                    &session,
-                    &format!("tail -n {} {}", lines, log_path)
+                    &connection,
+                    &filter.remote_command(&source, &query)
                 )?;
 
-                for line in output.lines() {
-                    if !line.trim().is_empty() {
-                        println!("📜 {}", line);
+                let server_timezone = logs::server_timezone(&connection);
+                let converter = logs::TimestampConverter::for_config(&config, server_timezone);
+                let mut formatter = structured::LogFormatter::new(columns, converter);
+                let mut range = logs::RangeFilter::new(query.range, server_timezone);
+                let mut levels = logs::SeverityFilter::new(query.level);
+                let matching: Vec<&str> = output
+                    .lines()
+                    .filter(|line| !line.trim().is_empty() && levels.accepts(line) && range.accepts(line) && filter.matches(line))
+                    .collect();
+                // Levels are checked here, so the server sent the whole log
+                let shown = if query.range.is_unbounded() { query.lines } else { matching.len() };
+                for line in &matching[matching.len().saturating_sub(shown)..] {
+                    for output in formatter.format(line) {
+                        println!("{}", output);
                     }
                 }
             }
         }
+        Commands::Backup { dir } => {
+            let dir = dir.unwrap_or_else(|| {
+                format!("rzen-backup-{}", chrono::Utc::now().format("%Y%m%d%H%M%S")).into()
+            });
+            let saved = commands::deploy::backup_config_files(&config, &dir).await?;
+            println!("{} Saved {} config files to {}", glyph("✅"), saved, dir.display());
+        }
+        Commands::Coredump { file } => {
+            let local_path = file.unwrap_or_else(|| format!("{}.core", config.binary_name()).into());
+            let host = utils::host::connect(&config).await?;
+            let bar = utils::progress::transfer_progress(0);
+            let on_progress = |transfer: &utils::progress::Transfer| utils::progress::show_transfer(&bar, transfer);
+            commands::monitor::crashloop::download_core_dump(host.as_ref(), &config, &local_path, Some(&on_progress))?;
+            bar.finish_and_clear();
+            println!("{} Saved the newest core dump of {} to {}", glyph("✅"), config.binary_name(), local_path.display());
+        }
+        Commands::Service { action, service } => {
+            commands::service::manage_service(&config, action, service.as_deref(), cli.dry_run, cli.output).await?;
+        }
+        Commands::Exec { command } => {
+            let code = commands::exec::run_command(&config, &command.join(" "), cli.dry_run).await?;
+            if code != 0 {
+                process::exit(code);
+            }
+        }
+        Commands::Cp { local, remote, recursive } => {
+            for copied in commands::transfer::upload(&config, &local, &remote, recursive, cli.dry_run).await? {
+                println!("{} Copied {} to {}:{} ({})", glyph("✅"), copied.local.display(), copied.host, copied.remote, utils::fs::format_size(copied.size));
+            }
+        }
+        Commands::Fetch { remote, local, recursive } => {
+            for copied in commands::transfer::fetch(&config, &remote, &local, recursive, cli.dry_run).await? {
+                println!("{} Saved {}:{} to {} ({})", glyph("✅"), copied.host, copied.remote, copied.local.display(), utils::fs::format_size(copied.size));
+            }
+        }
         Commands::Status => {
-            let status = commands::deploy::check_deployment_status(&config).await?;
-            println!("🚀 Deployment Status:");
-            println!("  Service Active: {}", if status.service_active { "✅ Yes" } else { "❌ No" });
-            if let Some(deployment) = &status.last_deployment {
-                println!("  Last Deployment: {}", deployment);
+            let statuses = commands::deploy::check_deployment_status(&config).await?;
+            if cli.output == cli::OutputFormat::Json {
+                println!("{}", serde_json::to_string_pretty(&statuses)?);
+                return Ok(());
             }
-            if let Some(version) = &status.version {
-                println!("  Version Info: {}", version);
+            println!("{} Deployment Status:", glyph("🚀"));
+            if let [status] = statuses.as_slice() {
+                let active = if status.service_active {
+                    format!("{} Yes", glyph("✅"))
+                } else {
+                    format!("{} No", glyph("❌"))
+                };
+                println!("  Service Active: {}", active);
+                if let Some(deployment) = &status.last_deployment {
+                    println!("  Last Deployment: {}", deployment);
+                }
+                if let Some(version) = &status.version {
+                    println!("  Version Info: {}", version);
+                }
+            } else {
+                let width = statuses.iter().map(|s| s.host.len()).max().unwrap_or(4).max(4);
+                println!("  {:<width$}  {:<6}  {:<23}  VERSION", "HOST", "ACTIVE", "LAST DEPLOYMENT");
+                for status in &statuses {
+                    println!(
+                        "  {:<width$}  {:<6}  {:<23}  {}",
+                        status.host,
+                        if status.service_active { "yes" } else { "no" },
+                        status.last_deployment.as_deref().unwrap_or("-"),
+                        status.version.as_deref().unwrap_or("-")
+                    );
+                }
+                let active = statuses.iter().filter(|s| s.service_active).count();
+                println!("{}", commands::monitor::fleet::verdict(active, statuses.len()));
             }
         }
+        Commands::Incidents { limit } => {
+            commands::monitor::incidents::show_incidents(&config, limit)?;
+        }
         Commands::CheckRebuild => {
             let needs_rebuild = commands::build::needs_rebuild(&config)?;
-            if needs_rebuild {
-                println!("🔄 Project needs rebuilding");
+            if cli.output == cli::OutputFormat::Json {
+                let result = serde_json::json!({
+                    "project": config.project.name,
+                    "needs_rebuild": needs_rebuild,
+                });
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else if needs_rebuild {
+                println!("{} Project needs rebuilding", glyph("🔄"));
             } else {
-                println!("✅ Project is up to date");
+                println!("{} Project is up to date", glyph("✅"));
             }
         }
+        Commands::Tui { tab, tab_option } => {
+            start_tui(cli, config, tab.or(tab_option)).await?;
+        }
     }
 
     Ok(())
@@ -174,6 +425,7 @@ fn init_configuration(
 
     if name.is_some() || host.is_some() {
         let mut config = config::Config {
+            version: config::CONFIG_VERSION,
             project: config::ProjectConfig {
                 path: ".".to_string(),
                 name: name.unwrap_or_else(|| "my-rust-app".to_string()),
@@ -181,20 +433,29 @@ fn init_configuration(
             },
             deploy: config::DeployConfig {
                 target: "vps".to_string(),
-                vps_host: host.unwrap_or_else(|| "your-vps.example.com".to_string()),
+                vps_host: host.unwrap_or_else(|| config::EXAMPLE_HOST.to_string()),
                 vps_user: "deploy".to_string(),
                 vps_key_path: Some("~/.ssh/id_rsa".to_string()),
                 vps_password: None,
                 deploy_path: "/opt/my-rust-app".to_string(),
                 service_name: None,
                 ssh_port: 22,
+                ..Default::default()
             },
             monitor: config::MonitorConfig {
                 health_endpoint: Some("http://your-vps.example.com:8080/health".to_string()),
                 log_path: Some("/var/log/my-rust-app.log".to_string()),
                 interval_secs: 10,
                 health_timeout_secs: 5,
+                ..Default::default()
             },
+            hooks: Default::default(),
+            tui: Default::default(),
+            retry: Default::default(),
+            env: Default::default(),
+            projects: Default::default(),
+            overrides: Default::default(),
+            extends: None,
         };
 
         config.deploy.service_name = Some(format!("{}.service", config.project.name));
@@ -215,30 +476,336 @@ fn init_configuration(
     Ok(())
 }
 
-/// Validate a configuration file
-fn validate_configuration(path: std::path::PathBuf) -> Result<()> {
-    logging::log::operation_start(&format!("Validating configuration: {}", path.display()));
+/// `rzen config`: read or change settings, encrypt or decrypt values, print the config schema,
+/// or upgrade the config file
+fn manage_config_values(action: ConfigAction, cli: &Cli) -> Result<()> {
+    let environment = cli.environment();
+    let project = cli.project.as_deref();
+    match action {
+        ConfigAction::Encrypt { value, recipient } => {
+            let value = match value {
+                Some(value) => value,
+                None if !utils::prompt::can_ask() => {
+                    return Err(utils::prompt::unavailable("the value to encrypt", "pass it as an argument"));
+                }
+                None => rpassword::prompt_password("Value to encrypt: ").context("Failed to read the value")?,
+            };
+            println!("{}", utils::secrets::encrypt(&value, recipient.as_deref())?);
+        }
+        ConfigAction::Decrypt { value } => {
+            let identity = utils::secrets::identity_path()?;
+            println!("{}", utils::secrets::decrypt(&value, &identity)?);
+        }
+        ConfigAction::Schema => {
+            println!("{}", serde_json::to_string_pretty(&config::Config::schema())?);
+        }
+        ConfigAction::Get { key } => {
+            let config = config::Config::from_file(config_path(cli)?, environment.as_deref(), project)?;
+            match config.setting(&key)? {
+                Some(toml::Value::String(value)) => println!("{}", value),
+                Some(toml::Value::Table(table)) => print!("{}", toml::to_string_pretty(&table)?),
+                Some(value) => println!("{}", value),
+                None => return Err(anyhow::anyhow!("{} is not set", key)),
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let path = config_path(cli)?;
+            let mut document = config::read_document(&path)?;
+            config::migrate(&mut document)?;
+            config::set_value(&mut document, environment.as_deref(), project, &key, &value)?;
+            config::Config::from_document(&path, document.clone(), environment.as_deref(), project)
+                .map_err(|e| anyhow::anyhow!("Not saved, {} = {} is invalid: {}", key, value, e))?;
+            std::fs::write(&path, document.to_string())
+                .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+            let section = project
+                .map(|name| format!(" for project '{}'", name))
+                .or_else(|| environment.map(|name| format!(" for environment '{}'", name)));
+            println!("{} Set {}{} in {}", glyph("✅"), key, section.unwrap_or_default(), path.display());
+        }
+        ConfigAction::Migrate { write } => {
+            let path = config_path(cli)?;
+            let mut document = config::read_document(&path)?;
+            let version = config::migrate(&mut document)?;
+            if !write {
+                print!("{}", document);
+                return Ok(());
+            }
+            std::fs::write(&path, document.to_string())
+                .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+            if version < config::CONFIG_VERSION {
+                println!(
+                    "{} Migrated {} from version {} to {}",
+                    glyph("✅"),
+                    path.display(),
+                    version,
+                    config::CONFIG_VERSION
+                );
+            } else {
+                println!("{} {} is at version {}", glyph("✅"), path.display(), version);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Store or remove the SSH password for the configured user and a host in the OS keyring
+fn manage_keyring_password(action: AuthAction, config: &config::Config) -> Result<()> {
+    let (AuthAction::Set { host } | AuthAction::Delete { host }) = &action;
+    let host = host.as_ref().unwrap_or(&config.deploy.vps_host);
+    if !config.hosts().contains(host) {
+        return Err(anyhow::anyhow!(
+            "{} is not one of the configured hosts: {}",
+            host,
+            config.hosts().join(", ")
+        ));
+    }
+    let user = &config.deploy.vps_user;
+    match action {
+        AuthAction::Set { .. } => {
+            if !utils::prompt::can_ask() {
+                return Err(utils::prompt::unavailable(
+                    "the password",
+                    "store it from a terminal, or set password_command instead",
+                ));
+            }
+            let prompt = format!("Password or key passphrase for {}@{}: ", user, host);
+            let secret = rpassword::prompt_password(prompt).context("Failed to read the password")?;
+            if secret.is_empty() {
+                return Err(anyhow::anyhow!("Password cannot be empty"));
+            }
+            utils::secrets::set(user, host, &secret)?;
+            println!("{} Stored the password for {}@{} in the keyring", glyph("✅"), user, host);
+            if config.deploy.password_source.as_deref() != Some("keyring") {
+                println!("Set password_source = \"keyring\" under [deploy] to use it");
+            }
+        }
+        AuthAction::Delete { .. } => {
+            if utils::secrets::delete(user, host)? {
+                println!("{} Removed the password for {}@{} from the keyring", glyph("✅"), user, host);
+            } else {
+                println!("No password for {}@{} in the keyring", user, host);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Result of `rzen validate`, printed with `--output json`
+#[derive(Debug, Default, serde::Serialize)]
+struct ValidationReport {
+    path: std::path::PathBuf,
+    valid: bool,
+    /// Why the configuration is invalid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    projects: Vec<ProjectReport>,
+    remote_checks: usize,
+    remote_checks_failed: usize,
+}
+
+/// Settings and findings for one project of a validated config file
+#[derive(Debug, serde::Serialize)]
+struct ProjectReport {
+    name: String,
+    extends: Option<String>,
+    build_mode: String,
+    deploy_target: String,
+    health_endpoint: Option<String>,
+    warnings: Vec<String>,
+    /// Environments checked, with the warnings they add to the base settings
+    environments: Vec<EnvironmentReport>,
+    /// Files read in an older layout
+    migrated: Vec<MigratedFile>,
+    /// Settings overridden by RZEN_* variables
+    overridden: Vec<String>,
+    remote_checks: Vec<RemoteCheck>,
+}
 
-    let config = config::Config::from_file(&path)?;
-    config.validate()?;
+#[derive(Debug, serde::Serialize)]
+struct EnvironmentReport {
+    name: String,
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct MigratedFile {
+    file: std::path::PathBuf,
+    /// Layout version the file is in
+    version: u32,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct RemoteCheck {
+    host: String,
+    name: String,
+    passed: bool,
+    detail: String,
+}
+
+/// Validate a configuration file: the given environment, or the base settings and every
+/// environment; for the given project, or each of its `[[projects]]`
+///
+/// With `--output json` the result is printed as one JSON document, an invalid file included.
+async fn validate_configuration(
+    path: std::path::PathBuf,
+    environment: Option<String>,
+    project: Option<String>,
+    remote: bool,
+    output: cli::OutputFormat,
+) -> Result<()> {
+    logging::log::operation_start(&format!("Validating configuration: {}", path.display()));
+    let text = output == cli::OutputFormat::Text;
+
+    let mut report = ValidationReport { path: path.clone(), ..Default::default() };
+    let configs = match validate_locally(&path, environment.as_deref(), project, text) {
+        Ok(configs) => configs,
+        Err(e) if !text => {
+            report.error = Some(format!("{:#}", e));
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Err(e);
+        }
+        Err(e) => return Err(e),
+    };
+    report.valid = true;
 
     logging::log::config_validated();
     logging::log::operation_success("Configuration validation passed");
-    println!("✅ Configuration file is valid: {}", path.display());
+    if text {
+        println!("{} Configuration file is valid: {}", glyph("✅"), path.display());
+    }
 
-    println!("Project: {}", config.project.name);
-    println!("Build Mode: {}", config.project.build_mode);
-    println!(
-        "Deploy Target: {} @ {}",
-        config.deploy.vps_user, config.deploy.vps_host
-    );
-    if let Some(endpoint) = &config.monitor.health_endpoint {
-        println!("Health Endpoint: {}", endpoint);
+    for (mut config, mut project) in configs {
+        if text {
+            println!();
+            println!("Project: {}", project.name);
+            if let Some(extends) = &project.extends {
+                println!("Extends: {}", extends);
+            }
+            for migrated in &project.migrated {
+                println!(
+                    "Upgraded from layout version {}: {} (run `rzen config migrate --write` to update it)",
+                    migrated.version,
+                    migrated.file.display()
+                );
+            }
+            if !project.overridden.is_empty() {
+                println!("Overridden by RZEN_* variables: {}", project.overridden.join(", "));
+            }
+            for warning in &project.warnings {
+                println!("{} Warning: {}", glyph("⚠️"), warning);
+            }
+            println!("Build Mode: {}", project.build_mode);
+            println!("Deploy Target: {}", project.deploy_target);
+            if let Some(endpoint) = &project.health_endpoint {
+                println!("Health Endpoint: {}", endpoint);
+            }
+        }
+        if remote {
+            config.load_password()?;
+            for host in config.hosts() {
+                if text {
+                    println!();
+                    println!("Remote checks for {}:", host);
+                }
+                for result in commands::deploy::preflight::check_host(&config.for_host(&host)).await {
+                    if text {
+                        println!("  {}", result.line());
+                    }
+                    report.remote_checks += 1;
+                    report.remote_checks_failed += usize::from(!result.passed);
+                    project.remote_checks.push(RemoteCheck {
+                        host: host.clone(),
+                        name: result.name,
+                        passed: result.passed,
+                        detail: result.detail,
+                    });
+                }
+            }
+        }
+        report.projects.push(project);
+    }
+
+    if !text {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    }
+    let (checks, failed) = (report.remote_checks, report.remote_checks_failed);
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{} of {} remote checks failed", failed, checks));
+    }
+    if remote && text {
+        println!();
+        println!("{} All {} remote checks passed", glyph("✅"), checks);
     }
 
     Ok(())
 }
 
+/// Load and validate each selected project and environment of the file at `path`, listing
+/// the valid environments as text with `text` set
+fn validate_locally(
+    path: &std::path::Path,
+    environment: Option<&str>,
+    project: Option<String>,
+    text: bool,
+) -> Result<Vec<(config::Config, ProjectReport)>> {
+    let projects = match project {
+        Some(name) => vec![name],
+        None => config::Config::projects_in(path)?,
+    };
+    let mut configs = Vec::new();
+    // A file without [[projects]] is validated as it is
+    let selections: Vec<Option<&str>> = if projects.is_empty() {
+        vec![None]
+    } else {
+        projects.iter().map(|name| Some(name.as_str())).collect()
+    };
+    for project in selections {
+        let config = config::Config::from_file(path, environment, project)?;
+        config.validate()?;
+        let warnings = config.warnings();
+        let mut environments = Vec::new();
+        if environment.is_none() {
+            for name in config.environments() {
+                let env_config = config::Config::from_file(path, Some(name), project)?;
+                // Those of the base settings are listed with them
+                let added: Vec<String> =
+                    env_config.warnings().into_iter().filter(|warning| !warnings.contains(warning)).collect();
+                if text {
+                    match project {
+                        Some(project) => {
+                            println!("{} Environment '{}' of project '{}' is valid", glyph("✅"), name, project)
+                        }
+                        None => println!("{} Environment '{}' is valid", glyph("✅"), name),
+                    }
+                    for warning in &added {
+                        println!("   {} {}", glyph("⚠️"), warning);
+                    }
+                }
+                environments.push(EnvironmentReport { name: name.to_string(), warnings: added });
+            }
+        }
+        let summary = ProjectReport {
+            name: config.project.name.clone(),
+            extends: config.extends.clone(),
+            build_mode: config.project.build_mode.clone(),
+            deploy_target: format!("{} @ {}", config.ssh_config().username, config.deploy.vps_host),
+            health_endpoint: config.monitor.health_endpoint.clone(),
+            warnings,
+            environments,
+            migrated: config
+                .overrides
+                .migrated
+                .iter()
+                .map(|(file, version)| MigratedFile { file: file.clone(), version: *version })
+                .collect(),
+            overridden: config.overrides.variables.clone(),
+            remote_checks: Vec::new(),
+        };
+        configs.push((config, summary));
+    }
+    Ok(configs)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,6 +817,12 @@ mod tests {
             config: None,
             log_level: 3,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: cli::OutputFormat::Text,
             command: None,
         };
         assert!(cli.should_run_tui());
@@ -258,6 +831,12 @@ mod tests {
             config: None,
             log_level: 3,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: cli::OutputFormat::Text,
             command: Some(Commands::Build {
                 mode: None,
                 cargo_args: vec![],
@@ -272,6 +851,12 @@ mod tests {
             config: None,
             log_level: 1,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: cli::OutputFormat::Text,
             command: None,
         };
         assert_eq!(cli.log_filter(), "error");
@@ -280,6 +865,12 @@ mod tests {
             config: None,
             log_level: 3,
             dry_run: false,
+            ascii: false,
+            env: None,
+            project: None,
+            non_interactive: false,
+            yes: false,
+            output: cli::OutputFormat::Text,
             command: None,
         };
         assert_eq!(cli.log_filter(), "info");
@@ -297,7 +888,7 @@ mod tests {
         );
         assert!(result.is_ok());
 
-        let config = config::Config::from_file(&config_path);
+        let config = config::Config::from_file(&config_path, None, None);
         assert!(config.is_ok());
         assert_eq!(config.unwrap().project.name, "test-app");
     }
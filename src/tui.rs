@@ -1,36 +1,82 @@
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEvent, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Tabs, Wrap},
+    text::{Line, Span, Text},
+    widgets::{
+        block::Title, Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Sparkline, Tabs, Wrap,
+    },
     Frame, Terminal,
 };
+use futures::StreamExt;
 use std::{
+    borrow::Cow,
     io,
-    sync::{Arc, Mutex},
-    time::Duration,
+    path::PathBuf,
+    time::{Duration, Instant},
 };
 use tokio::sync::mpsc;
 
+use crate::cli::TuiTab;
 use crate::config::Config;
-use crate::logging::log;
-
-/// Actions for the event loop
-enum Action {
-    Quit,
-    NextTab,
-    PrevTab,
-    StartBuild,
-    StartDeploy,
-    StartMonitor,
-    ClearStatus,
-}
+use crate::config::reload::{ConfigWatcher, Reload};
+use crate::logging::{glyph, log};
+use crate::utils::git;
+
+mod buffer;
+mod clipboard;
+mod console;
+mod editor;
+mod errors;
+mod export;
+mod keys;
+mod levels;
+mod lines;
+mod palette;
+mod search;
+mod selection;
+mod theme;
+mod vim;
+
+use buffer::LogBuffer;
+use keys::{Action, KeyMap};
+use levels::LevelFilter;
+use palette::CommandPalette;
+use selection::LogSelection;
+use theme::Theme;
+
+/// Rows of the title bar, tab bar and status bar around the current tab
+const TITLE_HEIGHT: u16 = 3;
+const TABS_HEIGHT: u16 = 3;
+const STATUS_HEIGHT: u16 = 3;
+
+/// How often the Build tab re-reads the project's git status
+const GIT_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often the config file is checked for changes made outside the TUI
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Settings the live log stream is started with; changing one restarts it
+const LOG_STREAM_SETTINGS: [&str; 4] = [
+    "deploy.",
+    "monitor.log_path",
+    "monitor.timezone",
+    "monitor.show_original_timestamps",
+];
+
+/// Smallest terminal the tabs are laid out for
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 20;
+
+/// Response times kept for the Dashboard latency sparkline
+const MAX_LATENCY_SAMPLES: usize = 120;
 
 /// Main TUI application
 pub struct App {
@@ -41,6 +87,44 @@ pub struct App {
     pub build_state: BuildState,
     pub deploy_state: DeployState,
     pub monitor_state: MonitorState,
+    pub config_editor: editor::ConfigEditor,
+    pub keys: KeyMap,
+    pub theme: Theme,
+    /// Key binding overview is shown over the current tab
+    pub show_help: bool,
+    /// Quit confirmation dialog is open
+    pub confirm_quit: bool,
+    /// Command palette while it is open
+    pub palette: Option<CommandPalette>,
+    /// `/` search over the current tab's log pane
+    pub log_search: search::LogSearch,
+    /// Line selection in the current tab's log pane while copying
+    pub log_selection: Option<LogSelection>,
+    /// Severity filter applied to every log pane
+    pub log_level: LevelFilter,
+    /// Log lines wrap instead of being cut at the pane's edge
+    pub log_wrap: bool,
+    /// Columns hidden at the left of unwrapped log lines
+    pub log_hscroll: usize,
+    /// Most recent failure, with its full error chain
+    pub last_error: Option<errors::ErrorDetail>,
+    /// Whether the error popup is open
+    pub show_error: bool,
+    /// Interactive shell of the Console tab
+    pub console: console::Console,
+    /// Vim-style keys, when `[tui] vim` is on
+    pub vim: Option<vim::VimMode>,
+    /// Host the Build/Deploy/Monitor tabs operate on; `None` means `deploy.vps_host`
+    pub target_host: Option<String>,
+    /// Cursor of the host picker popup while it is open
+    pub host_picker: Option<usize>,
+    /// Reloaded config waiting for confirmation because it changes the connection settings
+    pub pending_reload: Option<Reload>,
+    config_watcher: ConfigWatcher,
+    /// Last time the config file was checked for changes
+    config_polled: Option<Instant>,
+    monitor_task: Option<tokio::task::JoinHandle<()>>,
+    log_task: Option<tokio::task::JoinHandle<()>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -48,8 +132,9 @@ pub enum Tab {
     Build,
     Deploy,
     Monitor,
+    Dashboard,
+    Console,
     Config,
-    Exit,
 }
 
 impl Tab {
@@ -57,19 +142,21 @@ impl Tab {
         match self {
             Tab::Build => Tab::Deploy,
             Tab::Deploy => Tab::Monitor,
-            Tab::Monitor => Tab::Config,
-            Tab::Config => Tab::Exit,
-            Tab::Exit => Tab::Build,
+            Tab::Monitor => Tab::Dashboard,
+            Tab::Dashboard => Tab::Console,
+            Tab::Console => Tab::Config,
+            Tab::Config => Tab::Build,
         }
     }
 
     pub fn prev(&self) -> Self {
         match self {
-            Tab::Build => Tab::Exit,
+            Tab::Build => Tab::Config,
             Tab::Deploy => Tab::Build,
             Tab::Monitor => Tab::Deploy,
-            Tab::Config => Tab::Monitor,
-            Tab::Exit => Tab::Config,
+            Tab::Dashboard => Tab::Monitor,
+            Tab::Console => Tab::Dashboard,
+            Tab::Config => Tab::Console,
         }
     }
 
@@ -78,8 +165,22 @@ impl Tab {
             Tab::Build => "Build",
             Tab::Deploy => "Deploy",
             Tab::Monitor => "Monitor",
+            Tab::Dashboard => "Dashboard",
+            Tab::Console => "Console",
             Tab::Config => "Config",
-            Tab::Exit => "Exit",
+        }
+    }
+}
+
+impl From<TuiTab> for Tab {
+    fn from(tab: TuiTab) -> Self {
+        match tab {
+            TuiTab::Build => Tab::Build,
+            TuiTab::Deploy => Tab::Deploy,
+            TuiTab::Monitor => Tab::Monitor,
+            TuiTab::Dashboard => Tab::Dashboard,
+            TuiTab::Console => Tab::Console,
+            TuiTab::Config => Tab::Config,
         }
     }
 }
@@ -89,8 +190,12 @@ impl Tab {
 pub struct BuildState {
     pub is_building: bool,
     pub progress: f64,
-    pub logs: Vec<String>,
+    pub logs: LogBuffer,
     pub build_info: Option<crate::commands::build::BuildInfo>,
+    /// Git state of the project, or why it couldn't be read
+    pub git: Option<Result<git::RepoStatus, String>>,
+    /// Last time `git` was requested; refreshed while the Build tab is shown
+    pub git_checked: Option<Instant>,
 }
 
 /// Deploy tab state
@@ -100,8 +205,18 @@ pub struct DeployState {
     pub is_deploying: bool,
     pub progress: f64,
     pub current_step: String,
-    pub logs: Vec<String>,
+    pub logs: LogBuffer,
     pub deployment_status: Option<crate::commands::deploy::DeploymentStatus>,
+    /// Why the last status check failed
+    pub status_error: Option<String>,
+    /// Start of the last status check; polled every `monitor.interval_secs` on this tab
+    pub status_checked: Option<Instant>,
+    pub status_refreshing: bool,
+    /// Backups on the target host, newest first
+    pub backups: Vec<crate::commands::deploy::Backup>,
+    pub selected_backup: usize,
+    /// Rollback confirmation dialog is open for the selected backup
+    pub confirm_rollback: bool,
 }
 
 /// Monitor tab state
@@ -110,13 +225,23 @@ pub struct DeployState {
 pub struct MonitorState {
     pub is_monitoring: bool,
     pub status: Option<crate::commands::monitor::ApplicationStatus>,
-    pub logs: Vec<String>,
+    pub logs: LogBuffer,
+    /// Number of log lines shown while the log pane is paused
+    pub logs_paused_at: Option<usize>,
     pub metrics: Option<crate::commands::monitor::MonitoringMetrics>,
+    /// Response times of recent health checks in milliseconds, oldest first
+    pub latency_history: Vec<u64>,
 }
 
 impl App {
-    pub fn new(config: Config) -> Self {
-        Self {
+    pub fn new(config: Config, config_path: PathBuf) -> Result<Self> {
+        let keys = KeyMap::from_config(&config.tui)?;
+        let theme = Theme::from_config(&config.tui.theme)?;
+        let vim = config.tui.vim.then(vim::VimMode::default);
+        let log_lines = config.tui.log_lines;
+        let config_watcher = ConfigWatcher::new(config_path.clone(), &config);
+
+        Ok(Self {
             config,
             current_tab: Tab::Build,
             should_quit: false,
@@ -124,22 +249,795 @@ impl App {
             build_state: BuildState {
                 is_building: false,
                 progress: 0.0,
-                logs: Vec::new(),
+                logs: LogBuffer::new(log_lines),
                 build_info: None,
+                git: None,
+                git_checked: None,
             },
             deploy_state: DeployState {
                 is_deploying: false,
                 progress: 0.0,
                 current_step: "Ready".to_string(),
-                logs: Vec::new(),
+                logs: LogBuffer::new(log_lines),
                 deployment_status: None,
+                status_error: None,
+                status_checked: None,
+                status_refreshing: false,
+                backups: Vec::new(),
+                selected_backup: 0,
+                confirm_rollback: false,
             },
             monitor_state: MonitorState {
                 is_monitoring: false,
                 status: None,
-                logs: Vec::new(),
+                logs: LogBuffer::new(log_lines),
+                logs_paused_at: None,
                 metrics: None,
+                latency_history: Vec::new(),
             },
+            config_editor: editor::ConfigEditor::new(config_path),
+            keys,
+            theme,
+            show_help: false,
+            confirm_quit: false,
+            palette: None,
+            log_search: search::LogSearch::default(),
+            log_selection: None,
+            log_level: LevelFilter::default(),
+            log_wrap: false,
+            log_hscroll: 0,
+            last_error: None,
+            show_error: false,
+            console: console::Console::new(),
+            vim,
+            target_host: None,
+            host_picker: None,
+            pending_reload: None,
+            config_watcher,
+            config_polled: None,
+            monitor_task: None,
+            log_task: None,
+        })
+    }
+
+    /// Configuration for the selected target host
+    pub fn target_config(&self) -> Config {
+        match &self.target_host {
+            Some(host) if self.config.hosts().contains(host) => self.config.for_host(host),
+            _ => self.config.clone(),
+        }
+    }
+
+    /// Route a key press: popups and tab-specific keys first, then global actions
+    fn handle_key(&mut self, key: KeyEvent, tx: &mpsc::Sender<BackgroundMessage>) {
+        // A focused shell gets every key but its release key
+        if self.current_tab == Tab::Console && self.console.focused {
+            self.handle_console_key(key);
+            return;
+        }
+        // Popups and the Config tab take keys first so typed values don't trigger actions
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+        if self.show_error {
+            self.show_error = self.last_error.as_mut().is_some_and(|error| error.handle_key(key.code));
+            return;
+        }
+        if self.confirm_quit {
+            self.handle_quit_key(key.code);
+            return;
+        }
+        if self.pending_reload.is_some() {
+            self.handle_reload_key(key.code, tx);
+            return;
+        }
+        if let Some(palette) = &mut self.palette {
+            match palette.handle_key(key.code) {
+                palette::Outcome::Handled => {}
+                palette::Outcome::Run(action) => {
+                    self.palette = None;
+                    self.run_action(action, tx);
+                }
+                palette::Outcome::Close => self.palette = None,
+            }
+            return;
+        }
+        if self.handle_host_picker_key(key.code, tx) {
+            return;
+        }
+        if self.log_search.input.is_some() && self.handle_search_key(key.code) {
+            return;
+        }
+        let vim_prompt = self.vim.as_ref().is_some_and(|vim| vim.command.is_some());
+        if vim_prompt && self.handle_vim_key(&key, tx) {
+            return;
+        }
+        if self.log_selection.is_some() && self.handle_selection_key(key.code) {
+            return;
+        }
+        if self.current_tab == Tab::Deploy && self.handle_deploy_key(key.code, tx) {
+            return;
+        }
+        if self.current_tab == Tab::Monitor && self.handle_monitor_key(key.code) {
+            return;
+        }
+        if self.current_tab == Tab::Console && key.code == KeyCode::Enter {
+            if self.console.is_open() {
+                self.console.focused = true;
+            } else {
+                self.console.open(self.target_config(), tx);
+            }
+            return;
+        }
+        if self.current_tab == Tab::Config
+            && self.config_editor.handle_key(key.code, &mut self.config)
+        {
+            return;
+        }
+        if self.handle_vim_key(&key, tx) {
+            return;
+        }
+        if self.handle_search_key(key.code) || self.handle_selection_key(key.code) {
+            return;
+        }
+        if key.code == KeyCode::Char('f') && self.current_logs().is_some() {
+            self.cycle_log_level();
+            return;
+        }
+        if self.current_logs().is_some() && self.handle_layout_key(key.code) {
+            return;
+        }
+        let export = matches!(key.code, KeyCode::Char('w') | KeyCode::Char('W'));
+        if export && self.current_logs().is_some() {
+            self.export_logs(key.code == KeyCode::Char('W'));
+            return;
+        }
+        if let Some(action) = self.keys.action(&key) {
+            self.run_action(action, tx);
+        }
+    }
+
+    fn run_action(&mut self, action: Action, tx: &mpsc::Sender<BackgroundMessage>) {
+        match action {
+            Action::Quit => self.confirm_quit = true,
+            Action::NextTab => self.next_tab(),
+            Action::PrevTab => self.prev_tab(),
+            Action::StartBuild => {
+                if self.build_state.is_building {
+                    self.set_status("Build already in progress".to_string());
+                    return;
+                }
+                self.build_state.is_building = true;
+                self.build_state.progress = 0.0;
+                self.build_state.logs.clear();
+                self.log_search.focus = None;
+                self.log_selection = None;
+                let config = self.target_config();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let tx_for_error = tx.clone();
+                    if let Err(e) = start_build_operation_async(config, tx).await {
+                        let _ = tx_for_error.send(BackgroundMessage::BuildComplete(Err(e))).await;
+                    }
+                });
+            }
+            Action::StartDeploy => {
+                if self.deploy_state.is_deploying {
+                    self.set_status("Deployment already in progress".to_string());
+                    return;
+                }
+                self.deploy_state.is_deploying = true;
+                self.deploy_state.progress = 0.0;
+                self.deploy_state.current_step = "Starting deployment...".to_string();
+                // The Deploy tab follows one host, so it deploys to the selected host alone
+                let mut config = self.target_config();
+                config.deploy.hosts.clear();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let tx_for_error = tx.clone();
+                    if let Err(e) = start_deploy_operation_async(config, tx).await {
+                        let _ = tx_for_error.send(BackgroundMessage::DeployComplete(Err(e))).await;
+                    }
+                });
+            }
+            Action::StartMonitor => self.start_monitor(tx),
+            Action::SwitchHost => {
+                let hosts = self.config.hosts();
+                if hosts.len() > 1 {
+                    let current = self.target_config().deploy.vps_host;
+                    self.host_picker = Some(hosts.iter().position(|h| *h == current).unwrap_or(0));
+                } else {
+                    self.set_status("Only one host configured (add more in deploy.hosts)".to_string());
+                }
+            }
+            Action::ClearStatus => self.clear_status(),
+            Action::Help => self.show_help = true,
+            Action::Rollback => {
+                // Rolling back needs a backup picked from the Deploy tab's list
+                self.select_tab(Tab::Deploy);
+                self.list_backups(tx);
+            }
+            Action::RestartService => {
+                if self.deploy_state.is_deploying {
+                    self.set_status("Deployment already in progress".to_string());
+                    return;
+                }
+                let config = self.target_config();
+                self.set_status(format!("Restarting {} on {}...", config.service_name(), config.deploy.vps_host));
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let result = crate::commands::deploy::restart_service(&config)
+                        .await
+                        .map(|_| config.deploy.vps_host.clone());
+                    let _ = tx.send(BackgroundMessage::RestartComplete(result)).await;
+                });
+            }
+            Action::ToggleFollow => {
+                self.toggle_follow();
+                let message = match self.monitor_state.logs_paused_at {
+                    Some(_) => "Live log paused",
+                    None => "Live log resumed",
+                };
+                self.set_status(message.to_string());
+            }
+            Action::CommandPalette => self.palette = Some(CommandPalette::default()),
+            Action::ShowError => match &mut self.last_error {
+                Some(error) => {
+                    error.scroll = 0;
+                    self.show_error = true;
+                }
+                None => self.set_status("No errors so far".to_string()),
+            },
+        }
+    }
+
+    /// Log lines shown in the current tab after the level filter, if it has a log pane
+    pub fn current_logs(&self) -> Option<Cow<'_, [String]>> {
+        let lines = match self.current_tab {
+            Tab::Build => &self.build_state.logs,
+            Tab::Deploy => &self.deploy_state.logs,
+            Tab::Monitor => {
+                let state = &self.monitor_state;
+                &state.logs[..state.logs_paused_at.unwrap_or(state.logs.len())]
+            }
+            Tab::Dashboard | Tab::Console | Tab::Config => return None,
+        };
+        Some(self.log_level.apply(lines))
+    }
+
+    /// Save the current log pane to a file under the project: the lines shown, or with
+    /// `full` the whole buffer regardless of the level filter and pause
+    fn export_logs(&mut self, full: bool) {
+        let lines = match (full, self.current_tab) {
+            (true, Tab::Build) => Cow::Borrowed(&self.build_state.logs[..]),
+            (true, Tab::Deploy) => Cow::Borrowed(&self.deploy_state.logs[..]),
+            (true, Tab::Monitor) => Cow::Borrowed(&self.monitor_state.logs[..]),
+            _ => self.current_logs().unwrap_or_default(),
+        };
+        if lines.is_empty() {
+            self.set_status("Nothing to export".to_string());
+            return;
+        }
+
+        let pane = self.current_tab.title();
+        let result = self
+            .config
+            .project_path()
+            .and_then(|project| export::write(&project, pane, &lines, chrono::Local::now()));
+        let count = lines.len();
+        match result {
+            Ok(path) => {
+                self.set_status(format!("Exported {} line(s) to {}", count, path.display()));
+            }
+            Err(e) => self.record_error("Log export failed", &e, &[]),
+        }
+    }
+
+    /// Wrap toggle (`z`) and horizontal scrolling (`<`/`>`) of the log pane
+    fn handle_layout_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('z') => {
+                self.log_wrap = !self.log_wrap;
+                self.log_hscroll = 0;
+                let message = if self.log_wrap { "Wrapping long log lines" } else { "Cutting long log lines at the edge" };
+                self.set_status(message.to_string());
+            }
+            KeyCode::Char('<') | KeyCode::Char('>') if self.log_wrap => {
+                self.set_status("Wrapped lines are shown in full; 'z' turns wrapping off".to_string());
+            }
+            KeyCode::Char('<') => self.log_hscroll = self.log_hscroll.saturating_sub(lines::SCROLL_STEP),
+            KeyCode::Char('>') => {
+                // Stop once the longest line is scrolled fully out of view
+                let longest = self.current_logs().map_or(0, |lines| {
+                    lines.iter().map(|line| line.chars().count()).max().unwrap_or(0)
+                });
+                if self.log_hscroll + lines::SCROLL_STEP < longest {
+                    self.log_hscroll += lines::SCROLL_STEP;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Show fewer log lines by severity: all, then warnings and errors, then errors only
+    fn cycle_log_level(&mut self) {
+        self.log_level = self.log_level.next();
+        self.log_search.focus = None;
+        self.log_selection = None;
+        self.set_status(self.log_level.describe().to_string());
+    }
+
+    /// Handle a search key over the current tab's log pane; returns false for keys it does not use
+    fn handle_search_key(&mut self, key: KeyCode) -> bool {
+        let mut log_search = std::mem::take(&mut self.log_search);
+        let handled = self.current_logs().is_some_and(|lines| log_search.handle_key(key, &lines));
+        self.log_search = log_search;
+        handled
+    }
+
+    /// Handle a key for line selection in the current tab's log pane; returns false for keys it does not use
+    fn handle_selection_key(&mut self, key: KeyCode) -> bool {
+        let Some(len) = self.current_logs().map(|lines| lines.len()) else {
+            return false;
+        };
+        let Some(selection) = &mut self.log_selection else {
+            if key != KeyCode::Char('v') || len == 0 {
+                return false;
+            }
+            // Start at the focused search match, or the newest line
+            let cursor = self.log_search.focus.filter(|&focus| focus < len).unwrap_or(len - 1);
+            self.log_selection = Some(LogSelection::new(cursor));
+            return true;
+        };
+
+        match selection.handle_key(key, len) {
+            selection::Outcome::Handled => {}
+            selection::Outcome::Copy => {
+                let range = selection.range();
+                self.log_selection = None;
+                let lines = self.current_logs().unwrap_or_default();
+                let Some(selected) = lines.get(*range.start()..=(*range.end()).min(len.saturating_sub(1))) else {
+                    self.set_status("Nothing to copy".to_string());
+                    return true;
+                };
+                let count = selected.len();
+                match clipboard::copy(&selected.join("\n")) {
+                    Ok(via) => self.set_status(format!("Copied {} line(s) to the clipboard via {}", count, via)),
+                    Err(e) => self.set_status(format!("Copy failed: {}", e)),
+                }
+            }
+            selection::Outcome::Close => self.log_selection = None,
+        }
+        true
+    }
+
+    /// Handle a key while the host picker is open; returns false when it is closed
+    fn handle_host_picker_key(&mut self, key: KeyCode, tx: &mpsc::Sender<BackgroundMessage>) -> bool {
+        let Some(selected) = self.host_picker else {
+            return false;
+        };
+        let hosts = self.config.hosts();
+
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.host_picker = Some(selected.saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.host_picker = Some((selected + 1).min(hosts.len().saturating_sub(1)));
+            }
+            KeyCode::Enter => {
+                self.host_picker = None;
+                if let Some(host) = hosts.get(selected) {
+                    self.target_host = Some(host.clone());
+                    self.deploy_state.deployment_status = None;
+                    self.deploy_state.status_checked = None;
+                    self.deploy_state.backups.clear();
+                    self.set_status(format!("Target: {}", host));
+                    if self.monitor_task.is_some() {
+                        self.start_monitor(tx);
+                    }
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.host_picker = None,
+            _ => {}
+        }
+        true
+    }
+
+    /// Handle a key for the Deploy tab's backup list; returns false for keys it does not use
+    fn handle_deploy_key(&mut self, key: KeyCode, tx: &mpsc::Sender<BackgroundMessage>) -> bool {
+        let state = &mut self.deploy_state;
+        if state.confirm_rollback {
+            match key {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    state.confirm_rollback = false;
+                    self.start_rollback(tx);
+                }
+                KeyCode::Char('n') | KeyCode::Esc => state.confirm_rollback = false,
+                _ => {}
+            }
+            return true;
+        }
+
+        match key {
+            KeyCode::Char('r') => self.list_backups(tx),
+            KeyCode::Char('s') => self.refresh_deployment_status(tx),
+            // Without a backup list these keys are left to the log pane
+            KeyCode::Up | KeyCode::Char('k') if !state.backups.is_empty() => {
+                state.selected_backup = state.selected_backup.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if !state.backups.is_empty() => {
+                state.selected_backup = (state.selected_backup + 1).min(state.backups.len().saturating_sub(1));
+            }
+            KeyCode::Enter if !state.backups.is_empty() => {
+                if state.is_deploying {
+                    self.set_status("Deployment already in progress".to_string());
+                } else {
+                    state.confirm_rollback = true;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Load the backups on the target host into the Deploy tab in the background
+    /// Work that runs on the render tick: refreshing what the current tab shows
+    fn on_tick(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        let due = |checked: Option<Instant>, interval: Duration| {
+            checked.is_none_or(|checked| checked.elapsed() >= interval)
+        };
+        if due(self.config_polled, CONFIG_POLL_INTERVAL) {
+            self.poll_config(tx);
+        }
+        match self.current_tab {
+            Tab::Build if due(self.build_state.git_checked, GIT_REFRESH_INTERVAL) => {
+                self.refresh_git_status(tx);
+            }
+            Tab::Deploy => {
+                let state = &self.deploy_state;
+                // A running deploy restarts the service, so wait for it to finish
+                let idle = !state.is_deploying && !state.status_refreshing;
+                if idle && due(state.status_checked, self.status_interval()) {
+                    self.refresh_deployment_status(tx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Take over edits made to the config file outside the TUI
+    ///
+    /// Changes to the connection settings wait for confirmation; the others apply right away.
+    fn poll_config(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        self.config_polled = Some(Instant::now());
+        let Some(result) = self.config_watcher.poll() else {
+            return;
+        };
+        if self.config_editor.dirty {
+            self.set_status(format!(
+                "{} changed on disk; not reloaded over unsaved edits",
+                self.config_editor.path.display()
+            ));
+            return;
+        }
+        let reload = match result {
+            Ok(config) => Reload::new(&self.config, config),
+            Err(e) => {
+                self.record_error("Config not reloaded", &e, &[]);
+                return;
+            }
+        };
+        if !reload.reconnect.is_empty() {
+            self.pending_reload = Some(reload);
+        } else if !reload.is_empty() {
+            let changed = reload.applied.clone();
+            self.apply_config(reload.without_reconnect(), &changed, tx);
+        }
+    }
+
+    /// Keys of the reload dialog: reconnect with the new connection settings or keep the current ones
+    fn handle_reload_key(&mut self, key: KeyCode, tx: &mpsc::Sender<BackgroundMessage>) {
+        let reconnect = match key {
+            KeyCode::Char('y') | KeyCode::Enter => true,
+            KeyCode::Char('n') | KeyCode::Esc => false,
+            _ => return,
+        };
+        let Some(reload) = self.pending_reload.take() else {
+            return;
+        };
+        let mut changed = reload.applied.clone();
+        let config = if reconnect {
+            match reload.clone().with_reconnect() {
+                Ok(config) => {
+                    changed.extend(reload.reconnect);
+                    config
+                }
+                Err(e) => {
+                    self.record_error("Not reconnected", &e, &[]);
+                    reload.without_reconnect()
+                }
+            }
+        } else {
+            reload.without_reconnect()
+        };
+        self.apply_config(config, &changed, tx);
+    }
+
+    /// Use a reloaded configuration, restarting the background work it changes
+    fn apply_config(&mut self, config: Config, changed: &[String], tx: &mpsc::Sender<BackgroundMessage>) {
+        if changed.is_empty() {
+            return;
+        }
+        self.set_status(format!("Config reloaded: {}", changed.join(", ")));
+        let changed_in = |prefix: &str| changed.iter().any(|key| key.starts_with(prefix));
+        if changed_in("tui.keys") {
+            match KeyMap::from_config(&config.tui) {
+                Ok(keys) => self.keys = keys,
+                Err(e) => self.record_error("Keys not reloaded", &e, &[]),
+            }
+        }
+        if changed_in("tui.theme") {
+            match Theme::from_config(&config.tui.theme) {
+                Ok(theme) => self.theme = theme,
+                Err(e) => self.record_error("Theme not reloaded", &e, &[]),
+            }
+        }
+        if changed_in("tui.vim") {
+            self.vim = config.tui.vim.then(vim::VimMode::default);
+        }
+
+        self.config = config;
+        if self.target_host.as_ref().is_some_and(|host| !self.config.hosts().contains(host)) {
+            self.target_host = None;
+        }
+        self.deploy_state.status_checked = None;
+        if self.monitor_state.is_monitoring {
+            if changed.iter().any(|key| !key.starts_with("tui.")) {
+                self.spawn_monitor_task(tx);
+            }
+            if LOG_STREAM_SETTINGS.iter().any(|prefix| changed_in(prefix)) {
+                self.push_log(Tab::Monitor, "Config reloaded, restarting log stream".to_string());
+                self.spawn_log_task(tx);
+            }
+        }
+    }
+
+    fn status_interval(&self) -> Duration {
+        Duration::from_secs(self.config.monitor.interval_secs.max(1))
+    }
+
+    /// Time until the Deploy tab's status is checked again
+    pub fn next_status_refresh(&self) -> Option<Duration> {
+        let checked = self.deploy_state.status_checked?;
+        Some(self.status_interval().saturating_sub(checked.elapsed()))
+    }
+
+    /// Check the service on the target host in the background
+    fn refresh_deployment_status(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        let state = &mut self.deploy_state;
+        if state.status_refreshing {
+            return;
+        }
+        state.status_refreshing = true;
+        state.status_checked = Some(Instant::now());
+        let config = self.target_config();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = crate::commands::deploy::check_host_status(&config).await;
+            let _ = tx.send(BackgroundMessage::DeploymentStatus(result)).await;
+        });
+    }
+
+    /// Read the project's git status in the background
+    fn refresh_git_status(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        self.build_state.git_checked = Some(Instant::now());
+        let config = self.config.clone();
+        let tx = tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = config.project_path().and_then(|dir| git::status(&dir));
+            let _ = tx.blocking_send(BackgroundMessage::GitStatus(result));
+        });
+    }
+
+    fn list_backups(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        self.set_status("Listing backups...".to_string());
+        let config = self.target_config();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = crate::commands::deploy::list_backups(&config).await;
+            let _ = tx.send(BackgroundMessage::BackupsLoaded(result)).await;
+        });
+    }
+
+    /// Append a line to the Build or Deploy log, or for any other tab the Monitor log
+    ///
+    /// When the buffer is full its oldest line goes; the search cursor, selection and
+    /// pause point move along so they stay on the lines they were on.
+    fn push_log(&mut self, tab: Tab, line: String) {
+        let level = self.log_level;
+        let logs = match tab {
+            Tab::Build => &mut self.build_state.logs,
+            Tab::Deploy => &mut self.deploy_state.logs,
+            _ => &mut self.monitor_state.logs,
+        };
+        let Some(removed) = logs.push(line) else {
+            return;
+        };
+        // Search and selection index the filtered lines
+        let shown = level.apply(std::slice::from_ref(removed)).len();
+        if let Tab::Monitor | Tab::Dashboard = tab {
+            let paused_at = &mut self.monitor_state.logs_paused_at;
+            *paused_at = paused_at.map(|at| at.saturating_sub(1));
+        }
+        if self.current_tab == tab {
+            self.log_search.shift(shown);
+            if let Some(selection) = &mut self.log_selection {
+                selection.shift(shown);
+            }
+        }
+    }
+
+    /// Roll the target host back to the selected backup in the background
+    fn start_rollback(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        let Some(backup) = self.deploy_state.backups.get(self.deploy_state.selected_backup).cloned() else {
+            return;
+        };
+        self.deploy_state.is_deploying = true;
+        self.deploy_state.progress = 0.0;
+        self.deploy_state.current_step = format!("Rolling back to {}...", backup.name());
+        self.push_log(Tab::Deploy, format!("Rolling back to {}", backup.summary()));
+
+        let config = self.target_config();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = crate::commands::deploy::rollback_deployment(&config, Some(&backup.path))
+                .await
+                .map(|_| backup.name().to_string());
+            let _ = tx.send(BackgroundMessage::RollbackComplete(result)).await;
+        });
+    }
+
+    /// Handle a key for the Monitor tab's log pane; returns false for keys it does not use
+    fn handle_monitor_key(&mut self, key: KeyCode) -> bool {
+        let state = &mut self.monitor_state;
+        match key {
+            KeyCode::Char('p') => self.toggle_follow(),
+            KeyCode::Char('x') => {
+                state.logs.clear();
+                state.logs_paused_at = state.logs_paused_at.map(|_| 0);
+                self.log_search.focus = None;
+                self.log_selection = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Handle a vim-mode key; returns false for keys it does not use
+    ///
+    /// Motions move the log pane's focused line, the same one search matches
+    /// focus, so `n`, `v` and scrolling all continue from where the other left off.
+    fn handle_vim_key(&mut self, key: &KeyEvent, tx: &mpsc::Sender<BackgroundMessage>) -> bool {
+        let Some(vim) = &mut self.vim else {
+            return false;
+        };
+        let command = match vim.handle_key(key) {
+            vim::Outcome::Ignored => return false,
+            vim::Outcome::Handled => return true,
+            vim::Outcome::Move(motion) => {
+                let Some(len) = self.current_logs().map(|lines| lines.len()) else {
+                    return false;
+                };
+                self.log_search.focus = motion.apply(self.log_search.focus, len);
+                return true;
+            }
+            vim::Outcome::Run(Ok(command)) => command,
+            vim::Outcome::Run(Err(message)) => {
+                self.set_status(message);
+                return true;
+            }
+        };
+
+        match command {
+            vim::Command::Quit => self.run_action(Action::Quit, tx),
+            vim::Command::Write => {
+                self.select_tab(Tab::Config);
+                self.config_editor.preview_save(&self.config);
+            }
+            vim::Command::Line(line) => match self.current_logs().map(|lines| lines.len()) {
+                Some(len) if len > 0 => self.log_search.focus = Some(line.clamp(1, len) - 1),
+                Some(_) => self.set_status("The log pane is empty".to_string()),
+                None => self.set_status("No log pane in this tab".to_string()),
+            },
+            vim::Command::Tab(tab) => self.select_tab(tab.into()),
+            vim::Command::Run(action) => self.run_action(action, tx),
+        }
+        true
+    }
+
+    /// Pass a key to the focused shell; Ctrl+] hands the keyboard back to the TUI
+    fn handle_console_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char(']') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.console.focused = false;
+            return;
+        }
+        let application_cursor = self.console.parser.screen().application_cursor();
+        if let Some(bytes) = console::key_bytes(&key, application_cursor) {
+            self.console.send(bytes);
+        }
+    }
+
+    /// Pause the live log pane at the current line, or resume following it
+    fn toggle_follow(&mut self) {
+        let state = &mut self.monitor_state;
+        state.logs_paused_at = match state.logs_paused_at {
+            Some(_) => None,
+            None => Some(state.logs.len()),
+        };
+    }
+
+    /// (Re)start background monitoring and log streaming for the target host
+    fn start_monitor(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        self.monitor_state.is_monitoring = true;
+        self.monitor_state.status = None;
+        self.monitor_state.metrics = None;
+        self.monitor_state.latency_history.clear();
+        self.monitor_state.logs.clear();
+        self.monitor_state.logs_paused_at = None;
+
+        self.spawn_monitor_task(tx);
+        self.spawn_log_task(tx);
+    }
+
+    /// (Re)start the periodic status checks with the current configuration
+    fn spawn_monitor_task(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        if let Some(task) = self.monitor_task.take() {
+            task.abort();
+        }
+        let config = self.target_config();
+        let monitor_tx = tx.clone();
+        self.monitor_task = Some(tokio::spawn(async move {
+            if let Err(_e) = start_monitor_operation_async(config, monitor_tx).await {
+            }
+        }));
+    }
+
+    /// (Re)start following the application log with the current configuration
+    fn spawn_log_task(&mut self, tx: &mpsc::Sender<BackgroundMessage>) {
+        if let Some(task) = self.log_task.take() {
+            task.abort();
+        }
+        let config = self.target_config();
+        let tx = tx.clone();
+        self.log_task = Some(tokio::spawn(async move {
+            let result = start_log_stream_async(config, tx.clone()).await;
+            let message = match result {
+                Ok(()) => "Log stream ended".to_string(),
+                Err(e) => format!("Log stream failed: {}", e),
+            };
+            let _ = tx.send(BackgroundMessage::MonitorLog(message)).await;
+        }));
+    }
+
+    /// Keys of the quit dialog; other keys are ignored while it is open
+    fn handle_quit_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('y') | KeyCode::Char('q') | KeyCode::Enter => self.quit(),
+            KeyCode::Char('s') if self.config_editor.dirty => {
+                self.config_editor.save(&self.config);
+                if self.config_editor.dirty {
+                    // Keep running so the edits aren't lost, and say why saving failed
+                    self.confirm_quit = false;
+                    self.set_status(self.config_editor.message.clone().unwrap_or_default());
+                } else {
+                    self.quit();
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Esc => self.confirm_quit = false,
+            _ => {}
         }
     }
 
@@ -148,15 +1046,36 @@ impl App {
     }
 
     pub fn next_tab(&mut self) {
-        if self.current_tab == Tab::Exit {
-            self.quit();
-        } else {
-            self.current_tab = self.current_tab.next();
-        }
+        self.select_tab(self.current_tab.next());
     }
 
     pub fn prev_tab(&mut self) {
-        self.current_tab = self.current_tab.prev();
+        self.select_tab(self.current_tab.prev());
+    }
+
+    pub fn select_tab(&mut self, tab: Tab) {
+        self.log_search = search::LogSearch::default();
+        self.log_selection = None;
+        self.log_hscroll = 0;
+        self.current_tab = tab;
+    }
+
+    /// Fit the Console tab's screen to a terminal of this size
+    pub fn resize_console(&mut self, width: u16, height: u16) {
+        // The tab area of ui() less the pane's borders
+        let rows = height.saturating_sub(TITLE_HEIGHT + TABS_HEIGHT + STATUS_HEIGHT + 2);
+        self.console.resize(rows, width.saturating_sub(2));
+    }
+
+    /// Keep a failure for the error popup and point to it from the status bar
+    pub fn record_error(&mut self, title: &str, error: &anyhow::Error, output: &[String]) {
+        let detail = errors::ErrorDetail::new(title, error, output);
+        let key = match self.keys.keys(Action::ShowError) {
+            key if key.is_empty() => "the command palette".to_string(),
+            key => format!("'{}'", key),
+        };
+        self.set_status(format!("{} ({} for details)", detail.summary(), key));
+        self.last_error = Some(detail);
     }
 
     pub fn set_status(&mut self, message: String) {
@@ -169,15 +1088,22 @@ impl App {
 }
 
 /// Run the TUI application
-pub async fn run_tui(config: Config) -> Result<()> {
+pub async fn run_tui(config: Config, config_path: PathBuf, tab: Option<Tab>) -> Result<()> {
+    // Invalid [tui] settings are reported before the terminal is taken over
+    let mut app = App::new(config, config_path)?;
+    if let Some(tab) = tab {
+        app.select_tab(tab);
+    }
+
+    // A prompt can't be answered while the TUI owns the terminal; unknown keys fail instead
+    crate::utils::ssh::set_host_key_prompts(false);
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let app = Arc::new(Mutex::new(App::new(config)));
-    let res = run_app(&mut terminal, app.clone()).await;
+    let res = run_app(&mut terminal, &mut app).await;
 
     disable_raw_mode()?;
     execute!(
@@ -186,6 +1112,7 @@ pub async fn run_tui(config: Config) -> Result<()> {
         DisableMouseCapture
     )?;
     terminal.show_cursor()?;
+    crate::utils::ssh::set_host_key_prompts(true);
 
     if let Err(err) = res {
         println!("{:?}", err);
@@ -195,89 +1122,49 @@ pub async fn run_tui(config: Config) -> Result<()> {
 }
 
 /// Run the main application loop
+///
+/// Terminal events, background messages and a render tick are multiplexed on
+/// one task, so the app is only touched here and the loop sleeps while idle.
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
-    app: Arc<Mutex<App>>,
+    app: &mut App,
 ) -> Result<()> {
     let (tx, mut rx) = mpsc::channel(100);
+    let mut events = EventStream::new();
+    // Redraws between events keep running gauges and timestamps current
+    let mut tick = tokio::time::interval(Duration::from_millis(250));
 
-    let event_app = app.clone();
-    tokio::spawn(async move {
-        loop {
-            if event::poll(Duration::from_millis(100)).unwrap_or(false) {
-                if let Ok(Event::Key(key)) = event::read() {
-                    let action = match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
-                        KeyCode::Right | KeyCode::Char('l') => Some(Action::NextTab),
-                        KeyCode::Left | KeyCode::Char('h') => Some(Action::PrevTab),
-                        KeyCode::Char('b') => Some(Action::StartBuild),
-                        KeyCode::Char('d') => Some(Action::StartDeploy),
-                        KeyCode::Char('m') => Some(Action::StartMonitor),
-                        KeyCode::Char('c') => Some(Action::ClearStatus),
-                        _ => None,
-                    };
-
-                    if let Some(action) = action {
-                        let mut app = event_app.lock().unwrap();
-                        match action {
-                            Action::Quit => {
-                                app.quit();
-                                break;
-                            }
-                            Action::NextTab => app.next_tab(),
-                            Action::PrevTab => app.prev_tab(),
-                            Action::StartBuild => {
-                                let config = app.config.clone();
-                                let tx_clone = tx.clone();
-                                tokio::spawn(async move {
-                                                                            let tx_for_error = tx_clone.clone();
-                                    if let Err(e) = start_build_operation_async(config, tx_clone).await {
-                                        let _ = tx_for_error.send(BackgroundMessage::BuildComplete(Err(e))).await;
-                                    }
-                                });
-                            }
-                            Action::StartDeploy => {
-                                let config = app.config.clone();
-                                let tx_clone = tx.clone();
-                                tokio::spawn(async move {
-                                    let tx_for_error = tx_clone.clone();
-                                    if let Err(e) = start_deploy_operation_async(config, tx_clone).await {
-                                        let _ = tx_for_error.send(BackgroundMessage::DeployComplete(Err(e))).await;
-                                    }
-                                });
-                            }
-                            Action::StartMonitor => {
-                                let config = app.config.clone();
-                                let tx_clone = tx.clone();
-                                tokio::spawn(async move {
-                                    if let Err(_e) = start_monitor_operation_async(config, tx_clone).await {
-                                    }
-                                });
-                            }
-                            Action::ClearStatus => app.clear_status(),
-                        }
-                    }
-                }
-            }
-        }
-    });
+    let size = terminal.size()?;
+    app.resize_console(size.width, size.height);
 
-    loop {
-        {
-            let app = app.lock().unwrap();
-            if app.should_quit {
-                break;
-            }
-        }
+    // Opened straight on a monitoring view (`rzen tui monitor`), so start filling it
+    if matches!(app.current_tab, Tab::Monitor | Tab::Dashboard) {
+        app.start_monitor(&tx);
+    }
 
-        terminal.draw(|f| {
-            let app = app.lock().unwrap();
-            ui(f, &app);
-        })?;
+    while !app.should_quit {
+        terminal.draw(|f| ui(f, app))?;
 
-        if let Ok(message) = rx.try_recv() {
-            let mut app = app.lock().unwrap();
-            handle_background_message(&mut app, message);
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) => app.handle_key(key, &tx),
+                // Layouts are recomputed from the new size on the next draw
+                Some(Ok(Event::Resize(width, height))) => {
+                    terminal.autoresize()?;
+                    app.resize_console(width, height);
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+                None => break,
+            },
+            Some(message) = rx.recv() => {
+                handle_background_message(app, message);
+                // Drain everything queued so streamed build output doesn't lag behind
+                while let Ok(message) = rx.try_recv() {
+                    handle_background_message(app, message);
+                }
+            }
+            _ = tick.tick() => app.on_tick(&tx),
         }
     }
 
@@ -290,19 +1177,27 @@ fn handle_background_message(app: &mut App, message: BackgroundMessage) {
         BackgroundMessage::BuildProgress(progress, log_line) => {
             app.build_state.progress = progress;
             if let Some(log) = log_line {
-                app.build_state.logs.push(log);
+                app.push_log(Tab::Build, log);
             }
         }
         BackgroundMessage::BuildComplete(result) => {
             app.build_state.is_building = false;
             app.build_state.progress = 100.0;
+            // The build may have touched Cargo.lock
+            app.build_state.git_checked = None;
             match result {
                 Ok(info) => {
                     app.build_state.build_info = Some(info);
                     app.set_status("Build completed successfully".to_string());
                 }
                 Err(e) => {
-                    app.set_status(format!("Build failed: {}", e));
+                    // Compiler errors with their context lines, or the tail of the log without any
+                    let logs = &app.build_state.logs;
+                    let mut output = LevelFilter::Error.apply(logs).into_owned();
+                    if output.is_empty() {
+                        output = logs[logs.len().saturating_sub(20)..].to_vec();
+                    }
+                    app.record_error("Build failed", &e, &output);
                 }
             }
         }
@@ -310,24 +1205,107 @@ fn handle_background_message(app: &mut App, message: BackgroundMessage) {
             app.deploy_state.progress = progress;
             app.deploy_state.current_step = step;
             if let Some(log) = log_line {
-                app.deploy_state.logs.push(log);
+                app.push_log(Tab::Deploy, log);
             }
         }
         BackgroundMessage::DeployComplete(result) => {
             app.deploy_state.is_deploying = false;
             app.deploy_state.progress = 100.0;
+            app.deploy_state.status_checked = None;
             match result {
                 Ok(_) => {
+                    app.deploy_state.current_step = "Deployed".to_string();
                     app.set_status("Deployment completed successfully".to_string());
                 }
                 Err(e) => {
-                    app.set_status(format!("Deployment failed: {}", e));
+                    app.deploy_state.current_step = "Failed".to_string();
+                    let output = app.deploy_state.logs.to_vec();
+                    app.push_log(Tab::Deploy, format!("Deployment failed: {}", e));
+                    app.record_error("Deployment failed", &e, &output);
+                }
+            }
+        }
+        BackgroundMessage::BackupsLoaded(result) => match result {
+            Ok(backups) => {
+                app.set_status(format!("{} backup(s) on the server", backups.len()));
+                app.deploy_state.selected_backup = 0;
+                app.deploy_state.backups = backups;
+            }
+            Err(e) => app.record_error("Failed to list backups", &e, &[]),
+        },
+        BackgroundMessage::RollbackComplete(result) => {
+            app.deploy_state.is_deploying = false;
+            app.deploy_state.progress = 100.0;
+            app.deploy_state.status_checked = None;
+            match result {
+                Ok(backup) => {
+                    app.deploy_state.current_step = "Rolled back".to_string();
+                    app.push_log(Tab::Deploy, format!("Rolled back to {}", backup));
+                    app.set_status(format!("Rolled back to {}", backup));
+                }
+                Err(e) => {
+                    app.deploy_state.current_step = "Failed".to_string();
+                    let output = app.deploy_state.logs.to_vec();
+                    app.push_log(Tab::Deploy, format!("Rollback failed: {}", e));
+                    app.record_error("Rollback failed", &e, &output);
+                }
+            }
+        }
+        BackgroundMessage::RestartComplete(result) => {
+            app.deploy_state.status_checked = None;
+            match result {
+                Ok(host) => {
+                    app.push_log(Tab::Deploy, format!("Service restarted on {}", host));
+                    app.set_status(format!("Service restarted on {}", host));
+                }
+                Err(e) => {
+                    app.push_log(Tab::Deploy, format!("Restart failed: {}", e));
+                    app.record_error("Restart failed", &e, &[]);
+                }
+            }
+        }
+        BackgroundMessage::ConsoleOutput(bytes) => app.console.parser.process(&bytes),
+        BackgroundMessage::ConsoleClosed(result) => {
+            let host = app.console.host.clone().unwrap_or_default();
+            app.console.close();
+            let message = match &result {
+                Ok(()) => format!("Connection to {} closed", host),
+                Err(e) => format!("Shell on {} failed: {}", host, e),
+            };
+            app.console.parser.process(format!("\r\n[{}]\r\n", message).as_bytes());
+            match result {
+                Ok(()) => app.set_status(message),
+                Err(e) => app.record_error(&format!("Shell on {} failed", host), &e, &[]),
+            }
+        }
+        BackgroundMessage::DeploymentStatus(result) => {
+            let target = app.target_config().deploy.vps_host;
+            let state = &mut app.deploy_state;
+            state.status_refreshing = false;
+            match result {
+                // A reply for the previous target after switching hosts is dropped
+                Ok(status) if status.host == target => {
+                    state.deployment_status = Some(status);
+                    state.status_error = None;
                 }
+                Ok(_) => {}
+                Err(e) => state.status_error = Some(e.to_string()),
             }
         }
+        BackgroundMessage::GitStatus(result) => {
+            app.build_state.git = Some(result.map_err(|e| e.to_string()));
+        }
+        BackgroundMessage::MonitorLog(line) => app.push_log(Tab::Monitor, line),
         BackgroundMessage::MonitorUpdate(status, metrics) => {
-            app.monitor_state.status = Some(status);
-            app.monitor_state.metrics = metrics;
+            let history = &mut app.monitor_state.latency_history;
+            if let Some(response_time) = status.response_time {
+                history.push(response_time.as_millis() as u64);
+                if history.len() > MAX_LATENCY_SAMPLES {
+                    history.remove(0);
+                }
+            }
+            app.monitor_state.status = Some(*status);
+            app.monitor_state.metrics = metrics.map(|metrics| *metrics);
         }
     }
 }
@@ -337,9 +1315,20 @@ fn handle_background_message(app: &mut App, message: BackgroundMessage) {
 pub enum BackgroundMessage {
     BuildProgress(f64, Option<String>),
     BuildComplete(Result<crate::commands::build::BuildInfo>),
+    GitStatus(Result<git::RepoStatus>),
+    DeploymentStatus(Result<crate::commands::deploy::DeploymentStatus>),
     DeployProgress(f64, String, Option<String>),
     DeployComplete(Result<String>),
-    MonitorUpdate(crate::commands::monitor::ApplicationStatus, Option<crate::commands::monitor::MonitoringMetrics>),
+    BackupsLoaded(Result<Vec<crate::commands::deploy::Backup>>),
+    /// Name of the restored backup
+    RollbackComplete(Result<String>),
+    /// Host the service was restarted on
+    RestartComplete(Result<String>),
+    /// Output of the Console tab's shell
+    ConsoleOutput(Vec<u8>),
+    ConsoleClosed(Result<()>),
+    MonitorLog(String),
+    MonitorUpdate(Box<crate::commands::monitor::ApplicationStatus>, Option<Box<crate::commands::monitor::MonitoringMetrics>>),
 }
 
 /// Start build operation asynchronously
@@ -347,19 +1336,25 @@ async fn start_build_operation_async(
     config: Config,
     tx: mpsc::Sender<BackgroundMessage>,
 ) -> Result<()> {
-    for i in 0..=100 {
-        tokio::time::sleep(Duration::from_millis(50)).await;
-        let _ = tx.send(BackgroundMessage::BuildProgress(
-            i as f64,
-            Some(format!("Building... {}%", i))
-        )).await;
-    }
+    // Cargo output arrives through a synchronous callback; a full channel
+    // only drops that update
+    let progress_tx = tx.clone();
+    let on_progress = move |progress: f64, line: Option<&str>| {
+        let _ = progress_tx.try_send(BackgroundMessage::BuildProgress(
+            progress,
+            line.map(str::to_string)
+        ));
+    };
 
-    let result = crate::commands::build::build_project(&config, None, false).await;
-    let build_info = crate::commands::build::get_build_info(&config);
+    let result = crate::commands::build::build_project_with_progress(
+        &config,
+        None,
+        false,
+        Some(&on_progress),
+    ).await;
+    let build_info = result.and_then(|_| crate::commands::build::get_build_info(&config));
 
     let _ = tx.send(BackgroundMessage::BuildComplete(build_info)).await;
-    result?;
     Ok(())
 }
 
@@ -368,26 +1363,30 @@ async fn start_deploy_operation_async(
     config: Config,
     tx: mpsc::Sender<BackgroundMessage>,
 ) -> Result<()> {
-          let steps = [
-        "Connecting to server...",
-        "Creating remote directory...",
-        "Uploading binary...",
-        "Setting permissions...",
-        "Creating systemd service...",
-        "Starting service...",
-      ];
-
-    for (i, step) in steps.iter().enumerate() {
-        tokio::time::sleep(Duration::from_millis(500)).await;
-        let progress = ((i + 1) as f64 / steps.len() as f64) * 100.0;
-        let _ = tx.send(BackgroundMessage::DeployProgress(
+    let _ = tx.send(BackgroundMessage::DeployProgress(
+        0.0,
+        "Building project...".to_string(),
+        Some("Building project before upload".to_string())
+    )).await;
+
+    // The deploy reports each stage through a synchronous callback; a full
+    // channel only drops that progress update
+    let progress_tx = tx.clone();
+    let on_progress = move |progress: f64, step: &str, log_line: Option<&str>| {
+        let _ = progress_tx.try_send(BackgroundMessage::DeployProgress(
             progress,
             step.to_string(),
-            Some(format!("Step {}: {}", i + 1, step))
-        )).await;
-    }
+            log_line.map(str::to_string)
+        ));
+    };
 
-    let result = crate::commands::deploy::deploy_project(&config, false, false, false).await;
+    let result = crate::commands::deploy::deploy_project_with_progress(
+        &config,
+        false,
+        false,
+        false,
+        Some(&on_progress),
+    ).await;
     let _ = tx.send(BackgroundMessage::DeployComplete(result)).await;
     Ok(())
 }
@@ -397,15 +1396,14 @@ async fn start_monitor_operation_async(
     config: Config,
     tx: mpsc::Sender<BackgroundMessage>,
 ) -> Result<()> {
+    let monitor = crate::commands::monitor::ApplicationMonitor::new(config.clone());
     loop {
-        let status_result = crate::commands::monitor::ApplicationMonitor::new(config.clone())
-            .check_status()
-            .await;
+        let status_result = monitor.check_status().await;
 
         match status_result {
             Ok(status) => {
-                let metrics = crate::commands::monitor::get_metrics(&config).await.ok();
-                let _ = tx.send(BackgroundMessage::MonitorUpdate(status, metrics)).await;
+                let metrics = Some(Box::new(monitor.metrics(&status)));
+                let _ = tx.send(BackgroundMessage::MonitorUpdate(Box::new(status), metrics)).await;
             }
             Err(e) => {
                 log::monitor_event(&format!("Monitor error: {}", e));
@@ -416,37 +1414,79 @@ async fn start_monitor_operation_async(
     }
 }
 
+/// Follow the target's log into the Monitor tab until the channel closes
+async fn start_log_stream_async(
+    config: Config,
+    tx: mpsc::Sender<BackgroundMessage>,
+) -> Result<()> {
+    use crate::commands::monitor::logs;
+
+    let query = logs::LogQuery {
+        lines: 50,
+        follow: true,
+        range: logs::TimeRange::default(),
+        level: None,
+    };
+    let source = logs::LogSource::from_config(&config);
+    // Lines arrive through a synchronous callback; a full channel only drops that line
+    crate::commands::monitor::follow_logs(&config, &source, &query, &logs::LogFilter::default(), None, |line| {
+        let line = line
+            .strip_prefix(glyph("📜"))
+            .and_then(|line| line.strip_prefix(' '))
+            .unwrap_or(line);
+        let _ = tx.try_send(BackgroundMessage::MonitorLog(line.to_string()));
+        !tx.is_closed()
+    }).await
+}
+
 /// Main UI rendering function
 fn ui(f: &mut Frame, app: &App) {
     let size = f.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        draw_too_small(f, app, size);
+        if app.confirm_quit {
+            draw_quit_dialog(f, app, size);
+        }
+        return;
+    }
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),
-            Constraint::Length(3),
+            Constraint::Length(TITLE_HEIGHT),
+            Constraint::Length(TABS_HEIGHT),
             Constraint::Min(10),
-            Constraint::Length(3),
+            Constraint::Length(STATUS_HEIGHT),
         ])
         .split(size);
 
-    let title = Paragraph::new("🚀 rzen - Rust Project Manager")
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+    let mut title_block = Block::default().borders(Borders::ALL);
+    // Last error indicator on the border, pointing to the error popup
+    if let Some(error) = &app.last_error {
+        let key = app.keys.keys(Action::ShowError);
+        let indicator = format!(" {} {} ('{}') ", glyph("⚠️"), error.title, key);
+        title_block = title_block.title(
+            Title::from(Span::styled(indicator, app.theme.warning())).alignment(Alignment::Right),
+        );
+    }
+    let title = Paragraph::new(format!("{} rzen - Rust Project Manager", glyph("🚀")))
+        .style(app.theme.title())
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(title_block);
     f.render_widget(title, chunks[0]);
 
     let tab_titles: Vec<&str> = vec![
         Tab::Build.title(),
         Tab::Deploy.title(),
         Tab::Monitor.title(),
+        Tab::Dashboard.title(),
+        Tab::Console.title(),
         Tab::Config.title(),
-        Tab::Exit.title(),
     ];
     let tabs = Tabs::new(tab_titles)
         .select(app.current_tab as usize)
-        .style(Style::default().fg(Color::White))
-        .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .style(app.theme.text())
+        .highlight_style(app.theme.active())
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(tabs, chunks[1]);
 
@@ -454,19 +1494,81 @@ fn ui(f: &mut Frame, app: &App) {
         Tab::Build => draw_build_tab(f, app, chunks[2]),
         Tab::Deploy => draw_deploy_tab(f, app, chunks[2]),
         Tab::Monitor => draw_monitor_tab(f, app, chunks[2]),
+        Tab::Dashboard => draw_dashboard_tab(f, app, chunks[2]),
+        Tab::Console => draw_console_tab(f, app, chunks[2]),
         Tab::Config => draw_config_tab(f, app, chunks[2]),
-        Tab::Exit => draw_exit_tab(f, app, chunks[2]),
     }
 
-    let status = app.status_message.as_deref()
-        .unwrap_or("Press 'q' to quit | 'h/l' or arrow keys to navigate | 'b' build | 'd' deploy | 'm' monitor");
+    if let Some(selected) = app.host_picker {
+        draw_host_picker(f, app, selected, size);
+    }
+
+    if let Some(error) = app.last_error.as_ref().filter(|_| app.show_error) {
+        draw_error(f, app, error, size);
+    }
+
+    if app.show_help {
+        draw_help(f, app, size);
+    }
+
+    if let Some(palette) = &app.palette {
+        draw_palette(f, app, palette, size);
+    }
+
+    if let Some(reload) = &app.pending_reload {
+        draw_reload_dialog(f, app, reload, size);
+    }
+
+    if app.confirm_quit {
+        draw_quit_dialog(f, app, size);
+    }
+
+    let search_status = app
+        .vim
+        .as_ref()
+        .and_then(|vim| vim.status())
+        .or_else(|| app.current_logs().and_then(|lines| app.log_search.status(&lines)));
+    let default_status = format!(
+        "Press '{}' to quit | '{}'/'{}' navigate | '{}' build | '{}' deploy | '{}' monitor | '{}' target host | '{}' commands | '{}' help",
+        app.keys.keys(Action::Quit),
+        app.keys.keys(Action::PrevTab),
+        app.keys.keys(Action::NextTab),
+        app.keys.keys(Action::StartBuild),
+        app.keys.keys(Action::StartDeploy),
+        app.keys.keys(Action::StartMonitor),
+        app.keys.keys(Action::SwitchHost),
+        app.keys.keys(Action::CommandPalette),
+        app.keys.keys(Action::Help),
+    );
+    let status = search_status.as_deref()
+        .or(app.status_message.as_deref())
+        .unwrap_or(&default_status);
     let status_bar = Paragraph::new(status)
-        .style(Style::default().fg(Color::Green))
+        .style(app.theme.success())
         .alignment(Alignment::Center)
         .wrap(Wrap { trim: true });
     f.render_widget(status_bar, chunks[3]);
 }
 
+/// Placeholder shown instead of the tabs when the terminal is too small for them
+fn draw_too_small(f: &mut Frame, app: &App, area: Rect) {
+    let text = format!(
+        "Terminal too small\n{}x{} (need {}x{})\n\nResize the window or press '{}' to quit",
+        area.width,
+        area.height,
+        MIN_WIDTH,
+        MIN_HEIGHT,
+        app.keys.keys(Action::Quit)
+    );
+    let lines = text.lines().count() as u16;
+    let top = area.height.saturating_sub(lines) / 2;
+    let message = Paragraph::new(text)
+        .style(app.theme.warning())
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(message, Rect { y: area.y + top, height: area.height - top, ..area });
+}
+
 /// Draw build tab
 fn draw_build_tab(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
@@ -474,36 +1576,27 @@ fn draw_build_tab(f: &mut Frame, app: &App, area: Rect) {
         .constraints([
             Constraint::Length(3),
             Constraint::Min(5),
-            Constraint::Length(3),
+            Constraint::Length(4),
         ])
         .split(area);
 
     let progress = if app.build_state.is_building {
         Gauge::default()
             .block(Block::default().title("Build Progress").borders(Borders::ALL))
-            .gauge_style(Style::default().fg(Color::Green))
+            .gauge_style(app.theme.success())
             .percent(app.build_state.progress as u16)
             .label(format!("{:.1}%", app.build_state.progress))
     } else {
         Gauge::default()
             .block(Block::default().title("Build Status").borders(Borders::ALL))
-            .gauge_style(Style::default().fg(Color::Gray))
+            .gauge_style(app.theme.muted())
             .percent(0)
             .label("Ready")
     };
     f.render_widget(progress, chunks[0]);
 
-    let logs: Vec<ListItem> = app.build_state.logs
-        .iter()
-        .rev()
-        .take(20)
-        .rev()
-        .map(|log| ListItem::new(log.as_str()))
-        .collect();
-
-    let logs_list = List::new(logs)
-        .block(Block::default().title("Build Logs").borders(Borders::ALL))
-        .style(Style::default().fg(Color::White));
+    let logs = app.log_level.apply(&app.build_state.logs);
+    let logs_list = log_list("Build Logs".to_string(), &logs, chunks[1], app);
     f.render_widget(logs_list, chunks[1]);
 
     let info_text = if let Some(info) = &app.build_state.build_info {
@@ -514,8 +1607,17 @@ fn draw_build_tab(f: &mut Frame, app: &App, area: Rect) {
     } else {
         "No build information available".to_string()
     };
+    // Uncommitted changes would be deployed too, so they stand out
+    let git_line = match &app.build_state.git {
+        Some(Ok(status)) if status.dirty => {
+            Line::styled(format!("Git: {}", status.summary()), app.theme.warning())
+        }
+        Some(Ok(status)) => Line::from(format!("Git: {}", status.summary())),
+        Some(Err(e)) => Line::styled(format!("Git: unavailable ({})", e), app.theme.muted()),
+        None => Line::styled("Git: checking...", app.theme.muted()),
+    };
 
-    let info = Paragraph::new(info_text)
+    let info = Paragraph::new(vec![Line::from(info_text), git_line])
         .block(Block::default().title("Build Info").borders(Borders::ALL))
         .wrap(Wrap { trim: true });
     f.render_widget(info, chunks[2]);
@@ -528,46 +1630,160 @@ fn draw_deploy_tab(f: &mut Frame, app: &App, area: Rect) {
         .constraints([
             Constraint::Length(3),
             Constraint::Min(5),
-            Constraint::Length(3),
+            Constraint::Length(4),
         ])
         .split(area);
 
     let progress = if app.deploy_state.is_deploying {
         Gauge::default()
             .block(Block::default().title("Deploy Progress").borders(Borders::ALL))
-            .gauge_style(Style::default().fg(Color::Green))
+            .gauge_style(app.theme.success())
             .percent(app.deploy_state.progress as u16)
             .label(&app.deploy_state.current_step)
     } else {
         Gauge::default()
             .block(Block::default().title("Deploy Status").borders(Borders::ALL))
-            .gauge_style(Style::default().fg(Color::Gray))
+            .gauge_style(app.theme.muted())
             .percent(0)
             .label("Ready")
     };
     f.render_widget(progress, chunks[0]);
 
-    let logs: Vec<ListItem> = app.deploy_state.logs
-        .iter()
-        .rev()
-        .take(20)
-        .rev()
-        .map(|log| ListItem::new(log.as_str()))
-        .collect();
+    let middle = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[1]);
 
-    let logs_list = List::new(logs)
-        .block(Block::default().title("Deploy Logs").borders(Borders::ALL))
-        .style(Style::default().fg(Color::White));
-    f.render_widget(logs_list, chunks[1]);
+    let logs = app.log_level.apply(&app.deploy_state.logs);
+    let logs_list = log_list("Deploy Logs".to_string(), &logs, middle[0], app);
+    f.render_widget(logs_list, middle[0]);
 
-    let status_text = format!("Target: {} | User: {}",
-                             app.config.deploy.vps_host,
-                             app.config.deploy.vps_user);
+    let backups: Vec<ListItem> = if app.deploy_state.backups.is_empty() {
+        vec![ListItem::new("Press 'r' to list backups on the server")
+            .style(app.theme.muted())]
+    } else {
+        app.deploy_state.backups
+            .iter()
+            .enumerate()
+            .map(|(i, backup)| {
+                let style = if i == app.deploy_state.selected_backup {
+                    app.theme.selection()
+                } else {
+                    app.theme.text()
+                };
+                ListItem::new(backup.summary()).style(style)
+            })
+            .collect()
+    };
+    let backups_list = List::new(backups)
+        .block(Block::default().title("Backups ('r' refresh, Enter roll back)").borders(Borders::ALL));
+    f.render_widget(backups_list, middle[1]);
+
+    let target = app.target_config();
+    let state = &app.deploy_state;
+    let target_line = Line::from(format!("Target: {} | User: {}",
+                                         target.deploy.vps_host,
+                                         target.deploy.vps_user));
+    let service_line = match (&state.deployment_status, &state.status_error) {
+        (_, Some(e)) => Line::styled(format!("Status check failed: {}", e), app.theme.warning()),
+        (Some(status), None) => {
+            let (icon, state_name, style) = if status.service_active {
+                (glyph("🟢"), "active", app.theme.success())
+            } else {
+                (glyph("🔴"), "inactive", app.theme.warning())
+            };
+            Line::from(vec![
+                Span::styled(format!("{} {} ", icon, state_name), style),
+                Span::raw(format!("| Last deployment: {} | Version: {}",
+                                  status.last_deployment.as_deref().unwrap_or("-"),
+                                  status.version.as_deref().unwrap_or("-"))),
+            ])
+        }
+        (None, None) => Line::styled("Checking the service...", app.theme.muted()),
+    };
+    let refresh = match app.next_status_refresh() {
+        _ if state.status_refreshing => "refreshing...".to_string(),
+        Some(next) => format!("next refresh in {}s", next.as_secs_f64().ceil() as u64),
+        None => "refresh pending".to_string(),
+    };
 
-    let status = Paragraph::new(status_text)
-        .block(Block::default().title("Deploy Target").borders(Borders::ALL))
+    let status = Paragraph::new(vec![target_line, service_line])
+        .block(Block::default().title(format!("Status ({}, 's' refresh)", refresh)).borders(Borders::ALL))
         .wrap(Wrap { trim: true });
     f.render_widget(status, chunks[2]);
+
+    if app.deploy_state.confirm_rollback
+        && let Some(backup) = app.deploy_state.backups.get(app.deploy_state.selected_backup)
+    {
+        let text = format!(
+            "Roll back {} to {}?\n\nThe service is stopped while the backup is restored.\n\n'y' confirm | 'n' cancel",
+            target.deploy.vps_host,
+            backup.summary()
+        );
+        let popup = popup_area(text.lines().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 4, 8, area);
+        let dialog = Paragraph::new(text)
+            .style(app.theme.warning())
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().title("Confirm rollback").borders(Borders::ALL));
+        f.render_widget(Clear, popup);
+        f.render_widget(dialog, popup);
+    }
+}
+
+/// Log pane showing the newest lines, or the lines around the focused search match
+fn log_list<'a>(title: String, lines: &'a [String], area: Rect, app: &App) -> List<'a> {
+    let height = area.height.saturating_sub(2) as usize;
+    let selection = app.log_selection;
+    let focus = selection.map(|selection| selection.cursor).or(app.log_search.focus);
+    let width = area.width.saturating_sub(2) as usize;
+    let mut rows: Vec<(usize, Vec<Line>)> = search::window(lines.len(), height, focus)
+        .map(|i| {
+            let line = if selection.is_some_and(|selection| selection.range().contains(&i)) {
+                Line::styled(lines[i].as_str(), app.theme.selection())
+            } else {
+                app.log_search.highlight(&lines[i], focus == Some(i), &app.theme)
+            };
+            let rows = if app.log_wrap {
+                lines::wrap(line, width)
+            } else {
+                vec![lines::skip_columns(line, app.log_hscroll)]
+            };
+            (i, rows)
+        })
+        .collect();
+    // Wrapped lines take several rows; drop the oldest until the rest fit, keeping the focus in view
+    let mut used: usize = rows.iter().map(|(_, rows)| rows.len()).sum();
+    while used > height && rows.first().is_some_and(|(i, _)| Some(*i) != focus) {
+        used -= rows.remove(0).1.len();
+    }
+    let items: Vec<ListItem> = rows.into_iter().map(|(_, rows)| ListItem::new(Text::from(rows))).collect();
+
+    let mode = match selection {
+        Some(_) => " [select: 'v' range, 'y' copy, Esc cancel]",
+        None => "",
+    };
+    let layout = match (app.log_wrap, app.log_hscroll) {
+        (true, _) => " [wrap]".to_string(),
+        (false, 0) => String::new(),
+        (false, columns) => format!(" [col {}+]", columns + 1),
+    };
+    let title = format!("{}{}{}{}{}", title, app.log_level.title(), app.log_search.title(), layout, mode);
+    List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(app.theme.text())
+}
+
+/// Rect of at most `width` x `height` centered in `area`
+fn popup_area(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
 }
 
 /// Draw monitor tab
@@ -577,86 +1793,468 @@ fn draw_monitor_tab(f: &mut Frame, app: &App, area: Rect) {
         .constraints([
             Constraint::Length(5),
             Constraint::Min(5),
-            Constraint::Length(3),
+            Constraint::Length(4),
+            Constraint::Length(6),
         ])
         .split(area);
 
     let status_text = if let Some(status) = &app.monitor_state.status {
-        let health_icon = if status.is_healthy() { "🟢" } else { "🔴" };
-        let _response_time = status.response_time
+        let health_icon = glyph(if status.is_healthy() { "🟢" } else { "🔴" });
+        let response_time = status.response_time
             .map(|d| format!("{}ms", d.as_millis()))
             .unwrap_or_else(|| "N/A".to_string());
+        let network = status.network.as_ref()
+            .and_then(|network| network.rtt_ms)
+            .map(|rtt| format!("{:.1}ms", rtt))
+            .unwrap_or_else(|| "N/A".to_string());
 
-        format!("{} {}", health_icon, status.summary())
+        let mut text = format!("{} {} | Response: {} | Network: {}",
+                health_icon, status.summary(), response_time, network);
+        for warning in &status.warnings {
+            text.push_str(&format!("\n{} {}", glyph("⚠️"), warning));
+        }
+        if !status.ports.is_empty() {
+            text.push_str(&format!("\nPorts: {}", status.ports_summary()));
+        }
+        if !status.checks.is_empty() {
+            text.push_str(&format!("\nChecks: {}", status.checks_summary()));
+        }
+        if let Some(prometheus) = &status.prometheus {
+            text.push_str(&format!("\nApp Metrics: {}", prometheus.summary()));
+        }
+        text
     } else {
         "No monitoring data available".to_string()
     };
 
     let status = Paragraph::new(status_text)
-        .block(Block::default().title("Application Status").borders(Borders::ALL))
+        .block(Block::default()
+            .title(format!("Application Status ({})", app.target_config().deploy.vps_host))
+            .borders(Borders::ALL))
         .wrap(Wrap { trim: true });
     f.render_widget(status, chunks[0]);
 
-    let logs: Vec<ListItem> = app.monitor_state.logs
-        .iter()
-        .rev()
-        .take(20)
-        .rev()
-        .map(|log| ListItem::new(log.as_str()))
-        .collect();
-
-    let logs_list = List::new(logs)
-        .block(Block::default().title("Monitor Logs").borders(Borders::ALL))
-        .style(Style::default().fg(Color::White));
+    let state = &app.monitor_state;
+    let shown = state.logs_paused_at.unwrap_or(state.logs.len());
+    let logs_title = match state.logs_paused_at {
+        Some(_) => format!("Logs [PAUSED, {} new] ('p' resume, 'x' clear)", state.logs.len() - shown),
+        None if state.is_monitoring => "Logs (live, 'p' pause, 'x' clear)".to_string(),
+        None => "Logs (press 'm' to start monitoring)".to_string(),
+    };
+    let logs = app.log_level.apply(&state.logs[..shown]);
+    let logs_list = log_list(logs_title, &logs, chunks[1], app);
     f.render_widget(logs_list, chunks[1]);
 
     let metrics_text = if let Some(metrics) = &app.monitor_state.metrics {
-        format!("Uptime: {:.1}% | Errors: {} | Last Check: {}",
+        let mut text = format!("Uptime: {:.1}% | Errors: {} | Last Check: {}",
                 metrics.uptime_percentage,
                 metrics.error_count,
-                metrics.last_check.format("%H:%M:%S"))
+                app.config.display_timezone().format_with(metrics.last_check, "%H:%M:%S"));
+        if let Some(latency) = &metrics.latency {
+            text.push_str(&format!("\nLatency: {}", latency.summary()));
+        }
+        if let Some(status_codes) = &metrics.status_codes {
+            text.push_str(&format!("\nHTTP: {}", status_codes.summary()));
+        }
+        if let Some(system) = &metrics.system {
+            text.push_str(&format!("\n{}", system.summary()));
+        }
+        text
     } else {
         "No metrics available".to_string()
     };
 
+    let process = app.monitor_state.status.as_ref().and_then(|status| status.process.as_ref());
+    let process_title = match process {
+        Some(process) => {
+            let mut title = format!("Process: {}", process.summary());
+            let trend = app.monitor_state.metrics.as_ref().and_then(|m| m.process_trend.as_ref());
+            if let Some(trend) = trend {
+                title.push_str(&format!(" | RSS {}", trend));
+            }
+            title
+        }
+        None => "Process: no data".to_string(),
+    };
+    let rss_history = app.monitor_state.metrics
+        .as_ref()
+        .map(|metrics| metrics.rss_history.clone())
+        .unwrap_or_default();
+    let process = Sparkline::default()
+        .block(Block::default().title(process_title).borders(Borders::ALL))
+        .data(&rss_history)
+        .style(app.theme.chart());
+    f.render_widget(process, chunks[2]);
+
     let metrics = Paragraph::new(metrics_text)
         .block(Block::default().title("Metrics").borders(Borders::ALL))
         .wrap(Wrap { trim: true });
-    f.render_widget(metrics, chunks[2]);
+    f.render_widget(metrics, chunks[3]);
+}
+
+/// Draw dashboard tab: deploy progress, health, latency and recent logs at once
+fn draw_dashboard_tab(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(7),
+            Constraint::Length(5),
+            Constraint::Min(3),
+        ])
+        .split(area);
+
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[0]);
+
+    let deploy = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(2)])
+        .split(top[0]);
+
+    let target = app.target_config();
+    let state = &app.deploy_state;
+    let progress = Gauge::default()
+        .block(Block::default().title(format!("Deploy ({})", target.deploy.vps_host)).borders(Borders::ALL))
+        .gauge_style(if state.is_deploying { app.theme.success() } else { app.theme.muted() })
+        .percent(if state.is_deploying { state.progress as u16 } else { 0 })
+        .label(state.current_step.as_str());
+    f.render_widget(progress, deploy[0]);
+
+    let last_deploy_log = state.logs.last().map(String::as_str).unwrap_or("No deploy output yet");
+    let deploy_log = Paragraph::new(last_deploy_log)
+        .style(app.theme.muted())
+        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM))
+        .wrap(Wrap { trim: true });
+    f.render_widget(deploy_log, deploy[1]);
+
+    let health_text = match &app.monitor_state.status {
+        Some(status) => {
+            let health_icon = glyph(if status.is_healthy() { "🟢" } else { "🔴" });
+            let response_time = status.response_time
+                .map(|d| format!("{}ms", d.as_millis()))
+                .unwrap_or_else(|| "N/A".to_string());
+            let mut text = format!("{} {}\nResponse: {}", health_icon, status.summary(), response_time);
+            if let Some(metrics) = &app.monitor_state.metrics {
+                text.push_str(&format!("\nUptime: {:.1}% | Errors: {}", metrics.uptime_percentage, metrics.error_count));
+            }
+            for warning in &status.warnings {
+                text.push_str(&format!("\n{} {}", glyph("⚠️"), warning));
+            }
+            text
+        }
+        None if app.monitor_state.is_monitoring => "Waiting for the first health check...".to_string(),
+        None => format!("Press '{}' to start monitoring", app.keys.keys(Action::StartMonitor)),
+    };
+    let health = Paragraph::new(health_text)
+        .block(Block::default().title("Health").borders(Borders::ALL))
+        .wrap(Wrap { trim: true });
+    f.render_widget(health, top[1]);
+
+    let history = &app.monitor_state.latency_history;
+    let latency_title = match history.last() {
+        Some(latest) => format!("Latency: {}ms (max {}ms over {} checks)",
+                                latest,
+                                history.iter().max().unwrap_or(latest),
+                                history.len()),
+        None => "Latency: no data".to_string(),
+    };
+    // Show the newest samples that fit inside the borders
+    let visible = chunks[1].width.saturating_sub(2) as usize;
+    let latency = Sparkline::default()
+        .block(Block::default().title(latency_title).borders(Borders::ALL))
+        .data(&history[history.len().saturating_sub(visible)..])
+        .style(app.theme.chart());
+    f.render_widget(latency, chunks[1]);
+
+    let state = &app.monitor_state;
+    let shown = state.logs_paused_at.unwrap_or(state.logs.len());
+    let logs = app.log_level.apply(&state.logs[..shown]);
+    let logs_list = log_list("Recent Logs".to_string(), &logs, chunks[2], app);
+    f.render_widget(logs_list, chunks[2]);
+}
+
+/// Draw console tab: the remote shell's screen
+fn draw_console_tab(f: &mut Frame, app: &App, area: Rect) {
+    let host = app.console.host.as_deref().unwrap_or_default();
+    let title = if !app.console.is_open() {
+        let target = app.target_config();
+        format!(
+            "Console: press Enter to open a shell as {}@{}",
+            target.deploy.vps_user, target.deploy.vps_host
+        )
+    } else if app.console.focused {
+        format!("Console: {} (Ctrl+] to release the keyboard)", host)
+    } else {
+        format!("Console: {} (Enter to type)", host)
+    };
+    let style = if app.console.focused {
+        app.theme.active()
+    } else {
+        app.theme.text()
+    };
+    let block = Block::default()
+        .title(title)
+        .title_style(style)
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let screen = app.console.parser.screen();
+    f.render_widget(console::Screen(screen), inner);
+    if app.console.focused && !screen.hide_cursor() {
+        let (row, col) = screen.cursor_position();
+        f.set_cursor(
+            inner.x + col.min(inner.width.saturating_sub(1)),
+            inner.y + row.min(inner.height.saturating_sub(1)),
+        );
+    }
 }
 
 /// Draw config tab
 fn draw_config_tab(f: &mut Frame, app: &App, area: Rect) {
-    let config_text = format!(
-        "Project: {}\n\
-         Build Mode: {}\n\
-         Target: {}\n\
-         Host: {}\n\
-         Health Endpoint: {}\n\
-         Log Path: {}",
-        app.config.project.name,
-        app.config.project.build_mode,
-        app.config.deploy.target,
-        app.config.deploy.vps_host,
-        app.config.monitor.health_endpoint.as_deref().unwrap_or("None"),
-        app.config.monitor.log_path.as_deref().unwrap_or("None")
-    );
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let editor = &app.config_editor;
+    let fields: Vec<ListItem> = editor::ConfigField::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let selected = i == editor.selected;
+            let value = match &editor.input {
+                Some(input) if selected => format!("{}{}", input, glyph("▏")),
+                _ => field.get(&app.config),
+            };
+            let style = if selected && editor.input.is_some() {
+                app.theme.selection()
+            } else if selected {
+                app.theme.active()
+            } else {
+                app.theme.text()
+            };
+            ListItem::new(format!("{:<28} {}", field.key(), value)).style(style)
+        })
+        .collect();
 
-    let config = Paragraph::new(config_text)
-        .block(Block::default().title("Configuration").borders(Borders::ALL))
+    let title = format!("Configuration: {}{}",
+                        editor.path.display(),
+                        if editor.dirty { " (modified)" } else { "" });
+    let config = List::new(fields)
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(config, chunks[0]);
+
+    let help = if editor.preview.is_some() {
+        "y/Enter write file | n/Esc cancel | j/k scroll"
+    } else if editor.input.is_some() {
+        "Enter apply | Esc cancel"
+    } else {
+        "j/k or arrows select | Enter edit | s review and save"
+    };
+    let footer = match &editor.message {
+        Some(message) => format!("{} | {}", message, help),
+        None => help.to_string(),
+    };
+    let footer = Paragraph::new(footer)
+        .block(Block::default().borders(Borders::ALL))
         .wrap(Wrap { trim: true });
-    f.render_widget(config, area);
+    f.render_widget(footer, chunks[1]);
+
+    if let Some(preview) = &editor.preview {
+        let popup = popup_area(area.width.saturating_sub(4), chunks[0].height, chunks[0]);
+        let lines: Vec<Line> = preview.lines
+            .iter()
+            .skip(preview.scroll)
+            .map(|line| {
+                let style = if line.starts_with("+++") || line.starts_with("---") || line.starts_with("@@") {
+                    app.theme.muted()
+                } else if line.starts_with('+') {
+                    app.theme.success()
+                } else if line.starts_with('-') {
+                    app.theme.warning()
+                } else {
+                    app.theme.text()
+                };
+                Line::styled(line.as_str(), style)
+            })
+            .collect();
+        let diff = Paragraph::new(lines)
+            .block(Block::default().title("Save these changes? ('y' write, 'n' cancel)").borders(Borders::ALL));
+        f.render_widget(Clear, popup);
+        f.render_widget(diff, popup);
+    }
+}
+
+/// Draw the last error's full chain and output over the current tab
+fn draw_error(f: &mut Frame, app: &App, error: &errors::ErrorDetail, area: Rect) {
+    let width = area.width.saturating_sub(8).max(40);
+    let height = area.height.saturating_sub(4).max(10);
+    let popup = popup_area(width, height, area);
+    let lines: Vec<Line> = error.lines[error.scroll.min(error.lines.len())..]
+        .iter()
+        .map(|line| Line::from(line.as_str()))
+        .collect();
+    let title = format!("{} (j/k scroll, Esc close)", error.title);
+    let detail = Paragraph::new(lines)
+        .style(app.theme.text())
+        .wrap(Wrap { trim: false })
+        .block(Block::default().title(title).title_style(app.theme.warning()).borders(Borders::ALL));
+    f.render_widget(Clear, popup);
+    f.render_widget(detail, popup);
+}
+
+/// Draw the key binding overview over the current tab
+fn draw_help(f: &mut Frame, app: &App, area: Rect) {
+    let mut lines: Vec<String> = Action::ALL
+        .iter()
+        .map(|&action| {
+            let keys = app.keys.keys(action);
+            // Unbound actions are still reachable through the command palette
+            let keys = if keys.is_empty() { "(palette)".to_string() } else { keys };
+            format!("{:<14} {}", keys, action.describe())
+        })
+        .collect();
+    if app.vim.is_some() {
+        lines.extend([
+            String::new(),
+            "Vim mode:    j/k scroll | gg/G top/bottom | Ctrl-d/Ctrl-u half page".to_string(),
+            "             :q quit | :w save config | :<n> go to line | :tab <name> | :<action>".to_string(),
+        ]);
+    }
+    lines.extend([
+        String::new(),
+        "Deploy tab:  s refresh status | r list backups | j/k select | Enter roll back".to_string(),
+        "Monitor tab: p pause logs | x clear logs".to_string(),
+        "Log panes:   / search | n/N next/previous match | v select lines, y copy | f filter by level".to_string(),
+        "             w export shown lines | W export the whole log | z wrap | </> scroll sideways".to_string(),
+        "Config tab:  j/k select | Enter edit | s review changes and save".to_string(),
+        "Console tab: Enter connect / type into the shell | Ctrl+] release the keyboard".to_string(),
+        String::new(),
+        "Press any key to close".to_string(),
+    ]);
+
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 4;
+    let popup = popup_area(width, lines.len() as u16 + 2, area);
+    let help = Paragraph::new(lines.join("\n"))
+        .block(Block::default().title("Keys").borders(Borders::ALL));
+    f.render_widget(Clear, popup);
+    f.render_widget(help, popup);
 }
 
-/// Draw exit tab
-fn draw_exit_tab(f: &mut Frame, _app: &App, area: Rect) {
-    let exit_text = "Are you sure you want to exit?\n\n\
-                     Press 'q' or 'Enter' to confirm exit\n\
-                     Press 'h' or left arrow to go back";
+/// Draw the command palette over the current tab
+fn draw_palette(f: &mut Frame, app: &App, palette: &CommandPalette, area: Rect) {
+    let entries = palette.entries();
+    let items: Vec<ListItem> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, &action)| {
+            let style = if i == palette.selected {
+                app.theme.selection()
+            } else {
+                app.theme.text()
+            };
+            ListItem::new(format!("{:<32} {}", action.describe(), app.keys.keys(action))).style(style)
+        })
+        .collect();
+
+    let popup = popup_area(56, Action::ALL.len() as u16 + 3, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(popup);
+
+    let input = Paragraph::new(format!("> {}{}", palette.query, glyph("▏")))
+        .block(Block::default().title("Command palette (Enter run, Esc close)").borders(Borders::ALL));
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM));
+    f.render_widget(Clear, popup);
+    f.render_widget(input, chunks[0]);
+    f.render_widget(list, chunks[1]);
+}
+
+/// Draw the host picker popup over the current tab
+fn draw_host_picker(f: &mut Frame, app: &App, selected: usize, area: Rect) {
+    let hosts = app.config.hosts();
+    let current = app.target_config().deploy.vps_host;
+    let items: Vec<ListItem> = hosts
+        .iter()
+        .enumerate()
+        .map(|(i, host)| {
+            let marker = if *host == current { glyph("●") } else { " " };
+            let style = if i == selected {
+                app.theme.selection()
+            } else {
+                app.theme.text()
+            };
+            ListItem::new(format!("{} {}", marker, host)).style(style)
+        })
+        .collect();
+
+    let width = hosts.iter().map(|h| h.len()).max().unwrap_or(0).max(30) as u16 + 6;
+    let popup = popup_area(width, hosts.len() as u16 + 2, area);
+
+    let list = List::new(items)
+        .block(Block::default().title("Target host (Enter select, Esc close)").borders(Borders::ALL));
+    f.render_widget(Clear, popup);
+    f.render_widget(list, popup);
+}
 
-    let exit = Paragraph::new(exit_text)
-        .block(Block::default().title("Exit").borders(Borders::ALL))
+/// Draw the question whether to reconnect with the reloaded connection settings
+fn draw_reload_dialog(f: &mut Frame, app: &App, reload: &Reload, area: Rect) {
+    let mut lines = vec![
+        format!("{} changed the connection settings:", app.config_editor.path.display()),
+        reload.reconnect.join(", "),
+        String::new(),
+        "Reconnect with them? Running monitoring restarts.".to_string(),
+    ];
+    if !reload.applied.is_empty() {
+        lines.push(format!("Applied either way: {}", reload.applied.join(", ")));
+    }
+    lines.push(String::new());
+    lines.push("'y' reconnect | 'n' keep the current connection".to_string());
+
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 4;
+    let popup = popup_area(width, lines.len() as u16 + 2, area);
+    let dialog = Paragraph::new(lines.join("\n"))
+        .style(app.theme.warning())
         .alignment(Alignment::Center)
-        .wrap(Wrap { trim: true });
-    f.render_widget(exit, area);
+        .wrap(Wrap { trim: true })
+        .block(Block::default().title("Config changed").borders(Borders::ALL));
+    f.render_widget(Clear, popup);
+    f.render_widget(dialog, popup);
+}
+
+/// Draw the quit confirmation over the current tab
+fn draw_quit_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let mut lines = vec!["Quit rzen?".to_string()];
+    if app.config_editor.dirty {
+        lines.push(String::new());
+        lines.push(format!("Unsaved changes to {} will be lost.", app.config_editor.path.display()));
+    }
+    if app.build_state.is_building || app.deploy_state.is_deploying {
+        lines.push(String::new());
+        lines.push("A build or deployment is still running and will be stopped.".to_string());
+    }
+    lines.push(String::new());
+    lines.push(if app.config_editor.dirty {
+        "'y' quit | 's' save and quit | 'n' cancel".to_string()
+    } else {
+        "'y' quit | 'n' cancel".to_string()
+    });
+
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 4;
+    let popup = popup_area(width, lines.len() as u16 + 2, area);
+    let dialog = Paragraph::new(lines.join("\n"))
+        .style(app.theme.warning())
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().title("Quit").borders(Borders::ALL));
+    f.render_widget(Clear, popup);
+    f.render_widget(dialog, popup);
 }
@@ -0,0 +1,74 @@
+use std::ops::Deref;
+
+/// Log lines of one pane, keeping only the newest `capacity`
+///
+/// Lines dropped from the front are compacted away once `capacity` of them have piled up,
+/// so pushing stays cheap while the buffer still reads as one slice.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: Vec<String>,
+    /// Index of the oldest kept line in `lines`
+    start: usize,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Vec::new(),
+            start: 0,
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Append a line; returns the oldest line when it had to make room
+    pub fn push(&mut self, line: String) -> Option<&String> {
+        if self.start >= self.capacity {
+            self.lines.drain(..self.start);
+            self.start = 0;
+        }
+        self.lines.push(line);
+        if self.lines.len() - self.start <= self.capacity {
+            return None;
+        }
+        self.start += 1;
+        Some(&self.lines[self.start - 1])
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.start = 0;
+    }
+}
+
+impl Deref for LogBuffer {
+    type Target = [String];
+
+    fn deref(&self) -> &[String] {
+        &self.lines[self.start..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_newest_lines() {
+        let mut buffer = LogBuffer::new(3);
+        for i in 0..3 {
+            assert_eq!(buffer.push(format!("line {}", i)), None);
+        }
+        assert_eq!(buffer.push("line 3".to_string()).unwrap(), "line 0");
+        assert_eq!(&buffer[..], ["line 1", "line 2", "line 3"]);
+
+        for i in 4..10 {
+            assert!(buffer.push(format!("line {}", i)).is_some());
+        }
+        assert_eq!(&buffer[..], ["line 7", "line 8", "line 9"]);
+        assert!(buffer.lines.len() <= 6);
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+    }
+}
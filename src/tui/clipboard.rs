@@ -0,0 +1,72 @@
+use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// Copy text to the system clipboard; returns what performed the copy
+///
+/// Platform clipboard tools are tried first. Without one (e.g. over SSH) the
+/// text is handed to the terminal as an OSC 52 sequence, which most terminals
+/// turn into a clipboard update.
+pub fn copy(text: &str) -> Result<&'static str> {
+    for (program, args) in commands() {
+        if run(program, args, text).unwrap_or(false) {
+            return Ok(program);
+        }
+    }
+
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52(text).as_bytes())?;
+    stdout.flush()?;
+    Ok("terminal (OSC 52)")
+}
+
+/// Clipboard tools for the current platform and session, in order of preference
+fn commands() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        return vec![("pbcopy", &[])];
+    }
+    if cfg!(windows) {
+        return vec![("clip", &[])];
+    }
+
+    let mut commands: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        commands.push(("wl-copy", &[]));
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        commands.push(("xclip", &["-selection", "clipboard"]));
+        commands.push(("xsel", &["--clipboard", "--input"]));
+    }
+    commands
+}
+
+/// Pipe text into a clipboard tool; false when it is missing or fails
+fn run(program: &str, args: &[&str], text: &str) -> io::Result<bool> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    Ok(child.wait()?.success())
+}
+
+/// OSC 52 "set clipboard" sequence
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52("error: boom"), "\x1b]52;c;ZXJyb3I6IGJvb20=\x07");
+    }
+}
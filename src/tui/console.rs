@@ -0,0 +1,325 @@
+use anyhow::{Result, anyhow};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+use std::io::{ErrorKind, Read, Write};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::BackgroundMessage;
+use crate::config::Config;
+
+/// Lines of shell output kept above the visible screen
+const SCROLLBACK: usize = 1000;
+
+/// Input for the remote shell from the Console tab
+#[derive(Debug)]
+pub enum Command {
+    Input(Vec<u8>),
+    Resize { rows: u16, cols: u16 },
+}
+
+/// Console tab state: the emulated terminal screen and the link to the shell task
+pub struct Console {
+    pub parser: vt100::Parser,
+    commands: Option<mpsc::UnboundedSender<Command>>,
+    task: Option<tokio::task::JoinHandle<()>>,
+    /// Keys go to the shell instead of the TUI
+    pub focused: bool,
+    /// Host of the open (or last) session
+    pub host: Option<String>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self {
+            parser: vt100::Parser::new(24, 80, SCROLLBACK),
+            commands: None,
+            task: None,
+            focused: false,
+            host: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.commands
+            .as_ref()
+            .is_some_and(|commands| !commands.is_closed())
+    }
+
+    /// Start a shell on the target host, reporting its output and exit through `tx`
+    pub fn open(&mut self, config: Config, tx: &mpsc::Sender<BackgroundMessage>) {
+        self.close();
+        let (rows, cols) = self.parser.screen().size();
+        self.parser = vt100::Parser::new(rows, cols, SCROLLBACK);
+        self.host = Some(config.deploy.vps_host.clone());
+        self.focused = true;
+
+        let (commands, rx) = mpsc::unbounded_channel();
+        self.commands = Some(commands);
+        let tx = tx.clone();
+        self.task = Some(tokio::spawn(async move {
+            let result = run_shell(config, rows, cols, rx, &tx).await;
+            let _ = tx.send(BackgroundMessage::ConsoleClosed(result)).await;
+        }));
+    }
+
+    pub fn close(&mut self) {
+        self.commands = None;
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.focused = false;
+    }
+
+    pub fn send(&self, bytes: Vec<u8>) {
+        if let Some(commands) = &self.commands {
+            let _ = commands.send(Command::Input(bytes));
+        }
+    }
+
+    /// Fit the screen (and the remote PTY) to the pane
+    pub fn resize(&mut self, rows: u16, cols: u16) {
+        if (rows, cols) == self.parser.screen().size() || rows == 0 || cols == 0 {
+            return;
+        }
+        self.parser.set_size(rows, cols);
+        if let Some(commands) = &self.commands {
+            let _ = commands.send(Command::Resize { rows, cols });
+        }
+    }
+}
+
+/// Bytes a terminal sends for a key press, or `None` for keys without one
+pub fn key_bytes(key: &KeyEvent, application_cursor: bool) -> Option<Vec<u8>> {
+    let cursor = |code: u8| {
+        let prefix = if application_cursor { b'O' } else { b'[' };
+        vec![0x1b, prefix, code]
+    };
+    let mut bytes = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            match c.to_ascii_lowercase() {
+                c @ 'a'..='z' => vec![c as u8 - b'a' + 1],
+                ' ' | '@' => vec![0],
+                '[' => vec![0x1b],
+                '\\' => vec![0x1c],
+                ']' => vec![0x1d],
+                _ => return None,
+            }
+        }
+        KeyCode::Char(c) => c.to_string().into_bytes(),
+        KeyCode::Enter => vec![b'\r'],
+        KeyCode::Tab => vec![b'\t'],
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => cursor(b'A'),
+        KeyCode::Down => cursor(b'B'),
+        KeyCode::Right => cursor(b'C'),
+        KeyCode::Left => cursor(b'D'),
+        KeyCode::Home => cursor(b'H'),
+        KeyCode::End => cursor(b'F'),
+        KeyCode::Insert => b"\x1b[2~".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        KeyCode::F(n @ 1..=4) => vec![0x1b, b'O', b'P' + n - 1],
+        KeyCode::F(n @ 5..=12) => {
+            let code = [15, 17, 18, 19, 20, 21, 23, 24][usize::from(n - 5)];
+            format!("\x1b[{}~", code).into_bytes()
+        }
+        _ => return None,
+    };
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        bytes.insert(0, 0x1b);
+    }
+    Some(bytes)
+}
+
+/// Run an interactive shell on the target host until it exits or the commands channel closes
+async fn run_shell(
+    config: Config,
+    rows: u16,
+    cols: u16,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+    tx: &mpsc::Sender<BackgroundMessage>,
+) -> Result<()> {
+    let ssh_config = config.ssh_config();
+    // A session of its own, since the shell switches it to non-blocking mode
+    let session =
+        crate::utils::ssh::connect_with_retry(&ssh_config, ssh_config.options.max_retries).await?;
+
+    let mut channel = session
+        .channel_session()
+        .map_err(|e| anyhow!("Failed to create SSH channel: {}", e))?;
+    channel.request_pty(
+        "xterm-256color",
+        None,
+        Some((u32::from(cols), u32::from(rows), 0, 0)),
+    )?;
+    channel.shell()?;
+    // Non-blocking reads let the task yield while the shell is idle, so it can be cancelled
+    session.set_blocking(false);
+
+    let mut buf = [0; 8192];
+    loop {
+        let mut idle = true;
+
+        loop {
+            let command = match commands.try_recv() {
+                Ok(command) => command,
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => return Ok(()),
+            };
+            idle = false;
+            match command {
+                Command::Input(bytes) => {
+                    let mut written = 0;
+                    while written < bytes.len() {
+                        match channel.write(&bytes[written..]) {
+                            Ok(n) => written += n,
+                            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                                tokio::time::sleep(Duration::from_millis(5)).await;
+                            }
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
+                }
+                Command::Resize { rows, cols } => {
+                    // Best effort: the shell keeps its old size if the server refuses
+                    let _ = channel.request_pty_size(u32::from(cols), u32::from(rows), None, None);
+                }
+            }
+        }
+
+        match channel.read(&mut buf) {
+            Ok(0) if channel.eof() => return Ok(()),
+            Ok(0) => {}
+            Ok(n) => {
+                idle = false;
+                // Waiting for room keeps the output complete; dropped bytes would garble the screen
+                let output = BackgroundMessage::ConsoleOutput(buf[..n].to_vec());
+                if tx.send(output).await.is_err() {
+                    return Ok(());
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        if idle {
+            // Sends one only once `keepalive_interval_secs` has passed
+            let _ = session.keepalive_send();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
+
+/// Widget drawing the emulated terminal screen
+pub struct Screen<'a>(pub &'a vt100::Screen);
+
+impl Widget for Screen<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (rows, cols) = self.0.size();
+        for row in 0..rows.min(area.height) {
+            for col in 0..cols.min(area.width) {
+                let Some(cell) = self.0.cell(row, col) else {
+                    continue;
+                };
+                if cell.is_wide_continuation() {
+                    continue;
+                }
+
+                let mut style = Style::default()
+                    .fg(color(cell.fgcolor()))
+                    .bg(color(cell.bgcolor()));
+                for (set, modifier) in [
+                    (cell.bold(), Modifier::BOLD),
+                    (cell.italic(), Modifier::ITALIC),
+                    (cell.underline(), Modifier::UNDERLINED),
+                    (cell.inverse(), Modifier::REVERSED),
+                ] {
+                    if set {
+                        style = style.add_modifier(modifier);
+                    }
+                }
+
+                let contents = cell.contents();
+                let symbol = if contents.is_empty() {
+                    " "
+                } else {
+                    contents.as_str()
+                };
+                buf.get_mut(area.x + col, area.y + row)
+                    .set_symbol(symbol)
+                    .set_style(style);
+            }
+        }
+    }
+}
+
+fn color(color: vt100::Color) -> Color {
+    match color {
+        vt100::Color::Default => Color::Reset,
+        vt100::Color::Idx(index) => Color::Indexed(index),
+        vt100::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> Option<Vec<u8>> {
+        key_bytes(&KeyEvent::new(code, modifiers), false)
+    }
+
+    #[test]
+    fn test_key_bytes() {
+        assert_eq!(
+            press(KeyCode::Char('l'), KeyModifiers::NONE),
+            Some(b"l".to_vec())
+        );
+        assert_eq!(
+            press(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(vec![3])
+        );
+        assert_eq!(
+            press(KeyCode::Char('b'), KeyModifiers::ALT),
+            Some(b"\x1bb".to_vec())
+        );
+        assert_eq!(
+            press(KeyCode::Enter, KeyModifiers::NONE),
+            Some(b"\r".to_vec())
+        );
+        assert_eq!(
+            press(KeyCode::Up, KeyModifiers::NONE),
+            Some(b"\x1b[A".to_vec())
+        );
+        assert_eq!(
+            key_bytes(&KeyEvent::new(KeyCode::Up, KeyModifiers::NONE), true),
+            Some(b"\x1bOA".to_vec())
+        );
+        assert_eq!(
+            press(KeyCode::F(5), KeyModifiers::NONE),
+            Some(b"\x1b[15~".to_vec())
+        );
+        assert_eq!(press(KeyCode::Null, KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn test_render_screen() {
+        let mut parser = vt100::Parser::new(2, 10, 0);
+        parser.process(b"ok \x1b[31mfail\x1b[0m");
+
+        let area = Rect::new(0, 0, 10, 2);
+        let mut buf = Buffer::empty(area);
+        Screen(parser.screen()).render(area, &mut buf);
+        assert_eq!(buf.get(0, 0).symbol(), "o");
+        assert_eq!(buf.get(3, 0).symbol(), "f");
+        assert_eq!(buf.get(3, 0).fg, Color::Indexed(1));
+        assert_eq!(buf.get(0, 1).symbol(), " ");
+    }
+}
@@ -0,0 +1,378 @@
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use similar::TextDiff;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+
+/// Setting that can be edited from the Config tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigField {
+    ProjectName,
+    ProjectPath,
+    BuildMode,
+    Host,
+    User,
+    SshPort,
+    KeyPath,
+    DeployPath,
+    ServiceName,
+    HealthEndpoint,
+    LogPath,
+    Interval,
+    HealthTimeout,
+}
+
+impl ConfigField {
+    pub const ALL: [ConfigField; 13] = [
+        ConfigField::ProjectName,
+        ConfigField::ProjectPath,
+        ConfigField::BuildMode,
+        ConfigField::Host,
+        ConfigField::User,
+        ConfigField::SshPort,
+        ConfigField::KeyPath,
+        ConfigField::DeployPath,
+        ConfigField::ServiceName,
+        ConfigField::HealthEndpoint,
+        ConfigField::LogPath,
+        ConfigField::Interval,
+        ConfigField::HealthTimeout,
+    ];
+
+    /// Key of the setting in rzen.toml
+    pub fn key(self) -> &'static str {
+        match self {
+            ConfigField::ProjectName => "project.name",
+            ConfigField::ProjectPath => "project.path",
+            ConfigField::BuildMode => "project.build_mode",
+            ConfigField::Host => "deploy.vps_host",
+            ConfigField::User => "deploy.vps_user",
+            ConfigField::SshPort => "deploy.ssh_port",
+            ConfigField::KeyPath => "deploy.vps_key_path",
+            ConfigField::DeployPath => "deploy.deploy_path",
+            ConfigField::ServiceName => "deploy.service_name",
+            ConfigField::HealthEndpoint => "monitor.health_endpoint",
+            ConfigField::LogPath => "monitor.log_path",
+            ConfigField::Interval => "monitor.interval_secs",
+            ConfigField::HealthTimeout => "monitor.health_timeout_secs",
+        }
+    }
+
+    /// Current value as text; unset optional values are empty
+    pub fn get(self, config: &Config) -> String {
+        match self {
+            ConfigField::ProjectName => config.project.name.clone(),
+            ConfigField::ProjectPath => config.project.path.clone(),
+            ConfigField::BuildMode => config.project.build_mode.clone(),
+            ConfigField::Host => config.deploy.vps_host.clone(),
+            ConfigField::User => config.deploy.vps_user.clone(),
+            ConfigField::SshPort => config.deploy.ssh_port.to_string(),
+            ConfigField::KeyPath => config.deploy.vps_key_path.clone().unwrap_or_default(),
+            ConfigField::DeployPath => config.deploy.deploy_path.clone(),
+            ConfigField::ServiceName => config.deploy.service_name.clone().unwrap_or_default(),
+            ConfigField::HealthEndpoint => {
+                config.monitor.health_endpoint.clone().unwrap_or_default()
+            }
+            ConfigField::LogPath => config.monitor.log_path.clone().unwrap_or_default(),
+            ConfigField::Interval => config.monitor.interval_secs.to_string(),
+            ConfigField::HealthTimeout => config.monitor.health_timeout_secs.to_string(),
+        }
+    }
+
+    /// Parse `value` into the setting; an empty value unsets optional settings
+    pub fn set(self, config: &mut Config, value: &str) -> Result<()> {
+        let value = value.trim();
+        let optional = || (!value.is_empty()).then(|| value.to_string());
+        let number = || {
+            value
+                .parse::<u64>()
+                .with_context(|| format!("{} must be a number", self.key()))
+        };
+
+        match self {
+            ConfigField::ProjectName => config.project.name = value.to_string(),
+            ConfigField::ProjectPath => config.project.path = value.to_string(),
+            ConfigField::BuildMode => config.project.build_mode = value.to_string(),
+            ConfigField::Host => config.deploy.vps_host = value.to_string(),
+            ConfigField::User => config.deploy.vps_user = value.to_string(),
+            ConfigField::SshPort => {
+                config.deploy.ssh_port = value
+                    .parse()
+                    .with_context(|| format!("{} must be a port number", self.key()))?
+            }
+            ConfigField::KeyPath => config.deploy.vps_key_path = optional(),
+            ConfigField::DeployPath => config.deploy.deploy_path = value.to_string(),
+            ConfigField::ServiceName => config.deploy.service_name = optional(),
+            ConfigField::HealthEndpoint => config.monitor.health_endpoint = optional(),
+            ConfigField::LogPath => config.monitor.log_path = optional(),
+            ConfigField::Interval => config.monitor.interval_secs = number()?,
+            ConfigField::HealthTimeout => config.monitor.health_timeout_secs = number()?,
+        }
+        Ok(())
+    }
+}
+
+/// Lines moved by PageUp/PageDown in the save preview
+const PAGE: usize = 10;
+
+/// Unified diff of the config file against the edited settings, confirmed before saving
+#[derive(Debug, Clone)]
+pub struct SavePreview {
+    pub lines: Vec<String>,
+    /// First diff line shown
+    pub scroll: usize,
+}
+
+/// Config tab state: field selection, the line being edited, and save status
+#[derive(Debug, Clone)]
+pub struct ConfigEditor {
+    /// File the configuration was loaded from and is saved back to
+    pub path: PathBuf,
+    pub selected: usize,
+    /// Text being typed while a field is edited
+    pub input: Option<String>,
+    /// Changes applied since the last save
+    pub dirty: bool,
+    /// Outcome of the last edit or save
+    pub message: Option<String>,
+    /// Pending save, shown as a diff until it is confirmed or cancelled
+    pub preview: Option<SavePreview>,
+}
+
+impl ConfigEditor {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            selected: 0,
+            input: None,
+            dirty: false,
+            message: None,
+            preview: None,
+        }
+    }
+
+    pub fn selected_field(&self) -> ConfigField {
+        ConfigField::ALL[self.selected]
+    }
+
+    /// Handle a key press in the Config tab; returns false for keys it does not use
+    pub fn handle_key(&mut self, key: KeyCode, config: &mut Config) -> bool {
+        if let Some(preview) = &mut self.preview {
+            let last = preview.lines.len().saturating_sub(1);
+            match key {
+                KeyCode::Char('y') | KeyCode::Enter => self.save(config),
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.preview = None;
+                    self.message = Some("Save cancelled".to_string());
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    preview.scroll = preview.scroll.saturating_sub(1)
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    preview.scroll = (preview.scroll + 1).min(last)
+                }
+                KeyCode::PageUp => preview.scroll = preview.scroll.saturating_sub(PAGE),
+                KeyCode::PageDown => preview.scroll = (preview.scroll + PAGE).min(last),
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(input) = &mut self.input {
+            match key {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => self.commit(config),
+                KeyCode::Esc => {
+                    self.input = None;
+                    self.message = None;
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(ConfigField::ALL.len() - 1);
+            }
+            KeyCode::Enter | KeyCode::Char('e') => {
+                self.input = Some(self.selected_field().get(config));
+                self.message = None;
+            }
+            KeyCode::Char('s') => self.preview_save(config),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Apply the input to a copy of the config and keep it only if it validates
+    fn commit(&mut self, config: &mut Config) {
+        let Some(input) = &self.input else {
+            return;
+        };
+        let field = self.selected_field();
+        let mut updated = config.clone();
+
+        match field
+            .set(&mut updated, input)
+            .and_then(|_| updated.validate())
+        {
+            Ok(()) => {
+                *config = updated;
+                self.input = None;
+                self.dirty = true;
+                self.message = Some(format!("Updated {} (press 's' to save)", field.key()));
+            }
+            Err(e) => self.message = Some(format!("Invalid {}: {}", field.key(), e)),
+        }
+    }
+
+    /// Diff the file on disk against what saving would write, and ask to confirm it
+    pub fn preview_save(&mut self, config: &Config) {
+        let contents = match config.to_toml() {
+            Ok(contents) => contents,
+            Err(e) => {
+                self.message = Some(format!("Save failed: {}", e));
+                return;
+            }
+        };
+        // A missing file shows up as every line being added
+        let current = fs::read_to_string(&self.path).unwrap_or_default();
+        if current == contents {
+            self.dirty = false;
+            self.message = Some(format!("No changes to save in {}", self.path.display()));
+            return;
+        }
+
+        let path = self.path.display().to_string();
+        let diff = TextDiff::from_lines(&current, &contents)
+            .unified_diff()
+            .header(&path, &path)
+            .to_string();
+        self.preview = Some(SavePreview {
+            lines: diff.lines().map(str::to_string).collect(),
+            scroll: 0,
+        });
+    }
+
+    pub fn save(&mut self, config: &Config) {
+        self.preview = None;
+        match config.save(&self.path) {
+            Ok(()) => {
+                self.dirty = false;
+                self.message = Some(format!("Saved {}", self.path.display()));
+            }
+            Err(e) => self.message = Some(format!("Save failed: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        toml::from_str(
+            r#"
+[project]
+name = "app"
+
+[deploy]
+vps_host = "old.example.com"
+vps_user = "deploy"
+vps_key_path = "~/.ssh/id_ed25519"
+
+[monitor]
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_edit_fields() {
+        let mut config = config();
+        let mut editor = ConfigEditor::new(PathBuf::from("rzen.toml"));
+        editor.selected = 3;
+
+        assert!(editor.handle_key(KeyCode::Enter, &mut config));
+        assert_eq!(editor.input.as_deref(), Some("old.example.com"));
+        editor.input = Some("new.example.com".to_string());
+        assert!(editor.handle_key(KeyCode::Enter, &mut config));
+        assert_eq!(config.deploy.vps_host, "new.example.com");
+        assert!(editor.dirty);
+
+        // Invalid values are rejected and stay in the input line
+        editor.selected = 9;
+        editor.handle_key(KeyCode::Enter, &mut config);
+        editor.input = Some("not-a-url".to_string());
+        editor.handle_key(KeyCode::Enter, &mut config);
+        assert!(editor.input.is_some());
+        assert!(editor.message.as_deref().unwrap().starts_with("Invalid"));
+        assert_eq!(config.monitor.health_endpoint, None);
+
+        // Keys typed while editing never reach the global bindings
+        assert!(editor.handle_key(KeyCode::Char('q'), &mut config));
+        assert!(editor.handle_key(KeyCode::Esc, &mut config));
+        assert!(!editor.handle_key(KeyCode::Char('q'), &mut config));
+    }
+
+    #[test]
+    fn test_save_preview() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rzen.toml");
+        let mut config = config();
+        config.save(&path).unwrap();
+
+        let mut editor = ConfigEditor::new(path.clone());
+        editor.handle_key(KeyCode::Char('s'), &mut config);
+        assert!(editor.preview.is_none());
+        assert!(editor.message.as_deref().unwrap().starts_with("No changes"));
+
+        config.deploy.vps_host = "new.example.com".to_string();
+        editor.dirty = true;
+        editor.handle_key(KeyCode::Char('s'), &mut config);
+        let lines = &editor.preview.as_ref().unwrap().lines;
+        assert!(lines.contains(&"-vps_host = \"old.example.com\"".to_string()));
+        assert!(lines.contains(&"+vps_host = \"new.example.com\"".to_string()));
+
+        // Nothing is written until the diff is confirmed
+        assert!(editor.handle_key(KeyCode::Char('q'), &mut config));
+        assert!(editor.handle_key(KeyCode::Esc, &mut config));
+        assert!(editor.preview.is_none());
+        assert!(
+            fs::read_to_string(&path)
+                .unwrap()
+                .contains("old.example.com")
+        );
+
+        editor.handle_key(KeyCode::Char('s'), &mut config);
+        editor.handle_key(KeyCode::Char('y'), &mut config);
+        assert!(editor.preview.is_none());
+        assert!(!editor.dirty);
+        assert!(
+            fs::read_to_string(&path)
+                .unwrap()
+                .contains("new.example.com")
+        );
+    }
+
+    #[test]
+    fn test_field_parsing() {
+        let mut config = config();
+        assert!(ConfigField::SshPort.set(&mut config, "70000").is_err());
+        ConfigField::SshPort.set(&mut config, " 2222 ").unwrap();
+        assert_eq!(config.deploy.ssh_port, 2222);
+
+        ConfigField::LogPath.set(&mut config, "").unwrap();
+        assert_eq!(config.monitor.log_path, None);
+        assert_eq!(ConfigField::LogPath.get(&config), "");
+    }
+}
@@ -0,0 +1,105 @@
+use crossterm::event::KeyCode;
+
+/// Lines moved by PageUp/PageDown
+const PAGE: usize = 10;
+
+/// Output lines kept with an error, newest last
+const MAX_OUTPUT_LINES: usize = 200;
+
+/// Most recent failure with its full error chain, shown in the error popup
+#[derive(Debug, Clone)]
+pub struct ErrorDetail {
+    /// What failed, e.g. "Build failed"
+    pub title: String,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+}
+
+impl ErrorDetail {
+    /// Error chain (outermost context first) followed by the output that led up to it
+    pub fn new(title: &str, error: &anyhow::Error, output: &[String]) -> Self {
+        let mut lines = Vec::new();
+        for (i, cause) in error.chain().enumerate() {
+            let prefix = if i == 0 { "" } else { "Caused by: " };
+            for (j, line) in cause.to_string().lines().enumerate() {
+                let prefix = if j == 0 { prefix } else { "  " };
+                lines.push(format!("{}{}", prefix, line));
+            }
+        }
+
+        if !output.is_empty() {
+            lines.push(String::new());
+            lines.push("Output:".to_string());
+            let skipped = output.len().saturating_sub(MAX_OUTPUT_LINES);
+            if skipped > 0 {
+                lines.push(format!("  ({} earlier lines not shown)", skipped));
+            }
+            lines.extend(output[skipped..].iter().map(|line| format!("  {}", line)));
+        }
+
+        Self {
+            title: title.to_string(),
+            lines,
+            scroll: 0,
+        }
+    }
+
+    /// One-line form for the status bar: the outermost error message
+    pub fn summary(&self) -> String {
+        let first = self.lines.first().map(String::as_str).unwrap_or_default();
+        format!("{}: {}", self.title, first)
+    }
+
+    /// Handle a key while the popup is open; returns false when it should close
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        let last = self.lines.len().saturating_sub(1);
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = (self.scroll + 1).min(last),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(PAGE),
+            KeyCode::PageDown => self.scroll = (self.scroll + PAGE).min(last),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = last,
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') | KeyCode::Char('e') => {
+                return false;
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_error_chain() {
+        let error = Err::<(), _>(anyhow::anyhow!("Permission denied\nuse sudo"))
+            .context("Failed to upload binary")
+            .unwrap_err();
+        let output = vec!["Uploading app".to_string()];
+        let mut detail = ErrorDetail::new("Deployment failed", &error, &output);
+
+        assert_eq!(
+            detail.lines,
+            [
+                "Failed to upload binary",
+                "Caused by: Permission denied",
+                "  use sudo",
+                "",
+                "Output:",
+                "  Uploading app",
+            ]
+        );
+        assert_eq!(
+            detail.summary(),
+            "Deployment failed: Failed to upload binary"
+        );
+
+        assert!(detail.handle_key(KeyCode::PageDown));
+        assert_eq!(detail.scroll, 5);
+        assert!(!detail.handle_key(KeyCode::Esc));
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory under the project that exported logs are written to
+const EXPORT_DIR: &str = "rzen-logs";
+
+/// Write log lines to `<project>/rzen-logs/<pane>-<timestamp>.log`; returns the file's path
+pub fn write(
+    project: &Path,
+    pane: &str,
+    lines: &[String],
+    now: DateTime<Local>,
+) -> Result<PathBuf> {
+    let dir = project.join(EXPORT_DIR);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let name = format!(
+        "{}-{}.log",
+        pane.to_lowercase(),
+        now.format("%Y%m%d-%H%M%S")
+    );
+    let path = dir.join(name);
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    fs::write(&path, contents)
+        .with_context(|| format!("Failed to write logs: {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_write_logs() {
+        let project = tempfile::tempdir().unwrap();
+        let now = Local.with_ymd_and_hms(2024, 6, 1, 14, 0, 5).unwrap();
+        let lines = vec!["Compiling app".to_string(), "error: boom".to_string()];
+
+        let path = write(project.path(), "Build", &lines, now).unwrap();
+        assert_eq!(
+            path,
+            project.path().join("rzen-logs/build-20240601-140005.log")
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "Compiling app\nerror: boom\n"
+        );
+    }
+}
@@ -0,0 +1,327 @@
+use anyhow::{Result, anyhow};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+
+use crate::config::TuiConfig;
+use crate::logging::glyph;
+
+/// Actions for the event loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    NextTab,
+    PrevTab,
+    StartBuild,
+    StartDeploy,
+    StartMonitor,
+    SwitchHost,
+    ClearStatus,
+    Help,
+    Rollback,
+    RestartService,
+    ToggleFollow,
+    CommandPalette,
+    ShowError,
+}
+
+impl Action {
+    pub const ALL: [Action; 14] = [
+        Action::Quit,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::StartBuild,
+        Action::StartDeploy,
+        Action::StartMonitor,
+        Action::SwitchHost,
+        Action::ClearStatus,
+        Action::Help,
+        Action::Rollback,
+        Action::RestartService,
+        Action::ToggleFollow,
+        Action::CommandPalette,
+        Action::ShowError,
+    ];
+
+    /// Key of the action in `[tui.keys]`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::StartBuild => "build",
+            Action::StartDeploy => "deploy",
+            Action::StartMonitor => "monitor",
+            Action::SwitchHost => "switch_host",
+            Action::ClearStatus => "clear_status",
+            Action::Help => "help",
+            Action::Rollback => "rollback",
+            Action::RestartService => "restart_service",
+            Action::ToggleFollow => "toggle_follow",
+            Action::CommandPalette => "command_palette",
+            Action::ShowError => "last_error",
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::NextTab => "Next tab",
+            Action::PrevTab => "Previous tab",
+            Action::StartBuild => "Start build",
+            Action::StartDeploy => "Start deploy",
+            Action::StartMonitor => "Start monitoring",
+            Action::SwitchHost => "Choose the target host",
+            Action::ClearStatus => "Clear the status message",
+            Action::Help => "Show this help",
+            Action::Rollback => "Roll back to a backup",
+            Action::RestartService => "Restart the service",
+            Action::ToggleFollow => "Pause or resume the live log",
+            Action::CommandPalette => "Open the command palette",
+            Action::ShowError => "Show details of the last error",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "esc"],
+            Action::NextTab => &["right", "l"],
+            Action::PrevTab => &["left", "h"],
+            Action::StartBuild => &["b"],
+            Action::StartDeploy => &["d"],
+            Action::StartMonitor => &["m"],
+            Action::SwitchHost => &["t"],
+            Action::ClearStatus => &["c"],
+            Action::Help => &["?"],
+            // Less common actions are reached through the command palette unless bound
+            Action::Rollback | Action::RestartService | Action::ToggleFollow => &[],
+            Action::CommandPalette => &["ctrl+p"],
+            Action::ShowError => &["e"],
+        }
+    }
+}
+
+/// Key press bound to an action, e.g. `b`, `ctrl+d` or `f5`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    code: KeyCode,
+    /// Only Ctrl and Alt; Shift is part of the character itself
+    modifiers: KeyModifiers,
+}
+
+impl Binding {
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid key '{}'", text);
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = text;
+
+        // A lone "+" is a key of its own, so split only on a "+" followed by more text
+        while let Some((modifier, rest)) = key.split_once('+').filter(|(_, rest)| !rest.is_empty())
+        {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                _ => return Err(invalid()),
+            };
+            key = rest;
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').map(str::parse) {
+                    Some(Ok(n @ 1..=12)) => KeyCode::F(n),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+
+        Ok(Self { code, modifiers })
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        key.code == self.code
+            && key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT) == self.modifiers
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::Left => write!(f, "{}", glyph("←")),
+            KeyCode::Right => write!(f, "{}", glyph("→")),
+            KeyCode::Up => write!(f, "{}", glyph("↑")),
+            KeyCode::Down => write!(f, "{}", glyph("↓")),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Keys for each global action, from `[tui.keys]` over the defaults
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: Vec<(Action, Vec<Binding>)>,
+}
+
+impl KeyMap {
+    pub fn from_config(config: &TuiConfig) -> Result<Self> {
+        if let Some(name) = config
+            .keys
+            .keys()
+            .find(|name| !Action::ALL.iter().any(|action| action.name() == *name))
+        {
+            return Err(anyhow!(
+                "Unknown action in [tui.keys]: {} (expected one of: {})",
+                name,
+                Action::ALL.map(Action::name).join(", ")
+            ));
+        }
+
+        let mut bindings: Vec<(Action, Vec<Binding>)> = Vec::new();
+        for action in Action::ALL {
+            let keys = match config.keys.get(action.name()) {
+                Some(keys) => keys.keys(),
+                None => action.default_keys().to_vec(),
+            };
+            let keys = keys
+                .into_iter()
+                .map(|key| {
+                    Binding::parse(key)
+                        .map_err(|e| anyhow!("{} in [tui.keys] {}", e, action.name()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            for key in &keys {
+                if let Some((other, _)) = bindings.iter().find(|(_, bound)| bound.contains(key)) {
+                    return Err(anyhow!(
+                        "Key '{}' is bound to both {} and {} in [tui.keys]",
+                        key,
+                        other.name(),
+                        action.name()
+                    ));
+                }
+            }
+            bindings.push((action, keys));
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// Action bound to a key press
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|binding| binding.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Keys of an action for display, e.g. `q/Esc`
+    pub fn keys(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, keys)| {
+                keys.iter()
+                    .map(Binding::to_string)
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KeyList;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_bindings() {
+        let ctrl_d = Binding::parse("ctrl+d").unwrap();
+        assert!(ctrl_d.matches(&press(KeyCode::Char('d'), KeyModifiers::CONTROL)));
+        assert!(!ctrl_d.matches(&press(KeyCode::Char('d'), KeyModifiers::NONE)));
+        assert_eq!(ctrl_d.to_string(), "ctrl+d");
+
+        // Shifted characters arrive with the Shift modifier set
+        let upper = Binding::parse("B").unwrap();
+        assert!(upper.matches(&press(KeyCode::Char('B'), KeyModifiers::SHIFT)));
+
+        assert_eq!(Binding::parse("F5").unwrap().code, KeyCode::F(5));
+        assert_eq!(Binding::parse("+").unwrap().code, KeyCode::Char('+'));
+        assert_eq!(Binding::parse("Esc").unwrap().to_string(), "Esc");
+        assert!(Binding::parse("hyper+x").is_err());
+        assert!(Binding::parse("f13").is_err());
+    }
+
+    #[test]
+    fn test_keymap_from_config() {
+        let defaults = KeyMap::from_config(&TuiConfig::default()).unwrap();
+        assert_eq!(
+            defaults.action(&press(KeyCode::Char('b'), KeyModifiers::NONE)),
+            Some(Action::StartBuild)
+        );
+        assert_eq!(defaults.keys(Action::Quit), "q/Esc");
+
+        let mut config = TuiConfig::default();
+        config
+            .keys
+            .insert("build".to_string(), KeyList::One("B".to_string()));
+        config.keys.insert(
+            "deploy".to_string(),
+            KeyList::Many(vec!["D".to_string(), "ctrl+d".to_string()]),
+        );
+        let keys = KeyMap::from_config(&config).unwrap();
+        assert_eq!(
+            keys.action(&press(KeyCode::Char('b'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keys.action(&press(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            Some(Action::StartDeploy)
+        );
+        assert_eq!(keys.keys(Action::StartDeploy), "D/ctrl+d");
+
+        config
+            .keys
+            .insert("monitor".to_string(), KeyList::One("B".to_string()));
+        let error = KeyMap::from_config(&config).unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Key 'B' is bound to both build and monitor in [tui.keys]"
+        );
+
+        let mut config = TuiConfig::default();
+        config
+            .keys
+            .insert("bulid".to_string(), KeyList::One("x".to_string()));
+        assert!(KeyMap::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+
+use crate::commands::monitor::logs::SeverityFilter;
+use crate::commands::monitor::structured::Severity;
+
+/// Severity filter for the log panes, cycled with `f`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LevelFilter {
+    #[default]
+    All,
+    Warn,
+    Error,
+}
+
+impl LevelFilter {
+    pub fn next(self) -> Self {
+        match self {
+            LevelFilter::All => LevelFilter::Warn,
+            LevelFilter::Warn => LevelFilter::Error,
+            LevelFilter::Error => LevelFilter::All,
+        }
+    }
+
+    /// Pane title suffix naming the active filter
+    pub fn title(self) -> &'static str {
+        match self {
+            LevelFilter::All => "",
+            LevelFilter::Warn => " [warn+]",
+            LevelFilter::Error => " [errors]",
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            LevelFilter::All => "Showing all log lines",
+            LevelFilter::Warn => "Showing warnings and errors",
+            LevelFilter::Error => "Showing errors only",
+        }
+    }
+
+    fn minimum(self) -> Option<Severity> {
+        match self {
+            LevelFilter::All => None,
+            LevelFilter::Warn => Some(Severity::Warn),
+            LevelFilter::Error => Some(Severity::Error),
+        }
+    }
+
+    /// Lines at or above the filter's severity, see [`SeverityFilter`]
+    pub fn apply(self, lines: &[String]) -> Cow<'_, [String]> {
+        let Some(minimum) = self.minimum() else {
+            return Cow::Borrowed(lines);
+        };
+
+        let mut filter = SeverityFilter::new(Some(minimum));
+        Cow::Owned(
+            lines
+                .iter()
+                .filter(|line| filter.accepts(line))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_levels() {
+        let lines: Vec<String> = [
+            "Compiling app v0.1.0",
+            "warning: unused variable: `x`",
+            "   --> src/main.rs:2:9",
+            "error[E0308]: mismatched types",
+            "   --> src/main.rs:4:5",
+            "INFO server started",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+
+        assert!(matches!(LevelFilter::All.apply(&lines), Cow::Borrowed(_)));
+        assert_eq!(LevelFilter::Warn.apply(&lines).len(), 4);
+        assert_eq!(*LevelFilter::Error.apply(&lines), lines[3..5]);
+        assert_eq!(LevelFilter::Error.next(), LevelFilter::All);
+    }
+}
@@ -0,0 +1,83 @@
+use ratatui::text::{Line, Span};
+
+/// Columns moved by one horizontal scroll step
+pub const SCROLL_STEP: usize = 8;
+
+/// Line without its first `columns` characters, keeping each span's style
+pub fn skip_columns(line: Line<'_>, columns: usize) -> Line<'_> {
+    let mut remaining = columns;
+    let spans = line
+        .spans
+        .into_iter()
+        .filter_map(|span| {
+            let len = span.content.chars().count();
+            if remaining >= len {
+                remaining -= len;
+                return None;
+            }
+            let content: String = span.content.chars().skip(remaining).collect();
+            remaining = 0;
+            Some(Span::styled(content, span.style))
+        })
+        .collect::<Vec<_>>();
+    Line::from(spans)
+}
+
+/// Rows of at most `width` characters holding the whole line, keeping each span's style
+pub fn wrap(line: Line<'_>, width: usize) -> Vec<Line<'_>> {
+    let width = width.max(1);
+    let mut rows = vec![Vec::new()];
+    let mut used = 0;
+    for span in line.spans {
+        let mut chars = span.content.chars().peekable();
+        while chars.peek().is_some() {
+            if used == width {
+                rows.push(Vec::new());
+                used = 0;
+            }
+            let part: String = chars.by_ref().take(width - used).collect();
+            used += part.chars().count();
+            if let Some(row) = rows.last_mut() {
+                row.push(Span::styled(part, span.style));
+            }
+        }
+    }
+    rows.into_iter().map(Line::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Style, Stylize};
+
+    fn text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_skip_columns() {
+        let line = Line::from(vec![
+            Span::raw("abc"),
+            Span::styled("defg", Style::new().bold()),
+        ]);
+        let shifted = skip_columns(line.clone(), 4);
+        assert_eq!(text(&shifted), "efg");
+        assert_eq!(shifted.spans[0].style, Style::new().bold());
+        assert_eq!(text(&skip_columns(line, 10)), "");
+    }
+
+    #[test]
+    fn test_wrap() {
+        let line = Line::from(vec![
+            Span::raw("abcd"),
+            Span::styled("efghij", Style::new().bold()),
+        ]);
+        let rows = wrap(line, 4);
+        let rows: Vec<String> = rows.iter().map(text).collect();
+        assert_eq!(rows, ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap(Line::from(""), 4).len(), 1);
+    }
+}
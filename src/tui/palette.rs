@@ -0,0 +1,125 @@
+use crossterm::event::KeyCode;
+
+use super::keys::Action;
+
+/// What the event loop should do after a key in the command palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Handled,
+    Run(Action),
+    Close,
+}
+
+/// Ctrl+P palette listing every action, filtered by a fuzzy query
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    /// Index into `entries()`
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    /// Actions matching the query, best match first
+    pub fn entries(&self) -> Vec<Action> {
+        let mut scored: Vec<(usize, Action)> = Action::ALL
+            .into_iter()
+            .filter(|&action| action != Action::CommandPalette)
+            .filter_map(|action| {
+                let text = format!("{} {}", action.describe(), action.name());
+                score(&self.query, &text).map(|score| (score, action))
+            })
+            .collect();
+        // Stable, so equally good matches keep the order of `Action::ALL`
+        scored.sort_by_key(|(score, _)| *score);
+        scored.into_iter().map(|(_, action)| action).collect()
+    }
+
+    /// Handle a key; every key is used while the palette is open
+    pub fn handle_key(&mut self, key: KeyCode) -> Outcome {
+        match key {
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.entries().len().saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                return match self.entries().get(self.selected) {
+                    Some(&action) => Outcome::Run(action),
+                    None => Outcome::Handled,
+                };
+            }
+            KeyCode::Esc => return Outcome::Close,
+            _ => {}
+        }
+        Outcome::Handled
+    }
+}
+
+/// Case-insensitive subsequence match; lower scores are tighter matches
+///
+/// The score is where the match starts plus the characters skipped inside it,
+/// so "rb" ranks "Roll back" above "Start build".
+fn score(query: &str, text: &str) -> Option<usize> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let Some(&first) = query.first() else {
+        return Some(0);
+    };
+
+    (0..text.len())
+        .filter(|&start| text[start] == first)
+        .filter_map(|start| {
+            let mut end = start + 1;
+            for &c in &query[1..] {
+                end += text[end..].iter().position(|&t| t == c)? + 1;
+            }
+            Some(end - query.len())
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_query(palette: &mut CommandPalette, query: &str) {
+        for c in query.chars() {
+            assert_eq!(palette.handle_key(KeyCode::Char(c)), Outcome::Handled);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(score("", "Start build"), Some(0));
+        assert_eq!(score("build", "Start build"), Some(6));
+        assert_eq!(score("BLD", "Start build"), Some(8));
+        assert_eq!(score("xyz", "Start build"), None);
+        assert!(score("rb", "Roll back") < score("rb", "Start build"));
+    }
+
+    #[test]
+    fn test_palette_filters_and_runs() {
+        let mut palette = CommandPalette::default();
+        assert_eq!(palette.entries().len(), Action::ALL.len() - 1);
+
+        type_query(&mut palette, "restart");
+        assert_eq!(palette.entries()[0], Action::RestartService);
+        assert_eq!(
+            palette.handle_key(KeyCode::Enter),
+            Outcome::Run(Action::RestartService)
+        );
+
+        palette.handle_key(KeyCode::Backspace);
+        type_query(&mut palette, "qqq");
+        assert!(palette.entries().is_empty());
+        assert_eq!(palette.handle_key(KeyCode::Enter), Outcome::Handled);
+        assert_eq!(palette.handle_key(KeyCode::Esc), Outcome::Close);
+    }
+}
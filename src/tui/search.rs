@@ -0,0 +1,224 @@
+use crossterm::event::KeyCode;
+use ratatui::text::{Line, Span};
+use regex::Regex;
+use std::ops::Range;
+
+use super::theme::Theme;
+
+/// `/` search over the log pane of the current tab
+#[derive(Debug, Clone, Default)]
+pub struct LogSearch {
+    /// Text being typed at the `/` prompt
+    pub input: Option<String>,
+    /// Case-insensitive pattern of the last committed search
+    pattern: Option<Regex>,
+    query: String,
+    /// Index of the focused matching line
+    pub focus: Option<usize>,
+}
+
+impl LogSearch {
+    /// Handle a key over a log pane; returns false for keys it does not use
+    pub fn handle_key(&mut self, key: KeyCode, lines: &[String]) -> bool {
+        if let Some(input) = &mut self.input {
+            match key {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let query = self.input.take().unwrap_or_default();
+                    self.commit(&query, lines);
+                }
+                KeyCode::Esc => self.input = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        match key {
+            KeyCode::Char('/') => self.input = Some(String::new()),
+            KeyCode::Char('n') if self.pattern.is_some() => self.step(lines, true),
+            KeyCode::Char('N') if self.pattern.is_some() => self.step(lines, false),
+            KeyCode::Esc if self.pattern.is_some() => *self = Self::default(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Start searching for `query`, focusing the most recent match
+    fn commit(&mut self, query: &str, lines: &[String]) {
+        if query.is_empty() {
+            *self = Self::default();
+            return;
+        }
+        self.pattern = Regex::new(&format!("(?i){}", regex::escape(query))).ok();
+        self.query = query.to_string();
+        self.focus = self.matches(lines).last().copied();
+    }
+
+    /// Move the focus to the next (or previous) match, wrapping around
+    fn step(&mut self, lines: &[String], forward: bool) {
+        let matches = self.matches(lines);
+        self.focus = match (self.focus, forward) {
+            (Some(focus), true) => matches.iter().find(|&&i| i > focus).or(matches.first()),
+            (Some(focus), false) => matches
+                .iter()
+                .rev()
+                .find(|&&i| i < focus)
+                .or(matches.last()),
+            (None, _) => matches.last(),
+        }
+        .copied();
+    }
+
+    /// Indices of the lines matching the search
+    pub fn matches(&self, lines: &[String]) -> Vec<usize> {
+        let Some(pattern) = &self.pattern else {
+            return Vec::new();
+        };
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| pattern.is_match(line))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Lines were dropped from the front of the log
+    pub fn shift(&mut self, removed: usize) {
+        self.focus = self.focus.and_then(|focus| focus.checked_sub(removed));
+    }
+
+    /// Status bar text: the prompt while typing, then the match position
+    pub fn status(&self, lines: &[String]) -> Option<String> {
+        if let Some(input) = &self.input {
+            return Some(format!("/{}", input));
+        }
+        self.pattern.as_ref()?;
+
+        let matches = self.matches(lines);
+        let position = self
+            .focus
+            .and_then(|focus| matches.iter().position(|&i| i == focus));
+        Some(match position {
+            Some(position) => format!(
+                "Match {}/{} for '{}' | 'n' next, 'N' previous, Esc clear",
+                position + 1,
+                matches.len(),
+                self.query
+            ),
+            None => format!("Pattern not found: {}", self.query),
+        })
+    }
+
+    /// Title suffix naming the active search
+    pub fn title(&self) -> String {
+        match &self.pattern {
+            Some(_) => format!(" [/{}]", self.query),
+            None => String::new(),
+        }
+    }
+
+    /// Line with matches highlighted; the focused line is also shaded
+    pub fn highlight<'a>(&self, line: &'a str, focused: bool, theme: &Theme) -> Line<'a> {
+        let base = if focused { theme.focus() } else { theme.text() };
+        let Some(pattern) = &self.pattern else {
+            return Line::styled(line, base);
+        };
+
+        let mut spans = Vec::new();
+        let mut last = 0;
+        for found in pattern.find_iter(line) {
+            spans.push(Span::styled(&line[last..found.start()], base));
+            spans.push(Span::styled(found.as_str(), theme.selection()));
+            last = found.end();
+        }
+        spans.push(Span::styled(&line[last..], base));
+        Line::from(spans)
+    }
+}
+
+/// Lines to show in a pane `height` lines tall: the newest ones, or around the focus
+pub fn window(len: usize, height: usize, focus: Option<usize>) -> Range<usize> {
+    let tail = len.saturating_sub(height)..len;
+    match focus {
+        Some(focus) if focus < tail.start => {
+            let start = focus.saturating_sub(height / 2);
+            start..(start + height).min(len)
+        }
+        _ => tail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines() -> Vec<String> {
+        [
+            "Compiling app",
+            "error: mismatched types",
+            "warning: unused",
+            "ERROR: build failed",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+    }
+
+    fn search(search: &mut LogSearch, query: &str, lines: &[String]) {
+        assert!(search.handle_key(KeyCode::Char('/'), lines));
+        for c in query.chars() {
+            assert!(search.handle_key(KeyCode::Char(c), lines));
+        }
+        assert!(search.handle_key(KeyCode::Enter, lines));
+    }
+
+    #[test]
+    fn test_search_navigation() {
+        let lines = lines();
+        let mut log_search = LogSearch::default();
+        assert!(!log_search.handle_key(KeyCode::Char('n'), &lines));
+
+        search(&mut log_search, "error", &lines);
+        assert_eq!(log_search.matches(&lines), vec![1, 3]);
+        assert_eq!(log_search.focus, Some(3));
+        assert_eq!(
+            log_search.status(&lines).unwrap(),
+            "Match 2/2 for 'error' | 'n' next, 'N' previous, Esc clear"
+        );
+
+        log_search.handle_key(KeyCode::Char('n'), &lines);
+        assert_eq!(log_search.focus, Some(1));
+        log_search.handle_key(KeyCode::Char('N'), &lines);
+        assert_eq!(log_search.focus, Some(3));
+
+        search(&mut log_search, "panic", &lines);
+        assert_eq!(log_search.focus, None);
+        assert_eq!(
+            log_search.status(&lines).unwrap(),
+            "Pattern not found: panic"
+        );
+
+        assert!(log_search.handle_key(KeyCode::Esc, &lines));
+        assert_eq!(log_search.status(&lines), None);
+        assert!(!log_search.handle_key(KeyCode::Esc, &lines));
+    }
+
+    #[test]
+    fn test_highlight_and_window() {
+        let lines = lines();
+        let mut log_search = LogSearch::default();
+        search(&mut log_search, "ERROR", &lines);
+
+        let line = log_search.highlight(&lines[1], false, &Theme::default());
+        assert_eq!(line.spans.len(), 3);
+        assert_eq!(line.spans[1].content, "error");
+
+        assert_eq!(window(100, 10, None), 90..100);
+        assert_eq!(window(100, 10, Some(95)), 90..100);
+        assert_eq!(window(100, 10, Some(20)), 15..25);
+        assert_eq!(window(5, 10, Some(0)), 0..5);
+    }
+}
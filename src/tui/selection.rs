@@ -0,0 +1,94 @@
+use crossterm::event::KeyCode;
+use std::ops::RangeInclusive;
+
+/// Lines moved by PageUp/PageDown
+const PAGE: usize = 10;
+
+/// What the log pane should do after a key in selection mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Handled,
+    Copy,
+    Close,
+}
+
+/// Line cursor in a log pane, optionally spanning a range, for copying lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogSelection {
+    pub cursor: usize,
+    /// Other end of the range, set with `v` or Space
+    pub anchor: Option<usize>,
+}
+
+impl LogSelection {
+    pub fn new(cursor: usize) -> Self {
+        Self {
+            cursor,
+            anchor: None,
+        }
+    }
+
+    /// Handle a key over `len` log lines; every key is used while selecting
+    pub fn handle_key(&mut self, key: KeyCode, len: usize) -> Outcome {
+        let last = len.saturating_sub(1);
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.cursor = (self.cursor + 1).min(last),
+            KeyCode::PageUp => self.cursor = self.cursor.saturating_sub(PAGE),
+            KeyCode::PageDown => self.cursor = (self.cursor + PAGE).min(last),
+            KeyCode::Home | KeyCode::Char('g') => self.cursor = 0,
+            KeyCode::End | KeyCode::Char('G') => self.cursor = last,
+            KeyCode::Char('v') | KeyCode::Char(' ') => {
+                self.anchor = match self.anchor {
+                    Some(_) => None,
+                    None => Some(self.cursor),
+                };
+            }
+            KeyCode::Enter | KeyCode::Char('y') => return Outcome::Copy,
+            KeyCode::Esc | KeyCode::Char('q') => return Outcome::Close,
+            _ => {}
+        }
+        Outcome::Handled
+    }
+
+    /// Selected lines: the range between anchor and cursor, or the cursor line
+    pub fn range(&self) -> RangeInclusive<usize> {
+        let anchor = self.anchor.unwrap_or(self.cursor);
+        anchor.min(self.cursor)..=anchor.max(self.cursor)
+    }
+
+    /// Lines were dropped from the front of the log
+    pub fn shift(&mut self, removed: usize) {
+        self.cursor = self.cursor.saturating_sub(removed);
+        self.anchor = self.anchor.map(|anchor| anchor.saturating_sub(removed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_range() {
+        let mut selection = LogSelection::new(5);
+        assert_eq!(selection.range(), 5..=5);
+
+        assert_eq!(
+            selection.handle_key(KeyCode::Char('v'), 8),
+            Outcome::Handled
+        );
+        selection.handle_key(KeyCode::Char('k'), 8);
+        selection.handle_key(KeyCode::Up, 8);
+        assert_eq!(selection.range(), 3..=5);
+
+        selection.handle_key(KeyCode::PageDown, 8);
+        assert_eq!(selection.cursor, 7);
+        assert_eq!(selection.range(), 5..=7);
+
+        selection.shift(6);
+        assert_eq!(selection.range(), 0..=1);
+
+        assert_eq!(selection.handle_key(KeyCode::Char('y'), 8), Outcome::Copy);
+        assert_eq!(selection.handle_key(KeyCode::Esc, 8), Outcome::Close);
+    }
+}
@@ -0,0 +1,225 @@
+use anyhow::{Result, anyhow};
+use ratatui::style::{Color, Modifier, Style};
+use std::str::FromStr;
+
+use crate::config::ThemeConfig;
+
+/// Colors used across the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub accent: Color,
+    pub text: Color,
+    pub muted: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub selection_fg: Color,
+    pub selection_bg: Color,
+    /// Background of the focused search match line
+    pub focus_bg: Color,
+    /// Use text attributes instead of colors, for terminals without color support
+    monochrome: bool,
+}
+
+impl Theme {
+    pub const PRESETS: [&'static str; 4] = ["dark", "light", "solarized", "monochrome"];
+
+    pub fn preset(name: &str) -> Option<Self> {
+        let theme = match name.to_ascii_lowercase().as_str() {
+            "dark" => Self {
+                accent: Color::Cyan,
+                text: Color::White,
+                muted: Color::Gray,
+                success: Color::Green,
+                warning: Color::Yellow,
+                selection_fg: Color::Black,
+                selection_bg: Color::Yellow,
+                focus_bg: Color::DarkGray,
+                monochrome: false,
+            },
+            "light" => Self {
+                accent: Color::Rgb(0, 95, 175),
+                text: Color::Black,
+                muted: Color::DarkGray,
+                success: Color::Rgb(0, 128, 0),
+                warning: Color::Rgb(175, 95, 0),
+                selection_fg: Color::White,
+                selection_bg: Color::Rgb(0, 95, 175),
+                focus_bg: Color::Rgb(220, 220, 220),
+                monochrome: false,
+            },
+            "solarized" => Self {
+                accent: Color::Rgb(0x26, 0x8b, 0xd2),
+                text: Color::Rgb(0x83, 0x94, 0x96),
+                muted: Color::Rgb(0x58, 0x6e, 0x75),
+                success: Color::Rgb(0x85, 0x99, 0x00),
+                warning: Color::Rgb(0xb5, 0x89, 0x00),
+                selection_fg: Color::Rgb(0x00, 0x2b, 0x36),
+                selection_bg: Color::Rgb(0xb5, 0x89, 0x00),
+                focus_bg: Color::Rgb(0x07, 0x36, 0x42),
+                monochrome: false,
+            },
+            "monochrome" => Self {
+                accent: Color::Reset,
+                text: Color::Reset,
+                muted: Color::Reset,
+                success: Color::Reset,
+                warning: Color::Reset,
+                selection_fg: Color::Reset,
+                selection_bg: Color::Reset,
+                focus_bg: Color::Reset,
+                monochrome: true,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Theme from `[tui.theme]`; without a preset, `NO_COLOR` selects monochrome
+    pub fn from_config(config: &ThemeConfig) -> Result<Self> {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        let preset = match &config.preset {
+            Some(preset) => preset.as_str(),
+            None if no_color => "monochrome",
+            None => "dark",
+        };
+        let mut theme = Self::preset(preset).ok_or_else(|| {
+            anyhow!(
+                "Unknown theme preset in [tui.theme]: {} (expected one of: {})",
+                preset,
+                Self::PRESETS.join(", ")
+            )
+        })?;
+
+        for (name, value) in &config.colors {
+            let color = Color::from_str(value)
+                .map_err(|_| anyhow!("Invalid color in [tui.theme] {}: {}", name, value))?;
+            let slot = match name.as_str() {
+                "accent" => &mut theme.accent,
+                "text" => &mut theme.text,
+                "muted" => &mut theme.muted,
+                "success" => &mut theme.success,
+                "warning" => &mut theme.warning,
+                "selection_fg" => &mut theme.selection_fg,
+                "selection_bg" => &mut theme.selection_bg,
+                "focus_bg" => &mut theme.focus_bg,
+                _ => return Err(anyhow!("Unknown color in [tui.theme]: {}", name)),
+            };
+            *slot = color;
+        }
+        Ok(theme)
+    }
+
+    fn fg(&self, color: Color) -> Style {
+        if self.monochrome {
+            Style::default()
+        } else {
+            Style::default().fg(color)
+        }
+    }
+
+    /// Title bar
+    pub fn title(&self) -> Style {
+        self.fg(self.accent).add_modifier(Modifier::BOLD)
+    }
+
+    /// Regular text in lists and panes
+    pub fn text(&self) -> Style {
+        self.fg(self.text)
+    }
+
+    /// Hints and idle gauges
+    pub fn muted(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::DIM)
+        } else {
+            self.fg(self.muted)
+        }
+    }
+
+    /// Status bar and running gauges
+    pub fn success(&self) -> Style {
+        self.fg(self.success)
+    }
+
+    /// Confirmation dialogs
+    pub fn warning(&self) -> Style {
+        self.fg(self.warning).add_modifier(Modifier::BOLD)
+    }
+
+    /// Charts
+    pub fn chart(&self) -> Style {
+        self.fg(self.accent)
+    }
+
+    /// Current tab and the selected item of a list that is not being edited
+    pub fn active(&self) -> Style {
+        self.fg(self.warning).add_modifier(Modifier::BOLD)
+    }
+
+    /// Selected list entries, the field being edited and search matches
+    pub fn selection(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(self.selection_fg).bg(self.selection_bg)
+        }
+    }
+
+    /// Line of the focused search match
+    pub fn focus(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default().bg(self.focus_bg)
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset("dark").expect("dark preset exists")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_theme_from_config() {
+        let config = ThemeConfig {
+            preset: Some("Solarized".to_string()),
+            colors: BTreeMap::from([
+                ("accent".to_string(), "magenta".to_string()),
+                ("warning".to_string(), "#ff8800".to_string()),
+            ]),
+        };
+        let theme = Theme::from_config(&config).unwrap();
+        assert_eq!(theme.accent, Color::Magenta);
+        assert_eq!(theme.warning, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.success, Color::Rgb(0x85, 0x99, 0x00));
+
+        let mono = Theme::preset("monochrome").unwrap();
+        assert_eq!(mono.title().fg, None);
+        assert!(mono.selection().add_modifier.contains(Modifier::REVERSED));
+
+        let unknown = ThemeConfig {
+            preset: Some("neon".to_string()),
+            ..Default::default()
+        };
+        assert!(Theme::from_config(&unknown).is_err());
+
+        let bad_color = ThemeConfig {
+            preset: Some("dark".to_string()),
+            colors: BTreeMap::from([("accent".to_string(), "not-a-color".to_string())]),
+        };
+        assert!(Theme::from_config(&bad_color).is_err());
+
+        let bad_name = ThemeConfig {
+            preset: Some("dark".to_string()),
+            colors: BTreeMap::from([("background".to_string(), "black".to_string())]),
+        };
+        assert!(Theme::from_config(&bad_name).is_err());
+    }
+}
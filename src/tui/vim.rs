@@ -0,0 +1,199 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::str::FromStr;
+
+use super::keys::Action;
+use crate::cli::TuiTab;
+
+/// Lines moved by Ctrl-d/Ctrl-u
+const HALF_PAGE: usize = 10;
+
+/// Cursor movement in a log pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Down,
+    Up,
+    HalfPageDown,
+    HalfPageUp,
+    Top,
+    Bottom,
+}
+
+impl Motion {
+    /// New cursor over `len` lines; `None` follows the newest line
+    pub fn apply(self, cursor: Option<usize>, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let last = len - 1;
+        let cursor = cursor.unwrap_or(last).min(last);
+        let moved = match self {
+            Motion::Down => cursor + 1,
+            Motion::Up => cursor.saturating_sub(1),
+            Motion::HalfPageDown => cursor + HALF_PAGE,
+            Motion::HalfPageUp => cursor.saturating_sub(HALF_PAGE),
+            Motion::Top => 0,
+            Motion::Bottom => return None,
+        };
+        // Moving past the newest line goes back to following the log
+        (moved < last).then_some(moved)
+    }
+}
+
+/// Command entered at the `:` prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// `:q`
+    Quit,
+    /// `:w`, review and save the config
+    Write,
+    /// `:<n>`, 1-based line of the log pane
+    Line(usize),
+    /// `:tab <name>`
+    Tab(TuiTab),
+    /// Any action by its `[tui.keys]` name, e.g. `:build` or `:restart_service`
+    Run(Action),
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if let Ok(line) = text.parse::<usize>() {
+            return Ok(Command::Line(line));
+        }
+        if let Some(name) = text.strip_prefix("tab ") {
+            return clap::ValueEnum::from_str(name.trim(), true)
+                .map(Command::Tab)
+                .map_err(|_| format!("Unknown tab: {}", name.trim()));
+        }
+        match text {
+            "q" | "quit" => Ok(Command::Quit),
+            "w" | "write" => Ok(Command::Write),
+            _ => Action::ALL
+                .into_iter()
+                .find(|action| action.name() == text)
+                .map(Command::Run)
+                .ok_or_else(|| format!("Not an editor command: {}", text)),
+        }
+    }
+}
+
+/// What the event loop should do after a key in vim mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Not a vim key; handle it as usual
+    Ignored,
+    Handled,
+    Move(Motion),
+    Run(Result<Command, String>),
+}
+
+/// Vim-style keys (`[tui] vim = true`): j/k, gg/G, Ctrl-d/Ctrl-u and a `:` command line
+#[derive(Debug, Clone, Default)]
+pub struct VimMode {
+    /// Text typed at the `:` prompt while it is open
+    pub command: Option<String>,
+    /// First `g` of `gg` was pressed
+    pending_g: bool,
+}
+
+impl VimMode {
+    pub fn handle_key(&mut self, key: &KeyEvent) -> Outcome {
+        if let Some(command) = &mut self.command {
+            match key.code {
+                KeyCode::Char(c) => command.push(c),
+                // Deleting past the start closes the prompt, as in vim
+                KeyCode::Backspace if command.is_empty() => self.command = None,
+                KeyCode::Backspace => {
+                    command.pop();
+                }
+                KeyCode::Enter => {
+                    let command = self.command.take().unwrap_or_default();
+                    if command.trim().is_empty() {
+                        return Outcome::Handled;
+                    }
+                    return Outcome::Run(command.parse());
+                }
+                KeyCode::Esc => self.command = None,
+                _ => {}
+            }
+            return Outcome::Handled;
+        }
+
+        let pending_g = std::mem::take(&mut self.pending_g);
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char(':') => self.command = Some(String::new()),
+            KeyCode::Char('g') if pending_g => return Outcome::Move(Motion::Top),
+            KeyCode::Char('g') => self.pending_g = true,
+            KeyCode::Char('G') => return Outcome::Move(Motion::Bottom),
+            KeyCode::Char('j') => return Outcome::Move(Motion::Down),
+            KeyCode::Char('k') => return Outcome::Move(Motion::Up),
+            KeyCode::Char('d') if control => return Outcome::Move(Motion::HalfPageDown),
+            KeyCode::Char('u') if control => return Outcome::Move(Motion::HalfPageUp),
+            _ => return Outcome::Ignored,
+        }
+        Outcome::Handled
+    }
+
+    /// Status bar text while the `:` prompt is open
+    pub fn status(&self) -> Option<String> {
+        self.command.as_ref().map(|command| format!(":{}", command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(vim: &mut VimMode, c: char) -> Outcome {
+        vim.handle_key(&KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_motions() {
+        let mut vim = VimMode::default();
+        assert_eq!(press(&mut vim, 'g'), Outcome::Handled);
+        assert_eq!(press(&mut vim, 'g'), Outcome::Move(Motion::Top));
+        assert_eq!(press(&mut vim, 'g'), Outcome::Handled);
+        assert_eq!(press(&mut vim, 'x'), Outcome::Ignored);
+        assert_eq!(press(&mut vim, 'g'), Outcome::Handled);
+        let half_page = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(
+            vim.handle_key(&half_page),
+            Outcome::Move(Motion::HalfPageDown)
+        );
+
+        assert_eq!(Motion::Up.apply(None, 30), Some(28));
+        assert_eq!(Motion::HalfPageUp.apply(Some(5), 30), Some(0));
+        assert_eq!(Motion::HalfPageDown.apply(Some(15), 30), Some(25));
+        assert_eq!(Motion::Down.apply(Some(28), 30), None);
+        assert_eq!(Motion::Top.apply(None, 0), None);
+    }
+
+    #[test]
+    fn test_command_line() {
+        let mut vim = VimMode::default();
+        press(&mut vim, ':');
+        for c in "tab monitor".chars() {
+            assert_eq!(press(&mut vim, c), Outcome::Handled);
+        }
+        assert_eq!(vim.status().as_deref(), Some(":tab monitor"));
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(
+            vim.handle_key(&enter),
+            Outcome::Run(Ok(Command::Tab(TuiTab::Monitor)))
+        );
+        assert_eq!(vim.command, None);
+
+        assert_eq!("q".parse(), Ok(Command::Quit));
+        assert_eq!("42".parse(), Ok(Command::Line(42)));
+        assert_eq!(
+            "restart_service".parse(),
+            Ok(Command::Run(Action::RestartService))
+        );
+        assert!("tab nope".parse::<Command>().is_err());
+        assert!("wq!".parse::<Command>().is_err());
+    }
+}